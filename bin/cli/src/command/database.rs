@@ -1,10 +1,12 @@
 use std::path::Path;
 
 use anyhow::Context;
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use database::mungos::mongodb::bson::{Document, doc};
 use komodo_client::entities::{
-  config::cli::args::database::DatabaseCommand, optional_string,
+  backup::{BackupItemState, BackupItemTarget},
+  config::cli::args::database::DatabaseCommand,
+  optional_string,
 };
 
 use crate::{command::sanitize_uri, config::cli_config};
@@ -14,10 +16,20 @@ pub async fn handle(command: &DatabaseCommand) -> anyhow::Result<()> {
     DatabaseCommand::Backup { yes, .. } => backup(*yes).await,
     DatabaseCommand::Restore {
       restore_folder,
+      only,
+      list,
       index,
       yes,
       ..
-    } => restore(restore_folder.as_deref(), *index, *yes).await,
+    } => {
+      if *list {
+        list_restore_items(restore_folder.as_deref()).await
+      } else if !only.is_empty() {
+        restore_only(restore_folder.as_deref(), only, *yes).await
+      } else {
+        restore(restore_folder.as_deref(), *index, *yes).await
+      }
+    }
     DatabaseCommand::Prune { yes, .. } => prune(*yes).await,
     DatabaseCommand::Copy { yes, index, .. } => {
       copy(*index, *yes).await
@@ -152,6 +164,162 @@ async fn restore(
   .await
 }
 
+async fn list_restore_items(
+  restore_folder: Option<&Path>,
+) -> anyhow::Result<()> {
+  let config = cli_config();
+
+  let db = database::init(&config.database_target).await?;
+
+  let contents = database::utils::list_backup_items(
+    &db,
+    &config.backups_folder,
+    restore_folder,
+  )
+  .await?;
+
+  println!(
+    "\n{}: {:?}\n",
+    " - Restore Folder".dimmed(),
+    contents.restore_folder
+  );
+
+  if contents.items.is_empty() {
+    println!("{}", "No restorable items found in backup".dimmed());
+    return Ok(());
+  }
+
+  for item in &contents.items {
+    println!(
+      " - {}: {} | {}",
+      item.variant.as_ref().dimmed(),
+      item.name.bold(),
+      colored_state(item.state)
+    );
+  }
+
+  println!(
+    "\n{}",
+    "Restore specific items using '--only Variant:name'".dimmed()
+  );
+
+  Ok(())
+}
+
+async fn restore_only(
+  restore_folder: Option<&Path>,
+  only: &[String],
+  yes: bool,
+) -> anyhow::Result<()> {
+  let config = cli_config();
+
+  let targets = only
+    .iter()
+    .map(|target| target.parse::<BackupItemTarget>())
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+  println!(
+    "\n🦎  {} Database {} Utility  🦎",
+    "Komodo".bold(),
+    "Selective Restore".purple().bold()
+  );
+  println!(
+    "\n{}\n",
+    " - Restores only the selected items from gzip compressed files."
+      .dimmed()
+  );
+  if let Some(uri) = optional_string(&config.database_target.uri) {
+    println!("{}: {}", " - Target URI".dimmed(), sanitize_uri(&uri));
+  }
+  if let Some(address) =
+    optional_string(&config.database_target.address)
+  {
+    println!("{}: {address}", " - Target Address".dimmed());
+  }
+  if let Some(username) =
+    optional_string(&config.database_target.username)
+  {
+    println!("{}: {username}", " - Target Username".dimmed());
+  }
+  println!(
+    "{}: {}",
+    " - Target Db Name".dimmed(),
+    config.database_target.db_name,
+  );
+  println!(
+    "\n{}: {:?}",
+    " - Backups Folder".dimmed(),
+    config.backups_folder
+  );
+
+  let db = database::init(&config.database_target).await?;
+
+  let contents = database::utils::list_backup_items(
+    &db,
+    &config.backups_folder,
+    restore_folder,
+  )
+  .await?;
+
+  println!(
+    "{}: {:?}\n",
+    " - Restore Folder".dimmed(),
+    contents.restore_folder
+  );
+
+  for target in &targets {
+    let item = contents
+      .items
+      .iter()
+      .find(|item| {
+        item.variant == target.variant && item.name == target.name
+      })
+      .with_context(|| {
+        format!(
+          "{} '{}' does not exist in the backup",
+          target.variant, target.name
+        )
+      })?;
+    println!(
+      "{}: {} | {}",
+      item.variant.as_ref().dimmed(),
+      item.name.bold(),
+      colored_state(item.state)
+    );
+    for diff in &item.diff {
+      println!("  {}:", diff.field.cyan());
+      println!("    {} {}", "-".red(), diff.current.red());
+      println!("    {} {}", "+".green(), diff.backup.green());
+    }
+  }
+
+  crate::command::wait_for_enter("restore selected items", yes)?;
+
+  let restored = database::utils::restore_backup_items(
+    &db,
+    &config.backups_folder,
+    restore_folder,
+    &targets,
+  )
+  .await?;
+
+  info!(
+    "Finished restoring {} item{} ✅",
+    restored.len(),
+    if restored.len() == 1 { "" } else { "s" }
+  );
+
+  Ok(())
+}
+
+fn colored_state(state: BackupItemState) -> ColoredString {
+  match state {
+    BackupItemState::Unchanged => state.as_ref().dimmed(),
+    BackupItemState::Changed => state.as_ref().yellow(),
+    BackupItemState::Missing => state.as_ref().red(),
+  }
+}
+
 async fn prune(yes: bool) -> anyhow::Result<()> {
   let config = cli_config();
 
@@ -1,12 +1,20 @@
-use std::time::Duration;
+use std::{io::Write as _, time::Duration};
 
+use anyhow::{Context, anyhow};
 use colored::Colorize;
+use crossterm::event::{
+  self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+};
 use futures_util::{StreamExt, stream::FuturesUnordered};
 use komodo_client::{
-  api::execute::{
-    BatchExecutionResponse, BatchExecutionResponseItem, Execution,
+  api::{
+    execute::{
+      BatchExecutionResponse, BatchExecutionResponseItem, Execution,
+      RunAction,
+    },
+    read::GetActionArguments,
   },
-  entities::{resource_link, update::Update},
+  entities::{JsonValue, resource_link, update::Update},
 };
 
 use crate::config::cli_config;
@@ -29,13 +37,15 @@ pub async fn handle(
 
   println!("\n{}: Execution", "Mode".dimmed());
 
+  let mut execution = execution.clone();
+
   macro_rules! handle_execution {
     (
       execute: [$($ExecVariant:ident),* $(,)?],
       batch: [$($BatchVariant:ident),* $(,)?],
     ) => {{
       // Print data
-      match execution {
+      match &execution {
         $(Execution::$ExecVariant(data) => println!("{}: {data:?}", "Data".dimmed()),)*
         $(Execution::$BatchVariant(data) => println!("{}: {data:?}", "Data".dimmed()),)*
         Execution::CommitSync(data) => println!("{}: {data:?}", "Data".dimmed()),
@@ -43,6 +53,10 @@ pub async fn handle(
         Execution::None(data) => println!("{}: {data:?}", "Data".dimmed()),
       }
 
+      if !yes && let Execution::RunAction(request) = &mut execution {
+        prompt_action_arguments(request).await?;
+      }
+
       $crate::command::wait_for_enter("run execution", yes)?;
 
       info!("Running Execution...");
@@ -50,7 +64,7 @@ pub async fn handle(
       let client = $crate::command::komodo_client().await?;
 
       // Execute and get result
-      match execution.clone() {
+      match execution {
         $(
           Execution::$ExecVariant(request) => client
             .execute(request)
@@ -86,6 +100,7 @@ pub async fn handle(
       CancelProcedure,
       RunBuild,
       CancelBuild,
+      CleanupBuildImages,
       Deploy,
       PullDeployment,
       StartDeployment,
@@ -118,6 +133,7 @@ pub async fn handle(
       PruneVolumes,
       PruneDockerBuilders,
       PruneBuildx,
+      PruneBuildCache,
       PruneSystem,
       RunSync,
       DeployStack,
@@ -200,6 +216,95 @@ pub async fn handle(
   }
 }
 
+/// Prompts for the Action arguments which are required,
+/// but not given and without a default.
+/// The values are sent as strings, Core converts them to the declared type.
+async fn prompt_action_arguments(
+  request: &mut RunAction,
+) -> anyhow::Result<()> {
+  let arguments = super::komodo_client()
+    .await?
+    .read(GetActionArguments {
+      action: request.action.clone(),
+    })
+    .await
+    .context("Failed to get Action arguments")?;
+  let args = request.args.get_or_insert_default();
+  for argument in arguments {
+    if !argument.required
+      || argument.default.is_some()
+      || args
+        .get(&argument.name)
+        .is_some_and(|value| !value.is_null())
+    {
+      continue;
+    }
+    let mut prompt =
+      format!("{} ({})", argument.name.bold(), argument.kind);
+    if !argument.description.is_empty() {
+      prompt.push_str(&format!(" - {}", argument.description));
+    }
+    if !argument.choices.is_empty() {
+      prompt.push_str(&format!(
+        " {}",
+        JsonValue::Array(argument.choices.clone())
+          .to_string()
+          .dimmed()
+      ));
+    }
+    print!("{prompt}: ");
+    std::io::stdout()
+      .flush()
+      .context("Failed to flush stdout")?;
+    let value = if argument.secret {
+      read_hidden_line()?
+    } else {
+      let mut line = String::new();
+      std::io::stdin()
+        .read_line(&mut line)
+        .context("Failed to read argument")?;
+      line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    args.insert(argument.name, JsonValue::String(value));
+  }
+  Ok(())
+}
+
+/// Reads a line from the terminal without echoing it.
+fn read_hidden_line() -> anyhow::Result<String> {
+  let guard = super::terminal::RawModeGuard::enable_raw_mode()?;
+  let mut line = String::new();
+  loop {
+    let Event::Key(KeyEvent {
+      code,
+      modifiers,
+      kind: KeyEventKind::Press,
+      ..
+    }) = event::read().context("Failed to read terminal input")?
+    else {
+      continue;
+    };
+    match code {
+      KeyCode::Enter => break,
+      KeyCode::Backspace => {
+        line.pop();
+      }
+      KeyCode::Char('c')
+        if modifiers.contains(KeyModifiers::CONTROL) =>
+      {
+        drop(guard);
+        println!();
+        return Err(anyhow!("Cancelled"));
+      }
+      KeyCode::Char(c) => line.push(c),
+      _ => {}
+    }
+  }
+  drop(guard);
+  println!();
+  Ok(line)
+}
+
 async fn poll_update_until_complete(
   update: &Update,
 ) -> anyhow::Result<()> {
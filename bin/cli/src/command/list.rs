@@ -6,9 +6,9 @@ use komodo_client::{
   KomodoClient,
   api::read::{
     ListActions, ListAlerters, ListBuilders, ListBuilds,
-    ListDeployments, ListProcedures, ListRepos, ListResourceSyncs,
-    ListSchedules, ListServers, ListStacks, ListSwarms, ListTags,
-    ListTerminals,
+    ListDeployments, ListProcedures, ListRegistryRepositories,
+    ListRegistryTags, ListRepos, ListResourceSyncs, ListSchedules,
+    ListServers, ListStacks, ListSwarms, ListTags, ListTerminals,
   },
   entities::{
     ResourceTargetVariant,
@@ -18,7 +18,7 @@ use komodo_client::{
     builder::{BuilderListItem, BuilderListItemInfo},
     config::cli::args::{
       self,
-      list::{ListCommand, ResourceFilters},
+      list::{ListCommand, RegistryFilters, ResourceFilters},
     },
     deployment::{
       DeploymentListItem, DeploymentListItemInfo, DeploymentState,
@@ -26,6 +26,7 @@ use komodo_client::{
     procedure::{
       ProcedureListItem, ProcedureListItemInfo, ProcedureState,
     },
+    registry::{RegistryTag, RegistryTagResource},
     repo::{RepoListItem, RepoListItemInfo, RepoState},
     resource::{ResourceListItem, ResourceQuery},
     resource_link,
@@ -101,6 +102,9 @@ pub async fn handle(list: &args::list::List) -> anyhow::Result<()> {
     Some(ListCommand::Schedules(filters)) => {
       list_schedules(filters).await
     }
+    Some(ListCommand::Registry(filters)) => {
+      list_registry(filters).await
+    }
   }
 }
 
@@ -283,6 +287,38 @@ async fn list_schedules(
   Ok(())
 }
 
+async fn list_registry(
+  filters: &RegistryFilters,
+) -> anyhow::Result<()> {
+  let client = crate::command::komodo_client().await?;
+  let Some(repository) = &filters.repository else {
+    let repositories = client
+      .read(ListRegistryRepositories {
+        domain: filters.domain.clone(),
+        account: filters.account.clone(),
+      })
+      .await?
+      .into_iter()
+      .map(|repository| RegistryRepository { repository })
+      .collect::<Vec<_>>();
+    if !repositories.is_empty() {
+      print_items(repositories, filters.format, false)?;
+    }
+    return Ok(());
+  };
+  let tags = client
+    .read(ListRegistryTags {
+      domain: filters.domain.clone(),
+      account: filters.account.clone(),
+      repository: repository.clone(),
+    })
+    .await?;
+  if !tags.is_empty() {
+    print_items(tags, filters.format, false)?;
+  }
+  Ok(())
+}
+
 fn fix_tags<T>(
   resources: &mut [ResourceListItem<T>],
   tags: &HashMap<String, String>,
@@ -1377,3 +1413,70 @@ impl PrintTable for Schedule {
     res
   }
 }
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct RegistryRepository {
+  repository: String,
+}
+
+impl PrintTable for RegistryRepository {
+  fn header(_links: bool) -> &'static [&'static str] {
+    &["Repository"]
+  }
+  fn row(self, _links: bool) -> Vec<Cell> {
+    vec![Cell::new(self.repository).add_attribute(Attribute::Bold)]
+  }
+}
+
+impl PrintTable for RegistryTag {
+  fn header(_links: bool) -> &'static [&'static str] {
+    &[
+      "Tag",
+      "Digest",
+      "Size",
+      "Created",
+      "Platforms",
+      "Builds",
+      "Deployments",
+      "Stacks",
+    ]
+  }
+  fn row(self, _links: bool) -> Vec<Cell> {
+    let digest = self
+      .digest
+      .strip_prefix("sha256:")
+      .unwrap_or(&self.digest)
+      .chars()
+      .take(12)
+      .collect::<String>();
+    let created = match self.created {
+      Some(ts) => format_timetamp(ts)
+        .unwrap_or_else(|_| String::from("Invalid created ts")),
+      None => String::from("Unknown"),
+    };
+    let names = |resources: Vec<RegistryTagResource>| {
+      resources
+        .into_iter()
+        .map(|resource| resource.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+    let in_use =
+      !self.deployments.is_empty() || !self.stacks.is_empty();
+    let tag = Cell::new(self.tag).add_attribute(Attribute::Bold);
+    vec![
+      if in_use { tag.fg(Color::Green) } else { tag },
+      Cell::new(digest),
+      Cell::new(format!(
+        "{:.1} MiB",
+        self.size as f64 / (1024.0 * 1024.0)
+      )),
+      Cell::new(created),
+      Cell::new(self.platforms.join(", ")),
+      Cell::new(names(self.builds)),
+      Cell::new(names(self.deployments)),
+      Cell::new(names(self.stacks)),
+    ]
+  }
+}
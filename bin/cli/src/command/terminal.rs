@@ -361,10 +361,10 @@ fn resize_message() -> anyhow::Result<TerminalStdinMessage> {
   }))
 }
 
-struct RawModeGuard;
+pub(super) struct RawModeGuard;
 
 impl RawModeGuard {
-  fn enable_raw_mode() -> anyhow::Result<Self> {
+  pub(super) fn enable_raw_mode() -> anyhow::Result<Self> {
     crossterm::terminal::enable_raw_mode()
       .context("Failed to enable terminal raw mode")?;
     Ok(Self)
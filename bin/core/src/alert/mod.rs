@@ -515,6 +515,18 @@ fn standard_alert_content(alert: &Alert) -> String {
       let link = resource_link(ResourceTargetVariant::Repo, id);
       format!("{level} | Repo build for {name} failed\n{link}",)
     }
+    AlertData::ImageVulnerabilities {
+      resource_type,
+      id,
+      name,
+      image,
+      counts,
+    } => {
+      let link = resource_link(*resource_type, id);
+      format!(
+        "{level} | {name} ({resource_type}) | Image has vulnerabilities\nimage: {image}\n{counts}\n{link}"
+      )
+    }
     AlertData::ProcedureFailed { id, name } => {
       let link = resource_link(ResourceTargetVariant::Procedure, id);
       format!("{level} | Procedure {name} failed\n{link}")
@@ -504,6 +504,23 @@ pub async fn send_alert(
       ];
       (text, blocks.into())
     }
+    AlertData::ImageVulnerabilities {
+      resource_type,
+      id,
+      name,
+      image,
+      counts,
+    } => {
+      let text = format!(
+        "{level} | *{name}* ({resource_type}) | Image has vulnerabilities"
+      );
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!("image: *{image}*\n{counts}")),
+        Block::section(resource_link(*resource_type, id)),
+      ];
+      (text, blocks.into())
+    }
     AlertData::ProcedureFailed { id, name } => {
       let text = format!("{level} | Procedure *{name}* has *failed*");
       let blocks = vec![
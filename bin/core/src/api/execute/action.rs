@@ -2,13 +2,17 @@ use std::{
   collections::HashSet,
   path::{Path, PathBuf},
   sync::OnceLock,
+  time::Duration,
 };
 
-use anyhow::Context as _;
+use anyhow::{Context as _, anyhow};
 use command::{CommandOptions, run_komodo_standard_command};
 use database::{
   bson::doc,
-  mungos::{by_id::update_one_by_id, mongodb::bson::to_document},
+  mungos::{
+    by_id::update_one_by_id,
+    mongodb::{bson::to_document, options::FindOneOptions},
+  },
 };
 use interpolate::Interpolator;
 use komodo_client::{
@@ -16,15 +20,16 @@ use komodo_client::{
     BatchExecutionResponse, BatchRunAction, CancelAction, RunAction,
   },
   entities::{
-    FileFormat, JsonObject, Operation,
-    action::Action,
+    FileFormat, JsonObject, JsonValue, Operation,
+    action::{Action, ActionConfig},
     alert::{Alert, AlertData, SeverityLevel},
     config::core::CoreConfig,
     komodo_timestamp,
     permission::PermissionLevel,
     random_string,
-    update::{Update, UpdateStatus},
-    user::action_user,
+    server::Server,
+    update::{Log, Update, UpdateStatus},
+    user::{User, action_user},
   },
   parsers::parse_key_value_list,
 };
@@ -36,6 +41,7 @@ use mogh_auth_server::api::manage::api_key::{
 };
 use mogh_config::merge_objects;
 use mogh_resolver::Resolve;
+use periphery_client::api::{self, action::ActionLibrary};
 use tokio::fs;
 use tokio_util::sync::CancellationToken;
 
@@ -45,11 +51,14 @@ use crate::{
   auth::KomodoAuthImpl,
   config::core_config,
   helpers::{
-    query::{VariablesAndSecrets, get_variables_and_secrets},
+    periphery_client,
+    query::{
+      VariablesAndSecrets, get_user, get_variables_and_secrets,
+    },
     update::update_update,
   },
   permission::get_check_permissions,
-  resource::refresh_action_state_cache,
+  resource::{self, library_version, refresh_action_state_cache},
   state::{action_cancel_cache, action_states, db_client},
 };
 
@@ -117,6 +126,16 @@ impl Resolve<ExecuteArgs> for RunAction {
     )
     .await?;
 
+    if action.config.library {
+      return Err(
+        anyhow!(
+          "Action '{}' is a library, and can't be run",
+          action.name
+        )
+        .into(),
+      );
+    }
+
     // get the action state for the action (or insert default).
     let action_state = action_states()
       .action
@@ -141,7 +160,7 @@ impl Resolve<ExecuteArgs> for RunAction {
     )
     .context("Failed to parse default Action arguments")?;
 
-    let args = merge_objects(
+    let mut args = merge_objects(
       default_args,
       self.args.unwrap_or_default(),
       true,
@@ -149,9 +168,91 @@ impl Resolve<ExecuteArgs> for RunAction {
     )
     .context("Failed to merge request args with default args")?;
 
+    action
+      .config
+      .validate_arguments(&mut args)
+      .context("Invalid Action arguments")?;
+
+    // Hide the secret argument values from the run output
+    let secret_args = action
+      .config
+      .argument_schema
+      .iter()
+      .filter(|argument| argument.secret)
+      .filter_map(|argument| {
+        let value = match args.get(&argument.name)? {
+          JsonValue::String(value) => value.clone(),
+          value => value.to_string(),
+        };
+        (!value.is_empty())
+          .then(|| (value, format!("ARGS.{}", argument.name)))
+      })
+      .collect::<Vec<_>>();
+
     let args = serde_json::to_string(&args)
       .context("Failed to serialize action run arguments")?;
 
+    let server = if action.config.server_id.is_empty() {
+      None
+    } else {
+      Some(resource::get::<Server>(&action.config.server_id).await?)
+    };
+
+    // Actions on Periphery reach Core at its public address.
+    let (base_url, core_host) = if server.is_some() {
+      let host = core_config().host.trim_end_matches('/');
+      let core_host = url::Url::parse(host)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .context("Failed to parse Core 'host' for remote Action")?;
+      (host.to_string(), core_host)
+    } else {
+      let CoreConfig {
+        port, ssl_enabled, ..
+      } = core_config();
+      let protocol = if *ssl_enabled { "https" } else { "http" };
+      (
+        format!("{protocol}://localhost:{port}"),
+        format!("localhost:{port}"),
+      )
+    };
+
+    let mut libraries = get_libraries(&action).await?;
+    if !libraries.is_empty() {
+      update.push_simple_log(
+        "Libraries",
+        libraries
+          .iter()
+          .map(|library| {
+            format!(
+              "@lib/{} ({})",
+              library.name,
+              library_version(&library.contents)
+            )
+          })
+          .collect::<Vec<_>>()
+          .join("\n"),
+      );
+    }
+
+    let untrusted =
+      untrusted_sources(&action, &libraries, configured_by_admin)
+        .await?;
+    let (permission_flags, ignored) = deno_permission_flags(
+      &action.config,
+      untrusted.is_empty(),
+      core_host,
+    );
+    let mut permissions_log = format!("deno run{permission_flags}");
+    if !ignored.is_empty() {
+      permissions_log.push_str(&format!(
+        "\n\nIgnored (last configured by a non-admin: {}): {}",
+        untrusted.join(", "),
+        ignored.join(", ")
+      ));
+    }
+    update.push_simple_log("Permissions", permissions_log);
+
     let CreateApiKeyResponse { key, secret } = create_api_key(
       &KomodoAuthImpl,
       action_user().id.clone(),
@@ -171,39 +272,25 @@ impl Resolve<ExecuteArgs> for RunAction {
       let contents = &mut action.config.file_contents;
 
       // Wrap the file contents in the execution context.
-      *contents = full_contents(contents, &args, &key, &secret);
+      *contents =
+        full_contents(contents, &args, &base_url, &key, &secret);
 
       let replacers = interpolate(
         contents,
+        &mut libraries,
         &mut update,
         key.clone(),
         secret.clone(),
       )
       .await?
       .into_iter()
+      .chain(secret_args)
       .collect::<Vec<_>>();
 
-      let file = format!("{}.ts", random_string(10));
-      let path = core_config().action_directory.join(&file);
-
-      mogh_secret_file::write_async(&path, contents)
-        .await
-        .with_context(|| {
-          format!("Failed to write action file to {path:?}")
-        })?;
-
-      let CoreConfig { ssl_enabled, .. } = core_config();
-
-      let https_cert_flag = if *ssl_enabled {
-        " --unsafely-ignore-certificate-errors=localhost"
-      } else {
-        ""
-      };
-
-      let reload = if action.config.reload_deno_deps {
-        " --reload"
-      } else {
-        ""
+      let sanitize = |output: &str| {
+        svi::replace_in_string(output, &replacers)
+          .replace(&key, "<ACTION_API_KEY>")
+          .replace(&secret, "<ACTION_API_SECRET>")
       };
 
       let cancel = CancellationToken::new();
@@ -212,25 +299,47 @@ impl Resolve<ExecuteArgs> for RunAction {
         .insert(update.id.clone(), cancel.clone())
         .await;
 
-      let mut res = run_komodo_standard_command(
-        // Keep this stage name as is, the UI will find the latest update log by matching the stage name
-        "Execute Action",
-        format!(
-          "deno run --allow-all{https_cert_flag}{reload} {}",
-          path.display()
-        ),
-        CommandOptions::default().cancel(cancel),
-      )
-      .await;
+      let mut res = if let Some(server) = &server {
+        let request = api::action::RunAction {
+          id: update.id.clone(),
+          contents: contents.clone(),
+          permission_flags: permission_flags.clone(),
+          reload: action.config.reload_deno_deps,
+          libraries,
+        };
+        run_on_periphery(
+          server,
+          request,
+          cancel,
+          &mut update,
+          &sanitize,
+        )
+        .await?
+      } else {
+        run_on_core(
+          contents,
+          &libraries,
+          &permission_flags,
+          action.config.reload_deno_deps,
+          cancel,
+        )
+        .await?
+      };
 
-      res.stdout = svi::replace_in_string(&res.stdout, &replacers)
-        .replace(&key, "<ACTION_API_KEY>");
-      res.stderr = svi::replace_in_string(&res.stderr, &replacers)
-        .replace(&secret, "<ACTION_API_SECRET>");
+      res.stdout = sanitize(&res.stdout);
+      res.stderr = sanitize(&res.stderr);
 
-      cleanup_run(file + ".js", &path).await;
+      let output = extract_output(&mut res.stdout);
 
       update.logs.push(res);
+
+      if let Some(output) = output {
+        update.push_simple_log(
+          OUTPUT_STAGE,
+          serde_json::to_string_pretty(&output)
+            .context("Failed to serialize Action output")?,
+        );
+      }
       update.finalize();
 
       mogh_error::Ok(())
@@ -294,9 +403,335 @@ impl Resolve<ExecuteArgs> for RunAction {
   }
 }
 
-#[instrument("Interpolate", skip(contents, update, secret))]
+async fn run_on_core(
+  contents: &str,
+  libraries: &[ActionLibrary],
+  permission_flags: &str,
+  reload: bool,
+  cancel: CancellationToken,
+) -> anyhow::Result<Log> {
+  let name = random_string(10);
+  let file = format!("{name}.ts");
+  let path = core_config().action_directory.join(&file);
+
+  mogh_secret_file::write_async(&path, contents)
+    .await
+    .with_context(|| {
+      format!("Failed to write action file to {path:?}")
+    })?;
+
+  // The libraries are written to a directory named after the run
+  let libraries_dir = core_config().action_directory.join(&name);
+  let import_map = if libraries.is_empty() {
+    String::new()
+  } else {
+    write_libraries(&libraries_dir, libraries).await?;
+    format!(
+      " --import-map={}",
+      libraries_dir.join("import_map.json").display()
+    )
+  };
+
+  let https_cert_flag = if core_config().ssl_enabled {
+    " --unsafely-ignore-certificate-errors=localhost"
+  } else {
+    ""
+  };
+
+  let reload = if reload { " --reload" } else { "" };
+
+  let res = run_komodo_standard_command(
+    // Keep this stage name as is, the UI will find the latest update log by matching the stage name
+    "Execute Action",
+    format!(
+      "deno run{permission_flags}{import_map}{https_cert_flag}{reload} {}",
+      path.display()
+    ),
+    CommandOptions::default().cancel(cancel),
+  )
+  .await;
+
+  cleanup_run(file + ".js", &path).await;
+  if !libraries.is_empty() {
+    cleanup_libraries(&libraries_dir, libraries).await;
+  }
+
+  Ok(res)
+}
+
+/// The consecutive failures polling Periphery for the Action output
+/// before giving up on the run, about one per second.
+const MAX_ACTION_POLL_FAILURES: u32 = 60;
+
+/// Runs the Action on the Server's Periphery, polling for the output
+/// to stream it into the Update until the run finishes.
+async fn run_on_periphery(
+  server: &Server,
+  request: api::action::RunAction,
+  cancel: CancellationToken,
+  update: &mut Update,
+  sanitize: impl Fn(&str) -> String,
+) -> anyhow::Result<Log> {
+  let periphery = periphery_client(server).await?;
+  let id = request.id.clone();
+  let start_ts = komodo_timestamp();
+
+  periphery
+    .request(request)
+    .await
+    .context("Failed to start Action on Periphery")?;
+
+  let mut cancelled = false;
+  let mut poll_failures = 0;
+  loop {
+    tokio::select! {
+      _ = cancel.cancelled(), if !cancelled => {
+        cancelled = true;
+        if let Err(e) = periphery
+          .request(api::action::CancelAction { id: id.clone() })
+          .await
+        {
+          warn!("Failed to cancel Action on Periphery | {e:#}");
+        }
+      }
+      _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+    }
+
+    let run = match periphery
+      .request(api::action::GetActionRun { id: id.clone() })
+      .await
+    {
+      Ok(run) => {
+        poll_failures = 0;
+        run
+      }
+      // Retry through brief disconnects, the run continues on Periphery.
+      Err(e) if poll_failures < MAX_ACTION_POLL_FAILURES => {
+        poll_failures += 1;
+        warn!(
+          "Failed to get Action output from Periphery, retrying | {e:#}"
+        );
+        continue;
+      }
+      Err(e) => {
+        // Stop the run, as the API key is deleted when giving up.
+        if let Err(e) = periphery
+          .request(api::action::CancelAction { id: id.clone() })
+          .await
+        {
+          warn!("Failed to cancel Action on Periphery | {e:#}");
+        }
+        return Err(
+          e.context("Failed to get Action output from Periphery"),
+        );
+      }
+    };
+
+    if let Some(log) = run.log {
+      return Ok(log);
+    }
+
+    // Stream the output so far to clients
+    update.logs.push(Log {
+      // Keep this stage name as is, the UI will find the latest update log by matching the stage name
+      stage: String::from("Execute Action"),
+      stdout: sanitize(&run.stdout),
+      stderr: sanitize(&run.stderr),
+      success: true,
+      start_ts,
+      ..Default::default()
+    });
+    update_update(update.clone()).await?;
+    update.logs.pop();
+  }
+}
+
+/// Gets the libraries the Action imports,
+/// including the libraries imported by those libraries.
+async fn get_libraries(
+  action: &Action,
+) -> anyhow::Result<Vec<ActionLibrary>> {
+  let mut libraries = Vec::new();
+  let mut seen = HashSet::from([action.id.clone()]);
+  let mut queue = action.config.libraries.clone();
+  while let Some(library) = queue.pop() {
+    if library.is_empty() || seen.contains(&library) {
+      continue;
+    }
+    let library =
+      resource::get::<Action>(&library).await.with_context(|| {
+        format!("Failed to get Action library '{library}'")
+      })?;
+    if !seen.insert(library.id.clone()) {
+      continue;
+    }
+    if !library.config.library {
+      return Err(anyhow!(
+        "Action '{}' is not a library",
+        library.name
+      ));
+    }
+    queue.extend(library.config.libraries);
+    libraries.push(ActionLibrary {
+      id: library.id,
+      name: library.name,
+      contents: library.config.file_contents,
+    });
+  }
+  Ok(libraries)
+}
+
+/// Writes the library files and their import map to the directory.
+async fn write_libraries(
+  dir: &Path,
+  libraries: &[ActionLibrary],
+) -> anyhow::Result<()> {
+  fs::create_dir_all(dir).await.with_context(|| {
+    format!("Failed to create action libraries directory at {dir:?}")
+  })?;
+  for library in libraries {
+    let path = dir.join(library.file_name());
+    mogh_secret_file::write_async(&path, &library.contents)
+      .await
+      .with_context(|| {
+        format!(
+          "Failed to write action library {} to {path:?}",
+          library.name
+        )
+      })?;
+  }
+  let path = dir.join("import_map.json");
+  fs::write(&path, ActionLibrary::import_map(libraries))
+    .await
+    .with_context(|| {
+      format!("Failed to write action import map to {path:?}")
+    })
+}
+
+/// The Action and imported libraries last configured by a non-admin.
+/// The libraries run with the Action permissions,
+/// so they must also be trusted for the extra permissions.
+async fn untrusted_sources<F, Fut>(
+  action: &Action,
+  libraries: &[ActionLibrary],
+  configured_by_admin: F,
+) -> anyhow::Result<Vec<String>>
+where
+  F: Fn(String) -> Fut,
+  Fut: Future<Output = anyhow::Result<bool>>,
+{
+  let mut untrusted = Vec::new();
+  if !configured_by_admin(action.id.clone()).await? {
+    untrusted.push(action.name.clone());
+  }
+  for library in libraries {
+    if !configured_by_admin(library.id.clone()).await? {
+      untrusted.push(format!("@lib/{}", library.name));
+    }
+  }
+  Ok(untrusted)
+}
+
+/// Whether the latest change to the Action (or library) config was made
+/// by an admin. Actions can only be created by admins, so if no config
+/// change is found, the Action is considered configured by an admin.
+async fn configured_by_admin(
+  action_id: String,
+) -> anyhow::Result<bool> {
+  let update = db_client()
+    .updates
+    .find_one(doc! {
+      "target.type": "Action",
+      "target.id": &action_id,
+      "operation": { "$in": ["CreateAction", "UpdateAction"] },
+    })
+    .with_options(
+      FindOneOptions::builder()
+        .sort(doc! { "start_ts": -1 })
+        .build(),
+    )
+    .await
+    .context("Failed to query db for latest Action config update")?;
+  let Some(update) = update else {
+    return Ok(true);
+  };
+  Ok(operator_is_admin(&update.operator).await)
+}
+
+/// Service users are not trusted, as they act on behalf of
+/// whoever triggered them. For example an Action can update its own
+/// config through the `action_user` API key. Deleted users are not trusted.
+async fn operator_is_admin(operator: &str) -> bool {
+  if User::is_service_user(operator) {
+    return false;
+  }
+  get_user(operator)
+    .await
+    .map(|user| user.admin)
+    .unwrap_or_default()
+}
+
+/// Maps the declared permissions to Deno's granular `--allow-*` flags.
+/// Actions last configured by a non-admin, or importing a library last
+/// configured by a non-admin, may only declare network hosts, so they
+/// cannot read Core's config, keys or environment.
+/// Also returns the declared permissions which were ignored.
+fn deno_permission_flags(
+  config: &ActionConfig,
+  admin_configured: bool,
+  core_host: String,
+) -> (String, Vec<&'static str>) {
+  if admin_configured && config.allow_all {
+    return (String::from(" --allow-all"), Vec::new());
+  }
+
+  let mut ignored = Vec::new();
+  if config.allow_all {
+    ignored.push("allow_all");
+  }
+
+  // The Action always needs to reach Core using the komodo client.
+  let mut net = vec![core_host];
+  net.extend(config.allow_net.iter().cloned());
+  let mut flags = permission_flag("net", &net);
+
+  for (name, field, list) in [
+    ("read", "allow_read", &config.allow_read),
+    ("write", "allow_write", &config.allow_write),
+    ("env", "allow_env", &config.allow_env),
+    ("run", "allow_run", &config.allow_run),
+  ] {
+    if list.is_empty() {
+      continue;
+    }
+    if admin_configured {
+      flags.push_str(&permission_flag(name, list));
+    } else {
+      ignored.push(field);
+    }
+  }
+
+  // Fail on missing permissions rather than waiting for input.
+  flags.push_str(" --no-prompt");
+
+  (flags, ignored)
+}
+
+fn permission_flag(name: &str, list: &[String]) -> String {
+  if list.iter().any(|item| item == "*") {
+    format!(" --allow-{name}")
+  } else {
+    format!(" --allow-{name}={}", list.join(","))
+  }
+}
+
+#[instrument(
+  "Interpolate",
+  skip(contents, libraries, update, secret)
+)]
 async fn interpolate(
   contents: &mut String,
+  libraries: &mut [ActionLibrary],
   update: &mut Update,
   key: String,
   secret: String,
@@ -312,25 +747,62 @@ async fn interpolate(
   let mut interpolator =
     Interpolator::new(Some(&variables), &secrets);
 
-  interpolator
-    .interpolate_string(contents)?
-    .push_logs(&mut update.logs);
+  interpolator.interpolate_string(contents)?;
+  for library in libraries {
+    interpolator.interpolate_string(&mut library.contents)?;
+  }
+  interpolator.push_logs(&mut update.logs);
 
   Ok(interpolator.secret_replacers)
 }
 
+/// Prefixes the stdout line written by `setOutput`.
+const OUTPUT_MARKER: &str = "__KOMODO_ACTION_OUTPUT__";
+
+/// The stage of the `RunAction` log holding the output.
+const OUTPUT_STAGE: &str = "Output";
+
+/// The JSON output of a `RunAction` Update, kept in its
+/// "Output" log rather than a field, so large outputs
+/// don't grow every Update.
+pub fn action_run_output(update: &Update) -> Option<JsonValue> {
+  update
+    .logs
+    .iter()
+    .rev()
+    .find(|log| log.stage == OUTPUT_STAGE)
+    .and_then(|log| serde_json::from_str(&log.stdout).ok())
+}
+
+/// Removes the lines written by `setOutput` from the stdout,
+/// returning the last output set. If the Action called
+/// `setOutput(undefined)`, the output is cleared.
+fn extract_output(stdout: &mut String) -> Option<JsonValue> {
+  let mut output = None;
+  let mut found = false;
+  let mut rest = Vec::new();
+  for line in stdout.lines() {
+    if let Some(json) = line.strip_prefix(OUTPUT_MARKER) {
+      found = true;
+      output = serde_json::from_str(json).ok();
+    } else {
+      rest.push(line);
+    }
+  }
+  if found {
+    *stdout = rest.join("\n");
+  }
+  output
+}
+
 fn full_contents(
   contents: &str,
   // Pre-serialized to JSON string.
   args: &str,
+  base_url: &str,
   key: &str,
   secret: &str,
 ) -> String {
-  let CoreConfig {
-    port, ssl_enabled, ..
-  } = core_config();
-  let protocol = if *ssl_enabled { "https" } else { "http" };
-  let base_url = format!("{protocol}://localhost:{port}");
   format!(
     "import {{ KomodoClient, Types }} from '{base_url}/client/lib.js';
 import * as __YAML__ from 'jsr:@std/yaml';
@@ -352,6 +824,11 @@ const TOML = {{
 
 const ARGS = {args};
 
+/** Sets the JSON output of this run, available to later Procedure stages. */
+function setOutput(output: unknown) {{
+  console.log('{OUTPUT_MARKER}' + JSON.stringify(output));
+}}
+
 const komodo = KomodoClient('{base_url}', {{
   type: 'api-key',
   params: {{ key: '{key}', secret: '{secret}' }}
@@ -395,6 +872,27 @@ async fn cleanup_run(file: String, path: &Path) {
   delete_file(deno_dir.join("gen/file"), file).await;
 }
 
+/// Cleans up the libraries directory,
+/// and their generated files if $DENO_DIR is set.
+#[instrument("CleanupLibraries", skip(libraries))]
+async fn cleanup_libraries(dir: &Path, libraries: &[ActionLibrary]) {
+  if let Err(e) = fs::remove_dir_all(dir).await {
+    warn!(
+      "Failed to delete action libraries after action execution | {e:#}"
+    );
+  }
+  let Some(deno_dir) = deno_dir() else {
+    return;
+  };
+  for library in libraries {
+    delete_file(
+      deno_dir.join("gen/file"),
+      library.file_name() + ".js",
+    )
+    .await;
+  }
+}
+
 fn deno_dir() -> Option<&'static Path> {
   static DENO_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
   DENO_DIR
@@ -542,3 +1040,103 @@ impl Resolve<ExecuteArgs> for CancelAction {
     Ok(update)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use komodo_client::entities::user::{procedure_user, sync_user};
+
+  use super::*;
+
+  fn action(name: &str, config: ActionConfig) -> Action {
+    Action {
+      id: format!("{name}-id"),
+      name: name.to_string(),
+      description: Default::default(),
+      template: Default::default(),
+      tags: Default::default(),
+      info: Default::default(),
+      config,
+      base_permission: Default::default(),
+      updated_at: Default::default(),
+    }
+  }
+
+  fn library(name: &str) -> ActionLibrary {
+    ActionLibrary {
+      id: format!("{name}-id"),
+      name: name.to_string(),
+      contents: Default::default(),
+    }
+  }
+
+  #[tokio::test]
+  async fn library_edited_by_non_admin_drops_permissions() {
+    let action = action(
+      "trusted",
+      ActionConfig {
+        allow_all: true,
+        allow_read: vec![String::from("/etc/komodo")],
+        allow_env: vec![String::from("*")],
+        ..Default::default()
+      },
+    );
+    let libraries = [library("admin-lib"), library("edited-lib")];
+
+    // Only the library was last updated by a non-admin.
+    let untrusted =
+      untrusted_sources(&action, &libraries, |id| async move {
+        Ok(id != "edited-lib-id")
+      })
+      .await
+      .unwrap();
+    assert_eq!(untrusted, [String::from("@lib/edited-lib")]);
+
+    let (flags, ignored) = deno_permission_flags(
+      &action.config,
+      untrusted.is_empty(),
+      String::from("localhost:9120"),
+    );
+    assert_eq!(flags, " --allow-net=localhost:9120 --no-prompt");
+    assert_eq!(ignored, ["allow_all", "allow_read", "allow_env"]);
+  }
+
+  #[tokio::test]
+  async fn trusted_action_and_libraries_keep_permissions() {
+    let action = action(
+      "trusted",
+      ActionConfig {
+        allow_read: vec![String::from("/etc/komodo")],
+        ..Default::default()
+      },
+    );
+    let untrusted = untrusted_sources(
+      &action,
+      &[library("admin-lib")],
+      |_| async { Ok(true) },
+    )
+    .await
+    .unwrap();
+    assert!(untrusted.is_empty());
+
+    let (flags, ignored) = deno_permission_flags(
+      &action.config,
+      untrusted.is_empty(),
+      String::from("localhost:9120"),
+    );
+    assert_eq!(
+      flags,
+      " --allow-net=localhost:9120 --allow-read=/etc/komodo --no-prompt"
+    );
+    assert!(ignored.is_empty());
+  }
+
+  #[tokio::test]
+  async fn update_by_service_user_is_not_admin_configured() {
+    // An Action can call UpdateAction on itself using its
+    // action_user API key, which must not grant it extra permissions.
+    assert!(!operator_is_admin(&action_user().id).await);
+    assert!(!operator_is_admin(&action_user().username).await);
+    assert!(!operator_is_admin(&procedure_user().id).await);
+    assert!(!operator_is_admin(&sync_user().id).await);
+  }
+}
@@ -5,13 +5,16 @@ use std::{
 };
 
 use anyhow::{Context, anyhow};
-use database::mungos::{
-  by_id::update_one_by_id,
-  find::find_collect,
-  mongodb::{
-    bson::{doc, to_bson, to_document},
-    options::FindOneOptions,
+use database::{
+  mungos::{
+    by_id::update_one_by_id,
+    find::find_collect,
+    mongodb::{
+      bson::{doc, to_bson, to_document},
+      options::FindOneOptions,
+    },
   },
+  utils::gzip,
 };
 use formatting::format_serror;
 use futures_util::future::join_all;
@@ -19,12 +22,13 @@ use interpolate::Interpolator;
 use komodo_client::{
   api::{
     execute::{
-      BatchExecutionResponse, BatchRunBuild, CancelBuild, Deploy,
-      RunBuild,
+      BatchExecutionResponse, BatchRunBuild, CancelBuild,
+      CleanupBuildImages, Deploy, RunBuild,
     },
     write::RefreshBuildCache,
   },
   entities::{
+    RepoExecutionArgs,
     alert::{Alert, AlertData, SeverityLevel},
     all_logs_success,
     build::{Build, BuildConfig},
@@ -33,6 +37,7 @@ use komodo_client::{
     komodo_timestamp, optional_string,
     permission::PermissionLevel,
     repo::Repo,
+    sbom::{BuildSbom, SbomReport},
     update::{Log, Update},
     user::auto_redeploy_user,
   },
@@ -54,9 +59,12 @@ use crate::{
       get_variables_and_secrets,
     },
     registry_token,
+    scan::record_image_scan,
     update::{init_execution_update, update_update},
   },
+  periphery::PeripheryClient,
   permission::get_check_permissions,
+  registry::cleanup::cleanup_build_images,
   resource::{self, refresh_build_state_cache},
   state::{action_states, db_client},
 };
@@ -66,7 +74,11 @@ use super::{ExecuteArgs, ExecuteRequest};
 impl super::BatchExecute for BatchRunBuild {
   type Resource = Build;
   fn single_request(build: String) -> ExecuteRequest {
-    ExecuteRequest::RunBuild(RunBuild { build })
+    ExecuteRequest::RunBuild(RunBuild {
+      build,
+      commit: None,
+      version: None,
+    })
   }
 }
 
@@ -122,6 +134,16 @@ impl Resolve<ExecuteArgs> for RunBuild {
     )
     .await?;
 
+    // Per run overrides, eg. for the tag webhook trigger.
+    // These are not stored in the config.
+    if let Some(commit) = self.commit {
+      build.config.commit = commit;
+    }
+    let version_override = self.version.is_some();
+    if let Some(version) = self.version {
+      build.config.version = version;
+    }
+
     let mut repo = if !build.config.files_on_host
       && !build.config.linked_repo.is_empty()
     {
@@ -156,7 +178,7 @@ impl Resolve<ExecuteArgs> for RunBuild {
     let action_guard =
       action_state.update(|state| state.building = true)?;
 
-    if build.config.auto_increment_version {
+    if build.config.auto_increment_version && !version_override {
       build.config.version.increment();
     }
 
@@ -260,7 +282,7 @@ impl Resolve<ExecuteArgs> for RunBuild {
         res = periphery
           .request(api::git::PullOrCloneRepo {
             args: repo.as_ref().map(Into::into).unwrap_or((&build).into()),
-            git_token,
+            git_token: git_token.clone(),
             environment: Default::default(),
             env_file_path: Default::default(),
             on_clone: None,
@@ -303,19 +325,76 @@ impl Resolve<ExecuteArgs> for RunBuild {
       None
     };
 
-    if all_logs_success(&update.logs) {
+    let replacers = secret_replacers.into_iter().collect::<Vec<_>>();
+    let platform_groups = build.get_platform_builder_groups();
+    let fanned_out = !platform_groups.is_empty();
+
+    if all_logs_success(&update.logs)
+      && (!platform_groups.is_empty()
+        || !build.config.matrix.is_empty())
+    {
+      let args = ParallelBuildArgs {
+        build: &build,
+        repo: repo.as_ref(),
+        git_token,
+        registry_tokens: registry_tokens.clone(),
+        replacers,
+        commit_hash: optional_string(&update.commit_hash),
+        secrets: &secrets,
+        cancel: &cancel,
+      };
+      if build.config.matrix.is_empty() {
+        // RUN BUILD ACROSS PLATFORM BUILDERS
+        run_platform_builds(
+          &periphery,
+          &args,
+          platform_groups,
+          &mut update,
+        )
+        .await;
+      } else if platform_groups.is_empty() {
+        // RUN MATRIX BUILDS
+        run_matrix_builds(&periphery, &args, &mut update).await;
+      } else {
+        update.push_error_log(
+          "Build Matrix",
+          String::from(
+            "The build matrix can't be combined with platform builders",
+          ),
+        );
+      }
+      if cancel.is_cancelled() {
+        update.push_error_log(
+          "Build Cancelled",
+          String::from(
+            "User cancelled build during image build step",
+          ),
+        );
+        cleanup_builder_instance(
+          periphery,
+          cleanup_data,
+          &mut update,
+        )
+        .await;
+        return handle_early_return(
+          update, build.id, build.name, true,
+        )
+        .await;
+      }
+    } else if all_logs_success(&update.logs) {
       // RUN BUILD
       let res = tokio::select! {
         res = periphery
           .request(api::build::Build {
             build: build.clone(),
             repo,
-            registry_tokens,
-            replacers: secret_replacers.into_iter().collect(),
+            registry_tokens: registry_tokens.clone(),
+            replacers,
             // To push a commit hash tagged image
             commit_hash: optional_string(&update.commit_hash),
             // Unused for now
             additional_tags: Default::default(),
+            skip_push: push_after_scan(&build),
           }) => res.context("Failed at call to Periphery to build"),
         _ = cancel.cancelled() => {
           info!("Build cancelled during build, cleaning up builder");
@@ -348,22 +427,41 @@ impl Resolve<ExecuteArgs> for RunBuild {
       };
     }
 
+    // Matrix variants already ran these on their builders
+    if build.config.matrix.is_empty() {
+      run_post_build_steps(
+        &periphery,
+        &build,
+        &registry_tokens,
+        &secrets,
+        fanned_out,
+        &mut update,
+      )
+      .await;
+    }
+
     update.finalize();
 
     let db = db_client();
 
     if update.success {
+      let mut set = doc! {
+        "info.last_built_at": komodo_timestamp(),
+        "info.built_hash": &update.commit_hash,
+        "info.built_message": commit_message
+      };
+      if !version_override {
+        set.insert(
+          "config.version",
+          to_bson(&build.config.version)
+            .context("failed at converting version to bson")?,
+        );
+      }
       let _ = db
         .builds
         .update_one(
           doc! { "name": &build.name },
-          doc! { "$set": {
-            "config.version": to_bson(&build.config.version)
-              .context("failed at converting version to bson")?,
-            "info.last_built_at": komodo_timestamp(),
-            "info.built_hash": &update.commit_hash,
-            "info.built_message": commit_message
-          }},
+          doc! { "$set": set },
         )
         .await;
     }
@@ -436,6 +534,673 @@ impl Resolve<ExecuteArgs> for RunBuild {
   }
 }
 
+/// Shared across builds running in parallel,
+/// for platform builders and the build matrix.
+struct ParallelBuildArgs<'a> {
+  build: &'a Build,
+  repo: Option<&'a Repo>,
+  git_token: Option<String>,
+  registry_tokens: Vec<(String, String, String)>,
+  replacers: Vec<(String, String)>,
+  commit_hash: Option<String>,
+  secrets: &'a HashMap<String, String>,
+  cancel: &'a CancellationToken,
+}
+
+/// Fans the build out across the `platform_builders` in parallel,
+/// then combines the platform specific images into
+/// a manifest list using the main builder.
+async fn run_platform_builds(
+  periphery: &PeripheryClient,
+  args: &ParallelBuildArgs<'_>,
+  platform_groups: Vec<(String, Vec<String>)>,
+  update: &mut Update,
+) {
+  if args.build.config.image_registry.is_empty() {
+    update.push_error_log(
+      "Platform Builders",
+      String::from(
+        "Build must push to an image registry to use platform builders",
+      ),
+    );
+    return;
+  }
+
+  let results =
+    join_all(platform_groups.iter().map(
+      |(builder_id, platforms)| {
+        let mut build = platform_build(args.build, platforms);
+        build.config.builder_id.clone_from(builder_id);
+        let builds = [(platforms.join(", "), build)];
+        async move {
+          build_on_builder(periphery, args, &builds, None).await
+        }
+      },
+    ))
+    .await;
+
+  for logs in results {
+    update.logs.extend(logs);
+  }
+
+  let _ = update_update(update.clone()).await;
+
+  if args.cancel.is_cancelled() || !all_logs_success(&update.logs) {
+    return;
+  }
+
+  // Scan before the image is pushed under the build tags
+  if args.build.config.scan_image
+    && let Some((_, platforms)) = platform_groups.first()
+  {
+    scan_built_image(
+      periphery,
+      &platform_build(args.build, platforms),
+      update,
+    )
+    .await;
+    if !all_logs_success(&update.logs) {
+      return;
+    }
+  }
+
+  match periphery
+    .request(api::build::PushManifestList {
+      build: args.build.clone(),
+      registry_tokens: args.registry_tokens.clone(),
+      commit_hash: args.commit_hash.clone(),
+      platform_groups: platform_groups
+        .into_iter()
+        .map(|(_, platforms)| platforms)
+        .collect(),
+    })
+    .await
+  {
+    Ok(logs) => update.logs.extend(logs),
+    Err(e) => update.push_error_log(
+      "Push Manifest List",
+      format_serror(
+        &e.context("Failed to push manifest list").into(),
+      ),
+    ),
+  }
+}
+
+/// Builds the variants of the build `matrix`, in parallel
+/// across Builders. Variants on the same Builder share
+/// its build directory, so are built one after another.
+async fn run_matrix_builds(
+  periphery: &PeripheryClient,
+  args: &ParallelBuildArgs<'_>,
+  update: &mut Update,
+) {
+  let mut groups = Vec::<(String, Vec<(String, Build)>)>::new();
+  for entry in &args.build.config.matrix {
+    let build = args.build.get_matrix_build(entry);
+    let variant = (entry.image_tag.clone(), build);
+    match groups
+      .iter_mut()
+      .find(|(id, _)| id == &variant.1.config.builder_id)
+    {
+      Some((_, variants)) => variants.push(variant),
+      None => groups
+        .push((variant.1.config.builder_id.clone(), vec![variant])),
+    }
+  }
+
+  // The image steps run for each variant on its own builder,
+  // where the variant image is available.
+  let post_build = Update {
+    logs: Vec::new(),
+    ..update.clone()
+  };
+
+  let results = join_all(groups.iter().map(|(_, variants)| {
+    build_on_builder(periphery, args, variants, Some(&post_build))
+  }))
+  .await;
+
+  for logs in results {
+    update.logs.extend(logs);
+  }
+}
+
+/// Labels the logs with the variant of the build they belong to.
+fn push_labelled_logs(
+  logs: &mut Vec<Log>,
+  mut labelled: Vec<Log>,
+  label: &str,
+) {
+  for log in &mut labelled {
+    log.stage = format!("{} ({label})", log.stage);
+  }
+  logs.extend(labelled);
+}
+
+/// Runs the labelled builds one after another on their shared
+/// `builder_id`, connecting to the builder unless it is the
+/// main builder, which has already cloned the repo.
+/// If `post_build` is given, also runs the configured image
+/// steps for each build on the builder.
+async fn build_on_builder(
+  main: &PeripheryClient,
+  args: &ParallelBuildArgs<'_>,
+  builds: &[(String, Build)],
+  post_build: Option<&Update>,
+) -> Vec<Log> {
+  let Some((_, first)) = builds.first() else {
+    return Vec::new();
+  };
+  let label = builds
+    .iter()
+    .map(|(label, _)| label.as_str())
+    .collect::<Vec<_>>()
+    .join(", ");
+  let mut logs = Vec::new();
+
+  if first.config.builder_id == args.build.config.builder_id {
+    run_builder_builds(
+      main, args, builds, false, post_build, &mut logs,
+    )
+    .await;
+    return logs;
+  }
+
+  let builder =
+    match resource::get::<Builder>(&first.config.builder_id).await {
+      Ok(builder) => builder,
+      Err(e) => {
+        push_labelled_logs(
+          &mut logs,
+          vec![Log::error(
+            "Get Builder",
+            format_serror(&e.context("Failed to get Builder").into()),
+          )],
+          &label,
+        );
+        return logs;
+      }
+    };
+  let (periphery, cleanup_data) = match connect_builder_periphery(
+    first.name.clone(),
+    Some(first.config.version),
+    builder,
+    None,
+  )
+  .await
+  {
+    Ok(builder) => builder,
+    Err(e) => {
+      push_labelled_logs(
+        &mut logs,
+        vec![Log::error(
+          "Get Builder",
+          format_serror(&e.context("Failed to get Builder").into()),
+        )],
+        &label,
+      );
+      return logs;
+    }
+  };
+
+  let clone = !first.config.files_on_host
+    && (!first.config.repo.is_empty()
+      || !first.config.linked_repo.is_empty());
+  run_builder_builds(
+    &periphery, args, builds, clone, post_build, &mut logs,
+  )
+  .await;
+
+  let mut cleanup_update = Update::default();
+  cleanup_builder_instance(
+    periphery,
+    cleanup_data,
+    &mut cleanup_update,
+  )
+  .await;
+  push_labelled_logs(&mut logs, cleanup_update.logs, &label);
+
+  logs
+}
+
+async fn run_builder_builds(
+  periphery: &PeripheryClient,
+  args: &ParallelBuildArgs<'_>,
+  builds: &[(String, Build)],
+  clone: bool,
+  post_build: Option<&Update>,
+  logs: &mut Vec<Log>,
+) {
+  if clone {
+    let label = builds
+      .iter()
+      .map(|(label, _)| label.as_str())
+      .collect::<Vec<_>>()
+      .join(", ");
+    let mut clone_args: RepoExecutionArgs =
+      args.repo.map(Into::into).unwrap_or(args.build.into());
+    // Build the same commit as the main builder
+    if args.commit_hash.is_some() {
+      clone_args.commit.clone_from(&args.commit_hash);
+    }
+    let res = tokio::select! {
+      res = periphery
+        .request(api::git::PullOrCloneRepo {
+          args: clone_args,
+          git_token: args.git_token.clone(),
+          environment: Default::default(),
+          env_file_path: Default::default(),
+          on_clone: None,
+          on_pull: None,
+          skip_secret_interp: Default::default(),
+          replacers: Default::default(),
+        }) => res,
+      _ = args.cancel.cancelled() => return,
+    };
+    let clone_logs = match res {
+      Ok(res) => res.res.logs,
+      Err(e) => vec![Log::error(
+        "Clone Repo",
+        format_serror(&e.context("Failed to clone repo").into()),
+      )],
+    };
+    let success = all_logs_success(&clone_logs);
+    push_labelled_logs(logs, clone_logs, &label);
+    if !success {
+      return;
+    }
+  }
+
+  for (label, build) in builds {
+    if args.cancel.is_cancelled() {
+      return;
+    }
+    let skip_push = post_build.is_some() && push_after_scan(build);
+    let build_logs =
+      run_builder_build(periphery, args, build, skip_push).await;
+    let build_logs = match post_build {
+      Some(update) if all_logs_success(&build_logs) => {
+        let mut update = Update {
+          logs: build_logs,
+          ..update.clone()
+        };
+        run_post_build_steps(
+          periphery,
+          build,
+          &args.registry_tokens,
+          args.secrets,
+          false,
+          &mut update,
+        )
+        .await;
+        update.logs
+      }
+      _ => build_logs,
+    };
+    let success = all_logs_success(&build_logs);
+    push_labelled_logs(logs, build_logs, label);
+    if !success {
+      return;
+    }
+  }
+}
+
+async fn run_builder_build(
+  periphery: &PeripheryClient,
+  args: &ParallelBuildArgs<'_>,
+  build: &Build,
+  skip_push: bool,
+) -> Vec<Log> {
+  let mut logs = Vec::new();
+
+  let res = tokio::select! {
+    res = periphery
+      .request(api::build::Build {
+        build: build.clone(),
+        repo: args.repo.cloned(),
+        registry_tokens: args.registry_tokens.clone(),
+        replacers: args.replacers.clone(),
+        commit_hash: args.commit_hash.clone(),
+        additional_tags: Default::default(),
+        skip_push,
+      }) => res.context("Failed at call to Periphery to build"),
+    _ = args.cancel.cancelled() => {
+      if let Err(e) = periphery.request(api::build::CancelBuild {
+        id: build.id.clone()
+      })
+      .await
+      .context("Failed to cancel build execution on Server") {
+        logs.push(Log::error("Cancel Build", format_serror(&e.into())));
+      }
+      return logs;
+    },
+  };
+
+  match res {
+    Ok(build_logs) => logs.extend(build_logs),
+    Err(e) => logs.push(Log::error(
+      "Build Error",
+      format_serror(&e.context("Failed to build").into()),
+    )),
+  }
+
+  logs
+}
+
+/// Runs the configured image scan, the push held back for it,
+/// signing and SBOM generation for the built image,
+/// stopping at the first failure.
+/// Images fanned out across platform builders are scanned
+/// before the manifest list is pushed instead.
+async fn run_post_build_steps(
+  periphery: &PeripheryClient,
+  build: &Build,
+  registry_tokens: &[(String, String, String)],
+  secrets: &HashMap<String, String>,
+  fanned_out: bool,
+  update: &mut Update,
+) {
+  if !fanned_out
+    && build.config.scan_image
+    && all_logs_success(&update.logs)
+  {
+    scan_built_image(periphery, build, update).await;
+  }
+
+  if !fanned_out
+    && push_after_scan(build)
+    && all_logs_success(&update.logs)
+  {
+    push_built_image(periphery, build, registry_tokens, update).await;
+  }
+
+  if !build.config.signing_key.is_empty()
+    && all_logs_success(&update.logs)
+  {
+    sign_built_images(periphery, build, secrets, update).await;
+  }
+
+  if !build.config.sbom_format.is_none()
+    && all_logs_success(&update.logs)
+  {
+    generate_build_sbom(periphery, build, update).await;
+  }
+}
+
+/// Whether the image is only pushed once the scan passes the
+/// vulnerability threshold. Multi-platform images can't be
+/// loaded on the builder to scan, so are scanned after push.
+fn push_after_scan(build: &Build) -> bool {
+  build.config.scan_image
+    && build.config.scan_threshold.severity().is_some()
+    && build.config.platforms.len() <= 1
+    && !build.config.image_registry.is_empty()
+}
+
+/// Pushes the image built with `skip_push` to the registries.
+async fn push_built_image(
+  periphery: &PeripheryClient,
+  build: &Build,
+  registry_tokens: &[(String, String, String)],
+  update: &mut Update,
+) {
+  match periphery
+    .request(api::build::PushImage {
+      build: build.clone(),
+      registry_tokens: registry_tokens.to_vec(),
+      commit_hash: optional_string(&update.commit_hash),
+    })
+    .await
+  {
+    Ok(logs) => update.logs.extend(logs),
+    Err(e) => update.push_error_log(
+      "Push Image",
+      format_serror(&e.context("Failed to push image").into()),
+    ),
+  }
+}
+
+/// The build adjusted to build only the given platforms,
+/// pushing only the platform specific image tag.
+fn platform_build(build: &Build, platforms: &[String]) -> Build {
+  let mut platform_build = build.clone();
+  let config = &mut platform_build.config;
+  // Keep the registry build caches separate per platform group
+  if config.cache_ref.is_empty()
+    && let Some(cache_ref) = build.get_registry_cache_ref()
+  {
+    config.cache_ref = format!(
+      "{cache_ref}-{}",
+      platforms.join("-").replace(['/', ':'], "-")
+    );
+  }
+  config.image_tag = build.get_platform_image_tag(platforms);
+  config.include_latest_tag = false;
+  config.include_version_tags = false;
+  config.include_commit_tag = false;
+  config.platforms = platforms.to_vec();
+  platform_build
+}
+
+/// Scans the built image on the builder, failing the build
+/// if the findings exceed the configured threshold.
+async fn scan_built_image(
+  periphery: &PeripheryClient,
+  build: &Build,
+  update: &mut Update,
+) {
+  let Some(image) = built_image(build, update) else {
+    update.push_error_log(
+      "Image Scan",
+      String::from("Build has no image tags to scan"),
+    );
+    return;
+  };
+  let res = match periphery
+    .request(api::docker::ScanImage {
+      name: image.clone(),
+    })
+    .await
+  {
+    Ok(res) => res,
+    Err(e) => {
+      update.push_error_log(
+        "Image Scan",
+        format_serror(&e.context("Failed to scan image").into()),
+      );
+      return;
+    }
+  };
+  update.logs.push(res.log);
+  let Some(report) = res.report else {
+    return;
+  };
+  let scan =
+    match record_image_scan(update.target.clone(), image, report)
+      .await
+    {
+      Ok(scan) => scan,
+      Err(e) => {
+        update.push_error_log(
+          "Record Image Scan",
+          format_serror(&e.into()),
+        );
+        return;
+      }
+    };
+  let exceeded =
+    build.config.scan_threshold.exceeded_by(&scan.counts);
+  if exceeded > 0 {
+    update.push_error_log(
+      "Vulnerability Threshold",
+      format!(
+        "Found {exceeded} vulnerabilities at or above the {} threshold\n{}",
+        build.config.scan_threshold, scan.counts
+      ),
+    );
+  }
+}
+
+/// Signs the image pushed to each registry on the builder.
+/// The signature covers the image digest, so applies to all the tags.
+async fn sign_built_images(
+  periphery: &PeripheryClient,
+  build: &Build,
+  secrets: &HashMap<String, String>,
+  update: &mut Update,
+) {
+  if build.config.image_registry.is_empty() {
+    update.push_error_log(
+      "Sign Image",
+      String::from(
+        "Build must push to an image registry to sign the image",
+      ),
+    );
+    return;
+  }
+  let commit_hash = optional_string(&update.commit_hash);
+  for image_name in build.get_image_names() {
+    let Some(image) = build
+      .get_image_tags(&[image_name], commit_hash.as_deref(), &[])
+      .into_iter()
+      .next()
+    else {
+      continue;
+    };
+    let res = periphery
+      .request(api::docker::SignImage {
+        image,
+        key_name: build.config.signing_key.clone(),
+        key: secrets.get(&build.config.signing_key).cloned(),
+        password_name: build.config.signing_key_password.clone(),
+        password: secrets
+          .get(&build.config.signing_key_password)
+          .cloned(),
+      })
+      .await;
+    match res {
+      Ok(log) => update.logs.push(log),
+      Err(e) => update.push_error_log(
+        "Sign Image",
+        format_serror(&e.context("Failed to sign image").into()),
+      ),
+    }
+    if !all_logs_success(&update.logs) {
+      return;
+    }
+  }
+}
+
+/// Generates an SBOM for the built image on the builder,
+/// and stores it alongside the build version.
+async fn generate_build_sbom(
+  periphery: &PeripheryClient,
+  build: &Build,
+  update: &mut Update,
+) {
+  let Some(image) = built_image(build, update) else {
+    update.push_error_log(
+      "Generate SBOM",
+      String::from("Build has no image tags to generate SBOM for"),
+    );
+    return;
+  };
+  let res = match periphery
+    .request(api::build::GenerateSbom {
+      image: image.clone(),
+      format: build.config.sbom_format,
+    })
+    .await
+  {
+    Ok(res) => res,
+    Err(e) => {
+      update.push_error_log(
+        "Generate SBOM",
+        format_serror(&e.context("Failed to generate SBOM").into()),
+      );
+      return;
+    }
+  };
+  update.logs.push(res.log);
+  let Some(SbomReport {
+    format,
+    digest,
+    packages,
+    document,
+  }) = res.sbom
+  else {
+    return;
+  };
+  // Store the document compressed, to keep large
+  // SBOMs within the database document size limit.
+  let document_gzip = match gzip(document.as_bytes()).await {
+    Ok(document) if document.len() <= MAX_SBOM_DOCUMENT_SIZE => {
+      document
+    }
+    Ok(document) => {
+      update.push_simple_log(
+        "Record SBOM",
+        format!(
+          "The compressed SBOM document is {} bytes, over the {MAX_SBOM_DOCUMENT_SIZE} byte limit. Only the package list is stored.",
+          document.len()
+        ),
+      );
+      Vec::new()
+    }
+    Err(e) => {
+      update.push_simple_log(
+        "Record SBOM",
+        format_serror(
+          &e.context("Failed to compress SBOM document").into(),
+        ),
+      );
+      Vec::new()
+    }
+  };
+  let sbom = BuildSbom {
+    id: Default::default(),
+    ts: komodo_timestamp(),
+    build_id: build.id.clone(),
+    update_id: update.id.clone(),
+    version: build.config.version,
+    commit_hash: update.commit_hash.clone(),
+    image,
+    digest,
+    format,
+    packages,
+    document: String::new(),
+    document_gzip,
+  };
+  // The image is already built and pushed,
+  // so failing to record the SBOM doesn't fail the build.
+  if let Err(e) = db_client()
+    .build_sboms
+    .insert_one(sbom)
+    .await
+    .context("Failed to record SBOM on db")
+  {
+    warn!("Failed to record SBOM | {e:#}");
+    update.push_simple_log("Record SBOM", format_serror(&e.into()));
+  }
+}
+
+/// The compressed SBOM document is dropped above this size,
+/// leaving room for the package list in the database document.
+const MAX_SBOM_DOCUMENT_SIZE: usize = 8 * 1024 * 1024;
+
+/// The first image tag pushed by the build,
+/// used to reference the built image.
+fn built_image(build: &Build, update: &Update) -> Option<String> {
+  build
+    .get_image_tags(
+      &build.get_image_names(),
+      optional_string(&update.commit_hash).as_deref(),
+      &[],
+    )
+    .into_iter()
+    .next()
+}
+
 #[instrument("HandleEarlyReturn", skip(update))]
 async fn handle_early_return(
   mut update: Update,
@@ -602,6 +1367,58 @@ impl Resolve<ExecuteArgs> for CancelBuild {
   }
 }
 
+impl Resolve<ExecuteArgs> for CleanupBuildImages {
+  #[instrument(
+    "CleanupBuildImages",
+    skip_all,
+    fields(
+      task_id = task_id.to_string(),
+      operator = user.id,
+      update_id = update.id,
+      build = self.build,
+    )
+  )]
+  async fn resolve(
+    self,
+    ExecuteArgs {
+      user,
+      update,
+      task_id,
+    }: &ExecuteArgs,
+  ) -> mogh_error::Result<Update> {
+    let build = get_check_permissions::<Build>(
+      &self.build,
+      user,
+      PermissionLevel::Execute.into(),
+    )
+    .await?;
+
+    // get the action state for the build (or insert default).
+    let action_state =
+      action_states().build.get_or_insert_default(&build.id).await;
+
+    // Will check to ensure build not already busy before updating, and return Err if so.
+    // The returned guard will set the action state back to default when dropped.
+    let action_guard =
+      action_state.update(|state| state.cleaning_images = true)?;
+
+    let mut update = update.clone();
+
+    update_update(update.clone()).await?;
+
+    cleanup_build_images(&build, &mut update).await;
+
+    update.finalize();
+
+    // Drop action guard before updating
+    // clients to requery action state
+    drop(action_guard);
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
 #[instrument("PostBuildRedeploy")]
 async fn handle_post_build_redeploy(build_id: &str) {
   let Ok(redeploy_deployments) = find_collect(
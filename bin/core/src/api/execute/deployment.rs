@@ -6,7 +6,7 @@ use interpolate::Interpolator;
 use komodo_client::{
   api::execute::*,
   entities::{
-    SwarmOrServer, Version,
+    ResourceTarget, SwarmOrServer, Version,
     build::{Build, ImageRegistryConfig},
     deployment::{
       Deployment, DeploymentImage, DeploymentInfo,
@@ -29,7 +29,9 @@ use crate::{
     periphery_client,
     query::{VariablesAndSecrets, get_variables_and_secrets},
     registry_token,
+    scan::spawn_deployed_image_scans,
     swarm::swarm_request,
+    swarm_or_server_request,
     update::update_update,
   },
   monitor::{refresh_server_cache, refresh_swarm_cache},
@@ -205,6 +207,51 @@ impl Resolve<ExecuteArgs> for Deploy {
     update.version = version;
     update_update(update.clone()).await?;
 
+    // Verify the image signature, then deploy the verified digest
+    // so the tag can't be moved to different contents in between.
+    if !deployment.config.signature_keys.is_empty()
+      && let DeploymentImage::Image { image } =
+        &deployment.config.image
+    {
+      let res = match swarm_or_server_request(
+        &swarm_or_server,
+        api::docker::VerifyImageSignature {
+          image: image.clone(),
+          keys: deployment.config.signature_keys.clone(),
+          account: optional_string(
+            &deployment.config.image_registry_account,
+          ),
+          token: registry_token.clone(),
+        },
+      )
+      .await
+      {
+        Ok(res) => res,
+        Err(e) => api::docker::VerifyImageSignatureResponse {
+          log: Log::error(
+            "Verify Image Signature",
+            format_serror(
+              &e.context("Failed to verify image signature").into(),
+            ),
+          ),
+          image: None,
+        },
+      };
+      let verified = res.log.success;
+      update.logs.push(res.log);
+      match res.image {
+        Some(image) if verified => {
+          deployment.config.image = DeploymentImage::Image { image };
+        }
+        _ => {
+          update.finalize();
+          drop(action_guard);
+          update_update(update.clone()).await?;
+          return Ok(update);
+        }
+      }
+    }
+
     let deployment_id = deployment.id.clone();
     // Track the name the container / service is deployed under,
     // so the Deployment stays matched to it even if the
@@ -212,8 +259,19 @@ impl Resolve<ExecuteArgs> for Deploy {
     let fresh_name = deployment.custom_name().to_string();
     let prev_deployed_name = deployment.info.deployed_name.clone();
     let mut deployed = false;
+    let scan = deployment.config.scan_image.then(|| {
+      (
+        deployment.name.clone(),
+        deployment.config.scan_threshold,
+        match &deployment.config.image {
+          DeploymentImage::Image { image } => image.clone(),
+          // Build images are resolved to Image above
+          DeploymentImage::Build { .. } => String::new(),
+        },
+      )
+    });
 
-    match swarm_or_server {
+    match &swarm_or_server {
       SwarmOrServer::None => unreachable!(),
       SwarmOrServer::Swarm(swarm) => {
         match swarm_request(
@@ -227,7 +285,7 @@ impl Resolve<ExecuteArgs> for Deploy {
         .await
         {
           Ok(logs) => {
-            refresh_swarm_cache(&swarm, true).await;
+            refresh_swarm_cache(swarm, true).await;
             deployed = logs.iter().all(|log| log.success);
             update.logs.extend(logs)
           }
@@ -240,7 +298,7 @@ impl Resolve<ExecuteArgs> for Deploy {
         };
       }
       SwarmOrServer::Server(server) => {
-        match periphery_client(&server)
+        match periphery_client(server)
           .await?
           .request(api::container::RunContainer {
             deployment,
@@ -252,7 +310,7 @@ impl Resolve<ExecuteArgs> for Deploy {
           .await
         {
           Ok(log) => {
-            refresh_server_cache(&server, true).await;
+            refresh_server_cache(server, true).await;
             deployed = log.success;
             update.logs.push(log)
           }
@@ -270,6 +328,7 @@ impl Resolve<ExecuteArgs> for Deploy {
       &deployment_id,
       &DeploymentInfo {
         latest_image_digest: Default::default(),
+        latest_image: Default::default(),
         deployed_name: if deployed {
           fresh_name
         } else {
@@ -285,6 +344,16 @@ impl Resolve<ExecuteArgs> for Deploy {
       );
     }
 
+    if deployed && let Some((name, threshold, image)) = scan {
+      spawn_deployed_image_scans(
+        swarm_or_server,
+        ResourceTarget::Deployment(deployment_id),
+        name,
+        vec![image],
+        threshold,
+      );
+    }
+
     update.finalize();
 
     // Drop action guard before updating
@@ -1032,6 +1101,7 @@ impl Resolve<ExecuteArgs> for DestroyDeployment {
             .info
             .latest_image_digest
             .clone(),
+          latest_image: deployment.info.latest_image.clone(),
           deployed_name: Default::default(),
         },
       )
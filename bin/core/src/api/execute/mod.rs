@@ -38,7 +38,7 @@ use crate::{
   state::db_client,
 };
 
-mod action;
+pub mod action;
 mod alerter;
 mod build;
 mod deployment;
@@ -77,6 +77,7 @@ pub enum ExecuteRequest {
   BatchDeployStackIfChanged(BatchDeployStackIfChanged),
   PullStack(PullStack),
   BatchPullStack(BatchPullStack),
+  ValidateStack(ValidateStack),
   StartStack(StartStack),
   RestartStack(RestartStack),
   StopStack(StopStack),
@@ -102,6 +103,7 @@ pub enum ExecuteRequest {
   RunBuild(RunBuild),
   BatchRunBuild(BatchRunBuild),
   CancelBuild(CancelBuild),
+  CleanupBuildImages(CleanupBuildImages),
 
   // ==== REPO ====
   CloneRepo(CloneRepo),
@@ -150,6 +152,7 @@ pub enum ExecuteRequest {
   PruneVolumes(PruneVolumes),
   PruneDockerBuilders(PruneDockerBuilders),
   PruneBuildx(PruneBuildx),
+  PruneBuildCache(PruneBuildCache),
   PruneSystem(PruneSystem),
 
   // ==== SWARM ====
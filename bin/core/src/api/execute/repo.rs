@@ -46,7 +46,7 @@ use super::{ExecuteArgs, ExecuteRequest};
 impl super::BatchExecute for BatchCloneRepo {
   type Resource = Repo;
   fn single_request(repo: String) -> ExecuteRequest {
-    ExecuteRequest::CloneRepo(CloneRepo { repo })
+    ExecuteRequest::CloneRepo(CloneRepo { repo, commit: None })
   }
 }
 
@@ -102,6 +102,11 @@ impl Resolve<ExecuteArgs> for CloneRepo {
     )
     .await?;
 
+    // Per run override, eg. for the tag webhook trigger.
+    if let Some(commit) = self.commit {
+      repo.config.commit = commit;
+    }
+
     // get the action state for the repo (or insert default).
     let action_state =
       action_states().repo.get_or_insert_default(&repo.id).await;
@@ -190,7 +195,7 @@ impl Resolve<ExecuteArgs> for CloneRepo {
 impl super::BatchExecute for BatchPullRepo {
   type Resource = Repo;
   fn single_request(repo: String) -> ExecuteRequest {
-    ExecuteRequest::PullRepo(PullRepo { repo })
+    ExecuteRequest::PullRepo(PullRepo { repo, commit: None })
   }
 }
 
@@ -246,6 +251,11 @@ impl Resolve<ExecuteArgs> for PullRepo {
     )
     .await?;
 
+    // Per run override, eg. for the tag webhook trigger.
+    if let Some(commit) = self.commit {
+      repo.config.commit = commit;
+    }
+
     // get the action state for the repo (or insert default).
     let action_state =
       action_states().repo.get_or_insert_default(&repo.id).await;
@@ -380,7 +390,7 @@ async fn update_last_pulled_time(repo_name: &str) {
 impl super::BatchExecute for BatchBuildRepo {
   type Resource = Repo;
   fn single_request(repo: String) -> ExecuteRequest {
-    ExecuteRequest::CloneRepo(CloneRepo { repo })
+    ExecuteRequest::CloneRepo(CloneRepo { repo, commit: None })
   }
 }
 
@@ -436,6 +446,11 @@ impl Resolve<ExecuteArgs> for BuildRepo {
     )
     .await?;
 
+    // Per run override, eg. for the tag webhook trigger.
+    if let Some(commit) = self.commit {
+      repo.config.commit = commit;
+    }
+
     if repo.config.builder_id.is_empty() {
       return Err(anyhow!("Must attach builder to BuildRepo").into());
     }
@@ -1462,6 +1462,88 @@ impl Resolve<ExecuteArgs> for PruneBuildx {
   }
 }
 
+impl Resolve<ExecuteArgs> for PruneBuildCache {
+  #[instrument(
+    "PruneBuildCache",
+    skip_all,
+    fields(
+      task_id = task_id.to_string(),
+      operator = user.id,
+      update_id = update.id,
+      server = self.server,
+    )
+  )]
+  async fn resolve(
+    self,
+    ExecuteArgs {
+      user,
+      update,
+      task_id,
+    }: &ExecuteArgs,
+  ) -> mogh_error::Result<Update> {
+    let server = get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Execute.into(),
+    )
+    .await?;
+
+    // get the action state for the server (or insert default).
+    let action_state = action_states()
+      .server
+      .get_or_insert_default(&server.id)
+      .await;
+
+    // Will check to ensure server not already busy before updating, and return Err if so.
+    // The returned guard will set the action state back to default when dropped.
+    let action_guard = action_state
+      .update(|state| state.pruning_build_cache = true)?;
+
+    let mut update = update.clone();
+
+    update_update(update.clone()).await?;
+
+    let periphery = periphery_client(&server).await?;
+
+    match periphery.request(api::build::PruneBuildCache {}).await {
+      Ok(builders) => {
+        let mut reclaimed = Vec::with_capacity(builders.len());
+        for builder in builders {
+          reclaimed.push(format!(
+            "{}: {}",
+            builder.builder,
+            builder.reclaimed.as_deref().unwrap_or("unknown")
+          ));
+          update.logs.push(builder.log);
+        }
+        if reclaimed.is_empty() {
+          reclaimed.push(String::from("No buildx builders found"));
+        }
+        update
+          .push_simple_log("Reclaimed Space", reclaimed.join("\n"));
+      }
+      Err(e) => update.push_error_log(
+        "Prune Build Cache",
+        format!(
+          "Failed to prune build cache on server {} | {e:#?}",
+          server.name
+        ),
+      ),
+    };
+
+    refresh_server_cache(&server, true).await;
+
+    update.finalize();
+
+    // Drop action guard before updating
+    // clients to requery action state
+    drop(action_guard);
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
 impl Resolve<ExecuteArgs> for PruneSystem {
   #[instrument(
     "PruneSystem",
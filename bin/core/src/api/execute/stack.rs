@@ -5,11 +5,12 @@ use database::mungos::mongodb::bson::{
   doc, oid::ObjectId, to_bson, to_document,
 };
 use formatting::format_serror;
+use indexmap::IndexSet;
 use interpolate::Interpolator;
 use komodo_client::{
   api::{execute::*, write::RefreshStackCache},
   entities::{
-    FileContents, SwarmOrServer,
+    FileContents, ResourceTarget, SwarmOrServer, all_logs_success,
     permission::PermissionLevel,
     repo::Repo,
     server::Server,
@@ -33,6 +34,7 @@ use crate::{
   helpers::{
     periphery_client,
     query::{VariablesAndSecrets, get_variables_and_secrets},
+    scan::spawn_deployed_image_scans,
     stack_git_token,
     swarm::swarm_request,
     update::{
@@ -47,6 +49,7 @@ use crate::{
       execute_compose, execute_compose_with_stack_and_server,
     },
     setup_stack_execution,
+    validate::{push_validation_log, validate_stack},
   },
   state::{action_states, db_client},
 };
@@ -60,6 +63,7 @@ impl super::BatchExecute for BatchDeployStack {
       stack,
       services: Vec::new(),
       stop_time: None,
+      commit: None,
     })
   }
 }
@@ -120,6 +124,11 @@ impl Resolve<ExecuteArgs> for DeployStack {
 
     swarm_or_server.verify_has_target()?;
 
+    // Per run override, eg. for the tag webhook trigger.
+    if let Some(commit) = self.commit {
+      stack.config.commit = commit;
+    }
+
     let mut repo = if !stack.config.files_on_host
       && !stack.config.linked_repo.is_empty()
     {
@@ -153,6 +162,19 @@ impl Resolve<ExecuteArgs> for DeployStack {
       ))
     }
 
+    if stack.config.validate_before_deploy {
+      push_validation_log(
+        &mut update,
+        validate_stack(stack.clone(), &swarm_or_server).await,
+      );
+      if !all_logs_success(&update.logs) {
+        update.finalize();
+        drop(action_guard);
+        update_update(update.clone()).await?;
+        return Ok(update);
+      }
+    }
+
     let git_token =
       stack_git_token(&mut stack, repo.as_mut()).await?;
 
@@ -227,6 +249,26 @@ impl Resolve<ExecuteArgs> for DeployStack {
 
     update.logs.extend(logs);
 
+    let scan_images = if deployed && stack.config.scan_images {
+      let services = if services.is_empty() {
+        &stack.info.latest_services
+      } else {
+        &services
+      };
+      services
+        .iter()
+        .filter(|service| !service.image.is_empty())
+        .map(|service| service.image.clone())
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect()
+    } else {
+      Vec::new()
+    };
+    let stack_id = stack.id.clone();
+    let stack_name = stack.name.clone();
+    let scan_threshold = stack.config.scan_threshold;
+
     let update_info = async {
       let latest_services = if services.is_empty() {
         // maybe better to do something else here for services.
@@ -292,6 +334,7 @@ impl Resolve<ExecuteArgs> for DeployStack {
           .then_some(remote_errors),
         latest_hash: commit_hash,
         latest_message: commit_message,
+        latest_images: stack.info.latest_images,
       };
 
       let info = to_document(&info)
@@ -319,16 +362,24 @@ impl Resolve<ExecuteArgs> for DeployStack {
     }
 
     // Ensure cached stack state up to date by updating server cache
-    match swarm_or_server {
+    match &swarm_or_server {
       SwarmOrServer::None => unreachable!(),
       SwarmOrServer::Swarm(swarm) => {
-        refresh_swarm_cache(&swarm, true).await;
+        refresh_swarm_cache(swarm, true).await;
       }
       SwarmOrServer::Server(server) => {
-        refresh_server_cache(&server, true).await;
+        refresh_server_cache(server, true).await;
       }
     }
 
+    spawn_deployed_image_scans(
+      swarm_or_server,
+      ResourceTarget::Stack(stack_id),
+      stack_name,
+      scan_images,
+      scan_threshold,
+    );
+
     update.finalize();
 
     // Drop action guard before updating
@@ -448,6 +499,7 @@ impl Resolve<ExecuteArgs> for DeployStackIfChanged {
           stack: stack.name,
           services: Vec::new(),
           stop_time: self.stop_time,
+          commit: None,
         }
         .resolve(&ExecuteArgs {
           user: user.clone(),
@@ -582,6 +634,7 @@ async fn deploy_services(
     stack,
     services,
     stop_time: None,
+    commit: None,
   });
   let update = init_execution_update(&req, user).await?;
   let ExecuteRequest::DeployStack(req) = req else {
@@ -986,6 +1039,64 @@ impl Resolve<ExecuteArgs> for PullStack {
   }
 }
 
+impl Resolve<ExecuteArgs> for ValidateStack {
+  #[instrument(
+    "ValidateStack",
+    skip_all,
+    fields(
+      task_id = task_id.to_string(),
+      operator = user.id,
+      update_id = update.id,
+      stack = self.stack,
+    )
+  )]
+  async fn resolve(
+    self,
+    ExecuteArgs {
+      user,
+      update,
+      task_id,
+    }: &ExecuteArgs,
+  ) -> mogh_error::Result<Update> {
+    // Writes the stack files to the host, like PullStack.
+    let (stack, swarm_or_server) = setup_stack_execution(
+      &self.stack,
+      user,
+      PermissionLevel::Execute.into(),
+    )
+    .await?;
+    swarm_or_server.verify_has_target()?;
+
+    let action_state =
+      action_states().stack.get_or_insert_default(&stack.id).await;
+
+    // Writing the files conflicts with a concurrent deploy or pull.
+    // The returned guard will set the action state back to default when dropped.
+    let action_guard =
+      action_state.update(|state| state.pulling = true)?;
+
+    let mut update = update.clone();
+    update_update(update.clone()).await?;
+
+    let res = validate_stack(stack, &swarm_or_server).await;
+    let merged_config =
+      res.as_ref().ok().and_then(|res| res.merged_config.clone());
+    push_validation_log(&mut update, res);
+    if let Some(merged_config) = merged_config {
+      update.push_simple_log("Compose Config", merged_config);
+    }
+
+    update.finalize();
+
+    // Drop action guard before updating
+    // clients to requery action state
+    drop(action_guard);
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
 impl Resolve<ExecuteArgs> for StartStack {
   #[instrument(
     "StartStack",
@@ -0,0 +1,222 @@
+use anyhow::{Context, anyhow};
+use axum::http::HeaderMap;
+use data_encoding::BASE64;
+use komodo_client::entities::RepoExecutionArgs;
+use serde::Deserialize;
+
+use crate::config::core_config;
+
+use super::{
+  CommitStatus, ExtractBranch, ExtractChanges, ExtractPullRequest,
+  ExtractTag, PullRequestEvent, PushChanges, ReportCommitStatus,
+  VerifySecret, is_null_commit,
+};
+
+/// Listener implementation for Azure DevOps service hooks
+pub struct Azure;
+
+impl VerifySecret for Azure {
+  /// Azure DevOps service hooks don't sign the body.
+  /// The secret is configured as the basic auth password,
+  /// and the username is ignored.
+  #[instrument("VerifyAzureSecret", skip_all)]
+  fn verify_secret(
+    headers: &HeaderMap,
+    _body: &str,
+    custom_secret: &str,
+  ) -> anyhow::Result<()> {
+    let authorization = headers
+      .get("authorization")
+      .context("No azure basic auth in headers")?
+      .to_str()
+      .context("Failed to get authorization as string")?;
+    let credentials = authorization
+      .strip_prefix("Basic ")
+      .context("Authorization is not basic auth")?;
+    let credentials = BASE64
+      .decode(credentials.trim().as_bytes())
+      .context("Failed to decode basic auth credentials")?;
+    let credentials = String::from_utf8(credentials)
+      .context("Basic auth credentials are not valid utf8")?;
+    let (_, password) = credentials
+      .split_once(':')
+      .context("Basic auth credentials missing password")?;
+    let secret = if custom_secret.is_empty() {
+      core_config().webhook_secret.as_str()
+    } else {
+      custom_secret
+    };
+    if password == secret {
+      Ok(())
+    } else {
+      Err(anyhow!("Webhook secret does not match expected."))
+    }
+  }
+}
+
+/// Azure DevOps `git.push` body
+#[derive(Deserialize)]
+struct AzureWebhookBody {
+  resource: AzurePushResource,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzurePushResource {
+  ref_updates: Vec<AzureRefUpdate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureRefUpdate {
+  name: String,
+  old_object_id: Option<String>,
+  new_object_id: Option<String>,
+}
+
+impl ExtractBranch for Azure {
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    let branch = serde_json::from_str::<AzureWebhookBody>(body)
+      .context("Failed to parse azure request body")?
+      .resource
+      .ref_updates
+      .into_iter()
+      .next()
+      .context("No ref updates in azure request body")?
+      .name
+      .replace("refs/heads/", "");
+    Ok(branch)
+  }
+}
+
+/// Azure DevOps doesn't list the changed files in the push body,
+/// so they are always found from the commit range.
+impl ExtractChanges for Azure {
+  fn extract_changes(body: &str) -> anyhow::Result<PushChanges> {
+    let update = serde_json::from_str::<AzureWebhookBody>(body)
+      .context("Failed to parse azure request body")?
+      .resource
+      .ref_updates
+      .into_iter()
+      .next()
+      .context("No ref updates in azure request body")?;
+    Ok(PushChanges {
+      before: update.old_object_id,
+      after: update.new_object_id,
+      files: None,
+    })
+  }
+}
+
+impl ExtractTag for Azure {
+  fn extract_tag(body: &str) -> anyhow::Result<Option<String>> {
+    let update = serde_json::from_str::<AzureWebhookBody>(body)
+      .context("Failed to parse azure request body")?
+      .resource
+      .ref_updates
+      .into_iter()
+      .next()
+      .context("No ref updates in azure request body")?;
+    if update.new_object_id.as_deref().is_none_or(is_null_commit) {
+      return Ok(None);
+    }
+    Ok(update.name.strip_prefix("refs/tags/").map(str::to_string))
+  }
+}
+
+/// Pull request previews are not yet supported for Azure DevOps.
+impl ExtractPullRequest for Azure {
+  fn extract_pull_request(
+    _: &str,
+  ) -> anyhow::Result<Option<PullRequestEvent>> {
+    Ok(None)
+  }
+}
+
+/// Commit statuses are not yet supported for Azure DevOps.
+impl ReportCommitStatus for Azure {
+  fn commit_status_request(
+    _: &RepoExecutionArgs,
+    _: &str,
+    _: &str,
+    _: &CommitStatus,
+  ) -> anyhow::Result<Option<reqwest::RequestBuilder>> {
+    Ok(None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use axum::http::HeaderValue;
+
+  use super::*;
+
+  const PUSH: &str = include_str!("fixtures/azure_push.json");
+  const SECRET: &str = "komodo-webhook-secret";
+
+  fn basic_auth_headers(username: &str, password: &str) -> HeaderMap {
+    let credentials =
+      BASE64.encode(format!("{username}:{password}").as_bytes());
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      "authorization",
+      HeaderValue::from_str(&format!("Basic {credentials}")).unwrap(),
+    );
+    headers
+  }
+
+  #[test]
+  fn extracts_branch() {
+    assert_eq!(Azure::extract_branch(PUSH).unwrap(), "main");
+  }
+
+  #[test]
+  fn extracts_changes() {
+    let changes = Azure::extract_changes(PUSH).unwrap();
+    assert_eq!(
+      changes.before.as_deref(),
+      Some("aad331d8d3b131fa9ae03cf5e53965b51942618a")
+    );
+    assert_eq!(
+      changes.after.as_deref(),
+      Some("33b55f7cb7e7e245323987634f960cf4a6e6bc74")
+    );
+    assert!(changes.files.is_none());
+  }
+
+  #[test]
+  fn branch_push_has_no_tag() {
+    assert_eq!(Azure::extract_tag(PUSH).unwrap(), None);
+  }
+
+  #[test]
+  fn extracts_tag() {
+    let body = PUSH.replace("refs/heads/main", "refs/tags/v1.2.3");
+    assert_eq!(
+      Azure::extract_tag(&body).unwrap().as_deref(),
+      Some("v1.2.3")
+    );
+  }
+
+  #[test]
+  fn verifies_password() {
+    let headers = basic_auth_headers("komodo", SECRET);
+    Azure::verify_secret(&headers, PUSH, SECRET).unwrap();
+  }
+
+  #[test]
+  fn rejects_wrong_password() {
+    let headers = basic_auth_headers("komodo", "wrong-secret");
+    assert!(Azure::verify_secret(&headers, PUSH, SECRET).is_err());
+  }
+
+  #[test]
+  fn rejects_non_basic_auth() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      "authorization",
+      HeaderValue::from_static("Bearer komodo-webhook-secret"),
+    );
+    assert!(Azure::verify_secret(&headers, PUSH, SECRET).is_err());
+  }
+}
@@ -0,0 +1,347 @@
+use anyhow::{Context, anyhow};
+use axum::http::HeaderMap;
+use hex::ToHex;
+use hmac::{Hmac, KeyInit as _, Mac};
+use komodo_client::entities::RepoExecutionArgs;
+use serde::Deserialize;
+use sha2::Sha256;
+
+use crate::config::core_config;
+
+use super::{
+  CommitStatus, ExtractBranch, ExtractChanges, ExtractPullRequest,
+  ExtractTag, PullRequestEvent, PushChanges, ReportCommitStatus,
+  VerifySecret, is_null_commit,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Listener implementation for Bitbucket Cloud and Bitbucket Server / Data Center
+pub struct Bitbucket;
+
+impl VerifySecret for Bitbucket {
+  #[instrument("VerifyBitbucketSecret", skip_all)]
+  fn verify_secret(
+    headers: &HeaderMap,
+    body: &str,
+    custom_secret: &str,
+  ) -> anyhow::Result<()> {
+    let signature = headers
+      .get("x-hub-signature")
+      .context("No bitbucket signature in headers")?;
+    let signature = signature
+      .to_str()
+      .context("Failed to get signature as string")?;
+    let signature =
+      signature.strip_prefix("sha256=").unwrap_or(signature);
+    let secret_bytes = if custom_secret.is_empty() {
+      core_config().webhook_secret.as_bytes()
+    } else {
+      custom_secret.as_bytes()
+    };
+    let mut mac = HmacSha256::new_from_slice(secret_bytes)
+      .context("Failed to create hmac sha256 from secret")?;
+    mac.update(body.as_bytes());
+    let expected = mac.finalize().into_bytes().encode_hex::<String>();
+    if signature == expected {
+      Ok(())
+    } else {
+      Err(anyhow!("Signature does not equal expected"))
+    }
+  }
+}
+
+/// Bitbucket Cloud `repo:push` body
+#[derive(Deserialize)]
+struct BitbucketCloudWebhookBody {
+  push: BitbucketCloudPush,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCloudPush {
+  changes: Vec<BitbucketCloudChange>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCloudChange {
+  /// Null when the branch is created
+  old: Option<BitbucketCloudRef>,
+  /// Null when the branch is deleted
+  new: Option<BitbucketCloudRef>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCloudRef {
+  /// `branch` or `tag`
+  #[serde(rename = "type")]
+  kind: String,
+  name: String,
+  target: BitbucketCloudTarget,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCloudTarget {
+  hash: String,
+}
+
+/// Bitbucket Server / Data Center `repo:refs_changed` body
+#[derive(Deserialize)]
+struct BitbucketServerWebhookBody {
+  changes: Vec<BitbucketServerChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitbucketServerChange {
+  #[serde(rename = "ref")]
+  branch: BitbucketServerRef,
+  from_hash: String,
+  to_hash: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketServerRef {
+  /// eg `refs/heads/main` or `refs/tags/v1.2.3`
+  id: String,
+}
+
+impl ExtractBranch for Bitbucket {
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    if let Ok(body) =
+      serde_json::from_str::<BitbucketCloudWebhookBody>(body)
+    {
+      return body
+        .push
+        .changes
+        .into_iter()
+        .find_map(|change| change.new)
+        .filter(|new| new.kind == "branch")
+        .map(|new| new.name)
+        .context("No pushed branch in bitbucket request body");
+    }
+    let branch =
+      serde_json::from_str::<BitbucketServerWebhookBody>(body)
+        .context("Failed to parse bitbucket request body")?
+        .changes
+        .into_iter()
+        .next()
+        .context("No changes in bitbucket request body")?
+        .branch
+        .id
+        .replace("refs/heads/", "");
+    Ok(branch)
+  }
+}
+
+/// Bitbucket doesn't list the changed files in the push body,
+/// so they are always found from the commit range.
+impl ExtractChanges for Bitbucket {
+  fn extract_changes(body: &str) -> anyhow::Result<PushChanges> {
+    if let Ok(body) =
+      serde_json::from_str::<BitbucketCloudWebhookBody>(body)
+    {
+      let Some(change) = body
+        .push
+        .changes
+        .into_iter()
+        .find(|change| change.new.is_some())
+      else {
+        return Ok(PushChanges::default());
+      };
+      return Ok(PushChanges {
+        before: change.old.map(|old| old.target.hash),
+        after: change.new.map(|new| new.target.hash),
+        files: None,
+      });
+    }
+    let change =
+      serde_json::from_str::<BitbucketServerWebhookBody>(body)
+        .context("Failed to parse bitbucket request body")?
+        .changes
+        .into_iter()
+        .next()
+        .context("No changes in bitbucket request body")?;
+    Ok(PushChanges {
+      before: Some(change.from_hash),
+      after: Some(change.to_hash),
+      files: None,
+    })
+  }
+}
+
+impl ExtractTag for Bitbucket {
+  fn extract_tag(body: &str) -> anyhow::Result<Option<String>> {
+    if let Ok(body) =
+      serde_json::from_str::<BitbucketCloudWebhookBody>(body)
+    {
+      // 'new' is null when the tag is deleted.
+      let tag = body
+        .push
+        .changes
+        .into_iter()
+        .find_map(|change| change.new)
+        .filter(|new| new.kind == "tag")
+        .map(|new| new.name);
+      return Ok(tag);
+    }
+    let change =
+      serde_json::from_str::<BitbucketServerWebhookBody>(body)
+        .context("Failed to parse bitbucket request body")?
+        .changes
+        .into_iter()
+        .next()
+        .context("No changes in bitbucket request body")?;
+    if is_null_commit(&change.to_hash) {
+      return Ok(None);
+    }
+    Ok(
+      change
+        .branch
+        .id
+        .strip_prefix("refs/tags/")
+        .map(str::to_string),
+    )
+  }
+}
+
+/// Pull request previews are not yet supported for Bitbucket.
+impl ExtractPullRequest for Bitbucket {
+  fn extract_pull_request(
+    _: &str,
+  ) -> anyhow::Result<Option<PullRequestEvent>> {
+    Ok(None)
+  }
+}
+
+/// Commit statuses are not yet supported for Bitbucket.
+impl ReportCommitStatus for Bitbucket {
+  fn commit_status_request(
+    _: &RepoExecutionArgs,
+    _: &str,
+    _: &str,
+    _: &CommitStatus,
+  ) -> anyhow::Result<Option<reqwest::RequestBuilder>> {
+    Ok(None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use axum::http::HeaderValue;
+
+  use super::*;
+
+  const CLOUD_PUSH: &str =
+    include_str!("fixtures/bitbucket_cloud_push.json");
+  const SERVER_PUSH: &str =
+    include_str!("fixtures/bitbucket_server_push.json");
+  const SECRET: &str = "komodo-webhook-secret";
+
+  fn signed_headers(body: &str, secret: &str) -> HeaderMap {
+    let mut mac =
+      HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    let signature =
+      mac.finalize().into_bytes().encode_hex::<String>();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      "x-hub-signature",
+      HeaderValue::from_str(&format!("sha256={signature}")).unwrap(),
+    );
+    headers
+  }
+
+  #[test]
+  fn extracts_cloud_branch() {
+    assert_eq!(
+      Bitbucket::extract_branch(CLOUD_PUSH).unwrap(),
+      "main"
+    );
+  }
+
+  #[test]
+  fn extracts_server_branch() {
+    assert_eq!(
+      Bitbucket::extract_branch(SERVER_PUSH).unwrap(),
+      "release/2.0"
+    );
+  }
+
+  #[test]
+  fn extracts_cloud_changes() {
+    let changes = Bitbucket::extract_changes(CLOUD_PUSH).unwrap();
+    assert_eq!(
+      changes.before.as_deref(),
+      Some("1e65c05c1d5171631d92438a13901ca7dae9618c")
+    );
+    assert_eq!(
+      changes.after.as_deref(),
+      Some("8a9c5e6a3d0a6f1f0b31a4a5c0d5c40b39a6f7d2")
+    );
+    assert!(changes.files.is_none());
+  }
+
+  #[test]
+  fn extracts_server_changes() {
+    let changes = Bitbucket::extract_changes(SERVER_PUSH).unwrap();
+    assert_eq!(
+      changes.before.as_deref(),
+      Some("ecddabb624f6f5ba43816f5926e580a5f680a932")
+    );
+    assert_eq!(
+      changes.after.as_deref(),
+      Some("178864a7d521b6f5e720b386b2c2b0ef8563e0dc")
+    );
+  }
+
+  #[test]
+  fn branch_push_has_no_tag() {
+    assert_eq!(Bitbucket::extract_tag(CLOUD_PUSH).unwrap(), None);
+    assert_eq!(Bitbucket::extract_tag(SERVER_PUSH).unwrap(), None);
+  }
+
+  #[test]
+  fn extracts_cloud_tag() {
+    let body = CLOUD_PUSH
+      .replace("\"type\": \"branch\"", "\"type\": \"tag\"")
+      .replace("\"name\": \"main\"", "\"name\": \"v1.2.3\"");
+    assert_eq!(
+      Bitbucket::extract_tag(&body).unwrap().as_deref(),
+      Some("v1.2.3")
+    );
+    assert!(Bitbucket::extract_branch(&body).is_err());
+  }
+
+  #[test]
+  fn extracts_server_tag() {
+    let body = SERVER_PUSH
+      .replace("refs/heads/release/2.0", "refs/tags/v2.0.0");
+    assert_eq!(
+      Bitbucket::extract_tag(&body).unwrap().as_deref(),
+      Some("v2.0.0")
+    );
+  }
+
+  #[test]
+  fn verifies_signature() {
+    let headers = signed_headers(CLOUD_PUSH, SECRET);
+    Bitbucket::verify_secret(&headers, CLOUD_PUSH, SECRET).unwrap();
+  }
+
+  #[test]
+  fn rejects_wrong_secret() {
+    let headers = signed_headers(SERVER_PUSH, "wrong-secret");
+    assert!(
+      Bitbucket::verify_secret(&headers, SERVER_PUSH, SECRET)
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn rejects_missing_signature() {
+    assert!(
+      Bitbucket::verify_secret(&HeaderMap::new(), CLOUD_PUSH, SECRET)
+        .is_err()
+    );
+  }
+}
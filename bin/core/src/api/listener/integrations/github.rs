@@ -2,12 +2,19 @@ use anyhow::{Context, anyhow};
 use axum::http::HeaderMap;
 use hex::ToHex;
 use hmac::{Hmac, KeyInit as _, Mac};
+use komodo_client::entities::RepoExecutionArgs;
 use serde::Deserialize;
+use serde_json::json;
 use sha2::Sha256;
 
 use crate::config::core_config;
 
-use super::{ExtractBranch, VerifySecret};
+use super::{
+  CommitState, CommitStatus, ExtractBranch, ExtractChanges,
+  ExtractPullRequest, ExtractTag, PullRequestAction,
+  PullRequestEvent, PushChanges, ReportCommitStatus, VerifySecret,
+  http_client, is_null_commit,
+};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -61,3 +68,169 @@ impl ExtractBranch for Github {
     Ok(branch)
   }
 }
+
+#[derive(Deserialize)]
+struct GithubPushBody {
+  before: Option<String>,
+  after: Option<String>,
+  #[serde(default)]
+  commits: Vec<GithubPushCommit>,
+}
+
+#[derive(Deserialize)]
+struct GithubPushCommit {
+  #[serde(default)]
+  added: Vec<String>,
+  #[serde(default)]
+  removed: Vec<String>,
+  #[serde(default)]
+  modified: Vec<String>,
+}
+
+/// Github includes at most 2048 commits in the push body.
+const GITHUB_MAX_COMMITS: usize = 2048;
+
+impl ExtractChanges for Github {
+  fn extract_changes(body: &str) -> anyhow::Result<PushChanges> {
+    let body = serde_json::from_str::<GithubPushBody>(body)
+      .context("Failed to parse github request body")?;
+    let files = (!body.commits.is_empty()
+      && body.commits.len() < GITHUB_MAX_COMMITS)
+      .then(|| {
+        body
+          .commits
+          .into_iter()
+          .flat_map(|commit| {
+            [commit.added, commit.removed, commit.modified]
+          })
+          .flatten()
+          .collect()
+      });
+    Ok(PushChanges {
+      before: body.before,
+      after: body.after,
+      files,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+struct GithubTagBody {
+  #[serde(rename = "ref")]
+  tag: String,
+  #[serde(default)]
+  after: String,
+}
+
+impl ExtractTag for Github {
+  fn extract_tag(body: &str) -> anyhow::Result<Option<String>> {
+    let body = serde_json::from_str::<GithubTagBody>(body)
+      .context("Failed to parse github request body")?;
+    if is_null_commit(&body.after) {
+      return Ok(None);
+    }
+    Ok(body.tag.strip_prefix("refs/tags/").map(str::to_string))
+  }
+}
+
+/// `pull_request` event body
+#[derive(Deserialize)]
+struct GithubPullRequestBody {
+  action: Option<String>,
+  pull_request: Option<GithubPullRequest>,
+}
+
+#[derive(Deserialize)]
+struct GithubPullRequest {
+  number: i64,
+  head: GithubPullRequestRef,
+  base: GithubPullRequestRef,
+}
+
+#[derive(Deserialize)]
+struct GithubPullRequestRef {
+  #[serde(rename = "ref")]
+  branch: String,
+  /// Null if the head repo (fork) was deleted
+  repo: Option<GithubPullRequestRepo>,
+}
+
+#[derive(Deserialize)]
+struct GithubPullRequestRepo {
+  full_name: String,
+}
+
+impl ExtractPullRequest for Github {
+  fn extract_pull_request(
+    body: &str,
+  ) -> anyhow::Result<Option<PullRequestEvent>> {
+    let body = serde_json::from_str::<GithubPullRequestBody>(body)
+      .context("Failed to parse github request body")?;
+    let (Some(action), Some(pull_request)) =
+      (body.action, body.pull_request)
+    else {
+      return Ok(None);
+    };
+    let action = match action.as_str() {
+      "opened" | "reopened" | "synchronize" => {
+        PullRequestAction::Open
+      }
+      "closed" => PullRequestAction::Close,
+      _ => return Ok(None),
+    };
+    let from_fork =
+      match (&pull_request.head.repo, &pull_request.base.repo) {
+        (Some(head), Some(base)) => head.full_name != base.full_name,
+        _ => true,
+      };
+    Ok(Some(PullRequestEvent {
+      action,
+      number: pull_request.number,
+      source_branch: pull_request.head.branch,
+      target_branch: pull_request.base.branch,
+      from_fork,
+    }))
+  }
+}
+
+/// Github and Gitea / Forgejo, which serve a
+/// Github compatible commit status API under `/api/v1`.
+impl ReportCommitStatus for Github {
+  fn commit_status_request(
+    args: &RepoExecutionArgs,
+    token: &str,
+    sha: &str,
+    status: &CommitStatus,
+  ) -> anyhow::Result<Option<reqwest::RequestBuilder>> {
+    let repo = args
+      .repo
+      .as_deref()
+      .context("Resource has no repo attached")?;
+    let url = if args.provider == "github.com" {
+      format!("https://api.github.com/repos/{repo}/statuses/{sha}")
+    } else {
+      let protocol = if args.https { "https" } else { "http" };
+      format!(
+        "{protocol}://{}/api/v1/repos/{repo}/statuses/{sha}",
+        args.provider
+      )
+    };
+    let state = match status.state {
+      CommitState::Pending => "pending",
+      CommitState::Success => "success",
+      CommitState::Failure => "failure",
+    };
+    let request = http_client()
+      .post(url)
+      .bearer_auth(token)
+      .header("accept", "application/vnd.github+json")
+      .header("user-agent", "komodo")
+      .json(&json!({
+        "state": state,
+        "target_url": status.target_url,
+        "description": status.description,
+        "context": status.context,
+      }));
+    Ok(Some(request))
+  }
+}
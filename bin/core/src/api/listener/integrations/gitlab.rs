@@ -1,10 +1,17 @@
 use anyhow::{Context, anyhow};
 use axum::http::HeaderMap;
+use komodo_client::entities::RepoExecutionArgs;
 use serde::Deserialize;
+use serde_json::json;
 
 use crate::config::core_config;
 
-use super::{ExtractBranch, VerifySecret};
+use super::{
+  CommitState, CommitStatus, ExtractBranch, ExtractChanges,
+  ExtractPullRequest, ExtractTag, PullRequestAction,
+  PullRequestEvent, PushChanges, ReportCommitStatus, VerifySecret,
+  http_client, is_null_commit,
+};
 
 /// Listener implementation for Gitlab type API
 pub struct Gitlab;
@@ -49,3 +56,153 @@ impl ExtractBranch for Gitlab {
     Ok(branch)
   }
 }
+
+#[derive(Deserialize)]
+struct GitlabPushBody {
+  before: Option<String>,
+  after: Option<String>,
+  #[serde(default)]
+  total_commits_count: usize,
+  #[serde(default)]
+  commits: Vec<GitlabPushCommit>,
+}
+
+#[derive(Deserialize)]
+struct GitlabPushCommit {
+  #[serde(default)]
+  added: Vec<String>,
+  #[serde(default)]
+  removed: Vec<String>,
+  #[serde(default)]
+  modified: Vec<String>,
+}
+
+impl ExtractChanges for Gitlab {
+  fn extract_changes(body: &str) -> anyhow::Result<PushChanges> {
+    let body = serde_json::from_str::<GitlabPushBody>(body)
+      .context("Failed to parse gitlab request body")?;
+    // Gitlab only includes the latest 20 commits in the push body.
+    let files = (!body.commits.is_empty()
+      && body.commits.len() >= body.total_commits_count)
+      .then(|| {
+        body
+          .commits
+          .into_iter()
+          .flat_map(|commit| {
+            [commit.added, commit.removed, commit.modified]
+          })
+          .flatten()
+          .collect()
+      });
+    Ok(PushChanges {
+      before: body.before,
+      after: body.after,
+      files,
+    })
+  }
+}
+
+#[derive(Deserialize)]
+struct GitlabTagBody {
+  #[serde(rename = "ref")]
+  tag: String,
+  #[serde(default)]
+  after: String,
+}
+
+impl ExtractTag for Gitlab {
+  fn extract_tag(body: &str) -> anyhow::Result<Option<String>> {
+    let body = serde_json::from_str::<GitlabTagBody>(body)
+      .context("Failed to parse gitlab request body")?;
+    if is_null_commit(&body.after) {
+      return Ok(None);
+    }
+    Ok(body.tag.strip_prefix("refs/tags/").map(str::to_string))
+  }
+}
+
+/// `merge_request` event body
+#[derive(Deserialize)]
+struct GitlabMergeRequestBody {
+  object_kind: String,
+  object_attributes: Option<GitlabMergeRequest>,
+}
+
+#[derive(Deserialize)]
+struct GitlabMergeRequest {
+  iid: i64,
+  action: Option<String>,
+  source_branch: String,
+  target_branch: String,
+  source_project_id: i64,
+  target_project_id: i64,
+  /// Only included on `update` when new commits were pushed.
+  oldrev: Option<String>,
+}
+
+impl ExtractPullRequest for Gitlab {
+  fn extract_pull_request(
+    body: &str,
+  ) -> anyhow::Result<Option<PullRequestEvent>> {
+    let body = serde_json::from_str::<GitlabMergeRequestBody>(body)
+      .context("Failed to parse gitlab request body")?;
+    if body.object_kind != "merge_request" {
+      return Ok(None);
+    }
+    let Some(merge_request) = body.object_attributes else {
+      return Ok(None);
+    };
+    let action = match merge_request.action.as_deref() {
+      Some("open" | "reopen") => PullRequestAction::Open,
+      // Updates also include eg. title and label changes.
+      Some("update") if merge_request.oldrev.is_some() => {
+        PullRequestAction::Open
+      }
+      Some("close" | "merge") => PullRequestAction::Close,
+      _ => return Ok(None),
+    };
+    Ok(Some(PullRequestEvent {
+      action,
+      number: merge_request.iid,
+      source_branch: merge_request.source_branch,
+      target_branch: merge_request.target_branch,
+      from_fork: merge_request.source_project_id
+        != merge_request.target_project_id,
+    }))
+  }
+}
+
+impl ReportCommitStatus for Gitlab {
+  fn commit_status_request(
+    args: &RepoExecutionArgs,
+    token: &str,
+    sha: &str,
+    status: &CommitStatus,
+  ) -> anyhow::Result<Option<reqwest::RequestBuilder>> {
+    let repo = args
+      .repo
+      .as_deref()
+      .context("Resource has no repo attached")?;
+    let protocol = if args.https { "https" } else { "http" };
+    let url = format!(
+      "{protocol}://{}/api/v4/projects/{}/statuses/{sha}",
+      args.provider,
+      urlencoding::encode(repo)
+    );
+    let state = match status.state {
+      CommitState::Pending => "pending",
+      CommitState::Success => "success",
+      CommitState::Failure => "failed",
+    };
+    let request = http_client()
+      .post(url)
+      .header("private-token", token)
+      .json(&json!({
+        "state": state,
+        "target_url": status.target_url,
+        "description": status.description,
+        "name": status.context,
+      }));
+    Ok(Some(request))
+  }
+}
@@ -1,4 +1,11 @@
+pub mod azure;
+pub mod bitbucket;
 pub mod github;
 pub mod gitlab;
 
-use super::{ExtractBranch, VerifySecret};
+use super::{
+  ExtractBranch, ExtractChanges, ExtractPullRequest, ExtractTag,
+  PullRequestAction, PullRequestEvent, PushChanges,
+  ReportCommitStatus, VerifySecret, is_null_commit,
+  status::{CommitState, CommitStatus, http_client},
+};
@@ -1,15 +1,20 @@
 use std::sync::Arc;
 
 use axum::{Router, http::HeaderMap};
-use komodo_client::entities::resource::Resource;
+use komodo_client::entities::{
+  RepoExecutionArgs, resource::Resource,
+};
 use mogh_cache::CloneCache;
 use tokio::sync::Mutex;
 
 use crate::resource::KomodoResource;
 
 mod integrations;
+mod paths;
 mod resources;
 mod router;
+mod status;
+mod tags;
 
 use integrations::*;
 
@@ -17,6 +22,8 @@ pub fn router() -> Router {
   Router::new()
     .nest("/github", router::router::<github::Github>())
     .nest("/gitlab", router::router::<gitlab::Gitlab>())
+    .nest("/bitbucket", router::router::<bitbucket::Bitbucket>())
+    .nest("/azure", router::router::<azure::Azure>())
 }
 
 type ListenerLockCache = CloneCache<String, Arc<Mutex<()>>>;
@@ -59,6 +66,80 @@ trait ExtractBranch {
   }
 }
 
+/// The commit range and changed files of a push.
+#[derive(Default)]
+struct PushChanges {
+  /// The branch head before the push.
+  before: Option<String>,
+  /// The branch head after the push.
+  after: Option<String>,
+  /// The changed files listed in the webhook body.
+  /// `None` if the body doesn't list them, or the list is truncated.
+  files: Option<Vec<String>>,
+}
+
+/// Implemented on the integration struct, eg [integrations::github::Github]
+trait ExtractChanges {
+  fn extract_changes(body: &str) -> anyhow::Result<PushChanges>;
+}
+
+/// Implemented on the integration struct, eg [integrations::github::Github]
+trait ExtractTag {
+  /// Returns the pushed tag, or `None` if the push
+  /// was not to a tag, or deleted the tag.
+  fn extract_tag(body: &str) -> anyhow::Result<Option<String>>;
+}
+
+/// Implemented on the integration struct, eg [integrations::github::Github]
+trait ReportCommitStatus {
+  /// Returns the request posting the commit status,
+  /// or `None` if the git provider isn't supported.
+  fn commit_status_request(
+    args: &RepoExecutionArgs,
+    token: &str,
+    sha: &str,
+    status: &status::CommitStatus,
+  ) -> anyhow::Result<Option<reqwest::RequestBuilder>>;
+}
+
+/// Whether a pull request was opened / updated, or closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullRequestAction {
+  /// Opened, reopened, or new commits pushed.
+  Open,
+  /// Closed or merged.
+  Close,
+}
+
+/// A pull / merge request event.
+struct PullRequestEvent {
+  action: PullRequestAction,
+  /// The pull request number, eg `#12`.
+  number: i64,
+  /// The branch the pull request is merging from.
+  source_branch: String,
+  /// The branch the pull request is merging into.
+  target_branch: String,
+  /// Whether the source branch is in a different (forked) repo.
+  from_fork: bool,
+}
+
+/// Implemented on the integration struct, eg [integrations::github::Github]
+trait ExtractPullRequest {
+  /// Returns the pull request event, or `None` if the body
+  /// is not a pull request event, or the action is not handled,
+  /// eg. a label was added.
+  fn extract_pull_request(
+    body: &str,
+  ) -> anyhow::Result<Option<PullRequestEvent>>;
+}
+
+/// Git providers send the all zero hash as the
+/// old / new commit when a ref is created / deleted.
+fn is_null_commit(hash: &str) -> bool {
+  hash.chars().all(|c| c == '0')
+}
+
 /// For Procedures and Actions, incoming webhook
 /// can be triggered by any branch by using `__ANY__`
 /// as the branch in the webhook URL.
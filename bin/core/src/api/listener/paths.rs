@@ -0,0 +1,94 @@
+use anyhow::Context;
+use komodo_client::entities::RepoExecutionArgs;
+
+use crate::{
+  helpers::matcher::Matcher, stack::remote::ensure_remote_repo,
+};
+
+use super::{ExtractChanges, PushChanges, is_null_commit};
+
+/// Whether the push changed any file matching `include`
+/// (or any file, if `include` is empty) which is not also
+/// matched by `exclude`.
+///
+/// If the webhook body doesn't list the changed files,
+/// they are found using `git diff` in the Core repo cache.
+/// When the changes can't be determined at all,
+/// the webhook is allowed through.
+pub async fn paths_match<B: ExtractChanges>(
+  body: &str,
+  include: &[String],
+  exclude: &[String],
+  clone_args: RepoExecutionArgs,
+) -> anyhow::Result<bool> {
+  if include.is_empty() && exclude.is_empty() {
+    return Ok(true);
+  }
+
+  let include = include
+    .iter()
+    .map(|pattern| Matcher::new(pattern))
+    .collect::<anyhow::Result<Vec<_>>>()
+    .context("Invalid webhook include path")?;
+  let exclude = exclude
+    .iter()
+    .map(|pattern| Matcher::new(pattern))
+    .collect::<anyhow::Result<Vec<_>>>()
+    .context("Invalid webhook exclude path")?;
+
+  let Some(files) =
+    changed_files(B::extract_changes(body)?, clone_args).await
+  else {
+    return Ok(true);
+  };
+
+  let matches = files.iter().any(|file| {
+    (include.is_empty()
+      || include.iter().any(|matcher| matcher.is_match(file)))
+      && !exclude.iter().any(|matcher| matcher.is_match(file))
+  });
+
+  if !matches {
+    debug!(
+      "Ignoring webhook | none of the {} changed files match the webhook paths",
+      files.len()
+    );
+  }
+
+  Ok(matches)
+}
+
+async fn changed_files(
+  PushChanges {
+    before,
+    after,
+    files,
+  }: PushChanges,
+  clone_args: RepoExecutionArgs,
+) -> Option<Vec<String>> {
+  if files.is_some() {
+    return files;
+  }
+  let before = before.filter(|hash| !is_null_commit(hash))?;
+  let after = after.filter(|hash| !is_null_commit(hash))?;
+  let res = async {
+    let (repo_path, logs, _, _) = ensure_remote_repo(clone_args)
+      .await
+      .context("Failed to pull repo")?;
+    if let Some(failure) = logs.iter().find(|log| !log.success) {
+      return Err(anyhow::anyhow!("{}", failure.combined()))
+        .context("Failed to pull repo");
+    }
+    git::get_changed_files(&repo_path, &before, &after).await
+  }
+  .await;
+  match res {
+    Ok(files) => Some(files),
+    Err(e) => {
+      warn!(
+        "Failed to get webhook changed files, allowing webhook through | {e:#}"
+      );
+      None
+    }
+  }
+}
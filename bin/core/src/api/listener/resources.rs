@@ -7,8 +7,9 @@ use komodo_client::{
     write::{RefreshResourceSyncPending, RefreshStackCache},
   },
   entities::{
-    action::Action, build::Build, procedure::Procedure, repo::Repo,
-    stack::Stack, sync::ResourceSync, user::git_webhook_user,
+    RepoExecutionArgs, WebhookTrigger, action::Action, build::Build,
+    procedure::Procedure, repo::Repo, stack::Stack,
+    sync::ResourceSync, update::Update, user::git_webhook_user,
   },
 };
 use mogh_resolver::Resolve;
@@ -23,10 +24,18 @@ use crate::{
   },
   helpers::update::init_execution_update,
   resource,
+  stack::preview::{delete_stack_preview, upsert_stack_preview},
   state::action_states,
 };
 
-use super::{ANY_BRANCH, ListenerLockCache};
+use super::{
+  ANY_BRANCH, ExtractBranch, ExtractChanges, ExtractPullRequest,
+  ExtractTag, ListenerLockCache, PullRequestAction,
+  ReportCommitStatus,
+  paths::paths_match,
+  status::CommitStatusReporter,
+  tags::{tag_matches, version_from_tag},
+};
 
 // =======
 //  BUILD
@@ -38,7 +47,9 @@ impl super::CustomSecret for Build {
   }
 }
 
-pub async fn handle_build_webhook<B: super::ExtractBranch>(
+pub async fn handle_build_webhook<
+  B: ExtractBranch + ExtractChanges + ExtractTag + ReportCommitStatus,
+>(
   build: Build,
   body: String,
 ) -> anyhow::Result<()> {
@@ -47,19 +58,53 @@ pub async fn handle_build_webhook<B: super::ExtractBranch>(
   }
 
   // Use the correct target branch when using linked repo.
-  let branch = if build.config.linked_repo.is_empty() {
-    build.config.branch
+  let repo = if build.config.linked_repo.is_empty() {
+    None
   } else {
-    resource::get::<Repo>(&build.config.linked_repo)
-      .await
-      .context("Failed to find 'linked_repo'")?
-      .config
-      .branch
+    Some(
+      resource::get::<Repo>(&build.config.linked_repo)
+        .await
+        .context("Failed to find 'linked_repo'")?,
+    )
   };
+  let clone_args: RepoExecutionArgs =
+    repo.as_ref().map(Into::into).unwrap_or((&build).into());
+
+  let tag = if build.config.webhook_trigger == WebhookTrigger::Tag {
+    if repo.is_some() {
+      return Err(anyhow!(
+        "Tag webhook trigger is not supported with 'linked_repo'"
+      ));
+    }
+    let Some(tag) =
+      tag_matches::<B>(&body, &build.config.webhook_tag_pattern)?
+    else {
+      return Ok(());
+    };
+    Some(tag)
+  } else {
+    let branch = repo
+      .as_ref()
+      .map(|repo| repo.config.branch.as_str())
+      .unwrap_or(&build.config.branch);
 
-  if !B::branch_matches(&body, &branch)? {
-    return Ok(());
-  }
+    if !B::branch_matches(&body, branch)? {
+      return Ok(());
+    }
+
+    if !paths_match::<B>(
+      &body,
+      &build.config.webhook_include_paths,
+      &build.config.webhook_exclude_paths,
+      clone_args.clone(),
+    )
+    .await?
+    {
+      return Ok(());
+    }
+
+    None
+  };
 
   // Cancel if currently building
   if action_states()
@@ -92,21 +137,45 @@ pub async fn handle_build_webhook<B: super::ExtractBranch>(
     poll_build_until_cancelled(&build.id).await?;
   }
 
+  // Build at the pushed tag, without changing the config.
+  let version = match &tag {
+    Some(tag) if build.config.webhook_tag_version => {
+      Some(version_from_tag(tag)?)
+    }
+    _ => None,
+  };
+
+  let status = CommitStatusReporter::<B>::new::<Build>(
+    build.config.webhook_commit_status,
+    &body,
+    clone_args,
+    &build.id,
+    &build.name,
+  )
+  .await;
+  status.pending().await;
+
   let user = git_webhook_user().to_owned();
-  let run = ExecuteRequest::RunBuild(RunBuild { build: build.id });
+  let run = ExecuteRequest::RunBuild(RunBuild {
+    build: build.id,
+    commit: tag,
+    version,
+  });
   let update = init_execution_update(&run, &user).await?;
   let ExecuteRequest::RunBuild(run) = run else {
     unreachable!()
   };
 
-  run
+  let res = run
     .resolve(&ExecuteArgs {
       user,
       update,
       task_id: Uuid::new_v4(),
     })
     .await
-    .map_err(|e| e.error)?;
+    .map_err(|e| e.error);
+  status.finished(res.as_ref().ok()).await;
+  res?;
 
   Ok(())
 }
@@ -149,15 +218,23 @@ fn repo_locks() -> &'static ListenerLockCache {
 }
 
 pub trait RepoExecution {
-  async fn resolve(repo: Repo) -> anyhow::Result<()>;
+  /// The `commit` overrides the configured commit for this run.
+  async fn resolve(
+    repo: Repo,
+    commit: Option<String>,
+  ) -> anyhow::Result<Update>;
 }
 
 impl RepoExecution for CloneRepo {
-  async fn resolve(repo: Repo) -> anyhow::Result<()> {
+  async fn resolve(
+    repo: Repo,
+    commit: Option<String>,
+  ) -> anyhow::Result<Update> {
     let user = git_webhook_user().to_owned();
     let req =
       crate::api::execute::ExecuteRequest::CloneRepo(CloneRepo {
         repo: repo.id,
+        commit,
       });
     let update = init_execution_update(&req, &user).await?;
     let crate::api::execute::ExecuteRequest::CloneRepo(req) = req
@@ -171,17 +248,20 @@ impl RepoExecution for CloneRepo {
         task_id: Uuid::new_v4(),
       })
       .await
-      .map_err(|e| e.error)?;
-    Ok(())
+      .map_err(|e| e.error)
   }
 }
 
 impl RepoExecution for PullRepo {
-  async fn resolve(repo: Repo) -> anyhow::Result<()> {
+  async fn resolve(
+    repo: Repo,
+    commit: Option<String>,
+  ) -> anyhow::Result<Update> {
     let user = git_webhook_user().to_owned();
     let req =
       crate::api::execute::ExecuteRequest::PullRepo(PullRepo {
         repo: repo.id,
+        commit,
       });
     let update = init_execution_update(&req, &user).await?;
     let crate::api::execute::ExecuteRequest::PullRepo(req) = req
@@ -195,17 +275,20 @@ impl RepoExecution for PullRepo {
         task_id: Uuid::new_v4(),
       })
       .await
-      .map_err(|e| e.error)?;
-    Ok(())
+      .map_err(|e| e.error)
   }
 }
 
 impl RepoExecution for BuildRepo {
-  async fn resolve(repo: Repo) -> anyhow::Result<()> {
+  async fn resolve(
+    repo: Repo,
+    commit: Option<String>,
+  ) -> anyhow::Result<Update> {
     let user = git_webhook_user().to_owned();
     let req =
       crate::api::execute::ExecuteRequest::BuildRepo(BuildRepo {
         repo: repo.id,
+        commit,
       });
     let update = init_execution_update(&req, &user).await?;
     let crate::api::execute::ExecuteRequest::BuildRepo(req) = req
@@ -219,8 +302,7 @@ impl RepoExecution for BuildRepo {
         task_id: Uuid::new_v4(),
       })
       .await
-      .map_err(|e| e.error)?;
-    Ok(())
+      .map_err(|e| e.error)
   }
 }
 
@@ -232,7 +314,9 @@ pub enum RepoWebhookOption {
   Build,
 }
 
-pub async fn handle_repo_webhook<B: super::ExtractBranch>(
+pub async fn handle_repo_webhook<
+  B: ExtractBranch + ExtractChanges + ExtractTag + ReportCommitStatus,
+>(
   option: RepoWebhookOption,
   repo: Repo,
   body: String,
@@ -251,7 +335,7 @@ pub async fn handle_repo_webhook<B: super::ExtractBranch>(
 }
 
 async fn handle_repo_webhook_inner<
-  B: super::ExtractBranch,
+  B: ExtractBranch + ExtractChanges + ExtractTag + ReportCommitStatus,
   E: RepoExecution,
 >(
   repo: Repo,
@@ -267,11 +351,45 @@ async fn handle_repo_webhook_inner<
   let lock = repo_locks().get_or_insert_default(&repo.id).await;
   let _lock = lock.lock().await;
 
-  if !B::branch_matches(&body, &repo.config.branch)? {
-    return Ok(());
-  }
+  // Check out the pushed tag, without changing the config.
+  let tag = if repo.config.webhook_trigger == WebhookTrigger::Tag {
+    let Some(tag) =
+      tag_matches::<B>(&body, &repo.config.webhook_tag_pattern)?
+    else {
+      return Ok(());
+    };
+    Some(tag)
+  } else {
+    if !B::branch_matches(&body, &repo.config.branch)? {
+      return Ok(());
+    }
 
-  E::resolve(repo).await
+    if !paths_match::<B>(
+      &body,
+      &repo.config.webhook_include_paths,
+      &repo.config.webhook_exclude_paths,
+      (&repo).into(),
+    )
+    .await?
+    {
+      return Ok(());
+    }
+
+    None
+  };
+
+  let status = CommitStatusReporter::<B>::new::<Repo>(
+    repo.config.webhook_commit_status,
+    &body,
+    (&repo).into(),
+    &repo.id,
+    &repo.name,
+  )
+  .await;
+  status.pending().await;
+  let res = E::resolve(repo, tag).await;
+  status.finished(res.as_ref().ok()).await;
+  res.map(|_| ())
 }
 
 // =======
@@ -290,28 +408,44 @@ fn stack_locks() -> &'static ListenerLockCache {
 }
 
 pub trait StackExecution {
-  async fn resolve(stack: Stack) -> mogh_error::Result<()>;
+  /// Returns the Update, if the execution creates one.
+  /// The `commit` overrides the configured commit for this run.
+  async fn resolve(
+    stack: Stack,
+    commit: Option<String>,
+  ) -> mogh_error::Result<Option<Update>>;
 }
 
 impl StackExecution for RefreshStackCache {
-  async fn resolve(stack: Stack) -> mogh_error::Result<()> {
+  /// The cache always reflects the configured commit.
+  async fn resolve(
+    stack: Stack,
+    _commit: Option<String>,
+  ) -> mogh_error::Result<Option<Update>> {
     RefreshStackCache { stack: stack.id }
       .resolve(&WriteArgs {
         user: git_webhook_user().to_owned(),
       })
       .await?;
-    Ok(())
+    Ok(None)
   }
 }
 
 impl StackExecution for DeployStack {
-  async fn resolve(stack: Stack) -> mogh_error::Result<()> {
+  async fn resolve(
+    stack: Stack,
+    commit: Option<String>,
+  ) -> mogh_error::Result<Option<Update>> {
     let user = git_webhook_user().to_owned();
-    if stack.config.webhook_force_deploy {
+    // A pushed tag is always a change to deploy.
+    let update = if stack.config.webhook_force_deploy
+      || commit.is_some()
+    {
       let req = ExecuteRequest::DeployStack(DeployStack {
         stack: stack.id,
         services: Vec::new(),
         stop_time: None,
+        commit,
       });
       let update = init_execution_update(&req, &user).await?;
       let ExecuteRequest::DeployStack(req) = req else {
@@ -324,7 +458,7 @@ impl StackExecution for DeployStack {
           task_id: Uuid::new_v4(),
         })
         .await
-        .map_err(|e| e.error)?;
+        .map_err(|e| e.error)?
     } else {
       let req =
         ExecuteRequest::DeployStackIfChanged(DeployStackIfChanged {
@@ -342,10 +476,10 @@ impl StackExecution for DeployStack {
           task_id: Uuid::new_v4(),
         })
         .await
-        .map_err(|e| e.error)?;
-    }
+        .map_err(|e| e.error)?
+    };
 
-    Ok(())
+    Ok(Some(update))
   }
 }
 
@@ -354,30 +488,49 @@ impl StackExecution for DeployStack {
 pub enum StackWebhookOption {
   Refresh,
   Deploy,
+  Preview,
 }
 
-pub async fn handle_stack_webhook<B: super::ExtractBranch>(
+pub async fn handle_stack_webhook<
+  B: ExtractBranch
+    + ExtractChanges
+    + ExtractTag
+    + ExtractPullRequest
+    + ReportCommitStatus,
+>(
   option: StackWebhookOption,
   stack: Stack,
   body: String,
 ) -> anyhow::Result<()> {
   match option {
     StackWebhookOption::Refresh => {
-      handle_stack_webhook_inner::<B, RefreshStackCache>(stack, body)
-        .await
+      handle_stack_webhook_inner::<B, RefreshStackCache>(
+        stack, body, false,
+      )
+      .await
     }
     StackWebhookOption::Deploy => {
-      handle_stack_webhook_inner::<B, DeployStack>(stack, body).await
+      let commit_status = stack.config.webhook_commit_status;
+      handle_stack_webhook_inner::<B, DeployStack>(
+        stack,
+        body,
+        commit_status,
+      )
+      .await
+    }
+    StackWebhookOption::Preview => {
+      handle_stack_preview_webhook::<B>(stack, body).await
     }
   }
 }
 
 pub async fn handle_stack_webhook_inner<
-  B: super::ExtractBranch,
+  B: ExtractBranch + ExtractChanges + ExtractTag + ReportCommitStatus,
   E: StackExecution,
 >(
   stack: Stack,
   body: String,
+  commit_status: bool,
 ) -> anyhow::Result<()> {
   if !stack.config.webhook_enabled {
     return Ok(());
@@ -390,6 +543,104 @@ pub async fn handle_stack_webhook_inner<
   let _lock = lock.lock().await;
 
   // Use the correct target branch when using linked repo.
+  let repo = if stack.config.linked_repo.is_empty() {
+    None
+  } else {
+    Some(
+      resource::get::<Repo>(&stack.config.linked_repo)
+        .await
+        .context("Failed to find 'linked_repo'")?,
+    )
+  };
+  let clone_args: RepoExecutionArgs =
+    repo.as_ref().map(Into::into).unwrap_or((&stack).into());
+
+  // Deploy the pushed tag, without changing the config.
+  let tag = if stack.config.webhook_trigger == WebhookTrigger::Tag {
+    if repo.is_some() {
+      return Err(anyhow!(
+        "Tag webhook trigger is not supported with 'linked_repo'"
+      ));
+    }
+    let Some(tag) =
+      tag_matches::<B>(&body, &stack.config.webhook_tag_pattern)?
+    else {
+      return Ok(());
+    };
+    Some(tag)
+  } else {
+    let branch = repo
+      .as_ref()
+      .map(|repo| repo.config.branch.as_str())
+      .unwrap_or(&stack.config.branch);
+
+    if !B::branch_matches(&body, branch)? {
+      return Ok(());
+    }
+
+    if !paths_match::<B>(
+      &body,
+      &stack.config.webhook_include_paths,
+      &stack.config.webhook_exclude_paths,
+      clone_args.clone(),
+    )
+    .await?
+    {
+      return Ok(());
+    }
+
+    None
+  };
+
+  let status = CommitStatusReporter::<B>::new::<Stack>(
+    commit_status,
+    &body,
+    clone_args,
+    &stack.id,
+    &stack.name,
+  )
+  .await;
+  status.pending().await;
+  let res = E::resolve(stack, tag).await.map_err(|e| e.error);
+  status
+    .finished(res.as_ref().ok().and_then(Option::as_ref))
+    .await;
+  res.map(|_| ())
+}
+
+async fn handle_stack_preview_webhook<B: ExtractPullRequest>(
+  stack: Stack,
+  body: String,
+) -> anyhow::Result<()> {
+  if !stack.config.webhook_enabled || !stack.config.preview_enabled {
+    return Ok(());
+  }
+
+  let Some(pr) = B::extract_pull_request(&body)? else {
+    debug!("Ignoring webhook | not a handled pull request event");
+    return Ok(());
+  };
+
+  // Queue subsequent events for the same pull request,
+  // so the preview is never created twice.
+  let lock = stack_locks()
+    .get_or_insert_default(&format!("{}-pr-{}", stack.id, pr.number))
+    .await;
+  let _lock = lock.lock().await;
+
+  if pr.action == PullRequestAction::Close {
+    return delete_stack_preview(&stack.id, pr.number).await;
+  }
+
+  // Pull requests from forks could run arbitrary compose files.
+  if pr.from_fork {
+    warn!(
+      "Ignoring webhook | previews are not deployed for pull requests from forks (PR #{})",
+      pr.number
+    );
+    return Ok(());
+  }
+
   let branch = if stack.config.linked_repo.is_empty() {
     stack.config.branch.clone()
   } else {
@@ -399,12 +650,21 @@ pub async fn handle_stack_webhook_inner<
       .config
       .branch
   };
-
-  if !B::branch_matches(&body, &branch)? {
+  if pr.target_branch != branch {
+    debug!(
+      "Ignoring webhook | pull request into branch '{}' does not match expected branch '{branch}'",
+      pr.target_branch
+    );
     return Ok(());
   }
 
-  E::resolve(stack).await.map_err(|e| e.error)
+  let preview =
+    upsert_stack_preview(&stack, pr.number, &pr.source_branch)
+      .await?;
+  <DeployStack as StackExecution>::resolve(preview, None)
+    .await
+    .map_err(|e| e.error)
+    .map(|_| ())
 }
 
 // ======
@@ -423,23 +683,30 @@ fn sync_locks() -> &'static ListenerLockCache {
 }
 
 pub trait SyncExecution {
-  async fn resolve(sync: ResourceSync) -> anyhow::Result<()>;
+  /// Returns the Update, if the execution creates one.
+  async fn resolve(
+    sync: ResourceSync,
+  ) -> anyhow::Result<Option<Update>>;
 }
 
 impl SyncExecution for RefreshResourceSyncPending {
-  async fn resolve(sync: ResourceSync) -> anyhow::Result<()> {
+  async fn resolve(
+    sync: ResourceSync,
+  ) -> anyhow::Result<Option<Update>> {
     RefreshResourceSyncPending { sync: sync.id }
       .resolve(&WriteArgs {
         user: git_webhook_user().to_owned(),
       })
       .await
       .map_err(|e| e.error)?;
-    Ok(())
+    Ok(None)
   }
 }
 
 impl SyncExecution for RunSync {
-  async fn resolve(sync: ResourceSync) -> anyhow::Result<()> {
+  async fn resolve(
+    sync: ResourceSync,
+  ) -> anyhow::Result<Option<Update>> {
     let user = git_webhook_user().to_owned();
     let req = ExecuteRequest::RunSync(RunSync {
       sync: sync.id,
@@ -450,7 +717,7 @@ impl SyncExecution for RunSync {
     let ExecuteRequest::RunSync(req) = req else {
       unreachable!()
     };
-    req
+    let update = req
       .resolve(&ExecuteArgs {
         user,
         update,
@@ -458,7 +725,7 @@ impl SyncExecution for RunSync {
       })
       .await
       .map_err(|e| e.error)?;
-    Ok(())
+    Ok(Some(update))
   }
 }
 
@@ -469,7 +736,9 @@ pub enum SyncWebhookOption {
   Sync,
 }
 
-pub async fn handle_sync_webhook<B: super::ExtractBranch>(
+pub async fn handle_sync_webhook<
+  B: ExtractBranch + ExtractChanges + ReportCommitStatus,
+>(
   option: SyncWebhookOption,
   sync: ResourceSync,
   body: String,
@@ -477,22 +746,29 @@ pub async fn handle_sync_webhook<B: super::ExtractBranch>(
   match option {
     SyncWebhookOption::Refresh => {
       handle_sync_webhook_inner::<B, RefreshResourceSyncPending>(
-        sync, body,
+        sync, body, false,
       )
       .await
     }
     SyncWebhookOption::Sync => {
-      handle_sync_webhook_inner::<B, RunSync>(sync, body).await
+      let commit_status = sync.config.webhook_commit_status;
+      handle_sync_webhook_inner::<B, RunSync>(
+        sync,
+        body,
+        commit_status,
+      )
+      .await
     }
   }
 }
 
 async fn handle_sync_webhook_inner<
-  B: super::ExtractBranch,
+  B: ExtractBranch + ExtractChanges + ReportCommitStatus,
   E: SyncExecution,
 >(
   sync: ResourceSync,
   body: String,
+  commit_status: bool,
 ) -> anyhow::Result<()> {
   if !sync.config.webhook_enabled {
     return Ok(());
@@ -505,21 +781,51 @@ async fn handle_sync_webhook_inner<
   let _lock = lock.lock().await;
 
   // Use the correct target branch when using linked repo.
-  let branch = if sync.config.linked_repo.is_empty() {
-    sync.config.branch.clone()
+  let repo = if sync.config.linked_repo.is_empty() {
+    None
   } else {
-    resource::get::<Repo>(&sync.config.linked_repo)
-      .await
-      .context("Failed to find 'linked_repo'")?
-      .config
-      .branch
+    Some(
+      resource::get::<Repo>(&sync.config.linked_repo)
+        .await
+        .context("Failed to find 'linked_repo'")?,
+    )
   };
+  let branch = repo
+    .as_ref()
+    .map(|repo| repo.config.branch.as_str())
+    .unwrap_or(&sync.config.branch);
+
+  if !B::branch_matches(&body, branch)? {
+    return Ok(());
+  }
 
-  if !B::branch_matches(&body, &branch)? {
+  let clone_args: RepoExecutionArgs =
+    repo.as_ref().map(Into::into).unwrap_or((&sync).into());
+  if !paths_match::<B>(
+    &body,
+    &sync.config.webhook_include_paths,
+    &sync.config.webhook_exclude_paths,
+    clone_args.clone(),
+  )
+  .await?
+  {
     return Ok(());
   }
 
-  E::resolve(sync).await
+  let status = CommitStatusReporter::<B>::new::<ResourceSync>(
+    commit_status,
+    &body,
+    clone_args,
+    &sync.id,
+    &sync.name,
+  )
+  .await;
+  status.pending().await;
+  let res = E::resolve(sync).await;
+  status
+    .finished(res.as_ref().ok().and_then(Option::as_ref))
+    .await;
+  res.map(|_| ())
 }
 
 // ===========
@@ -538,7 +844,7 @@ fn procedure_locks() -> &'static ListenerLockCache {
   PROCEDURE_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_procedure_webhook<B: super::ExtractBranch>(
+pub async fn handle_procedure_webhook<B: ExtractBranch>(
   procedure: Procedure,
   target_branch: &str,
   body: String,
@@ -594,7 +900,7 @@ fn action_locks() -> &'static ListenerLockCache {
   ACTION_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_action_webhook<B: super::ExtractBranch>(
+pub async fn handle_action_webhook<B: ExtractBranch>(
   action: Action,
   target_branch: &str,
   body: String,
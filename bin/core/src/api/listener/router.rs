@@ -15,7 +15,8 @@ use tracing::Instrument;
 use crate::{auth::GENERAL_RATE_LIMITER, resource::KomodoResource};
 
 use super::{
-  CustomSecret, ExtractBranch, VerifySecret,
+  CustomSecret, ExtractBranch, ExtractChanges, ExtractPullRequest,
+  ExtractTag, ReportCommitStatus, VerifySecret,
   resources::{
     RepoWebhookOption, StackWebhookOption, SyncWebhookOption,
     handle_action_webhook, handle_build_webhook,
@@ -46,7 +47,14 @@ fn default_branch() -> String {
   String::from("main")
 }
 
-pub fn router<P: VerifySecret + ExtractBranch>() -> Router {
+pub fn router<
+  P: VerifySecret
+    + ExtractBranch
+    + ExtractChanges
+    + ExtractTag
+    + ExtractPullRequest
+    + ReportCommitStatus,
+>() -> Router {
   Router::new()
   .route(
     "/build/{id}",
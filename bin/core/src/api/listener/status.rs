@@ -0,0 +1,206 @@
+use std::{marker::PhantomData, sync::OnceLock};
+
+use anyhow::{Context, anyhow};
+use komodo_client::entities::{
+  RepoExecutionArgs, resource_link, update::Update,
+};
+
+use crate::{
+  config::core_config, helpers::git_token, resource::KomodoResource,
+};
+
+use super::{ExtractChanges, ReportCommitStatus, is_null_commit};
+
+#[derive(Debug, Clone, Copy)]
+pub enum CommitState {
+  Pending,
+  Success,
+  Failure,
+}
+
+pub struct CommitStatus<'a> {
+  pub state: CommitState,
+  /// Link to the resource / update in Komodo
+  pub target_url: &'a str,
+  pub description: &'a str,
+  /// Identifies the status on the commit, eg `komodo/build/my-build`
+  pub context: &'a str,
+}
+
+/// Reports the result of webhook triggered executions
+/// back to the git provider as a commit status.
+/// Failures to report are only logged, and never fail the execution.
+pub struct CommitStatusReporter<B> {
+  /// `None` if reporting is disabled, or not possible.
+  target: Option<StatusTarget>,
+  /// The integration is only used for its associated fns,
+  /// so `fn() -> B` keeps the reporter Send + Sync.
+  _integration: PhantomData<fn() -> B>,
+}
+
+struct StatusTarget {
+  args: RepoExecutionArgs,
+  token: String,
+  sha: String,
+  context: String,
+  resource_url: String,
+}
+
+impl<B: ExtractChanges + ReportCommitStatus> CommitStatusReporter<B> {
+  /// If enabled, but the commit status can't be reported,
+  /// eg. there is no git account configured to authenticate with,
+  /// logs a warning and reporting is skipped.
+  pub async fn new<R: KomodoResource>(
+    enabled: bool,
+    body: &str,
+    args: RepoExecutionArgs,
+    id: &str,
+    name: &str,
+  ) -> CommitStatusReporter<B> {
+    let target = if enabled {
+      StatusTarget::new::<B, R>(body, args, id, name)
+        .await
+        .inspect_err(|e| {
+          warn!("Cannot report webhook commit status | {e:#}")
+        })
+        .ok()
+    } else {
+      None
+    };
+    CommitStatusReporter {
+      target,
+      _integration: PhantomData,
+    }
+  }
+
+  pub async fn pending(&self) {
+    let Some(target) = &self.target else {
+      return;
+    };
+    target
+      .report::<B>(CommitStatus {
+        state: CommitState::Pending,
+        target_url: &target.resource_url,
+        description: "Running on Komodo",
+        context: &target.context,
+      })
+      .await
+  }
+
+  /// Reports success / failure of the execution.
+  /// If there is no update, the execution failed to start.
+  pub async fn finished(&self, update: Option<&Update>) {
+    let Some(target) = &self.target else {
+      return;
+    };
+    let update_url;
+    let (state, target_url, description) = match update {
+      Some(update) => {
+        update_url =
+          format!("{}/updates/{}", core_config().host, update.id);
+        if update.success {
+          (CommitState::Success, &update_url, "Succeeded on Komodo")
+        } else {
+          (CommitState::Failure, &update_url, "Failed on Komodo")
+        }
+      }
+      None => (
+        CommitState::Failure,
+        &target.resource_url,
+        "Failed to run on Komodo",
+      ),
+    };
+    target
+      .report::<B>(CommitStatus {
+        state,
+        target_url,
+        description,
+        context: &target.context,
+      })
+      .await
+  }
+}
+
+impl StatusTarget {
+  async fn new<B: ExtractChanges, R: KomodoResource>(
+    body: &str,
+    mut args: RepoExecutionArgs,
+    id: &str,
+    name: &str,
+  ) -> anyhow::Result<StatusTarget> {
+    let sha = B::extract_changes(body)?
+      .after
+      .filter(|sha| !is_null_commit(sha))
+      .context("No pushed commit in webhook body")?;
+    let account =
+      args.account.clone().context("No git account configured")?;
+    let token =
+      git_token(&args.provider, &account, |https| args.https = https)
+        .await?
+        .with_context(|| {
+          format!(
+            "No token found for git account {account} on {}",
+            args.provider
+          )
+        })?;
+    let resource_type = R::resource_type();
+    Ok(StatusTarget {
+      args,
+      token,
+      sha,
+      context: format!(
+        "komodo/{}/{name}",
+        resource_type.toml_header()
+      ),
+      resource_url: resource_link(
+        &core_config().host,
+        resource_type,
+        id,
+      ),
+    })
+  }
+
+  async fn report<B: ReportCommitStatus>(
+    &self,
+    status: CommitStatus<'_>,
+  ) {
+    let state = status.state;
+    if let Err(e) = self.send::<B>(status).await {
+      warn!(
+        "Failed to report {state:?} commit status for {} | {e:#}",
+        self.context
+      );
+    }
+  }
+
+  async fn send<B: ReportCommitStatus>(
+    &self,
+    status: CommitStatus<'_>,
+  ) -> anyhow::Result<()> {
+    let Some(request) = B::commit_status_request(
+      &self.args,
+      &self.token,
+      &self.sha,
+      &status,
+    )?
+    else {
+      return Ok(());
+    };
+    let response = request
+      .send()
+      .await
+      .context("Failed to send commit status")?;
+    let status = response.status();
+    if status.is_success() {
+      Ok(())
+    } else {
+      let text = response.text().await.unwrap_or_default();
+      Err(anyhow!("{status} | {text}"))
+    }
+  }
+}
+
+pub fn http_client() -> &'static reqwest::Client {
+  static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+  CLIENT.get_or_init(reqwest::Client::new)
+}
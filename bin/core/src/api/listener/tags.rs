@@ -0,0 +1,129 @@
+use anyhow::Context;
+use komodo_client::entities::Version;
+
+use crate::helpers::matcher::Matcher;
+
+use super::ExtractTag;
+
+/// Returns the pushed tag if it matches `pattern`.
+/// An empty pattern matches any tag.
+pub fn tag_matches<B: ExtractTag>(
+  body: &str,
+  pattern: &str,
+) -> anyhow::Result<Option<String>> {
+  let Some(tag) = B::extract_tag(body)? else {
+    debug!("Ignoring webhook | push was not to a tag");
+    return Ok(None);
+  };
+  if pattern.is_empty()
+    || Matcher::new(pattern)
+      .context("Invalid webhook tag pattern")?
+      .is_match(&tag)
+  {
+    Ok(Some(tag))
+  } else {
+    debug!(
+      "Ignoring webhook | pushed tag '{tag}' does not match tag pattern '{pattern}'"
+    );
+    Ok(None)
+  }
+}
+
+/// Parses the semver out of a tag, ignoring
+/// any prefix or pre-release / build suffix.
+/// eg `v1.2.3` or `release-1.2.3-rc.1` -> `1.2.3`
+pub fn version_from_tag(tag: &str) -> anyhow::Result<Version> {
+  let version = tag
+    .trim_start_matches(|c: char| !c.is_ascii_digit())
+    .split(|c: char| !c.is_ascii_digit() && c != '.')
+    .next()
+    .unwrap_or_default()
+    .trim_end_matches('.');
+  Version::try_from(version)
+    .with_context(|| format!("No version in tag '{tag}'"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Treats the body as the pushed tag, empty for a branch push.
+  struct Tag;
+
+  impl ExtractTag for Tag {
+    fn extract_tag(body: &str) -> anyhow::Result<Option<String>> {
+      Ok((!body.is_empty()).then(|| body.to_string()))
+    }
+  }
+
+  fn version(major: i32, minor: i32, patch: i32) -> Version {
+    Version {
+      major,
+      minor,
+      patch,
+    }
+  }
+
+  #[test]
+  fn parses_version_from_tag() {
+    assert_eq!(version_from_tag("v1.2.3").unwrap(), version(1, 2, 3));
+    assert_eq!(
+      version_from_tag("release-1.2.3-rc.1").unwrap(),
+      version(1, 2, 3)
+    );
+    assert_eq!(version_from_tag("v1.2").unwrap(), version(1, 2, 0));
+    assert_eq!(
+      version_from_tag("1.2.3+build.4").unwrap(),
+      version(1, 2, 3)
+    );
+  }
+
+  #[test]
+  fn rejects_tag_without_version() {
+    assert!(version_from_tag("latest").is_err());
+    assert!(version_from_tag("release-").is_err());
+    assert!(version_from_tag("").is_err());
+  }
+
+  #[test]
+  fn matches_any_tag_with_empty_pattern() {
+    assert_eq!(
+      tag_matches::<Tag>("v1.2.3", "").unwrap().as_deref(),
+      Some("v1.2.3")
+    );
+  }
+
+  #[test]
+  fn ignores_branch_push() {
+    assert!(tag_matches::<Tag>("", "").unwrap().is_none());
+    assert!(tag_matches::<Tag>("", "v*").unwrap().is_none());
+  }
+
+  #[test]
+  fn matches_glob_pattern() {
+    assert_eq!(
+      tag_matches::<Tag>("v1.2.3", "v*").unwrap().as_deref(),
+      Some("v1.2.3")
+    );
+    assert_eq!(
+      tag_matches::<Tag>("release-1.2.3-rc.1", "release-*-rc.*")
+        .unwrap()
+        .as_deref(),
+      Some("release-1.2.3-rc.1")
+    );
+    assert!(
+      tag_matches::<Tag>("release-1.2.3", "v*").unwrap().is_none()
+    );
+  }
+
+  #[test]
+  fn matches_regex_pattern() {
+    let pattern = r"\^v\d+\.\d+\.\d+$\";
+    assert!(tag_matches::<Tag>("v1.2.3", pattern).unwrap().is_some());
+    assert!(
+      tag_matches::<Tag>("v1.2.3-rc.1", pattern)
+        .unwrap()
+        .is_none()
+    );
+  }
+}
@@ -1,7 +1,9 @@
-use anyhow::Context;
+use anyhow::{Context, anyhow};
+use database::mungos::by_id::find_one_by_id;
 use komodo_client::{
   api::read::*,
   entities::{
+    Operation, ResourceTarget, ResourceTargetVariant,
     action::{
       Action, ActionActionState, ActionListItem, ActionSortBy,
       ActionState,
@@ -12,10 +14,13 @@ use komodo_client::{
 use mogh_resolver::Resolve;
 
 use crate::{
-  helpers::query::{get_action_state, get_all_tags},
+  api::execute::action::action_run_output,
+  helpers::query::{
+    get_action_state, get_all_tags, get_latest_update,
+  },
   permission::get_check_permissions,
   resource,
-  state::{action_state_cache, action_states},
+  state::{action_state_cache, action_states, db_client},
 };
 
 use super::{ReadArgs, list_limit};
@@ -147,6 +152,60 @@ impl Resolve<ReadArgs> for GetActionActionState {
   }
 }
 
+impl Resolve<ReadArgs> for GetActionArguments {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<GetActionArgumentsResponse> {
+    let action = get_check_permissions::<Action>(
+      &self.action,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    Ok(action.config.argument_schema)
+  }
+}
+
+impl Resolve<ReadArgs> for GetActionOutput {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<GetActionOutputResponse> {
+    let action = get_check_permissions::<Action>(
+      &self.action,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let update = match self.update_id {
+      Some(update_id) if !update_id.is_empty() => {
+        let update = find_one_by_id(&db_client().updates, &update_id)
+          .await
+          .context("Failed to query db for update")?
+          .context("No update exists with given id")?;
+        if update.operation != Operation::RunAction
+          || update.target != ResourceTarget::Action(action.id)
+        {
+          return Err(
+            anyhow!("Update is not a run of the given Action").into(),
+          );
+        }
+        Some(update)
+      }
+      _ => {
+        get_latest_update(
+          ResourceTargetVariant::Action,
+          &action.id,
+          Operation::RunAction,
+        )
+        .await?
+      }
+    };
+    Ok(update.as_ref().and_then(action_run_output))
+  }
+}
+
 impl Resolve<ReadArgs> for GetActionsSummary {
   async fn resolve(
     self,
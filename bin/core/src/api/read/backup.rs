@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use komodo_client::api::read::*;
+use mogh_error::{AddStatusCode as _, AddStatusCodeError};
+use mogh_resolver::Resolve;
+use reqwest::StatusCode;
+
+use crate::{
+  config::core_config,
+  helpers::validations::validate_backup_folder_name,
+  state::db_client,
+};
+
+use super::ReadArgs;
+
+impl Resolve<ReadArgs> for ListBackupItems {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListBackupItemsResponse> {
+    if !user.admin {
+      return Err(
+        anyhow!("Only Admins can list backup items")
+          .status_code(StatusCode::FORBIDDEN),
+      );
+    }
+
+    if let Some(backup) = &self.backup {
+      validate_backup_folder_name(backup)
+        .status_code(StatusCode::BAD_REQUEST)?;
+    }
+
+    let contents = database::utils::list_backup_items(
+      &db_client().db,
+      &core_config().backups_folder,
+      self.backup.as_deref().map(Path::new),
+    )
+    .await?;
+
+    Ok(ListBackupItemsResponse {
+      backup: contents
+        .restore_folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default(),
+      items: contents.items,
+    })
+  }
+}
@@ -0,0 +1,106 @@
+use anyhow::anyhow;
+use komodo_client::{
+  api::read::*,
+  entities::{
+    file::{FileDownload, FileEntry},
+    permission::PermissionLevel,
+    server::{Server, ServerState},
+    user::User,
+  },
+};
+use mogh_resolver::Resolve;
+use periphery_client::api as periphery;
+
+use crate::{
+  api::read::ReadArgs, config::core_config,
+  helpers::periphery_client, permission::get_check_permissions,
+  state::server_status_cache,
+};
+
+impl Resolve<ReadArgs> for ListContainerDirectory {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<Vec<FileEntry>> {
+    let server = get_check_server(&self.server, user).await?;
+    let res = periphery_client(&server)
+      .await?
+      .request(periphery::file::ListContainerDirectory {
+        container: self.container,
+        path: self.path,
+      })
+      .await?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetContainerFile {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<FileDownload> {
+    let server = get_check_server(&self.server, user).await?;
+    let res = periphery_client(&server)
+      .await?
+      .request(periphery::file::ReadContainerFile {
+        container: self.container,
+        path: self.path,
+        max_size: core_config().max_file_transfer_size(),
+      })
+      .await?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for ListServerDirectory {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<Vec<FileEntry>> {
+    let server = get_check_server(&self.server, user).await?;
+    let res = periphery_client(&server)
+      .await?
+      .request(periphery::file::ListHostDirectory { path: self.path })
+      .await?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetServerFile {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<FileDownload> {
+    let server = get_check_server(&self.server, user).await?;
+    let res = periphery_client(&server)
+      .await?
+      .request(periphery::file::ReadHostFile {
+        path: self.path,
+        max_size: core_config().max_file_transfer_size(),
+      })
+      .await?;
+    Ok(res)
+  }
+}
+
+async fn get_check_server(
+  server: &str,
+  user: &User,
+) -> anyhow::Result<Server> {
+  let server = get_check_permissions::<Server>(
+    server,
+    user,
+    PermissionLevel::Read.inspect(),
+  )
+  .await?;
+  let cache = server_status_cache()
+    .get_or_insert_default(&server.id)
+    .await;
+  if cache.state != ServerState::Ok {
+    return Err(anyhow!(
+      "Cannot browse files: server is {:?}",
+      cache.state
+    ));
+  }
+  Ok(server)
+}
@@ -41,15 +41,21 @@ use super::Variant;
 mod action;
 mod alert;
 mod alerter;
+mod backup;
 mod build;
 mod builder;
 mod deployment;
 mod docker;
+mod file;
 mod onboarding_key;
 mod permission;
 mod procedure;
 mod provider;
+mod registry;
 mod repo;
+mod revision;
+mod sbom;
+mod scan;
 mod schedule;
 mod server;
 mod stack;
@@ -118,6 +124,7 @@ enum ReadRequest {
   GetServer(GetServer),
   GetServerState(GetServerState),
   GetPeripheryInformation(GetPeripheryInformation),
+  ListServerPortAllocations(ListServerPortAllocations),
   GetServerActionState(GetServerActionState),
   ListServers(ListServers),
   ListFullServers(ListFullServers),
@@ -153,6 +160,12 @@ enum ReadRequest {
   #[serde(alias = "InspectDockerVolume")]
   InspectVolume(InspectVolume),
 
+  // ==== FILE ====
+  ListContainerDirectory(ListContainerDirectory),
+  GetContainerFile(GetContainerFile),
+  ListServerDirectory(ListServerDirectory),
+  GetServerFile(GetServerFile),
+
   // ==== SERVER STATS ====
   GetSystemInformation(GetSystemInformation),
   GetSystemStats(GetSystemStats),
@@ -173,6 +186,7 @@ enum ReadRequest {
   ListAllStackServices(ListAllStackServices),
   ListCommonStackExtraArgs(ListCommonStackExtraArgs),
   ListCommonStackBuildExtraArgs(ListCommonStackBuildExtraArgs),
+  ListStackPreviews(ListStackPreviews),
 
   // ==== DEPLOYMENT ====
   GetDeploymentsSummary(GetDeploymentsSummary),
@@ -194,6 +208,9 @@ enum ReadRequest {
   GetBuildActionState(GetBuildActionState),
   GetBuildMonthlyStats(GetBuildMonthlyStats),
   ListBuildVersions(ListBuildVersions),
+  ListBuildSboms(ListBuildSboms),
+  GetBuildSbom(GetBuildSbom),
+  DiffBuildSboms(DiffBuildSboms),
   ListBuilds(ListBuilds),
   ListFullBuilds(ListFullBuilds),
   ListCommonBuildExtraArgs(ListCommonBuildExtraArgs),
@@ -216,6 +233,8 @@ enum ReadRequest {
   GetActionsSummary(GetActionsSummary),
   GetAction(GetAction),
   GetActionActionState(GetActionActionState),
+  GetActionArguments(GetActionArguments),
+  GetActionOutput(GetActionOutput),
   ListActions(ListActions),
   ListFullActions(ListFullActions),
 
@@ -241,6 +260,11 @@ enum ReadRequest {
   ListAlerters(ListAlerters),
   ListFullAlerters(ListFullAlerters),
 
+  // ==== REVISION ====
+  ListResourceRevisions(ListResourceRevisions),
+  GetResourceRevision(GetResourceRevision),
+  DiffResourceRevisions(DiffResourceRevisions),
+
   // ==== TOML ====
   ExportAllResourcesToToml(ExportAllResourcesToToml),
   ExportResourcesToToml(ExportResourcesToToml),
@@ -271,6 +295,10 @@ enum ReadRequest {
   ListAlerts(ListAlerts),
   GetAlert(GetAlert),
 
+  // ==== IMAGE SCAN ====
+  ListImageScans(ListImageScans),
+  GetImageScan(GetImageScan),
+
   // ==== VARIABLE ====
   GetVariable(GetVariable),
   ListVariables(ListVariables),
@@ -282,9 +310,14 @@ enum ReadRequest {
   GetImageRegistryAccount(GetImageRegistryAccount),
   #[serde(alias = "ListDockerRegistryAccounts")]
   ListImageRegistryAccounts(ListImageRegistryAccounts),
+  ListRegistryRepositories(ListRegistryRepositories),
+  ListRegistryTags(ListRegistryTags),
 
   // ==== ONBOARDING KEY ====
   ListOnboardingKeys(ListOnboardingKeys),
+
+  // ==== BACKUP ====
+  ListBackupItems(ListBackupItems),
 }
 
 pub fn router() -> Router {
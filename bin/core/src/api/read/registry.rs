@@ -0,0 +1,123 @@
+use anyhow::{Context, anyhow};
+use futures_util::{StreamExt, stream};
+use komodo_client::{
+  api::read::{
+    ListRegistryRepositories, ListRegistryRepositoriesResponse,
+    ListRegistryTags, ListRegistryTagsResponse,
+  },
+  entities::registry::{RegistryTag, RegistryTagResource},
+};
+use mogh_resolver::Resolve;
+
+use crate::{
+  registry::{
+    REGISTRY_CONCURRENCY, RegistryClient,
+    tags::is_build_tag,
+    usage::{image_usage, normalize_image_name},
+  },
+  state::all_resources_cache,
+};
+
+use super::ReadArgs;
+
+impl Resolve<ReadArgs> for ListRegistryRepositories {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListRegistryRepositoriesResponse> {
+    if !user.admin {
+      return Err(
+        anyhow!("Only admins can browse image registries").into(),
+      );
+    }
+    let client =
+      RegistryClient::new(&self.domain, &self.account).await?;
+    let mut repositories = client.list_repositories().await?;
+    repositories.sort();
+    Ok(repositories)
+  }
+}
+
+impl Resolve<ReadArgs> for ListRegistryTags {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListRegistryTagsResponse> {
+    if !user.admin {
+      return Err(
+        anyhow!("Only admins can browse image registries").into(),
+      );
+    }
+    let repository = self.repository.trim_matches('/');
+    let client =
+      RegistryClient::new(&self.domain, &self.account).await?;
+    let tags = client.list_tags(repository).await?;
+
+    let image_name =
+      normalize_image_name(&format!("{}/{repository}", self.domain));
+    let all = all_resources_cache().load_full();
+    let builds = all
+      .builds
+      .values()
+      .filter(|build| {
+        build
+          .get_image_names()
+          .iter()
+          .any(|name| normalize_image_name(name) == image_name)
+      })
+      .collect::<Vec<_>>();
+    let mut usage = image_usage().await.remove(&image_name);
+
+    let mut tags = stream::iter(tags.into_iter().map(|tag| {
+      let client = &client;
+      async move {
+        let info = client
+          .get_image_info(repository, &tag)
+          .await
+          .with_context(|| {
+            format!("Failed to get image info for {tag}")
+          })
+          .inspect_err(|e| warn!("{e:#}"))
+          .unwrap_or_default();
+        (tag, info)
+      }
+    }))
+    .buffer_unordered(REGISTRY_CONCURRENCY)
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .map(|(tag, info)| {
+      let mut builds = builds
+        .iter()
+        .filter(|build| is_build_tag(build, &tag))
+        .map(|build| RegistryTagResource {
+          name: build.name.clone(),
+          id: build.id.clone(),
+        })
+        .collect::<Vec<_>>();
+      builds.sort();
+      let usage = usage
+        .as_mut()
+        .and_then(|usage| usage.remove(&tag))
+        .unwrap_or_default();
+      RegistryTag {
+        tag,
+        digest: info.digest,
+        size: info.size,
+        created: info.created,
+        platforms: info.platforms,
+        builds,
+        deployments: usage.deployments.into_iter().collect(),
+        stacks: usage.stacks.into_iter().collect(),
+      }
+    })
+    .collect::<Vec<_>>();
+
+    // Newest first
+    tags.sort_by(|a, b| {
+      b.created.cmp(&a.created).then_with(|| a.tag.cmp(&b.tag))
+    });
+
+    Ok(tags)
+  }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, anyhow};
+use database::mungos::{
+  by_id::find_one_by_id,
+  find::find_collect,
+  mongodb::{bson::doc, options::FindOptions},
+};
+use komodo_client::{
+  api::read::{
+    DiffResourceRevisions, DiffResourceRevisionsResponse,
+    GetResourceRevision, GetResourceRevisionResponse,
+    ListResourceRevisions, ListResourceRevisionsResponse,
+  },
+  entities::{
+    JsonObject, ResourceTarget,
+    action::Action,
+    alerter::Alerter,
+    build::Build,
+    builder::Builder,
+    deployment::Deployment,
+    permission::PermissionLevel,
+    procedure::Procedure,
+    repo::Repo,
+    revision::{ResourceRevision, RevisionFieldDiff},
+    server::Server,
+    stack::Stack,
+    swarm::Swarm,
+    sync::ResourceSync,
+  },
+};
+use mogh_resolver::Resolve;
+
+use crate::{
+  permission::check_user_target_access,
+  resource::{
+    self, KomodoResource, config_object, diff_configs,
+    revision_filter,
+  },
+  state::db_client,
+};
+
+use super::ReadArgs;
+
+impl Resolve<ReadArgs> for ListResourceRevisions {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListResourceRevisionsResponse> {
+    check_user_target_access(
+      &self.target,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let revisions = find_collect(
+      &db_client().resource_revisions,
+      revision_filter(&self.target),
+      FindOptions::builder()
+        .sort(doc! { "ts": -1 })
+        // The configs are only returned by GetResourceRevision.
+        .projection(doc! { "config": 0 })
+        .limit(self.limit)
+        .build(),
+    )
+    .await
+    .context("failed to get resource revisions from db")?;
+    Ok(revisions)
+  }
+}
+
+impl Resolve<ReadArgs> for GetResourceRevision {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<GetResourceRevisionResponse> {
+    let revision = get_revision(&self.id).await?;
+    check_user_target_access(
+      &revision.target,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    Ok(revision)
+  }
+}
+
+impl Resolve<ReadArgs> for DiffResourceRevisions {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<DiffResourceRevisionsResponse> {
+    let from = get_revision(&self.from).await?;
+    check_user_target_access(
+      &from.target,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let to = match self.to {
+      Some(to) => {
+        let to = get_revision(&to).await?;
+        if to.target != from.target {
+          return Err(
+            anyhow!("Revisions are not of the same resource").into(),
+          );
+        }
+        Some(to.config)
+      }
+      None => None,
+    };
+    let diffs = match &from.target {
+      ResourceTarget::System(_) => {
+        return Err(anyhow!("System has no revisions").into());
+      }
+      ResourceTarget::Swarm(id) => {
+        diff_revision::<Swarm>(id, from.config, to).await?
+      }
+      ResourceTarget::Server(id) => {
+        diff_revision::<Server>(id, from.config, to).await?
+      }
+      ResourceTarget::Stack(id) => {
+        diff_revision::<Stack>(id, from.config, to).await?
+      }
+      ResourceTarget::Deployment(id) => {
+        diff_revision::<Deployment>(id, from.config, to).await?
+      }
+      ResourceTarget::Build(id) => {
+        diff_revision::<Build>(id, from.config, to).await?
+      }
+      ResourceTarget::Repo(id) => {
+        diff_revision::<Repo>(id, from.config, to).await?
+      }
+      ResourceTarget::Procedure(id) => {
+        diff_revision::<Procedure>(id, from.config, to).await?
+      }
+      ResourceTarget::Action(id) => {
+        diff_revision::<Action>(id, from.config, to).await?
+      }
+      ResourceTarget::ResourceSync(id) => {
+        diff_revision::<ResourceSync>(id, from.config, to).await?
+      }
+      ResourceTarget::Builder(id) => {
+        diff_revision::<Builder>(id, from.config, to).await?
+      }
+      ResourceTarget::Alerter(id) => {
+        diff_revision::<Alerter>(id, from.config, to).await?
+      }
+    };
+    Ok(diffs)
+  }
+}
+
+async fn get_revision(id: &str) -> anyhow::Result<ResourceRevision> {
+  find_one_by_id(&db_client().resource_revisions, id)
+    .await
+    .context("failed to query db for resource revision")?
+    .context("no resource revision found with given id")
+}
+
+/// Diffs the revision config against the `to` config,
+/// or the current resource config if not given.
+async fn diff_revision<T: KomodoResource>(
+  id: &str,
+  from: JsonObject,
+  to: Option<JsonObject>,
+) -> anyhow::Result<Vec<RevisionFieldDiff>> {
+  let to = match to {
+    Some(to) => to,
+    None => config_object(&resource::get::<T>(id).await?.config)?,
+  };
+  diff_configs::<T>(from, to)
+}
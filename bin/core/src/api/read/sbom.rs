@@ -0,0 +1,127 @@
+use anyhow::Context;
+use database::{
+  mungos::{
+    by_id::find_one_by_id,
+    find::find_collect,
+    mongodb::{
+      bson::doc,
+      options::{FindOneOptions, FindOptions},
+    },
+  },
+  utils::gunzip,
+};
+use komodo_client::{
+  api::read::{
+    DiffBuildSboms, DiffBuildSbomsResponse, GetBuildSbom,
+    GetBuildSbomResponse, ListBuildSboms, ListBuildSbomsResponse,
+  },
+  entities::{
+    Version,
+    build::Build,
+    permission::PermissionLevel,
+    sbom::{BuildSbom, SbomDiff},
+  },
+};
+use mogh_resolver::Resolve;
+
+use crate::{permission::get_check_permissions, state::db_client};
+
+use super::ReadArgs;
+
+impl Resolve<ReadArgs> for ListBuildSboms {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListBuildSbomsResponse> {
+    let build = get_check_permissions::<Build>(
+      &self.build,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let sboms = find_collect(
+      &db_client().build_sboms,
+      doc! { "build_id": &build.id },
+      FindOptions::builder()
+        .sort(doc! { "ts": -1 })
+        // These can be large, they are only returned by GetBuildSbom.
+        .projection(doc! {
+          "packages": 0,
+          "document": 0,
+          "document_gzip": 0,
+        })
+        .limit(self.limit)
+        .build(),
+    )
+    .await
+    .context("failed to get build sboms from db")?;
+    Ok(sboms)
+  }
+}
+
+impl Resolve<ReadArgs> for GetBuildSbom {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<GetBuildSbomResponse> {
+    let mut sbom = find_one_by_id(&db_client().build_sboms, &self.id)
+      .await
+      .context("failed to query db for build sbom")?
+      .context("no build sbom found with given id")?;
+    get_check_permissions::<Build>(
+      &sbom.build_id,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    if !sbom.document_gzip.is_empty() {
+      let document = gunzip(&std::mem::take(&mut sbom.document_gzip))
+        .await
+        .context("Failed to decompress SBOM document")?;
+      sbom.document = String::from_utf8(document)
+        .context("SBOM document is not valid UTF-8")?;
+    }
+    Ok(sbom)
+  }
+}
+
+impl Resolve<ReadArgs> for DiffBuildSboms {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<DiffBuildSbomsResponse> {
+    let build = get_check_permissions::<Build>(
+      &self.build,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let from = get_version_sbom(&build.id, self.from).await?;
+    let to = get_version_sbom(&build.id, self.to).await?;
+    Ok(SbomDiff::new(&from.packages, &to.packages))
+  }
+}
+
+/// Gets the most recent SBOM generated for the build version.
+async fn get_version_sbom(
+  build_id: &str,
+  version: Version,
+) -> anyhow::Result<BuildSbom> {
+  db_client()
+    .build_sboms
+    .find_one(doc! {
+      "build_id": build_id,
+      "version.major": version.major,
+      "version.minor": version.minor,
+      "version.patch": version.patch,
+    })
+    .with_options(
+      FindOneOptions::builder()
+        .sort(doc! { "ts": -1 })
+        .projection(doc! { "document": 0, "document_gzip": 0 })
+        .build(),
+    )
+    .await
+    .context("failed to query db for build sbom")?
+    .with_context(|| format!("no sbom found for version {version}"))
+}
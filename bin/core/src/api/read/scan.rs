@@ -0,0 +1,81 @@
+use anyhow::Context;
+use database::mungos::{
+  by_id::find_one_by_id,
+  find::find_collect,
+  mongodb::{bson::doc, options::FindOptions},
+};
+use komodo_client::{
+  api::read::{
+    GetImageScan, GetImageScanResponse, ListImageScans,
+    ListImageScansResponse,
+  },
+  entities::permission::PermissionLevel,
+};
+use mogh_resolver::Resolve;
+
+use crate::{
+  config::core_config,
+  permission::{
+    check_user_target_access, user_resource_target_query,
+  },
+  state::db_client,
+};
+
+use super::ReadArgs;
+
+const NUM_SCANS_PER_PAGE: u64 = 100;
+
+impl Resolve<ReadArgs> for ListImageScans {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListImageScansResponse> {
+    let query = user_resource_target_query(user, self.query)
+      .await?
+      .unwrap_or_default();
+
+    let scans = find_collect(
+      &db_client().image_scans,
+      query,
+      FindOptions::builder()
+        .sort(doc! { "ts": -1 })
+        // The findings can be large, they are only returned by GetImageScan.
+        .projection(doc! { "findings": 0 })
+        .limit(NUM_SCANS_PER_PAGE as i64)
+        .skip(self.page.saturating_mul(NUM_SCANS_PER_PAGE))
+        .build(),
+    )
+    .await
+    .context("failed to get image scans from db")?;
+
+    let next_page = if scans.len() < NUM_SCANS_PER_PAGE as usize {
+      None
+    } else {
+      Some((self.page + 1) as i64)
+    };
+
+    Ok(ListImageScansResponse { scans, next_page })
+  }
+}
+
+impl Resolve<ReadArgs> for GetImageScan {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<GetImageScanResponse> {
+    let scan = find_one_by_id(&db_client().image_scans, &self.id)
+      .await
+      .context("failed to query db for image scan")?
+      .context("no image scan found with given id")?;
+    if user.admin || core_config().transparent_mode {
+      return Ok(scan);
+    }
+    check_user_target_access(
+      &scan.target,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    Ok(scan)
+  }
+}
@@ -1,15 +1,20 @@
 use std::cmp::Ordering;
 
+use anyhow::Context;
+use database::mungos::find::find_collect;
 use futures_util::future::join_all;
 use komodo_client::{
   api::read::*,
   entities::{
     ResourceTarget,
     action::{Action, ActionQuerySpecifics},
+    deployment::Deployment,
     permission::PermissionLevel,
     procedure::{Procedure, ProcedureQuerySpecifics},
     resource::{ResourceQuery, TemplatesQueryBehavior},
     schedule::{Schedule, ScheduleSortBy},
+    stack::Stack,
+    tag::Tag,
   },
 };
 use mogh_resolver::Resolve;
@@ -18,6 +23,7 @@ use crate::{
   helpers::query::{get_all_tags, get_last_run_at},
   resource::list_full_for_user,
   schedule::get_schedule_item_info,
+  state::db_client,
 };
 
 use super::{ReadArgs, list_limit};
@@ -52,7 +58,7 @@ impl Resolve<ReadArgs> for ListSchedules {
           templates: TemplatesQueryBehavior::Include,
           tag_behavior: self.tag_behavior,
           tags: self.tags.clone(),
-          terms: self.terms,
+          terms: self.terms.clone(),
           specific: ProcedureQuerySpecifics {
             scheduled: Some(true),
             ..Default::default()
@@ -110,10 +116,16 @@ impl Resolve<ReadArgs> for ListSchedules {
     let (actions, procedures) =
       tokio::join!(join_all(actions), join_all(procedures));
 
+    let deploys =
+      list_scheduled_deploys(&self, &all_tags, args).await?;
+
     // The terms / scheduled filters are already applied
     // at the db level by the queries above.
-    let mut schedules =
-      actions.into_iter().chain(procedures).collect::<Vec<_>>();
+    let mut schedules = actions
+      .into_iter()
+      .chain(procedures)
+      .chain(deploys)
+      .collect::<Vec<_>>();
 
     // The schedules are composed in memory across resource types,
     // so all matching schedules are collected and sorted
@@ -158,3 +170,86 @@ impl Resolve<ReadArgs> for ListSchedules {
     Ok(schedules.into_iter().skip(skip).take(take).collect())
   }
 }
+
+/// The one-off scheduled deploys on Deployments / Stacks
+/// the user has access to, matching the query.
+async fn list_scheduled_deploys(
+  query: &ListSchedules,
+  all_tags: &[Tag],
+  args: &ReadArgs,
+) -> anyhow::Result<Vec<Schedule>> {
+  let scheduled_deploys =
+    find_collect(&db_client().scheduled_deploys, None, None)
+      .await
+      .context("Failed to get scheduled deploys from db")?;
+  if scheduled_deploys.is_empty() {
+    return Ok(Vec::new());
+  }
+  let (deployments, stacks) = tokio::try_join!(
+    list_full_for_user::<Deployment>(
+      ResourceQuery {
+        templates: TemplatesQueryBehavior::Include,
+        tag_behavior: query.tag_behavior,
+        tags: query.tags.clone(),
+        terms: query.terms.clone(),
+        ..Default::default()
+      },
+      None,
+      None,
+      &args.user,
+      PermissionLevel::Read.into(),
+      all_tags,
+    ),
+    list_full_for_user::<Stack>(
+      ResourceQuery {
+        templates: TemplatesQueryBehavior::Include,
+        tag_behavior: query.tag_behavior,
+        tags: query.tags.clone(),
+        terms: query.terms.clone(),
+        ..Default::default()
+      },
+      None,
+      None,
+      &args.user,
+      PermissionLevel::Read.into(),
+      all_tags,
+    )
+  )?;
+  let schedules = scheduled_deploys
+    .into_iter()
+    .filter_map(|scheduled| {
+      let (name, tags) = match &scheduled.target {
+        ResourceTarget::Deployment(id) => deployments
+          .iter()
+          .find(|deployment| &deployment.id == id)
+          .map(|deployment| {
+            (deployment.name.clone(), deployment.tags.clone())
+          })?,
+        ResourceTarget::Stack(id) => stacks
+          .iter()
+          .find(|stack| &stack.id == id)
+          .map(|stack| (stack.name.clone(), stack.tags.clone()))?,
+        _ => return None,
+      };
+      let schedule =
+        chrono::DateTime::from_timestamp_millis(scheduled.run_at)
+          .map(|run_at| {
+            format!("Once at {}", run_at.format("%Y-%m-%d %H:%M UTC"))
+          })
+          .unwrap_or_default();
+      Some(Schedule {
+        target: scheduled.target,
+        name,
+        schedule_format: Default::default(),
+        schedule,
+        enabled: true,
+        schedule_timezone: String::new(),
+        last_run_at: None,
+        next_scheduled_run: Some(scheduled.run_at),
+        schedule_error: None,
+        tags,
+      })
+    })
+    .collect();
+  Ok(schedules)
+}
@@ -31,6 +31,7 @@ use tokio::sync::Mutex;
 
 use crate::{
   helpers::{
+    allocation::server_allocations,
     periphery_client,
     query::{get_all_tags, get_cached_server_state},
   },
@@ -302,6 +303,21 @@ impl Resolve<ReadArgs> for GetPeripheryInformation {
   }
 }
 
+impl Resolve<ReadArgs> for ListServerPortAllocations {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListServerPortAllocationsResponse> {
+    let server = get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    Ok(server_allocations(&server.id).await.port_allocations())
+  }
+}
+
 impl Resolve<ReadArgs> for GetSystemInformation {
   async fn resolve(
     self,
@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 
 use anyhow::{Context, anyhow};
+use database::mungos::{
+  find::find_collect,
+  mongodb::{bson::doc, options::FindOptions},
+};
 use komodo_client::{
   api::read::*,
   entities::{
@@ -32,7 +36,7 @@ use crate::{
   permission::get_check_permissions,
   resource,
   stack::setup_stack_execution,
-  state::{action_states, stack_status_cache},
+  state::{action_states, db_client, stack_status_cache},
 };
 
 use super::{ReadArgs, list_limit};
@@ -595,6 +599,28 @@ impl Resolve<ReadArgs> for ListCommonStackBuildExtraArgs {
   }
 }
 
+impl Resolve<ReadArgs> for ListStackPreviews {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> mogh_error::Result<ListStackPreviewsResponse> {
+    let stack = get_check_permissions::<Stack>(
+      &self.stack,
+      user,
+      PermissionLevel::Read.into(),
+    )
+    .await?;
+    let previews = find_collect(
+      &db_client().stack_previews,
+      doc! { "stack_id": &stack.id },
+      FindOptions::builder().sort(doc! { "pr_number": 1 }).build(),
+    )
+    .await
+    .context("Failed to get stack previews from db")?;
+    Ok(previews)
+  }
+}
+
 impl Resolve<ReadArgs> for GetStackActionState {
   async fn resolve(
     self,
@@ -0,0 +1,117 @@
+use std::{fmt::Write as _, path::Path};
+
+use anyhow::anyhow;
+use formatting::{Color, bold, colored, format_serror, muted};
+use komodo_client::{
+  api::write::*,
+  entities::{
+    Operation, ResourceTarget, backup::BackupItem, update::Update,
+  },
+};
+use mogh_error::{AddStatusCode as _, AddStatusCodeError};
+use mogh_resolver::Resolve;
+use reqwest::StatusCode;
+
+use crate::{
+  config::core_config,
+  helpers::{
+    update::{add_update, make_update},
+    validations::validate_backup_folder_name,
+  },
+  resource::refresh_all_resources_cache,
+  state::db_client,
+};
+
+use super::WriteArgs;
+
+impl Resolve<WriteArgs> for RestoreBackupItems {
+  #[instrument(
+    "RestoreBackupItems",
+    skip_all,
+    fields(
+      operator = user.id,
+      backup = self.backup,
+      items = format!("{:?}", self.items),
+    )
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> mogh_error::Result<Update> {
+    if !user.admin {
+      return Err(
+        anyhow!("Only Admins can restore backup items")
+          .status_code(StatusCode::FORBIDDEN),
+      );
+    }
+
+    if let Some(backup) = &self.backup {
+      validate_backup_folder_name(backup)
+        .status_code(StatusCode::BAD_REQUEST)?;
+    }
+
+    let mut update = make_update(
+      ResourceTarget::system(),
+      Operation::RestoreBackupItems,
+      user,
+    );
+
+    match database::utils::restore_backup_items(
+      &db_client().db,
+      &core_config().backups_folder,
+      self.backup.as_deref().map(Path::new),
+      &self.items,
+    )
+    .await
+    {
+      Ok(restored) => {
+        update.push_simple_log(
+          "Restore Backup Items",
+          format_restored_items(
+            self.backup.as_deref().unwrap_or("latest"),
+            &restored,
+          ),
+        );
+      }
+      Err(e) => {
+        update.push_error_log(
+          "Restore Backup Items",
+          format_serror(&e.into()),
+        );
+      }
+    }
+
+    refresh_all_resources_cache().await;
+
+    update.finalize();
+    update.id = add_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+fn format_restored_items(
+  backup: &str,
+  restored: &[BackupItem],
+) -> String {
+  let mut log = format!("{}: {backup}", muted("backup"));
+  for item in restored {
+    let _ = write!(
+      &mut log,
+      "\n\n{}: {} | {}",
+      muted(item.variant),
+      bold(&item.name),
+      item.state,
+    );
+    for diff in &item.diff {
+      let _ = write!(
+        &mut log,
+        "\n  {}:\n    {}\n    {}",
+        muted(&diff.field),
+        colored(format!("- {}", diff.current), Color::Red),
+        colored(format!("+ {}", diff.backup), Color::Green),
+      );
+    }
+  }
+  log
+}
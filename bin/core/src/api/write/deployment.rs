@@ -9,8 +9,9 @@ use komodo_client::{
     Operation, ResourceTarget, SwarmOrServer,
     alert::{Alert, AlertData, SeverityLevel},
     deployment::{
-      Deployment, DeploymentImage, DeploymentInfo, DeploymentState,
-      PartialDeploymentConfig, RestartMode, extract_registry_domain,
+      Deployment, DeploymentConfig, DeploymentImage, DeploymentInfo,
+      DeploymentState, PartialDeploymentConfig, RestartMode,
+      extract_registry_domain,
     },
     docker::container::RestartPolicyNameEnum,
     komodo_timestamp, optional_string,
@@ -23,13 +24,22 @@ use komodo_client::{
   },
 };
 use mogh_cache::SetCache;
+use mogh_error::AddStatusCodeError;
 use mogh_resolver::Resolve;
+use partial_derive2::HasPartial;
 use periphery_client::api::{self, container::InspectContainer};
+use reqwest::StatusCode;
 
 use crate::{
   alert::send_alerts,
   api::execute::{self, ExecuteRequest, ExecutionResult},
   helpers::{
+    allocation::{
+      check_allocation_conflicts, deployment_allocations,
+      server_allocations,
+    },
+    auto_update::{dequeue_auto_update, queue_auto_update},
+    maintenance::is_auto_update_allowed,
     periphery_client,
     query::{
       get_all_tags, get_deployment_state, get_swarm_or_server,
@@ -38,6 +48,7 @@ use crate::{
     update::{add_update, make_update, poll_update_until_complete},
   },
   permission::get_check_permissions,
+  registry::update::check_tag_update,
   resource::{
     self, list_full_for_user_using_pattern,
     setup_deployment_execution,
@@ -64,6 +75,16 @@ impl Resolve<WriteArgs> for CreateDeployment {
     self,
     WriteArgs { user }: &WriteArgs,
   ) -> mogh_error::Result<Deployment> {
+    check_container_name_conflict(
+      self.config.server_id.as_deref().unwrap_or_default(),
+      self.config.swarm_id.as_deref().unwrap_or_default(),
+      self.config.custom_name.as_deref().unwrap_or_default(),
+      &self.name,
+      None,
+    )
+    .await?;
+    check_create_allocations(&self.name, self.config.clone().into())
+      .await?;
     resource::create::<Deployment>(
       &self.name,
       self.config,
@@ -95,6 +116,15 @@ impl Resolve<WriteArgs> for CopyDeployment {
         PermissionLevel::Read.into(),
       )
       .await?;
+    check_container_name_conflict(
+      &config.server_id,
+      &config.swarm_id,
+      &config.custom_name,
+      &self.name,
+      None,
+    )
+    .await?;
+    check_create_allocations(&self.name, config.clone()).await?;
     resource::create::<Deployment>(
       &self.name,
       config.into(),
@@ -247,6 +277,33 @@ impl Resolve<WriteArgs> for UpdateDeployment {
       .map(|image| image.as_image().is_some())
       .unwrap_or_default();
 
+    let deployment = get_check_permissions::<Deployment>(
+      &self.id,
+      user,
+      PermissionLevel::Write.into(),
+    )
+    .await?;
+    let mut updated = deployment.clone();
+    updated.config =
+      updated.config.merge_partial(self.config.clone());
+    // Only check when the container target or ports change,
+    // so existing conflicts don't block unrelated updates.
+    let (old, new) = (&deployment.config, &updated.config);
+    if new.swarm_id.is_empty()
+      && (new.server_id != old.server_id
+        || new.swarm_id != old.swarm_id
+        || new.custom_name.trim() != old.custom_name.trim()
+        || new.ports != old.ports
+        || new.network != old.network)
+    {
+      check_allocation_conflicts(
+        &new.server_id,
+        Some(&ResourceTarget::Deployment(updated.id.clone())),
+        &deployment_allocations(&updated),
+      )
+      .await?;
+    }
+
     let deployment =
       resource::update::<Deployment>(&self.id, self.config, user)
         .await?;
@@ -303,6 +360,15 @@ impl Resolve<WriteArgs> for RenameDeployment {
 
     let name = to_container_compatible_name(&self.name);
 
+    check_container_name_conflict(
+      &deployment.config.server_id,
+      &deployment.config.swarm_id,
+      &deployment.config.custom_name,
+      &name,
+      Some(&deployment.id),
+    )
+    .await?;
+
     let state = get_deployment_state(&deployment.id).await?;
 
     // When no custom name is configured, the container / service
@@ -397,6 +463,76 @@ impl Resolve<WriteArgs> for RenameDeployment {
 
 //
 
+/// Rejects Deployment writes which would give the container
+/// the name of another container on the Server, as deploying
+/// replaces any existing container with the same name.
+async fn check_container_name_conflict(
+  server_id: &str,
+  swarm_id: &str,
+  custom_name: &str,
+  name: &str,
+  deployment_id: Option<&str>,
+) -> mogh_error::Result<()> {
+  if server_id.is_empty() || !swarm_id.is_empty() {
+    return Ok(());
+  }
+  // Missing servers are reported by the config validation.
+  let Ok(server) = resource::get::<Server>(server_id).await else {
+    return Ok(());
+  };
+  let container_name = if custom_name.trim().is_empty() {
+    to_container_compatible_name(name)
+  } else {
+    to_container_compatible_name(custom_name)
+  };
+  let target = deployment_id
+    .map(|id| ResourceTarget::Deployment(id.to_string()));
+  let conflict = server_allocations(&server.id)
+    .await
+    .container_name_conflicts(target.as_ref(), &[container_name])
+    .into_iter()
+    .next();
+  match conflict {
+    Some(conflict) => Err(
+      anyhow!(
+        "{} on Server '{}'. Deploying would replace the existing container, use a different name or custom name.",
+        conflict.message,
+        server.name
+      )
+      .status_code(StatusCode::CONFLICT),
+    ),
+    None => Ok(()),
+  }
+}
+
+/// Rejects creating a Deployment with ports
+/// already published by a running container on the Server.
+async fn check_create_allocations(
+  name: &str,
+  config: DeploymentConfig,
+) -> mogh_error::Result<()> {
+  if !config.swarm_id.is_empty() {
+    return Ok(());
+  }
+  let deployment = Deployment {
+    id: Default::default(),
+    name: name.to_string(),
+    description: Default::default(),
+    template: Default::default(),
+    tags: Default::default(),
+    info: Default::default(),
+    config,
+    base_permission: Default::default(),
+    updated_at: Default::default(),
+  };
+  check_allocation_conflicts(
+    &deployment.config.server_id,
+    None,
+    &deployment_allocations(&deployment),
+  )
+  .await
+}
+
 impl Resolve<WriteArgs> for CheckDeploymentForUpdate {
   #[instrument(
     "CheckDeploymentForUpdate",
@@ -475,6 +611,7 @@ pub async fn check_deployment_for_update_inner(
         return Ok(CheckDeploymentForUpdateResponse {
           deployment: deployment.id,
           update_available: false,
+          latest_image: None,
         });
       }
       let domain = extract_registry_domain(image)?;
@@ -494,18 +631,36 @@ pub async fn check_deployment_for_update_inner(
       return Ok(CheckDeploymentForUpdateResponse {
         deployment: deployment.id,
         update_available: false,
+        latest_image: None,
       });
     }
   };
 
   let latest_digest = image_digest_cache()
-    .get(swarm_or_server, image, account, token)
+    .get(swarm_or_server, image, account.clone(), token)
     .await?;
 
+  // A failed tag check shouldn't block the digest check.
+  let latest_image = check_tag_update(
+    image,
+    account.as_deref().unwrap_or_default(),
+    deployment.config.update_policy,
+    &deployment.config.update_tag_filter,
+  )
+  .await
+  .inspect_err(|e| {
+    warn!(
+      "Failed to check tag update for Deployment {} | {e:#}",
+      deployment.name
+    )
+  })
+  .unwrap_or_default();
+
   resource::update_info::<Deployment>(
     &deployment.id,
     &DeploymentInfo {
       latest_image_digest: latest_digest.clone(),
+      latest_image: latest_image.clone().unwrap_or_default(),
       deployed_name: deployment.info.deployed_name.clone(),
     },
   )
@@ -521,21 +676,49 @@ pub async fn check_deployment_for_update_inner(
     return Ok(CheckDeploymentForUpdateResponse {
       deployment: deployment.id,
       update_available: false,
+      latest_image: None,
     });
   };
 
-  // If not running or latest digest matches current, early return
+  // If not running, or latest digest matches current
+  // without a newer version tag, early return
   if !matches!(state, DeploymentState::Running)
-    || !latest_digest.update_available(&current_digests)
+    || (!latest_digest.update_available(&current_digests)
+      && latest_image.is_none())
   {
     alert_cache.remove(&deployment.id).await;
     return Ok(CheckDeploymentForUpdateResponse {
       deployment: deployment.id,
       update_available: false,
+      latest_image: None,
     });
   }
 
   if !skip_auto_update && deployment.config.auto_update {
+    let target = ResourceTarget::Deployment(deployment.id.clone());
+
+    // Outside of the update windows, queue the update
+    // to be deployed once a window opens.
+    if !is_auto_update_allowed(
+      &deployment.config.auto_update_windows,
+      deployment.config.auto_update_in_maintenance,
+      swarm_or_server,
+      komodo_timestamp(),
+    ) {
+      info!(
+        "Queued auto update for Deployment {} until an update window opens",
+        deployment.name
+      );
+      queue_auto_update(target);
+      return Ok(CheckDeploymentForUpdateResponse {
+        deployment: deployment.id,
+        update_available: true,
+        latest_image,
+      });
+    }
+
+    dequeue_auto_update(&target);
+
     // Trigger deploy + alert
 
     // Conservatively remove from alert cache so 'skip_auto_update'
@@ -549,9 +732,28 @@ pub async fn check_deployment_for_update_inner(
       swarm_or_server.server_name().map(str::to_string);
     let id = deployment.id.clone();
     let name = deployment.name.clone();
-    let image = image.clone();
+    let new_image = latest_image.clone();
+    let image = latest_image.clone().unwrap_or_else(|| image.clone());
 
     let run = async move {
+      // Move the deployment to the newer version tag before deploying.
+      if let Some(image) = new_image {
+        let res = resource::update::<Deployment>(
+          &id,
+          PartialDeploymentConfig {
+            image: Some(DeploymentImage::Image { image }),
+            ..Default::default()
+          },
+          auto_redeploy_user(),
+        )
+        .await;
+        if let Err(e) = res {
+          warn!(
+            "Failed to update image tag for Deployment {name} | {e:#}"
+          );
+          return;
+        }
+      }
       match execute::inner_handler(
         ExecuteRequest::Deploy(Deploy {
           deployment: name.clone(),
@@ -616,6 +818,7 @@ pub async fn check_deployment_for_update_inner(
       return Ok(CheckDeploymentForUpdateResponse {
         deployment: deployment.id,
         update_available: true,
+        latest_image,
       });
     }
     alert_cache.insert(deployment.id.clone()).await;
@@ -636,7 +839,7 @@ pub async fn check_deployment_for_update_inner(
         server_name: swarm_or_server
           .server_name()
           .map(str::to_string),
-        image: image.clone(),
+        image: latest_image.clone().unwrap_or_else(|| image.clone()),
       },
     };
     let res = db_client().alerts.insert_one(&alert).await;
@@ -651,6 +854,8 @@ pub async fn check_deployment_for_update_inner(
   Ok(CheckDeploymentForUpdateResponse {
     deployment: deployment.id,
     update_available: !deployment.config.auto_update,
+    latest_image: latest_image
+      .filter(|_| !deployment.config.auto_update),
   })
 }
 
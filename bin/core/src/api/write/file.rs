@@ -0,0 +1,164 @@
+use anyhow::{Context, anyhow};
+use data_encoding::BASE64;
+use formatting::format_serror;
+use komodo_client::{
+  api::write::*,
+  entities::{
+    Operation,
+    permission::PermissionLevel,
+    server::Server,
+    update::{Update, UpdateStatus},
+  },
+};
+use mogh_error::AddStatusCode as _;
+use mogh_resolver::Resolve;
+use periphery_client::api;
+use reqwest::StatusCode;
+
+use crate::{
+  config::core_config,
+  helpers::{
+    periphery_client,
+    update::{add_update, make_update, update_update},
+  },
+  permission::get_check_permissions,
+};
+
+use super::WriteArgs;
+
+impl Resolve<WriteArgs> for WriteContainerFile {
+  #[instrument(
+    "WriteContainerFile",
+    skip_all,
+    fields(
+      operator = user.id,
+      server = self.server,
+      container = self.container,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> mogh_error::Result<Update> {
+    let size = check_contents(&self.contents)
+      .status_code(StatusCode::BAD_REQUEST)?;
+
+    let server = get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read.terminal(),
+    )
+    .await?;
+
+    let periphery = periphery_client(&server).await?;
+
+    let mut update =
+      make_update(&server, Operation::WriteContainerFile, user);
+    update.status = UpdateStatus::InProgress;
+    update.push_simple_log(
+      "Write Container File",
+      format!(
+        "Writing {size} bytes to '{}' in container '{}'",
+        self.path, self.container
+      ),
+    );
+    update.id = add_update(update.clone()).await?;
+
+    match periphery
+      .request(api::file::WriteContainerFile {
+        container: self.container,
+        path: self.path,
+        contents: self.contents,
+      })
+      .await
+    {
+      Ok(log) => update.logs.push(log),
+      Err(e) => update.push_error_log(
+        "Write Container File",
+        format_serror(
+          &e.context("Failed to write file to container").into(),
+        ),
+      ),
+    };
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<WriteArgs> for WriteServerFile {
+  #[instrument(
+    "WriteServerFile",
+    skip_all,
+    fields(
+      operator = user.id,
+      server = self.server,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> mogh_error::Result<Update> {
+    let size = check_contents(&self.contents)
+      .status_code(StatusCode::BAD_REQUEST)?;
+
+    let server = get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read.terminal(),
+    )
+    .await?;
+
+    let periphery = periphery_client(&server).await?;
+
+    let mut update =
+      make_update(&server, Operation::WriteServerFile, user);
+    update.status = UpdateStatus::InProgress;
+    update.push_simple_log(
+      "Write Server File",
+      format!("Writing {size} bytes to '{}'", self.path),
+    );
+    update.id = add_update(update.clone()).await?;
+
+    match periphery
+      .request(api::file::WriteHostFile {
+        path: self.path,
+        contents: self.contents,
+      })
+      .await
+    {
+      Ok(log) => update.logs.push(log),
+      Err(e) => update.push_error_log(
+        "Write Server File",
+        format_serror(
+          &e.context("Failed to write file to server").into(),
+        ),
+      ),
+    };
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+/// Validates the base64 contents are within the size limit,
+/// returning the decoded size in bytes.
+fn check_contents(contents: &str) -> anyhow::Result<usize> {
+  let size = BASE64
+    .decode(contents.as_bytes())
+    .context("File contents are not valid base64")?
+    .len();
+  let max_size = core_config().max_file_transfer_size();
+  if size as u64 > max_size {
+    return Err(anyhow!(
+      "File is {size} bytes, which is over the maximum of {max_size} bytes"
+    ));
+  }
+  Ok(size)
+}
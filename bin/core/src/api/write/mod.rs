@@ -21,15 +21,18 @@ use super::Variant;
 mod action;
 mod alert;
 mod alerter;
+mod backup;
 mod build;
 mod builder;
 mod deployment;
+mod file;
 mod onboarding;
 mod permissions;
 mod procedure;
 mod provider;
 mod repo;
 mod resource;
+mod schedule;
 mod server;
 mod service_user;
 mod stack;
@@ -62,6 +65,11 @@ pub struct WriteArgs {
 pub enum WriteRequest {
   // ==== RESOURCE ====
   UpdateResourceMeta(UpdateResourceMeta),
+  RestoreResourceRevision(RestoreResourceRevision),
+
+  // ==== SCHEDULE ====
+  ScheduleDeploy(ScheduleDeploy),
+  CancelScheduledDeploy(CancelScheduledDeploy),
 
   // ==== SWARM ====
   CreateSwarm(CreateSwarm),
@@ -86,6 +94,10 @@ pub enum WriteRequest {
   DeleteAllTerminals(DeleteAllTerminals),
   BatchDeleteAllTerminals(BatchDeleteAllTerminals),
 
+  // ==== FILE ====
+  WriteContainerFile(WriteContainerFile),
+  WriteServerFile(WriteServerFile),
+
   // ==== STACK ====
   CreateStack(CreateStack),
   CopyStack(CopyStack),
@@ -220,6 +232,9 @@ pub enum WriteRequest {
 
   // ==== ALERT ====
   CloseAlert(CloseAlert),
+
+  // ==== BACKUP ====
+  RestoreBackupItems(RestoreBackupItems),
 }
 
 pub fn router() -> Router {
@@ -1,18 +1,27 @@
-use anyhow::anyhow;
+use anyhow::{Context, anyhow};
+use database::mungos::by_id::find_one_by_id;
 use komodo_client::{
-  api::write::{UpdateResourceMeta, UpdateResourceMetaResponse},
+  api::write::{
+    RestoreResourceRevision, RestoreResourceRevisionResponse,
+    UpdateResourceMeta, UpdateResourceMetaResponse,
+  },
   entities::{
-    ResourceTarget, action::Action, alerter::Alerter, build::Build,
-    builder::Builder, deployment::Deployment, procedure::Procedure,
-    repo::Repo, server::Server, stack::Stack, swarm::Swarm,
-    sync::ResourceSync,
+    JsonObject, ResourceTarget, action::Action, alerter::Alerter,
+    build::Build, builder::Builder, deployment::Deployment,
+    procedure::Procedure, repo::Repo, server::Server, stack::Stack,
+    swarm::Swarm, sync::ResourceSync,
   },
 };
-use mogh_error::AddStatusCodeError;
+use mogh_error::{AddStatusCode, AddStatusCodeError};
 use mogh_resolver::Resolve;
 use reqwest::StatusCode;
 
-use crate::resource::{self, ResourceMetaUpdate};
+use crate::{
+  resource::{
+    self, KomodoResource, ResourceMetaUpdate, parse_config,
+  },
+  state::db_client,
+};
 
 use super::WriteArgs;
 
@@ -83,3 +92,80 @@ impl Resolve<WriteArgs> for UpdateResourceMeta {
     Ok(UpdateResourceMetaResponse {})
   }
 }
+
+impl Resolve<WriteArgs> for RestoreResourceRevision {
+  #[instrument(
+    "RestoreResourceRevision",
+    skip_all,
+    fields(
+      operator = args.user.id,
+      revision_id = self.id,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &WriteArgs,
+  ) -> mogh_error::Result<RestoreResourceRevisionResponse> {
+    let revision =
+      find_one_by_id(&db_client().resource_revisions, &self.id)
+        .await
+        .context("failed to query db for resource revision")?
+        .context("no resource revision found with given id")
+        .status_code(StatusCode::NOT_FOUND)?;
+    let config = revision.config;
+    match revision.target {
+      ResourceTarget::System(_) => {
+        return Err(
+          anyhow!("cannot restore System resource target")
+            .status_code(StatusCode::BAD_REQUEST),
+        );
+      }
+      ResourceTarget::Swarm(id) => {
+        restore::<Swarm>(&id, config, args).await?;
+      }
+      ResourceTarget::Server(id) => {
+        restore::<Server>(&id, config, args).await?;
+      }
+      ResourceTarget::Stack(id) => {
+        restore::<Stack>(&id, config, args).await?;
+      }
+      ResourceTarget::Deployment(id) => {
+        restore::<Deployment>(&id, config, args).await?;
+      }
+      ResourceTarget::Build(id) => {
+        restore::<Build>(&id, config, args).await?;
+      }
+      ResourceTarget::Repo(id) => {
+        restore::<Repo>(&id, config, args).await?;
+      }
+      ResourceTarget::Procedure(id) => {
+        restore::<Procedure>(&id, config, args).await?;
+      }
+      ResourceTarget::Action(id) => {
+        restore::<Action>(&id, config, args).await?;
+      }
+      ResourceTarget::ResourceSync(id) => {
+        restore::<ResourceSync>(&id, config, args).await?;
+      }
+      ResourceTarget::Builder(id) => {
+        restore::<Builder>(&id, config, args).await?;
+      }
+      ResourceTarget::Alerter(id) => {
+        restore::<Alerter>(&id, config, args).await?;
+      }
+    }
+    Ok(RestoreResourceRevisionResponse {})
+  }
+}
+
+/// Applies the full revision config as a normal update,
+/// which checks write permissions and records a new revision.
+async fn restore<T: KomodoResource>(
+  id: &str,
+  config: JsonObject,
+  args: &WriteArgs,
+) -> anyhow::Result<()> {
+  let config = parse_config::<T>(config)?;
+  resource::update::<T>(id, config.into(), &args.user).await?;
+  Ok(())
+}
@@ -0,0 +1,144 @@
+use anyhow::{Context, anyhow};
+use komodo_client::{
+  api::write::{
+    CancelScheduledDeploy, CancelScheduledDeployResponse,
+    ScheduleDeploy, ScheduleDeployResponse,
+  },
+  entities::{
+    ResourceTarget, deployment::Deployment, komodo_timestamp,
+    permission::PermissionLevel, schedule::ScheduledDeploy,
+    stack::Stack, user::User,
+  },
+};
+use mogh_error::AddStatusCodeError;
+use mogh_resolver::Resolve;
+use reqwest::StatusCode;
+
+use crate::{
+  permission::get_check_permissions,
+  schedule::{
+    add_scheduled_deploy, cancel_schedule, scheduled_deploy_filter,
+  },
+  state::db_client,
+};
+
+use super::WriteArgs;
+
+impl Resolve<WriteArgs> for ScheduleDeploy {
+  #[instrument(
+    "ScheduleDeploy",
+    skip_all,
+    fields(
+      operator = user.id,
+      resource_type = self.target.extract_variant().to_string(),
+      resource_id = self.target.extract_variant_id().1,
+      run_at = self.run_at,
+    )
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> mogh_error::Result<ScheduleDeployResponse> {
+    let target = check_deploy_target(&self.target, user).await?;
+
+    let ts = komodo_timestamp();
+    if self.run_at <= ts {
+      return Err(
+        anyhow!("Scheduled deploy time must be in the future")
+          .status_code(StatusCode::BAD_REQUEST),
+      );
+    }
+
+    let mut scheduled = ScheduledDeploy {
+      id: Default::default(),
+      target,
+      run_at: self.run_at,
+      operator: user.id.clone(),
+      created_at: ts,
+    };
+
+    let scheduled_deploys = &db_client().scheduled_deploys;
+    let filter = scheduled_deploy_filter(&scheduled.target);
+    scheduled_deploys
+      .delete_many(filter)
+      .await
+      .context("Failed to remove existing scheduled deploy")?;
+    scheduled.id = scheduled_deploys
+      .insert_one(&scheduled)
+      .await
+      .context("Failed to add scheduled deploy to db")?
+      .inserted_id
+      .as_object_id()
+      .context("Inserted id is not ObjectId")?
+      .to_hex();
+
+    add_scheduled_deploy(&scheduled);
+
+    Ok(scheduled)
+  }
+}
+
+impl Resolve<WriteArgs> for CancelScheduledDeploy {
+  #[instrument(
+    "CancelScheduledDeploy",
+    skip_all,
+    fields(
+      operator = user.id,
+      resource_type = self.target.extract_variant().to_string(),
+      resource_id = self.target.extract_variant_id().1,
+    )
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> mogh_error::Result<CancelScheduledDeployResponse> {
+    let target = check_deploy_target(&self.target, user).await?;
+    let res = db_client()
+      .scheduled_deploys
+      .delete_many(scheduled_deploy_filter(&target))
+      .await
+      .context("Failed to remove scheduled deploy")?;
+    if res.deleted_count == 0 {
+      return Err(
+        anyhow!("No deploy is scheduled for this resource")
+          .status_code(StatusCode::NOT_FOUND),
+      );
+    }
+    cancel_schedule(&target);
+    Ok(CancelScheduledDeployResponse {})
+  }
+}
+
+/// Checks the user can deploy the target,
+/// and returns the target with the resource id.
+async fn check_deploy_target(
+  target: &ResourceTarget,
+  user: &User,
+) -> mogh_error::Result<ResourceTarget> {
+  match target {
+    ResourceTarget::Deployment(id) => {
+      let deployment = get_check_permissions::<Deployment>(
+        id,
+        user,
+        PermissionLevel::Execute.into(),
+      )
+      .await?;
+      Ok(ResourceTarget::Deployment(deployment.id))
+    }
+    ResourceTarget::Stack(id) => {
+      let stack = get_check_permissions::<Stack>(
+        id,
+        user,
+        PermissionLevel::Execute.into(),
+      )
+      .await?;
+      Ok(ResourceTarget::Stack(stack.id))
+    }
+    _ => Err(
+      anyhow!(
+        "Only Deployments and Stacks can be scheduled to deploy"
+      )
+      .status_code(StatusCode::BAD_REQUEST),
+    ),
+  }
+}
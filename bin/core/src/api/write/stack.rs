@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::PathBuf, sync::OnceLock};
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+  sync::OnceLock,
+};
 
 use anyhow::{Context, anyhow};
 use database::{
@@ -21,8 +25,8 @@ use komodo_client::{
     repo::Repo,
     resource::ResourceQuery,
     stack::{
-      Stack, StackInfo, StackServiceNames, StackServiceWithUpdate,
-      StackState,
+      PartialStackConfig, Stack, StackInfo, StackServiceImage,
+      StackServiceNames, StackServiceWithUpdate, StackState,
     },
     update::Update,
     user::{auto_redeploy_user, stack_user, system_user},
@@ -30,6 +34,7 @@ use komodo_client::{
 };
 use mogh_cache::SetCache;
 use mogh_resolver::Resolve;
+use partial_derive2::HasPartial;
 use periphery_client::api::compose::{
   GetComposeContentsOnHost, GetComposeContentsOnHostResponse,
   WriteComposeContentsToHost,
@@ -40,16 +45,21 @@ use crate::{
   api::execute::{self, ExecuteRequest, ExecutionResult},
   config::core_config,
   helpers::{
+    allocation::{check_allocation_conflicts, stack_allocations},
+    auto_update::{dequeue_auto_update, queue_auto_update},
+    maintenance::is_auto_update_allowed,
     query::{get_all_tags, get_swarm_or_server},
     stack_git_token, swarm_or_server_request,
     update::{add_update, make_update, poll_update_until_complete},
   },
   permission::get_check_permissions,
+  registry::update::check_tag_update,
   resource::{self, list_full_for_user_using_pattern},
   stack::{
     remote::{RemoteComposeContents, get_repo_compose_contents},
     services::{
       extract_services_from_stack, extract_services_into_res,
+      replace_compose_images,
     },
     setup_stack_execution,
   },
@@ -72,6 +82,18 @@ impl Resolve<WriteArgs> for CreateStack {
     self,
     WriteArgs { user }: &WriteArgs,
   ) -> mogh_error::Result<Stack> {
+    let stack = Stack {
+      id: Default::default(),
+      name: self.name.clone(),
+      description: Default::default(),
+      template: Default::default(),
+      tags: Default::default(),
+      info: Default::default(),
+      config: self.config.clone().into(),
+      base_permission: Default::default(),
+      updated_at: Default::default(),
+    };
+    check_stack_allocations(&stack, None).await?;
     resource::create::<Stack>(&self.name, self.config, None, user)
       .await
   }
@@ -139,6 +161,26 @@ impl Resolve<WriteArgs> for UpdateStack {
       || self.config.files_on_host.is_some()
       || self.config.file_contents.is_some();
 
+    let stack = get_check_permissions::<Stack>(
+      &self.id,
+      user,
+      PermissionLevel::Write.into(),
+    )
+    .await?;
+    let mut updated = stack.clone();
+    updated.config =
+      updated.config.merge_partial(self.config.clone());
+    // Only check when the compose file or Server change,
+    // so existing conflicts don't block unrelated updates.
+    let (old, new) = (&stack.config, &updated.config);
+    if new.server_id != old.server_id
+      || new.swarm_id != old.swarm_id
+      || new.project_name != old.project_name
+      || new.file_contents != old.file_contents
+    {
+      check_stack_allocations(&updated, Some(&stack.id)).await?;
+    }
+
     let stack =
       resource::update::<Stack>(&self.id, self.config, user).await?;
 
@@ -161,6 +203,29 @@ impl Resolve<WriteArgs> for UpdateStack {
   }
 }
 
+/// Rejects Stack writes with allocations which would fail the
+/// deploy, see `check_allocation_conflicts`. Only the UI defined
+/// compose file is known before the write, the conflicts of
+/// files in a repo or on the host are logged after the refresh.
+async fn check_stack_allocations(
+  stack: &Stack,
+  stack_id: Option<&str>,
+) -> mogh_error::Result<()> {
+  if !stack.config.swarm_id.is_empty()
+    || stack.config.file_contents.trim().is_empty()
+  {
+    return Ok(());
+  }
+  let target =
+    stack_id.map(|id| ResourceTarget::Stack(id.to_string()));
+  check_allocation_conflicts(
+    &stack.config.server_id,
+    target.as_ref(),
+    &stack_allocations(stack),
+  )
+  .await
+}
+
 impl Resolve<WriteArgs> for RenameStack {
   #[instrument(
     "RenameStack",
@@ -659,6 +724,7 @@ impl Resolve<WriteArgs> for RefreshStackCache {
       remote_errors,
       latest_hash,
       latest_message,
+      latest_images: stack.info.latest_images.clone(),
     };
 
     let info = to_document(&info)
@@ -762,6 +828,7 @@ pub async fn check_stack_for_update_inner(
   let mut stack = resource::get::<Stack>(&stack).await?;
 
   let cache = image_digest_cache();
+  let mut latest_images = Vec::new();
 
   for service in &mut stack.info.latest_services {
     // Prefer the image coming from deployed services
@@ -791,6 +858,31 @@ pub async fn check_stack_for_update_inner(
       service.image_digest = None;
       continue;
     }
+    if let Some(policy) = stack
+      .config
+      .update_policies
+      .iter()
+      .find(|policy| policy.service == service.service_name)
+    {
+      match check_tag_update(
+        image,
+        &stack.config.registry_account,
+        policy.policy,
+        &policy.tag_filter,
+      )
+      .await
+      {
+        Ok(Some(latest)) => latest_images.push(StackServiceImage {
+          service: service.service_name.clone(),
+          image: latest,
+        }),
+        Ok(None) => {}
+        Err(e) => warn!(
+          "Failed to check tag update | Stack: {} | Service: {} | Error: {e:#}",
+          stack.name, service.service_name
+        ),
+      }
+    }
     match cache.get(swarm_or_server, image, None, None).await {
       Ok(digest) => service.image_digest = Some(digest),
       Err(e) => {
@@ -804,13 +896,20 @@ pub async fn check_stack_for_update_inner(
     };
   }
 
+  stack.info.latest_images = latest_images;
+
   let latest_services = to_bson(&stack.info.latest_services)
     .context("Failed to serialize stack latest services to BSON")?;
+  let latest_images = to_bson(&stack.info.latest_images)
+    .context("Failed to serialize stack latest images to BSON")?;
 
   update_one_by_id(
     &db_client().stacks,
     &stack.id,
-    doc! { "$set": { "info.latest_services": latest_services } },
+    doc! { "$set": {
+      "info.latest_services": latest_services,
+      "info.latest_images": latest_images,
+    } },
     None,
   )
   .await?;
@@ -877,26 +976,33 @@ pub async fn check_stack_for_update_inner(
       ),
     };
 
-    let Some(current_digests) = &service.image_digests else {
-      services.push(service_with_update);
-      continue;
-    };
-
-    let Some(latest_digest) =
+    let latest_digest =
       stack.info.latest_services.iter().find_map(|s| {
         if s.service_name == service.service {
           s.image_digest.clone()
         } else {
           None
         }
-      })
-    else {
-      services.push(service_with_update);
-      continue;
-    };
+      });
 
     service_with_update.update_available =
-      latest_digest.update_available(current_digests);
+      match (&service.image_digests, latest_digest) {
+        (Some(current_digests), Some(latest_digest)) => {
+          latest_digest.update_available(current_digests)
+        }
+        _ => false,
+      };
+
+    // A newer version tag allowed by the service update policy.
+    if let Some(latest) = stack
+      .info
+      .latest_images
+      .iter()
+      .find(|latest| latest.service == service.service)
+    {
+      service_with_update.update_available = true;
+      service_with_update.latest_image = Some(latest.image.clone());
+    }
 
     if service_with_update.update_available
       && (skip_auto_update || !stack.config.auto_update)
@@ -928,7 +1034,10 @@ pub async fn check_stack_for_update_inner(
             .map(str::to_string),
           server_id: swarm_or_server.server_id().map(str::to_string),
           service: service.service.clone(),
-          image: service.image.clone(),
+          image: service_with_update
+            .latest_image
+            .clone()
+            .unwrap_or_else(|| service.image.clone()),
         },
       };
       let res = db_client().alerts.insert_one(&alert).await;
@@ -959,6 +1068,29 @@ pub async fn check_stack_for_update_inner(
     });
   }
 
+  let target = ResourceTarget::Stack(stack.id.clone());
+
+  // Outside of the update windows, queue the update
+  // to be deployed once a window opens.
+  if !is_auto_update_allowed(
+    &stack.config.auto_update_windows,
+    stack.config.auto_update_in_maintenance,
+    swarm_or_server,
+    komodo_timestamp(),
+  ) {
+    info!(
+      "Queued auto update for Stack {} until an update window opens",
+      stack.name
+    );
+    queue_auto_update(target);
+    return Ok(CheckStackForUpdateResponse {
+      stack: stack.id,
+      services,
+    });
+  }
+
+  dequeue_auto_update(&target);
+
   // Conservatively remove from alert cache so 'skip_auto_update'
   // doesn't cause alerts not to be sent on subsequent calls.
   alert_cache
@@ -984,12 +1116,57 @@ pub async fn check_stack_for_update_inner(
 
   let stack_id = stack.id.clone();
 
+  // Services moving to a newer version tag,
+  // as (current image, new image).
+  let tag_updates = services_with_update
+    .iter()
+    .filter_map(|service| {
+      stack
+        .info
+        .latest_images
+        .iter()
+        .find(|latest| latest.service == service.service)
+        .map(|latest| (service.image.clone(), latest.image.clone()))
+    })
+    .collect::<Vec<_>>();
+
   let run = async move {
+    // Move the compose files to the newer version tags before deploying.
+    // Services which fail to move are left out of the auto update.
+    let failed = if tag_updates.is_empty() {
+      Vec::new()
+    } else {
+      write_stack_image_tags(&stack, &tag_updates).await
+    };
+    for (image, e) in &failed {
+      warn!(
+        "Failed to update image tag of {image} for Stack {} | {e}",
+        stack.name
+      );
+    }
+    let services_with_update = services_with_update
+      .into_iter()
+      .filter(|service| {
+        !failed.iter().any(|(image, _)| image == &service.image)
+      })
+      .collect::<Vec<_>>();
+    if services_with_update.is_empty() {
+      return;
+    }
+    let deploy_services = deploy_services
+      .into_iter()
+      .filter(|service| {
+        services_with_update
+          .iter()
+          .any(|update| &update.service == service)
+      })
+      .collect();
     match execute::inner_handler(
       ExecuteRequest::DeployStack(DeployStack {
         stack: stack.id.clone(),
         services: deploy_services,
         stop_time: None,
+        commit: None,
       }),
       auto_redeploy_user().to_owned(),
     )
@@ -1021,7 +1198,13 @@ pub async fn check_stack_for_update_inner(
               server_name,
               images: services_with_update
                 .iter()
-                .map(|service| service.image.clone())
+                .map(|service| {
+                  tag_updates
+                    .iter()
+                    .find(|(current, _)| current == &service.image)
+                    .map(|(_, latest)| latest.clone())
+                    .unwrap_or_else(|| service.image.clone())
+                })
                 .collect(),
             },
           };
@@ -1066,6 +1249,128 @@ fn find_latest_image(
   })
 }
 
+/// Rewrites the compose images to the newer version tags,
+/// given (current image, new image) pairs.
+/// Writes the UI defined contents to the Stack config,
+/// otherwise writes the files on host / in the repo.
+/// Returns the current images which failed to update,
+/// with the reason.
+async fn write_stack_image_tags(
+  stack: &Stack,
+  images: &[(String, String)],
+) -> Vec<(String, String)> {
+  let user = auto_redeploy_user();
+  let mut failed = Vec::new();
+
+  if !stack.config.files_on_host
+    && stack.config.repo.is_empty()
+    && stack.config.linked_repo.is_empty()
+  {
+    let mut contents = stack.config.file_contents.clone();
+    let mut written = Vec::new();
+    for image in images {
+      match replace_compose_images(
+        &contents,
+        std::slice::from_ref(image),
+      ) {
+        Some(replaced) => {
+          contents = replaced;
+          written.push(image.0.clone());
+        }
+        None => failed.push((
+          image.0.clone(),
+          String::from("No compose image matches"),
+        )),
+      }
+    }
+    if written.is_empty() {
+      return failed;
+    }
+    if let Err(e) = resource::update::<Stack>(
+      &stack.id,
+      PartialStackConfig {
+        file_contents: Some(contents),
+        ..Default::default()
+      },
+      user,
+    )
+    .await
+    {
+      let e = format!("{e:#}");
+      failed
+        .extend(written.into_iter().map(|image| (image, e.clone())));
+    }
+    return failed;
+  }
+
+  let mut written = HashSet::new();
+
+  for file in stack.info.remote_contents.iter().flatten() {
+    let Some(contents) =
+      replace_compose_images(&file.contents, images)
+    else {
+      continue;
+    };
+    // The images used in this file
+    let file_images = images
+      .iter()
+      .filter(|image| {
+        replace_compose_images(
+          &file.contents,
+          std::slice::from_ref(image),
+        )
+        .is_some()
+      })
+      .map(|(current, _)| current.clone())
+      .collect::<Vec<_>>();
+    let mut update =
+      make_update(stack, Operation::WriteStackContents, user);
+    update.push_simple_log("File contents to write", &contents);
+    let res = if stack.config.files_on_host {
+      write_stack_file_contents_on_host(
+        stack.clone(),
+        file.path.clone(),
+        contents,
+        update,
+      )
+      .await
+    } else {
+      write_stack_file_contents_git(
+        stack.clone(),
+        &file.path,
+        &contents,
+        &user.username,
+        update,
+      )
+      .await
+    };
+    let e = match res {
+      Ok(update) if update.success => {
+        written.extend(file_images);
+        continue;
+      }
+      Ok(_) => format!("Failed to write {}", file.path),
+      Err(e) => format!("{:#}", e.error),
+    };
+    failed.extend(
+      file_images.into_iter().map(|image| (image, e.clone())),
+    );
+  }
+
+  for (image, _) in images {
+    if !written.contains(image)
+      && !failed.iter().any(|(failed, _)| failed == image)
+    {
+      failed.push((
+        image.clone(),
+        String::from("No compose image matches"),
+      ));
+    }
+  }
+
+  failed
+}
+
 //
 
 impl Resolve<WriteArgs> for BatchCheckStackForUpdate {
@@ -342,6 +342,9 @@ pub fn core_config() -> &'static CoreConfig {
       keep_alerts_for_days: env
         .komodo_keep_alerts_for_days
         .unwrap_or(config.keep_alerts_for_days),
+      keep_revisions_for_days: env
+        .komodo_keep_revisions_for_days
+        .unwrap_or(config.keep_revisions_for_days),
       webhook_base_url: env
         .komodo_webhook_base_url
         .unwrap_or(config.webhook_base_url),
@@ -452,6 +455,12 @@ pub fn core_config() -> &'static CoreConfig {
       action_directory: env
         .komodo_action_directory
         .unwrap_or(config.action_directory),
+      backups_folder: env
+        .komodo_backups_folder
+        .unwrap_or(config.backups_folder),
+      max_file_transfer_size_mb: env
+        .komodo_max_file_transfer_size_mb
+        .unwrap_or(config.max_file_transfer_size_mb),
 
       // These can't be overridden on env
       secrets: config.secrets,
@@ -0,0 +1,818 @@
+//! Index of the host ports, container names and named volumes
+//! allocated on each Server, to catch conflicts between
+//! Deployments / Stacks before the second one fails to deploy.
+//!
+//! The index is built on demand from the resource cache
+//! and the latest docker lists in the server status cache,
+//! so it always reflects the current configs and containers.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use komodo_client::entities::{
+  ResourceTarget,
+  deployment::{Deployment, conversions_from_str},
+  docker::container::{ContainerListItem, PortTypeEnum},
+  server::{Server, ServerPortAllocation},
+  stack::Stack,
+  to_container_compatible_name,
+  update::Update,
+};
+use mogh_error::AddStatusCodeError;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+  resource,
+  state::{all_resources_cache, server_status_cache},
+};
+
+/// The host ports, container names and named volumes
+/// a single Deployment / Stack allocates on its Server.
+#[derive(Debug, Default)]
+pub struct ResourceAllocations {
+  pub ports: Vec<PortBinding>,
+  pub container_names: Vec<String>,
+  pub volumes: Vec<String>,
+}
+
+/// A published host port, or port range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortBinding {
+  /// Empty when bound on all interfaces.
+  pub host_ip: String,
+  pub start: u16,
+  pub end: u16,
+  pub protocol: String,
+}
+
+impl PortBinding {
+  fn overlaps(&self, other: &PortBinding) -> bool {
+    self.protocol == other.protocol
+      && self.start <= other.end
+      && other.start <= self.end
+      && (self.host_ip.is_empty()
+        || other.host_ip.is_empty()
+        || self.host_ip == other.host_ip)
+  }
+}
+
+impl std::fmt::Display for PortBinding {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.host_ip.contains(':') {
+      write!(f, "[{}]:", self.host_ip)?;
+    } else if !self.host_ip.is_empty() {
+      write!(f, "{}:", self.host_ip)?;
+    }
+    if self.start == self.end {
+      write!(f, "{}/{}", self.start, self.protocol)
+    } else {
+      write!(f, "{}-{}/{}", self.start, self.end, self.protocol)
+    }
+  }
+}
+
+/// A conflict with an allocation of another resource,
+/// or of a container not managed by Komodo.
+#[derive(Debug)]
+pub struct AllocationConflict {
+  /// Whether the conflicting allocation is held
+  /// by a running container, rather than only configured.
+  pub running: bool,
+  pub message: String,
+}
+
+/// The allocations on a single Server.
+#[derive(Default)]
+pub struct ServerAllocations {
+  ports: Vec<Allocated<PortBinding>>,
+  container_names: Vec<Allocated<String>>,
+  volumes: Vec<Allocated<String>>,
+}
+
+struct Allocated<T> {
+  value: T,
+  owner: Option<Owner>,
+  container: Option<String>,
+}
+
+#[derive(Clone)]
+struct Owner {
+  target: ResourceTarget,
+  name: String,
+}
+
+/// Builds the allocation index for the Server.
+pub async fn server_allocations(
+  server_id: &str,
+) -> ServerAllocations {
+  let mut index = ServerAllocations::default();
+  let mut deployment_containers = HashMap::new();
+  let mut stack_projects = HashMap::new();
+
+  {
+    let all = all_resources_cache().load();
+    for deployment in all.deployments.values().filter(|deployment| {
+      !deployment.template
+        && deployment.config.swarm_id.is_empty()
+        && deployment.config.server_id == server_id
+    }) {
+      let owner = Owner {
+        target: ResourceTarget::Deployment(deployment.id.clone()),
+        name: deployment.name.clone(),
+      };
+      deployment_containers.insert(
+        deployment.deployed_name().to_string(),
+        owner.clone(),
+      );
+      index.add_resource(owner, deployment_allocations(deployment));
+    }
+    for stack in all.stacks.values().filter(|stack| {
+      !stack.template
+        && stack.config.swarm_id.is_empty()
+        && stack.config.server_id == server_id
+    }) {
+      let owner = Owner {
+        target: ResourceTarget::Stack(stack.id.clone()),
+        name: stack.name.clone(),
+      };
+      stack_projects.insert(stack.project_name(false), owner.clone());
+      index.add_resource(owner, stack_allocations(stack));
+    }
+  }
+
+  if let Some(status) =
+    server_status_cache().get(&server_id.to_string()).await
+    && let Some(docker) = &status.docker
+  {
+    for container in &docker.containers {
+      let owner = deployment_containers
+        .get(&container.name)
+        .or_else(|| {
+          container
+            .labels
+            .get("com.docker.compose.project")
+            .and_then(|project| stack_projects.get(project))
+        })
+        .cloned();
+      index.add_container(owner, container);
+    }
+  }
+
+  index
+}
+
+impl ServerAllocations {
+  fn add_resource(
+    &mut self,
+    owner: Owner,
+    allocations: ResourceAllocations,
+  ) {
+    fn allocated<T>(
+      owner: &Owner,
+      values: Vec<T>,
+    ) -> Vec<Allocated<T>> {
+      values
+        .into_iter()
+        .map(|value| Allocated {
+          value,
+          owner: Some(owner.clone()),
+          container: None,
+        })
+        .collect()
+    }
+    self.ports.extend(allocated(&owner, allocations.ports));
+    self
+      .container_names
+      .extend(allocated(&owner, allocations.container_names));
+    self.volumes.extend(allocated(&owner, allocations.volumes));
+  }
+
+  /// Adds the live allocations of the container,
+  /// merged with the configured allocations of its owner.
+  fn add_container(
+    &mut self,
+    owner: Option<Owner>,
+    container: &ContainerListItem,
+  ) {
+    let same_owner = |other: &Option<Owner>| {
+      owner.as_ref().map(|owner| &owner.target)
+        == other.as_ref().map(|owner| &owner.target)
+    };
+
+    if let Some(allocated) =
+      self.container_names.iter_mut().find(|allocated| {
+        allocated.value == container.name
+          && same_owner(&allocated.owner)
+      })
+    {
+      allocated.container = Some(container.name.clone());
+    } else {
+      self.container_names.push(Allocated {
+        value: container.name.clone(),
+        owner: owner.clone(),
+        container: Some(container.name.clone()),
+      });
+    }
+
+    for port in &container.ports {
+      let Some(public_port) = port.public_port else {
+        continue;
+      };
+      let binding = PortBinding {
+        host_ip: normalize_host_ip(
+          port.ip.as_deref().unwrap_or_default(),
+        ),
+        start: public_port,
+        end: public_port,
+        protocol: port_protocol(port.typ).to_string(),
+      };
+      if let Some(allocated) =
+        self.ports.iter_mut().find(|allocated| {
+          same_owner(&allocated.owner)
+            && allocated.value.overlaps(&binding)
+            && allocated
+              .container
+              .as_ref()
+              .is_none_or(|name| name == &container.name)
+        })
+      {
+        allocated.container = Some(container.name.clone());
+        continue;
+      }
+      self.ports.push(Allocated {
+        value: binding,
+        owner: owner.clone(),
+        container: Some(container.name.clone()),
+      });
+    }
+
+    for volume in &container.volumes {
+      if is_anonymous_volume(volume) {
+        continue;
+      }
+      if let Some(allocated) =
+        self.volumes.iter_mut().find(|allocated| {
+          &allocated.value == volume && same_owner(&allocated.owner)
+        })
+      {
+        allocated.container.get_or_insert(container.name.clone());
+        continue;
+      }
+      self.volumes.push(Allocated {
+        value: volume.clone(),
+        owner: owner.clone(),
+        container: Some(container.name.clone()),
+      });
+    }
+  }
+
+  /// The published ports on the Server, sorted by port.
+  pub fn port_allocations(&self) -> Vec<ServerPortAllocation> {
+    let mut ports = self
+      .ports
+      .iter()
+      .map(|allocated| ServerPortAllocation {
+        port: allocated.value.start,
+        end_port: allocated.value.end,
+        protocol: allocated.value.protocol.clone(),
+        host_ip: allocated.value.host_ip.clone(),
+        resource: allocated
+          .owner
+          .as_ref()
+          .map(|owner| owner.target.clone()),
+        container: allocated.container.clone(),
+      })
+      .collect::<Vec<_>>();
+    ports.sort_by(|a, b| {
+      (a.port, a.end_port, &a.protocol, &a.host_ip).cmp(&(
+        b.port,
+        b.end_port,
+        &b.protocol,
+        &b.host_ip,
+      ))
+    });
+    ports
+  }
+
+  /// Conflicts of the ports with those allocated by other resources
+  /// or unmanaged containers. Pass the `target` to exclude its own
+  /// allocations, or None for a resource which doesn't exist yet.
+  pub fn port_conflicts(
+    &self,
+    target: Option<&ResourceTarget>,
+    ports: &[PortBinding],
+  ) -> Vec<AllocationConflict> {
+    let mut conflicts = Vec::new();
+    for port in ports {
+      for allocated in &self.ports {
+        if !allocated.value.overlaps(port)
+          || is_own(allocated, target)
+        {
+          continue;
+        }
+        push_conflict(
+          &mut conflicts,
+          allocated,
+          format!("Port {port} is already allocated"),
+        );
+      }
+    }
+    conflicts
+  }
+
+  /// Conflicts of the container names with those allocated
+  /// by other resources or unmanaged containers.
+  pub fn container_name_conflicts(
+    &self,
+    target: Option<&ResourceTarget>,
+    container_names: &[String],
+  ) -> Vec<AllocationConflict> {
+    let mut conflicts = Vec::new();
+    for name in container_names {
+      for allocated in &self.container_names {
+        if &allocated.value != name || is_own(allocated, target) {
+          continue;
+        }
+        push_conflict(
+          &mut conflicts,
+          allocated,
+          format!("Container name '{name}' is already used"),
+        );
+      }
+    }
+    conflicts
+  }
+
+  /// Conflicts of the named volumes with those used
+  /// by other resources or unmanaged containers.
+  pub fn volume_conflicts(
+    &self,
+    target: Option<&ResourceTarget>,
+    volumes: &[String],
+  ) -> Vec<AllocationConflict> {
+    let mut conflicts = Vec::new();
+    for volume in volumes {
+      for allocated in &self.volumes {
+        if &allocated.value != volume || is_own(allocated, target) {
+          continue;
+        }
+        push_conflict(
+          &mut conflicts,
+          allocated,
+          format!("Volume '{volume}' is already used"),
+        );
+      }
+    }
+    conflicts
+  }
+
+  /// All the conflicts of the resource allocations.
+  pub fn conflicts(
+    &self,
+    target: Option<&ResourceTarget>,
+    allocations: &ResourceAllocations,
+  ) -> Vec<AllocationConflict> {
+    let mut conflicts = self
+      .container_name_conflicts(target, &allocations.container_names);
+    conflicts.extend(self.port_conflicts(target, &allocations.ports));
+    conflicts
+      .extend(self.volume_conflicts(target, &allocations.volumes));
+    conflicts
+  }
+}
+
+fn is_own<T>(
+  allocated: &Allocated<T>,
+  target: Option<&ResourceTarget>,
+) -> bool {
+  target.is_some()
+    && allocated.owner.as_ref().map(|owner| &owner.target) == target
+}
+
+fn push_conflict<T>(
+  conflicts: &mut Vec<AllocationConflict>,
+  allocated: &Allocated<T>,
+  prefix: String,
+) {
+  let by = match (&allocated.owner, &allocated.container) {
+    (Some(owner), _) => {
+      format!("{} '{}'", owner.target.extract_variant(), owner.name)
+    }
+    (None, Some(container)) => format!("container '{container}'"),
+    (None, None) => String::from("another container"),
+  };
+  let message = format!("{prefix} by {by}");
+  let running = allocated.container.is_some();
+  if let Some(conflict) = conflicts
+    .iter_mut()
+    .find(|conflict| conflict.message == message)
+  {
+    conflict.running |= running;
+    return;
+  }
+  conflicts.push(AllocationConflict { running, message });
+}
+
+/// Formats the conflicts for the Update logs.
+pub fn format_conflicts(conflicts: &[AllocationConflict]) -> String {
+  conflicts
+    .iter()
+    .map(|conflict| {
+      let state = if conflict.running {
+        "running"
+      } else {
+        "configured"
+      };
+      format!("- {} ({state})", conflict.message)
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Logs the conflicts of the resource allocations with the
+/// rest of the Server to the Update. These don't fail the Update,
+/// they are a warning the resource may fail to deploy.
+pub async fn log_allocation_conflicts(
+  server_id: &str,
+  target: &ResourceTarget,
+  allocations: &ResourceAllocations,
+  update: &mut Update,
+) {
+  let conflicts = server_allocations(server_id)
+    .await
+    .conflicts(Some(target), allocations);
+  if conflicts.is_empty() {
+    return;
+  }
+  update.push_simple_log(
+    "Resource Conflicts",
+    format!(
+      "The resource conflicts with others on the Server, and may fail to deploy:\n{}",
+      format_conflicts(&conflicts)
+    ),
+  );
+}
+
+/// Rejects resource writes which would certainly fail to deploy:
+/// a container name already used on the Server, or a port published
+/// by another running container. Ports only configured by other
+/// resources and shared volumes are logged by the resource
+/// create / update hooks instead.
+pub async fn check_allocation_conflicts(
+  server_id: &str,
+  target: Option<&ResourceTarget>,
+  allocations: &ResourceAllocations,
+) -> mogh_error::Result<()> {
+  if server_id.is_empty() {
+    return Ok(());
+  }
+  // Missing servers are reported by the config validation.
+  let Ok(server) = resource::get::<Server>(server_id).await else {
+    return Ok(());
+  };
+  let index = server_allocations(&server.id).await;
+  let mut conflicts = index
+    .container_name_conflicts(target, &allocations.container_names);
+  conflicts.extend(
+    index
+      .port_conflicts(target, &allocations.ports)
+      .into_iter()
+      .filter(|conflict| conflict.running),
+  );
+  if conflicts.is_empty() {
+    return Ok(());
+  }
+  Err(
+    anyhow!(
+      "The resource conflicts with others on Server '{}', and would fail to deploy:\n{}",
+      server.name,
+      format_conflicts(&conflicts)
+    )
+    .status_code(StatusCode::CONFLICT),
+  )
+}
+
+/// The allocations of the Deployment from its config.
+/// Ports are ignored in `host` network mode.
+pub fn deployment_allocations(
+  deployment: &Deployment,
+) -> ResourceAllocations {
+  let ports = if deployment.config.network == "host" {
+    Vec::new()
+  } else {
+    conversions_from_str(&deployment.config.ports)
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|conversion| {
+        parse_port_spec(&format!(
+          "{}:{}",
+          conversion.local, conversion.container
+        ))
+      })
+      .collect()
+  };
+  let volumes = conversions_from_str(&deployment.config.volumes)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|conversion| conversion.local)
+    .filter(|local| is_named_volume(local))
+    .collect();
+  ResourceAllocations {
+    ports,
+    container_names: vec![to_container_compatible_name(
+      deployment.custom_name(),
+    )],
+    volumes,
+  }
+}
+
+/// The allocations of the Stack from its latest compose files,
+/// falling back to the last deployed config.
+/// Only explicit `container_name`s are included, the generated
+/// names are unique to the compose project.
+pub fn stack_allocations(stack: &Stack) -> ResourceAllocations {
+  let contents = if stack.config.file_contents.trim().is_empty() {
+    stack
+      .info
+      .remote_contents
+      .iter()
+      .flatten()
+      .map(|file| file.contents.as_str())
+      .collect::<Vec<_>>()
+  } else {
+    vec![stack.config.file_contents.as_str()]
+  };
+  let mut files = contents
+    .into_iter()
+    .filter_map(|contents| {
+      serde_yaml_ng::from_str::<ComposeFile>(contents).ok()
+    })
+    .collect::<Vec<_>>();
+  if files.is_empty()
+    && let Some(config) = &stack.info.deployed_config
+    && let Ok(file) = serde_yaml_ng::from_str(config)
+  {
+    files.push(file);
+  }
+
+  let project = stack.project_name(true).to_lowercase();
+  let mut allocations = ResourceAllocations::default();
+  for file in files {
+    let mut services = file.services.into_iter().collect::<Vec<_>>();
+    services.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (_, service) in services {
+      if let Some(container_name) = service.container_name
+        && !container_name.contains('$')
+      {
+        allocations.container_names.push(container_name);
+      }
+      if service.network_mode.as_deref() == Some("host") {
+        continue;
+      }
+      allocations
+        .ports
+        .extend(service.ports.iter().filter_map(compose_port));
+    }
+    for (key, volume) in file.volumes {
+      let volume = volume.unwrap_or_default();
+      if volume.is_external() {
+        continue;
+      }
+      let name =
+        volume.name.unwrap_or_else(|| format!("{project}_{key}"));
+      if !name.contains('$') && !allocations.volumes.contains(&name) {
+        allocations.volumes.push(name);
+      }
+    }
+  }
+  allocations
+}
+
+/// Parses a compose `ports` entry, in the short syntax
+/// (eg. `127.0.0.1:8080:80/udp`) or the long syntax.
+/// Entries without a fixed host port return None.
+pub fn compose_port(
+  port: &serde_yaml_ng::Value,
+) -> Option<PortBinding> {
+  match port {
+    serde_yaml_ng::Value::String(spec) => parse_port_spec(spec),
+    serde_yaml_ng::Value::Mapping(_) => {
+      let port =
+        serde_yaml_ng::from_value::<ComposeLongPort>(port.clone())
+          .ok()?;
+      let published = match port.published? {
+        serde_yaml_ng::Value::String(published) => published,
+        serde_yaml_ng::Value::Number(published) => {
+          published.to_string()
+        }
+        _ => return None,
+      };
+      let (start, end) = parse_port_range(&published)?;
+      Some(PortBinding {
+        host_ip: normalize_host_ip(
+          port.host_ip.as_deref().unwrap_or_default(),
+        ),
+        start,
+        end,
+        protocol: port
+          .protocol
+          .unwrap_or_else(|| String::from("tcp"))
+          .to_lowercase(),
+      })
+    }
+    _ => None,
+  }
+}
+
+/// Parses a docker port spec: `[host_ip:]host_port[-end]:container_port[/protocol]`.
+fn parse_port_spec(spec: &str) -> Option<PortBinding> {
+  let spec = spec.trim().trim_matches(['"', '\'']);
+  let (spec, protocol) = match spec.rsplit_once('/') {
+    Some((spec, protocol)) => (spec, protocol.to_lowercase()),
+    None => (spec, String::from("tcp")),
+  };
+  let (host_ip, rest) = if let Some(rest) = spec.strip_prefix('[') {
+    rest.split_once("]:")?
+  } else if spec.matches(':').count() == 2 {
+    spec.split_once(':')?
+  } else {
+    ("", spec)
+  };
+  let (published, _container) = rest.split_once(':')?;
+  let (start, end) = parse_port_range(published)?;
+  Some(PortBinding {
+    host_ip: normalize_host_ip(host_ip),
+    start,
+    end,
+    protocol,
+  })
+}
+
+fn parse_port_range(published: &str) -> Option<(u16, u16)> {
+  let (start, end) = match published.split_once('-') {
+    Some((start, end)) => {
+      (start.trim().parse().ok()?, end.trim().parse().ok()?)
+    }
+    None => {
+      let port = published.trim().parse().ok()?;
+      (port, port)
+    }
+  };
+  (start <= end).then_some((start, end))
+}
+
+/// Unspecified host ips bind all interfaces.
+fn normalize_host_ip(host_ip: &str) -> String {
+  match host_ip.trim() {
+    "0.0.0.0" | "::" => String::new(),
+    host_ip => host_ip.to_string(),
+  }
+}
+
+fn port_protocol(typ: PortTypeEnum) -> &'static str {
+  match typ {
+    PortTypeEnum::EMPTY | PortTypeEnum::TCP => "tcp",
+    PortTypeEnum::UDP => "udp",
+    PortTypeEnum::SCTP => "sctp",
+  }
+}
+
+/// Bind mounts start with a path, named volumes with a name.
+fn is_named_volume(local: &str) -> bool {
+  local.starts_with(|c: char| c.is_ascii_alphanumeric())
+    && !local.contains(['/', '\\', '$'])
+}
+
+/// Anonymous volumes are named by a random 64 char hex id.
+fn is_anonymous_volume(volume: &str) -> bool {
+  volume.len() == 64 && volume.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The parts of a compose file used for the allocations.
+#[derive(Deserialize)]
+struct ComposeFile {
+  #[serde(default)]
+  services: HashMap<String, ComposeService>,
+  #[serde(default)]
+  volumes: HashMap<String, Option<ComposeVolume>>,
+}
+
+#[derive(Deserialize)]
+struct ComposeService {
+  container_name: Option<String>,
+  network_mode: Option<String>,
+  #[serde(default)]
+  ports: Vec<serde_yaml_ng::Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct ComposeVolume {
+  name: Option<String>,
+  external: Option<serde_yaml_ng::Value>,
+}
+
+impl ComposeVolume {
+  fn is_external(&self) -> bool {
+    match &self.external {
+      Some(serde_yaml_ng::Value::Bool(external)) => *external,
+      // Legacy syntax: `external: { name: ... }`
+      Some(serde_yaml_ng::Value::Mapping(_)) => true,
+      _ => false,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct ComposeLongPort {
+  published: Option<serde_yaml_ng::Value>,
+  host_ip: Option<String>,
+  protocol: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn binding(
+    host_ip: &str,
+    start: u16,
+    end: u16,
+    protocol: &str,
+  ) -> PortBinding {
+    PortBinding {
+      host_ip: host_ip.to_string(),
+      start,
+      end,
+      protocol: protocol.to_string(),
+    }
+  }
+
+  #[test]
+  fn parses_port_specs() {
+    for (spec, expected) in [
+      ("8080:80", Some(binding("", 8080, 8080, "tcp"))),
+      ("'8080:80'", Some(binding("", 8080, 8080, "tcp"))),
+      ("8080:80/udp", Some(binding("", 8080, 8080, "udp"))),
+      ("8080:80/UDP", Some(binding("", 8080, 8080, "udp"))),
+      ("8000-8010:8000-8010", Some(binding("", 8000, 8010, "tcp"))),
+      (
+        "127.0.0.1:8080:80",
+        Some(binding("127.0.0.1", 8080, 8080, "tcp")),
+      ),
+      (
+        "127.0.0.1:5000-5001:5000-5001/udp",
+        Some(binding("127.0.0.1", 5000, 5001, "udp")),
+      ),
+      ("0.0.0.0:8080:80", Some(binding("", 8080, 8080, "tcp"))),
+      ("[::1]:8080:80", Some(binding("::1", 8080, 8080, "tcp"))),
+      ("[::]:53:53/udp", Some(binding("", 53, 53, "udp"))),
+      // Container port only, the host port is random.
+      ("80", None),
+      ("127.0.0.1::80", None),
+      // Invalid
+      ("9000-8000:80", None),
+      ("70000:80", None),
+      ("http:80", None),
+    ] {
+      assert_eq!(parse_port_spec(spec), expected, "spec: {spec}");
+    }
+  }
+
+  #[test]
+  fn parses_compose_ports() {
+    for (port, expected) in [
+      ("8080:80", Some(binding("", 8080, 8080, "tcp"))),
+      ("'[::1]:53:53/udp'", Some(binding("::1", 53, 53, "udp"))),
+      (
+        "{ target: 80, published: 8080 }",
+        Some(binding("", 8080, 8080, "tcp")),
+      ),
+      (
+        "{ target: 80, published: '8000-8005', host_ip: 127.0.0.1, protocol: UDP }",
+        Some(binding("127.0.0.1", 8000, 8005, "udp")),
+      ),
+      (
+        "{ target: 80, published: 8080, host_ip: '::' }",
+        Some(binding("", 8080, 8080, "tcp")),
+      ),
+      ("{ target: 80 }", None),
+      ("80", None),
+    ] {
+      let value = serde_yaml_ng::from_str(port).unwrap();
+      assert_eq!(compose_port(&value), expected, "port: {port}");
+    }
+  }
+
+  #[test]
+  fn port_bindings_overlap() {
+    let all = binding("", 8000, 8010, "tcp");
+    assert!(all.overlaps(&binding("127.0.0.1", 8010, 8010, "tcp")));
+    assert!(!all.overlaps(&binding("", 8011, 8020, "tcp")));
+    assert!(!all.overlaps(&binding("", 8000, 8000, "udp")));
+    assert!(
+      !binding("127.0.0.1", 80, 80, "tcp")
+        .overlaps(&binding("::1", 80, 80, "tcp"))
+    );
+  }
+}
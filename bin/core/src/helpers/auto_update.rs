@@ -0,0 +1,138 @@
+use std::{
+  collections::HashMap,
+  sync::{OnceLock, RwLock},
+};
+
+use async_timing_util::{Timelength, wait_until_timelength};
+use komodo_client::entities::{
+  I64, ResourceTarget, deployment::Deployment, komodo_timestamp,
+  stack::Stack,
+};
+
+use crate::{
+  api::write::{
+    check_deployment_for_update_inner, check_stack_for_update_inner,
+  },
+  helpers::{
+    maintenance::is_auto_update_allowed, query::get_swarm_or_server,
+  },
+  resource,
+};
+
+/// Maps resource to the time the auto update was queued.
+type QueuedAutoUpdates = HashMap<ResourceTarget, I64>;
+
+/// Auto updates found outside of the resource update windows,
+/// waiting for a window to open.
+fn queued_auto_updates() -> &'static RwLock<QueuedAutoUpdates> {
+  static QUEUED: OnceLock<RwLock<QueuedAutoUpdates>> =
+    OnceLock::new();
+  QUEUED.get_or_init(Default::default)
+}
+
+/// Queue the auto update for the target,
+/// keeping the original queued time if already queued.
+pub fn queue_auto_update(target: ResourceTarget) {
+  queued_auto_updates()
+    .write()
+    .unwrap()
+    .entry(target)
+    .or_insert_with(komodo_timestamp);
+}
+
+pub fn dequeue_auto_update(target: &ResourceTarget) {
+  queued_auto_updates().write().unwrap().remove(target);
+}
+
+/// Every minute, runs the queued auto updates
+/// for resources with an open update window.
+pub fn spawn_queued_auto_update_loop() {
+  tokio::spawn(async move {
+    loop {
+      wait_until_timelength(Timelength::OneMinute, 0).await;
+      let queued = queued_auto_updates()
+        .read()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+      for target in queued {
+        if let Err(e) = run_queued_auto_update(&target).await {
+          warn!(
+            "Failed to run queued auto update for {target:?} | {e:#}"
+          );
+        }
+      }
+    }
+  });
+}
+
+async fn run_queued_auto_update(
+  target: &ResourceTarget,
+) -> anyhow::Result<()> {
+  match target {
+    ResourceTarget::Deployment(id) => {
+      let deployment = resource::get::<Deployment>(id)
+        .await
+        .inspect_err(|_| dequeue_auto_update(target))?;
+      if !deployment.config.auto_update {
+        dequeue_auto_update(target);
+        return Ok(());
+      }
+      let swarm_or_server = get_swarm_or_server(
+        &deployment.config.swarm_id,
+        &deployment.config.server_id,
+      )
+      .await?;
+      if !is_auto_update_allowed(
+        &deployment.config.auto_update_windows,
+        deployment.config.auto_update_in_maintenance,
+        &swarm_or_server,
+        komodo_timestamp(),
+      ) {
+        return Ok(());
+      }
+      dequeue_auto_update(target);
+      check_deployment_for_update_inner(
+        deployment,
+        &swarm_or_server,
+        false,
+        true,
+      )
+      .await?;
+    }
+    ResourceTarget::Stack(id) => {
+      let stack = resource::get::<Stack>(id)
+        .await
+        .inspect_err(|_| dequeue_auto_update(target))?;
+      if !stack.config.auto_update {
+        dequeue_auto_update(target);
+        return Ok(());
+      }
+      let swarm_or_server = get_swarm_or_server(
+        &stack.config.swarm_id,
+        &stack.config.server_id,
+      )
+      .await?;
+      if !is_auto_update_allowed(
+        &stack.config.auto_update_windows,
+        stack.config.auto_update_in_maintenance,
+        &swarm_or_server,
+        komodo_timestamp(),
+      ) {
+        return Ok(());
+      }
+      dequeue_auto_update(target);
+      check_stack_for_update_inner(
+        stack.id,
+        &swarm_or_server,
+        false,
+        true,
+        false,
+      )
+      .await?;
+    }
+    _ => dequeue_auto_update(target),
+  }
+  Ok(())
+}
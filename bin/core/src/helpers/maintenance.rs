@@ -4,6 +4,7 @@ use anyhow::Context;
 use chrono::{Datelike, Local};
 use komodo_client::entities::{
   DayOfWeek, MaintenanceScheduleType, MaintenanceWindow,
+  SwarmOrServer,
 };
 
 use crate::config::core_config;
@@ -18,6 +19,36 @@ pub fn is_in_maintenance(
     .any(|window| is_maintenance_window_active(window, timestamp))
 }
 
+/// Check if an auto update can be deployed at the timestamp.
+/// Uses the resource `auto_update_windows`, along with the
+/// Server / Swarm `maintenance_windows` if `in_maintenance` is enabled.
+/// If no windows are enabled, auto updates are always allowed.
+pub fn is_auto_update_allowed(
+  windows: &[MaintenanceWindow],
+  in_maintenance: bool,
+  swarm_or_server: &SwarmOrServer,
+  timestamp: i64,
+) -> bool {
+  let maintenance_windows = match swarm_or_server {
+    SwarmOrServer::Server(server) if in_maintenance => {
+      server.config.maintenance_windows.as_slice()
+    }
+    SwarmOrServer::Swarm(swarm) if in_maintenance => {
+      swarm.config.maintenance_windows.as_slice()
+    }
+    _ => &[],
+  };
+  if !windows
+    .iter()
+    .chain(maintenance_windows)
+    .any(|window| window.enabled)
+  {
+    return true;
+  }
+  is_in_maintenance(windows, timestamp)
+    || is_in_maintenance(maintenance_windows, timestamp)
+}
+
 /// Check if the current timestamp falls within this maintenance window
 pub fn is_maintenance_window_active(
   window: &MaintenanceWindow,
@@ -28,6 +28,8 @@ use crate::{
 
 pub mod action_state;
 pub mod all_resources;
+pub mod allocation;
+pub mod auto_update;
 pub mod builder;
 pub mod channel;
 pub mod image_digest;
@@ -36,6 +38,7 @@ pub mod matcher;
 pub mod procedure;
 pub mod prune;
 pub mod query;
+pub mod scan;
 pub mod swarm;
 pub mod terminal;
 pub mod update;
@@ -1,4 +1,7 @@
-use std::time::{Duration, Instant};
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
 
 use anyhow::{Context, anyhow};
 use database::mungos::by_id::find_one_by_id;
@@ -7,6 +10,7 @@ use futures_util::future::join_all;
 use komodo_client::{
   api::execute::*,
   entities::{
+    JsonValue, ResourceTarget,
     action::Action,
     build::Build,
     deployment::Deployment,
@@ -25,7 +29,9 @@ use uuid::Uuid;
 
 use crate::{
   api::{
-    execute::{ExecuteArgs, ExecuteRequest},
+    execute::{
+      ExecuteArgs, ExecuteRequest, action::action_run_output,
+    },
     write::WriteArgs,
   },
   config::core_config,
@@ -35,11 +41,16 @@ use crate::{
 
 use super::update::{init_execution_update, update_update};
 
+/// The outputs of the Actions run in previous stages,
+/// keyed by Action name.
+type ActionOutputs = HashMap<String, JsonValue>;
+
 pub async fn execute_procedure(
   procedure: &Procedure,
   update: &Mutex<Update>,
   cancel: CancellationToken,
 ) -> anyhow::Result<()> {
+  let mut outputs = ActionOutputs::new();
   for stage in &procedure.config.stages {
     if cancel.is_cancelled() {
       add_line_to_update(
@@ -66,7 +77,7 @@ pub async fn execute_procedure(
     )
     .await;
     let timer = Instant::now();
-    execute_procedure_stage(
+    let stage_outputs = execute_procedure_stage(
       stage
         .executions
         .iter()
@@ -75,6 +86,7 @@ pub async fn execute_procedure(
         .collect(),
       &procedure.id,
       &procedure.name,
+      &outputs,
       update,
     )
     .await
@@ -85,6 +97,7 @@ pub async fn execute_procedure(
         timer.elapsed(),
       )
     })?;
+    outputs.extend(stage_outputs);
     add_line_to_update(
       update,
       &format!(
@@ -106,8 +119,9 @@ async fn execute_procedure_stage(
   _executions: Vec<Execution>,
   parent_id: &str,
   parent_name: &str,
+  outputs: &ActionOutputs,
   update: &Mutex<Update>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<ActionOutputs> {
   let mut executions = Vec::with_capacity(_executions.capacity());
   for execution in _executions {
     match execution {
@@ -198,6 +212,21 @@ async fn execute_procedure_stage(
       execution => executions.push(execution),
     }
   }
+  // Fill in references to outputs of previous stages
+  for execution in &mut executions {
+    if let Execution::RunAction(req) = execution
+      && let Some(args) = &mut req.args
+    {
+      for value in args.values_mut() {
+        template_outputs(value, outputs).with_context(|| {
+          format!(
+            "Failed to fill in outputs for Action '{}' args",
+            req.action
+          )
+        })?;
+      }
+    }
+  }
   let futures = executions.into_iter().map(|execution| async move {
     let now = Instant::now();
     add_line_to_update(
@@ -225,13 +254,148 @@ async fn execute_procedure_stage(
     .await;
     res
   });
-  join_all(futures)
+  let updates = join_all(futures)
     .await
     .into_iter()
     .collect::<anyhow::Result<Vec<_>>>()?;
+  let all = all_resources_cache().load();
+  stage_outputs(updates.into_iter().filter_map(|update| {
+    let ResourceTarget::Action(id) = &update.target else {
+      return None;
+    };
+    let name = all.actions.get(id)?.name.clone();
+    Some((name, action_run_output(&update)?))
+  }))
+}
+
+/// Collects the outputs of the Actions run in a stage.
+/// The outputs are keyed by Action name, so an Action
+/// with output may only run once per stage.
+fn stage_outputs(
+  outputs: impl IntoIterator<Item = (String, JsonValue)>,
+) -> anyhow::Result<ActionOutputs> {
+  let mut res = ActionOutputs::new();
+  for (name, output) in outputs {
+    if res.insert(name.clone(), output).is_some() {
+      return Err(anyhow!(
+        "Action '{name}' ran more than once in the stage, so its outputs would overwrite each other"
+      ));
+    }
+  }
+  Ok(res)
+}
+
+/// Replaces `{{ outputs.<action>.<path> }}` references in string values
+/// with the output of an Action run in a previous stage.
+/// A string which is exactly one reference takes the referenced JSON value,
+/// otherwise the values are inserted into the string.
+fn template_outputs(
+  value: &mut JsonValue,
+  outputs: &ActionOutputs,
+) -> anyhow::Result<()> {
+  match value {
+    JsonValue::String(string) => {
+      if let Some(templated) = template_string(string, outputs)? {
+        *value = templated;
+      }
+    }
+    JsonValue::Array(values) => {
+      for value in values {
+        template_outputs(value, outputs)?;
+      }
+    }
+    JsonValue::Object(object) => {
+      for value in object.values_mut() {
+        template_outputs(value, outputs)?;
+      }
+    }
+    _ => {}
+  }
   Ok(())
 }
 
+fn template_string(
+  string: &str,
+  outputs: &ActionOutputs,
+) -> anyhow::Result<Option<JsonValue>> {
+  if !string.contains("{{") {
+    return Ok(None);
+  }
+
+  // Exact reference keeps the JSON type
+  if let Some(reference) = string
+    .trim()
+    .strip_prefix("{{")
+    .and_then(|rest| rest.strip_suffix("}}"))
+    && !reference.contains("{{")
+    && let Some(path) = reference.trim().strip_prefix("outputs.")
+  {
+    return resolve_output(path, outputs).map(Some);
+  }
+
+  let mut res = String::new();
+  let mut rest = string;
+  while let Some(start) = rest.find("{{") {
+    res.push_str(&rest[..start]);
+    let after = &rest[start + 2..];
+    let Some(end) = after.find("}}") else {
+      rest = &rest[start..];
+      break;
+    };
+    match after[..end].trim().strip_prefix("outputs.") {
+      Some(path) => match resolve_output(path, outputs)? {
+        JsonValue::String(value) => res.push_str(&value),
+        value => res.push_str(&value.to_string()),
+      },
+      // Not an output reference, leave it as is.
+      None => res.push_str(&rest[start..start + end + 4]),
+    }
+    rest = &after[end + 2..];
+  }
+  res.push_str(rest);
+
+  Ok(Some(JsonValue::String(res)))
+}
+
+/// Resolves `<action>.<path>` against the outputs.
+/// Action names may contain '.', so the longest matching name is used.
+fn resolve_output(
+  path: &str,
+  outputs: &ActionOutputs,
+) -> anyhow::Result<JsonValue> {
+  let (name, output) = outputs
+    .iter()
+    .filter(|(name, _)| {
+      path == name.as_str()
+        || path
+          .strip_prefix(name.as_str())
+          .is_some_and(|rest| rest.starts_with('.'))
+    })
+    .max_by_key(|(name, _)| name.len())
+    .with_context(|| {
+      format!(
+        "No Action in a previous stage has output for 'outputs.{path}'"
+      )
+    })?;
+  let mut value = output;
+  if let Some(fields) = path[name.len()..].strip_prefix('.') {
+    for field in fields.split('.') {
+      value = match value {
+        JsonValue::Object(object) => object.get(field),
+        JsonValue::Array(values) => field
+          .parse::<usize>()
+          .ok()
+          .and_then(|index| values.get(index)),
+        _ => None,
+      }
+      .with_context(|| {
+        format!("Output of Action '{name}' has no field '{fields}'")
+      })?;
+    }
+  }
+  Ok(value.clone())
+}
+
 #[instrument(
   "ExecuteProcedureExecution",
   skip(parent_id, parent_name)
@@ -241,7 +405,7 @@ async fn execute_execution(
   // used to prevent recursive procedure
   parent_id: &str,
   parent_name: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Update> {
   let user = procedure_user().to_owned();
   let task_id = Uuid::new_v4();
   // Standard pattern: init update, resolve with ExecuteArgs, handle result.
@@ -281,7 +445,7 @@ async fn execute_execution(
   }
 
   let update = match execution {
-    Execution::None(_) => return Ok(()),
+    Execution::None(_) => return Ok(Default::default()),
     // Special: self-referential guard
     Execution::RunProcedure(req) => {
       if req.procedure == parent_id || req.procedure == parent_name {
@@ -349,6 +513,9 @@ async fn execute_execution(
     }
     Execution::RunBuild(req) => resolve_execute!(RunBuild, req),
     Execution::CancelBuild(req) => resolve_execute!(CancelBuild, req),
+    Execution::CleanupBuildImages(req) => {
+      resolve_execute!(CleanupBuildImages, req)
+    }
     Execution::Deploy(req) => resolve_execute!(Deploy, req),
     Execution::PullDeployment(req) => {
       resolve_execute!(PullDeployment, req)
@@ -431,6 +598,9 @@ async fn execute_execution(
       resolve_execute!(PruneDockerBuilders, req)
     }
     Execution::PruneBuildx(req) => resolve_execute!(PruneBuildx, req),
+    Execution::PruneBuildCache(req) => {
+      resolve_execute!(PruneBuildCache, req)
+    }
     Execution::PruneSystem(req) => resolve_execute!(PruneSystem, req),
     Execution::RunSync(req) => resolve_execute!(RunSync, req),
     Execution::DeployStack(req) => resolve_execute!(DeployStack, req),
@@ -503,7 +673,7 @@ async fn execute_execution(
   };
 
   if update.success {
-    Ok(())
+    Ok(update)
   } else {
     Err(anyhow!(
       "{}: Execution not successful, see update: '{}/updates/{}'",
@@ -595,28 +765,32 @@ impl ExtendBatch for BatchRunAction {
 impl ExtendBatch for BatchRunBuild {
   type Resource = Build;
   fn single_execution(build: String) -> Execution {
-    Execution::RunBuild(RunBuild { build })
+    Execution::RunBuild(RunBuild {
+      build,
+      commit: None,
+      version: None,
+    })
   }
 }
 
 impl ExtendBatch for BatchCloneRepo {
   type Resource = Repo;
   fn single_execution(repo: String) -> Execution {
-    Execution::CloneRepo(CloneRepo { repo })
+    Execution::CloneRepo(CloneRepo { repo, commit: None })
   }
 }
 
 impl ExtendBatch for BatchPullRepo {
   type Resource = Repo;
   fn single_execution(repo: String) -> Execution {
-    Execution::PullRepo(PullRepo { repo })
+    Execution::PullRepo(PullRepo { repo, commit: None })
   }
 }
 
 impl ExtendBatch for BatchBuildRepo {
   type Resource = Repo;
   fn single_execution(repo: String) -> Execution {
-    Execution::BuildRepo(BuildRepo { repo })
+    Execution::BuildRepo(BuildRepo { repo, commit: None })
   }
 }
 
@@ -649,6 +823,7 @@ impl ExtendBatch for BatchDeployStack {
       stack,
       services: Vec::new(),
       stop_time: None,
+      commit: None,
     })
   }
 }
@@ -749,6 +924,7 @@ pub fn replace_procedure_stage_ids_with_names(
         CancelAction => action, actions;
         RunBuild => build, builds;
         CancelBuild => build, builds;
+        CleanupBuildImages => build, builds;
         Deploy => deployment, deployments;
         PullDeployment => deployment, deployments;
         StartDeployment => deployment, deployments;
@@ -781,6 +957,7 @@ pub fn replace_procedure_stage_ids_with_names(
         PruneVolumes => server, servers;
         PruneDockerBuilders => server, servers;
         PruneBuildx => server, servers;
+        PruneBuildCache => server, servers;
         PruneSystem => server, servers;
         RunSync => sync, syncs;
         CommitSync => sync, syncs;
@@ -809,3 +986,141 @@ pub fn replace_procedure_stage_ids_with_names(
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn outputs() -> ActionOutputs {
+    HashMap::from([
+      (
+        String::from("build"),
+        json!({ "version": "1.2.3", "count": 3, "ok": true }),
+      ),
+      (
+        String::from("deploy"),
+        json!({ "servers": ["server-a", "server-b"] }),
+      ),
+      (String::from("app"), json!({ "v": 1 })),
+      (String::from("app.v2"), json!({ "tag": "latest" })),
+    ])
+  }
+
+  fn template(string: &str) -> anyhow::Result<Option<JsonValue>> {
+    template_string(string, &outputs())
+  }
+
+  #[test]
+  fn exact_reference_keeps_json_type() {
+    assert_eq!(
+      template("{{ outputs.build.count }}").unwrap(),
+      Some(json!(3))
+    );
+    assert_eq!(
+      template(" {{outputs.build.ok}} ").unwrap(),
+      Some(json!(true))
+    );
+    assert_eq!(
+      template("{{ outputs.deploy }}").unwrap(),
+      Some(json!({ "servers": ["server-a", "server-b"] }))
+    );
+  }
+
+  #[test]
+  fn inserts_several_references_inline() {
+    assert_eq!(
+      template(
+        "v{{ outputs.build.version }} x{{ outputs.build.count }}"
+      )
+      .unwrap(),
+      Some(json!("v1.2.3 x3"))
+    );
+  }
+
+  #[test]
+  fn leaves_unterminated_reference() {
+    assert_eq!(
+      template("v{{ outputs.build.version }} {{ outputs.build")
+        .unwrap(),
+      Some(json!("v1.2.3 {{ outputs.build"))
+    );
+  }
+
+  #[test]
+  fn leaves_other_braces() {
+    assert_eq!(template("no braces").unwrap(), None);
+    assert_eq!(
+      template("{{ secrets.token }}").unwrap(),
+      Some(json!("{{ secrets.token }}"))
+    );
+    assert_eq!(
+      template("{{ secrets.token }}-{{ outputs.build.count }}")
+        .unwrap(),
+      Some(json!("{{ secrets.token }}-3"))
+    );
+  }
+
+  #[test]
+  fn indexes_arrays() {
+    assert_eq!(
+      template("{{ outputs.deploy.servers.1 }}").unwrap(),
+      Some(json!("server-b"))
+    );
+    assert!(template("{{ outputs.deploy.servers.2 }}").is_err());
+    assert!(template("{{ outputs.deploy.servers.first }}").is_err());
+  }
+
+  #[test]
+  fn matches_action_names_with_dots() {
+    assert_eq!(
+      template("{{ outputs.app.v2.tag }}").unwrap(),
+      Some(json!("latest"))
+    );
+    assert_eq!(
+      template("{{ outputs.app.v }}").unwrap(),
+      Some(json!(1))
+    );
+    assert!(template("{{ outputs.app.v3 }}").is_err());
+  }
+
+  #[test]
+  fn fails_on_missing_output() {
+    assert!(template("{{ outputs.missing.field }}").is_err());
+    assert!(template("v{{ outputs.build.missing }}").is_err());
+  }
+
+  #[test]
+  fn templates_nested_args() {
+    let mut value = json!({
+      "tag": "{{ outputs.build.version }}",
+      "servers": ["{{ outputs.deploy.servers.0 }}", 1],
+    });
+    template_outputs(&mut value, &outputs()).unwrap();
+    assert_eq!(
+      value,
+      json!({ "tag": "1.2.3", "servers": ["server-a", 1] })
+    );
+  }
+
+  #[test]
+  fn rejects_duplicate_action_outputs() {
+    assert!(
+      stage_outputs([
+        (String::from("build"), json!(1)),
+        (String::from("build"), json!(2)),
+      ])
+      .is_err()
+    );
+    assert_eq!(
+      stage_outputs([
+        (String::from("build"), json!(1)),
+        (String::from("deploy"), json!(2)),
+      ])
+      .unwrap()
+      .len(),
+      2
+    );
+  }
+}
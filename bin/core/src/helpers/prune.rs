@@ -6,7 +6,10 @@ use database::mungos::{find::find_collect, mongodb::bson::doc};
 use futures_util::{StreamExt, stream::FuturesUnordered};
 use periphery_client::api::docker::PruneImages;
 
-use crate::{config::core_config, state::db_client};
+use crate::{
+  config::core_config, stack::preview::prune_stack_previews,
+  state::db_client,
+};
 
 use super::periphery_client;
 
@@ -14,8 +17,19 @@ pub fn spawn_prune_loop() {
   tokio::spawn(async move {
     loop {
       wait_until_timelength(Timelength::OneDay, 5000).await;
-      let (images_res, stats_res, alerts_res) =
-        tokio::join!(prune_images(), prune_stats(), prune_alerts());
+      let (
+        images_res,
+        stats_res,
+        alerts_res,
+        revisions_res,
+        previews_res,
+      ) = tokio::join!(
+        prune_images(),
+        prune_stats(),
+        prune_alerts(),
+        prune_revisions(),
+        prune_stack_previews()
+      );
       if let Err(e) = images_res {
         error!("error in pruning images | {e:#}");
       }
@@ -25,6 +39,12 @@ pub fn spawn_prune_loop() {
       if let Err(e) = alerts_res {
         error!("error in pruning alerts | {e:#}");
       }
+      if let Err(e) = revisions_res {
+        error!("error in pruning resource revisions | {e:#}");
+      }
+      if let Err(e) = previews_res {
+        error!("error in pruning stack previews | {e:#}");
+      }
     }
   });
 }
@@ -101,3 +121,22 @@ async fn prune_alerts() -> anyhow::Result<()> {
   }
   Ok(())
 }
+
+async fn prune_revisions() -> anyhow::Result<()> {
+  if core_config().keep_revisions_for_days == 0 {
+    return Ok(());
+  }
+  let delete_before_ts = (unix_timestamp_ms()
+    - core_config().keep_revisions_for_days as u128 * ONE_DAY_MS)
+    as i64;
+  let res = db_client()
+    .resource_revisions
+    .delete_many(doc! {
+      "ts": { "$lt": delete_before_ts }
+    })
+    .await?;
+  if res.deleted_count > 0 {
+    info!("deleted {} resource revisions from db", res.deleted_count);
+  }
+  Ok(())
+}
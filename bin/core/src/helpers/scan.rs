@@ -0,0 +1,140 @@
+use anyhow::Context;
+use database::mungos::mongodb::bson::doc;
+use komodo_client::entities::{
+  ResourceTarget, SwarmOrServer,
+  alert::{Alert, AlertData, SeverityLevel},
+  komodo_timestamp,
+  scan::{ImageScan, ImageScanReport, VulnerabilityThreshold},
+};
+use periphery_client::api::docker::ScanImage;
+
+use crate::{
+  alert::send_alerts, helpers::swarm_or_server_request,
+  state::db_client,
+};
+
+/// Stores the scan report, replacing any previous
+/// scan of the same image digest for the target.
+pub async fn record_image_scan(
+  target: ResourceTarget,
+  image: String,
+  report: ImageScanReport,
+) -> anyhow::Result<ImageScan> {
+  let ImageScanReport {
+    scanner,
+    digest,
+    counts,
+    findings,
+  } = report;
+  let scan = ImageScan {
+    id: Default::default(),
+    ts: komodo_timestamp(),
+    target,
+    image,
+    digest,
+    scanner,
+    counts,
+    findings,
+  };
+  let (variant, id) = scan.target.extract_variant_id();
+  db_client()
+    .image_scans
+    .replace_one(
+      doc! {
+        "target.type": variant.as_ref(),
+        "target.id": id,
+        "image": &scan.image,
+        "digest": &scan.digest,
+      },
+      &scan,
+    )
+    .upsert(true)
+    .await
+    .context("Failed to record image scan on db")?;
+  Ok(scan)
+}
+
+/// Sends an `ImageVulnerabilities` alert if the
+/// scan findings exceed the threshold.
+pub async fn alert_image_scan(
+  scan: &ImageScan,
+  name: String,
+  threshold: VulnerabilityThreshold,
+) {
+  if threshold.exceeded_by(&scan.counts) == 0 {
+    return;
+  }
+  let (resource_type, id) = scan.target.extract_variant_id();
+  let ts = komodo_timestamp();
+  let alert = Alert {
+    id: Default::default(),
+    target: scan.target.clone(),
+    ts,
+    resolved: true,
+    resolved_ts: Some(ts),
+    level: SeverityLevel::Warning,
+    data: AlertData::ImageVulnerabilities {
+      resource_type,
+      id: id.clone(),
+      name,
+      image: scan.image.clone(),
+      counts: scan.counts,
+    },
+  };
+  send_alerts(std::slice::from_ref(&alert)).await;
+  if let Err(e) = db_client().alerts.insert_one(alert).await {
+    error!(
+      "Failed to record image vulnerabilities alert to db | {e:#}"
+    );
+  }
+}
+
+/// Scans images used by a Deployment / Stack after deploy,
+/// recording the results and alerting on the threshold.
+/// Runs in the background to not hold up the deploy.
+pub fn spawn_deployed_image_scans(
+  swarm_or_server: SwarmOrServer,
+  target: ResourceTarget,
+  name: String,
+  images: Vec<String>,
+  threshold: VulnerabilityThreshold,
+) {
+  if images.is_empty() {
+    return;
+  }
+  tokio::spawn(async move {
+    for image in images {
+      let res = match swarm_or_server_request(
+        &swarm_or_server,
+        ScanImage {
+          name: image.clone(),
+        },
+      )
+      .await
+      {
+        Ok(res) => res,
+        Err(e) => {
+          warn!("Failed to scan image {image} for {name} | {e:#}");
+          continue;
+        }
+      };
+      let Some(report) = res.report else {
+        warn!(
+          "Failed to scan image {image} for {name} | {}",
+          res.log.stderr
+        );
+        continue;
+      };
+      match record_image_scan(target.clone(), image.clone(), report)
+        .await
+      {
+        Ok(scan) => {
+          alert_image_scan(&scan, name.clone(), threshold).await
+        }
+        Err(e) => {
+          warn!("Failed to record image scan for {image} | {e:#}")
+        }
+      }
+    }
+  });
+}
@@ -206,6 +206,7 @@ pub async fn init_execution_update(
       (PruneVolumes, Server, server),
       (PruneDockerBuilders, Server, server),
       (PruneBuildx, Server, server),
+      (PruneBuildCache, Server, server),
       (PruneSystem, Server, server),
       // Deployment
       (Deploy, Deployment, deployment),
@@ -219,6 +220,7 @@ pub async fn init_execution_update(
       // Build
       (RunBuild, Build, build),
       (CancelBuild, Build, build),
+      (CleanupBuildImages, Build, build),
       // Repo
       (CloneRepo, Repo, repo),
       (PullRepo, Repo, repo),
@@ -234,6 +236,7 @@ pub async fn init_execution_update(
       (RunSync, ResourceSync, sync),
       // Stack (simple)
       (RunStackService, Stack, stack),
+      (ValidateStack, Stack, stack),
       // Alerter
       (TestAlerter, Alerter, alerter),
     ],
@@ -3,7 +3,9 @@
 //! This module provides validation functions for user inputs to prevent
 //! invalid data from entering the system and improve security.
 
-use anyhow::Context;
+use std::path::{Component, Path};
+
+use anyhow::{Context, anyhow};
 use mogh_validations::{StringValidator, StringValidatorMatches};
 
 use crate::config::core_config;
@@ -83,3 +85,17 @@ pub fn validate_variable_value(value: &str) -> anyhow::Result<()> {
     .validate(value)
     .context("Failed to validate variable value")
 }
+
+/// Validate database backup folder names
+///
+/// - Must be a single folder name inside the backups folder,
+///   eg `2025-08-01_05-04-53`.
+pub fn validate_backup_folder_name(name: &str) -> anyhow::Result<()> {
+  let mut components = Path::new(name).components();
+  match (components.next(), components.next()) {
+    (Some(Component::Normal(_)), None) => Ok(()),
+    _ => Err(anyhow!(
+      "Backup must be a single folder name, got '{name}'"
+    )),
+  }
+}
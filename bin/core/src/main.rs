@@ -19,6 +19,7 @@ mod monitor;
 mod network;
 mod periphery;
 mod permission;
+mod registry;
 mod report;
 mod resource;
 mod schedule;
@@ -72,6 +73,7 @@ async fn app() -> anyhow::Result<()> {
     resource::spawn_action_state_refresh_loop();
     schedule::spawn_schedule_executor();
     helpers::prune::spawn_prune_loop();
+    helpers::auto_update::spawn_queued_auto_update_loop();
     report::spawn_reporting_loop();
   }
   .instrument(startup_span)
@@ -0,0 +1,347 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use formatting::format_serror;
+use futures_util::{StreamExt, stream};
+use komodo_client::entities::{
+  Version,
+  build::{Build, BuildConfig},
+  komodo_timestamp,
+  update::Update,
+};
+
+use super::{
+  REGISTRY_CONCURRENCY, RegistryClient,
+  tags::{image_tag_postfixes, is_commit_tag, parse_version_tag},
+  usage::{TagUsage, image_usage},
+};
+
+const ONE_DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Deletes old versions of the Build image from each of its
+/// image registries, according to the Build retention policy.
+/// Versions in use by any Deployment or Stack are always kept.
+pub async fn cleanup_build_images(
+  build: &Build,
+  update: &mut Update,
+) {
+  let BuildConfig {
+    retain_versions,
+    retain_days,
+    image_registry,
+    ..
+  } = &build.config;
+
+  if *retain_versions <= 0 && *retain_days <= 0 {
+    update.push_error_log(
+      "Cleanup Images",
+      String::from(
+        "Build has no retention policy. Configure 'retain_versions' and / or 'retain_days'.",
+      ),
+    );
+    return;
+  }
+
+  if image_registry.iter().all(|r| r.domain.is_empty()) {
+    update.push_error_log(
+      "Cleanup Images",
+      String::from("Build does not push to an image registry"),
+    );
+    return;
+  }
+
+  let usage = image_usage().await;
+
+  for (registry, image_name) in
+    image_registry.iter().zip(build.get_image_names())
+  {
+    if registry.domain.is_empty() {
+      continue;
+    }
+    let stage = format!("Cleanup {image_name}");
+    let res = cleanup_image(
+      build,
+      &registry.domain,
+      &registry.account,
+      &image_name,
+      usage.get(&image_name),
+    )
+    .await
+    .with_context(|| format!("Failed to clean up {image_name}"));
+    match res {
+      Ok(deleted) if deleted.is_empty() => update
+        .push_simple_log(&stage, String::from("No images to delete")),
+      Ok(deleted) => update.push_simple_log(
+        &stage,
+        format!("Deleted tags:\n{}", deleted.join("\n")),
+      ),
+      Err(e) => {
+        update.push_error_log(&stage, format_serror(&e.into()))
+      }
+    }
+  }
+}
+
+/// Returns the deleted tags.
+async fn cleanup_image(
+  build: &Build,
+  domain: &str,
+  account: &str,
+  image_name: &str,
+  in_use: Option<&HashMap<String, TagUsage>>,
+) -> anyhow::Result<Vec<String>> {
+  let repository = image_name
+    .strip_prefix(domain)
+    .and_then(|repository| repository.strip_prefix('/'))
+    .context("Image name is not under the registry domain")?;
+  let client = RegistryClient::new(domain, account).await?;
+  let tags = client.list_tags(repository).await?;
+
+  let empty = HashMap::new();
+  let in_use = in_use.unwrap_or(&empty);
+  let now = komodo_timestamp();
+
+  let mut delete_tags = HashSet::new();
+  for (version, tag) in expired_versions(build, &tags, in_use) {
+    if build.config.retain_days > 0 {
+      let info =
+        client.get_image_info(repository, tag).await.with_context(
+          || format!("Failed to get created time for {tag}"),
+        )?;
+      // Keep images with unknown age
+      let Some(created) = info.created else {
+        continue;
+      };
+      if now - created < build.config.retain_days * ONE_DAY_MS {
+        continue;
+      }
+    }
+    delete_tags.insert(tag.clone());
+    // Also remove the platform specific tags of the version
+    delete_tags.extend(platform_tags(build, version, &tags));
+  }
+
+  if delete_tags.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  // Get the digests to keep those which any other tag points to.
+  // Commit hash tags always share a digest with a version tag,
+  // so can be ignored.
+  // The tags are owned by the futures, borrowed tags
+  // make the stream not Send for the execute api.
+  let digest_tags = tags
+    .iter()
+    .filter(|tag| !is_commit_tag(build, tag))
+    .cloned()
+    .collect::<Vec<_>>();
+  let client = &client;
+  let digests =
+    stream::iter(digest_tags.into_iter().map(|tag| async move {
+      client
+        .get_digest(repository, &tag)
+        .await
+        .map(|digest| (tag, digest))
+    }))
+    .buffer_unordered(REGISTRY_CONCURRENCY)
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+  let mut deleted = Vec::new();
+  for (digest, mut tags) in delete_digests(&digests, &delete_tags) {
+    client.delete_manifest(repository, digest).await?;
+    tags.sort();
+    deleted.push(format!("{} ({digest})", tags.join(", ")));
+  }
+  deleted.sort();
+
+  Ok(deleted)
+}
+
+/// The version tags outside of `retain_versions`
+/// for each variant postfix, skipping tags in use.
+/// These are deleted once older than `retain_days`.
+fn expired_versions<'a>(
+  build: &Build,
+  tags: &'a [String],
+  in_use: &HashMap<String, TagUsage>,
+) -> Vec<(Version, &'a String)> {
+  let retain = build.config.retain_versions.max(0) as usize;
+  let mut expired = Vec::new();
+  for postfix in image_tag_postfixes(build) {
+    let mut versions = tags
+      .iter()
+      .filter_map(|tag| {
+        parse_version_tag(tag, &postfix).map(|v| (v, tag))
+      })
+      .collect::<Vec<_>>();
+    // Newest first
+    versions.sort_by_key(|(v, _)| {
+      std::cmp::Reverse((v.major, v.minor, v.patch))
+    });
+    expired.extend(
+      versions
+        .into_iter()
+        .skip(retain)
+        .filter(|(_, tag)| !in_use.contains_key(*tag)),
+    );
+  }
+  expired
+}
+
+/// The platform specific tags of the version
+/// pushed by platform builders which exist in the registry.
+fn platform_tags(
+  build: &Build,
+  version: Version,
+  tags: &[String],
+) -> Vec<String> {
+  let mut version_build = build.clone();
+  version_build.config.version = version;
+  build
+    .get_platform_builder_groups()
+    .into_iter()
+    .map(|(_, platforms)| {
+      version_build.get_platform_image_tag(&platforms)
+    })
+    .filter(|tag| tags.contains(tag))
+    .collect()
+}
+
+/// Groups the tags to delete by digest.
+/// Deleting a digest removes every tag pointing to it,
+/// so digests which any other tag points to are kept.
+fn delete_digests<'a>(
+  digests: &'a HashMap<String, String>,
+  delete_tags: &HashSet<String>,
+) -> HashMap<&'a String, Vec<&'a str>> {
+  let keep_digests = digests
+    .iter()
+    .filter(|(tag, _)| !delete_tags.contains(*tag))
+    .map(|(_, digest)| digest)
+    .collect::<HashSet<_>>();
+  let mut delete = HashMap::<&String, Vec<&str>>::new();
+  for (tag, digest) in digests {
+    if delete_tags.contains(tag) && !keep_digests.contains(digest) {
+      delete.entry(digest).or_default().push(tag);
+    }
+  }
+  delete
+}
+
+#[cfg(test)]
+mod tests {
+  use komodo_client::entities::build::BuildMatrixEntry;
+
+  use super::*;
+
+  fn build(retain_versions: i64) -> Build {
+    let mut build = Build::default();
+    build.config.retain_versions = retain_versions;
+    build
+  }
+
+  fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+  }
+
+  fn expired(
+    build: &Build,
+    tags: &[String],
+    in_use: &[&str],
+  ) -> Vec<String> {
+    let in_use = in_use
+      .iter()
+      .map(|tag| (tag.to_string(), TagUsage::default()))
+      .collect();
+    let mut expired = expired_versions(build, tags, &in_use)
+      .into_iter()
+      .map(|(_, tag)| tag.clone())
+      .collect::<Vec<_>>();
+    expired.sort();
+    expired
+  }
+
+  #[test]
+  fn retains_newest_versions() {
+    let tags = tags(&[
+      "latest", "1", "1.2", "0.9.0", "1.2.0", "1.10.0", "1.2.1",
+      "a6f8e83",
+    ]);
+    assert_eq!(expired(&build(2), &tags, &[]), ["0.9.0", "1.2.0"]);
+    assert_eq!(expired(&build(5), &tags, &[]), Vec::<String>::new());
+    // Age only policy considers every version
+    assert_eq!(
+      expired(&build(0), &tags, &[]),
+      ["0.9.0", "1.10.0", "1.2.0", "1.2.1"]
+    );
+  }
+
+  #[test]
+  fn keeps_versions_in_use() {
+    let tags = tags(&["1.0.0", "1.1.0", "1.2.0", "1.3.0"]);
+    assert_eq!(
+      expired(&build(1), &tags, &["1.1.0"]),
+      ["1.0.0", "1.2.0"]
+    );
+  }
+
+  #[test]
+  fn retains_per_matrix_variant() {
+    let mut build = build(1);
+    build.config.matrix = ["alpine", "debian"]
+      .into_iter()
+      .map(|image_tag| BuildMatrixEntry {
+        image_tag: image_tag.to_string(),
+        ..Default::default()
+      })
+      .collect();
+    let tags = tags(&[
+      "1.0.0-alpine",
+      "1.1.0-alpine",
+      "1.0.0-debian",
+      "1.1.0-debian",
+      "1.2.0-debian",
+    ]);
+    assert_eq!(
+      expired(&build, &tags, &[]),
+      ["1.0.0-alpine", "1.0.0-debian", "1.1.0-debian"]
+    );
+  }
+
+  #[test]
+  fn keeps_shared_digests() {
+    let digests = [
+      ("1.0.0", "sha256:a"),
+      ("1.1.0", "sha256:b"),
+      // Rebuilt without changes, sharing the digest
+      ("1.2.0", "sha256:b"),
+      ("1.3.0", "sha256:c"),
+      ("1.3.1", "sha256:c"),
+    ]
+    .into_iter()
+    .map(|(tag, digest)| (tag.to_string(), digest.to_string()))
+    .collect::<HashMap<_, _>>();
+    let delete_tags = ["1.0.0", "1.1.0", "1.3.0", "1.3.1"]
+      .into_iter()
+      .map(str::to_string)
+      .collect::<HashSet<_>>();
+    let mut delete = delete_digests(&digests, &delete_tags)
+      .into_iter()
+      .map(|(digest, mut tags)| {
+        tags.sort();
+        (digest.as_str(), tags)
+      })
+      .collect::<Vec<_>>();
+    delete.sort();
+    assert_eq!(
+      delete,
+      [
+        ("sha256:a", vec!["1.0.0"]),
+        ("sha256:c", vec!["1.3.0", "1.3.1"])
+      ]
+    );
+  }
+}
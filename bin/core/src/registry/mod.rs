@@ -0,0 +1,551 @@
+//! Client for the Docker Registry HTTP API v2,
+//! used to manage the images pushed by Builds.
+
+use std::{
+  collections::HashMap,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::{Context, anyhow};
+use reqwest::{
+  Method, RequestBuilder, Response, StatusCode,
+  header::{ACCEPT, LINK, WWW_AUTHENTICATE},
+};
+use serde::Deserialize;
+
+use crate::helpers::registry_token;
+
+pub mod cleanup;
+pub mod tags;
+pub mod update;
+pub mod usage;
+
+/// Accept both single platform manifests and manifest lists,
+/// in both the Docker and OCI formats.
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, application/vnd.docker.distribution.manifest.list.v2+json, application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.v2+json";
+
+/// Limit concurrent requests to the registry.
+pub const REGISTRY_CONCURRENCY: usize = 8;
+
+fn http_client() -> &'static reqwest::Client {
+  static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+  CLIENT.get_or_init(reqwest::Client::new)
+}
+
+pub struct RegistryClient {
+  /// eg. `https://ghcr.io`
+  base_url: String,
+  /// The registry account username and token.
+  credentials: Option<(String, String)>,
+  /// Caches bearer tokens by scope.
+  tokens: Mutex<HashMap<String, String>>,
+}
+
+impl RegistryClient {
+  /// Uses the token of the registry account
+  /// configured in Komodo, if one exists.
+  pub async fn new(
+    domain: &str,
+    account: &str,
+  ) -> anyhow::Result<RegistryClient> {
+    let credentials = if account.is_empty() {
+      None
+    } else {
+      registry_token(domain, account)
+        .await?
+        .map(|token| (account.to_string(), token))
+    };
+    Ok(RegistryClient {
+      base_url: registry_base_url(domain),
+      credentials,
+      tokens: Default::default(),
+    })
+  }
+
+  /// Lists all the repositories in the registry,
+  /// using the `_catalog` endpoint.
+  pub async fn list_repositories(
+    &self,
+  ) -> anyhow::Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Catalog {
+      repositories: Option<Vec<String>>,
+    }
+    let mut url = format!("{}/v2/_catalog?n=1000", self.base_url);
+    let mut repositories = Vec::new();
+    loop {
+      let res = self
+        .send(Method::GET, &url, "registry:catalog:*")
+        .await
+        .context("Failed to list repositories")?;
+      let next = next_page_url(&res);
+      let catalog = check_status(res)
+        .await
+        .context("Registry may not support listing repositories")?
+        .json::<Catalog>()
+        .await
+        .context("Failed to parse repository catalog")?;
+      repositories.extend(catalog.repositories.unwrap_or_default());
+      match next {
+        Some(next) => url = self.absolute_url(next),
+        None => return Ok(repositories),
+      }
+    }
+  }
+
+  /// Lists all the tags of the repository,
+  /// or an empty list if the repository doesn't exist.
+  pub async fn list_tags(
+    &self,
+    repository: &str,
+  ) -> anyhow::Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct TagList {
+      tags: Option<Vec<String>>,
+    }
+    let mut url =
+      format!("{}/v2/{repository}/tags/list?n=1000", self.base_url);
+    let mut tags = Vec::new();
+    loop {
+      let res = self
+        .send(Method::GET, &url, &pull_scope(repository))
+        .await
+        .context("Failed to list tags")?;
+      if res.status() == StatusCode::NOT_FOUND {
+        return Ok(tags);
+      }
+      let next = next_page_url(&res);
+      let list = check_status(res)
+        .await?
+        .json::<TagList>()
+        .await
+        .context("Failed to parse tag list")?;
+      tags.extend(list.tags.unwrap_or_default());
+      match next {
+        Some(next) => url = self.absolute_url(next),
+        None => return Ok(tags),
+      }
+    }
+  }
+
+  /// Gets the manifest digest the tag points to.
+  pub async fn get_digest(
+    &self,
+    repository: &str,
+    tag: &str,
+  ) -> anyhow::Result<String> {
+    let url =
+      format!("{}/v2/{repository}/manifests/{tag}", self.base_url);
+    let res = self
+      .send(Method::HEAD, &url, &pull_scope(repository))
+      .await
+      .with_context(|| format!("Failed to get digest for {tag}"))?;
+    let res = check_status(res).await?;
+    res
+      .headers()
+      .get("docker-content-digest")
+      .and_then(|digest| digest.to_str().ok())
+      .map(str::to_string)
+      .with_context(|| {
+        format!("Registry did not return a digest for {tag}")
+      })
+  }
+
+  /// Whether the tag exists in the repository.
+  pub async fn image_exists(
+    &self,
+    repository: &str,
+    tag: &str,
+  ) -> anyhow::Result<bool> {
+    let url =
+      format!("{}/v2/{repository}/manifests/{tag}", self.base_url);
+    let res = self
+      .send(Method::HEAD, &url, &pull_scope(repository))
+      .await
+      .with_context(|| format!("Failed to check image {tag}"))?;
+    if res.status() == StatusCode::NOT_FOUND {
+      return Ok(false);
+    }
+    check_status(res).await?;
+    Ok(true)
+  }
+
+  /// Gets the digest, size, creation time and platforms of the image.
+  /// For manifest lists, the size is the total across the platforms,
+  /// and the creation time is taken from the first platform.
+  pub async fn get_image_info(
+    &self,
+    repository: &str,
+    reference: &str,
+  ) -> anyhow::Result<ImageInfo> {
+    let (digest, manifest) =
+      self.get_manifest(repository, reference).await?;
+
+    let Some(manifests) = manifest.manifests else {
+      let config = self
+        .get_image_config(repository, manifest.config.as_ref())
+        .await?;
+      return Ok(ImageInfo {
+        digest,
+        size: manifest.layers.iter().map(|l| l.size).sum(),
+        created: config.created_ms(),
+        platforms: config.platform().into_iter().collect(),
+      });
+    };
+
+    let mut info = ImageInfo {
+      digest,
+      ..Default::default()
+    };
+    for entry in manifests {
+      let Some(platform) = entry.platform.and_then(|p| p.name())
+      else {
+        // Skip attestation manifests
+        continue;
+      };
+      let (_, manifest) =
+        self.get_manifest(repository, &entry.digest).await?;
+      info.size +=
+        manifest.layers.iter().map(|l| l.size).sum::<i64>();
+      if info.created.is_none() {
+        info.created = self
+          .get_image_config(repository, manifest.config.as_ref())
+          .await?
+          .created_ms();
+      }
+      info.platforms.push(platform);
+    }
+    Ok(info)
+  }
+
+  /// Returns the manifest digest, and the manifest.
+  async fn get_manifest(
+    &self,
+    repository: &str,
+    reference: &str,
+  ) -> anyhow::Result<(String, Manifest)> {
+    let url = format!(
+      "{}/v2/{repository}/manifests/{reference}",
+      self.base_url
+    );
+    let res = self
+      .send(Method::GET, &url, &pull_scope(repository))
+      .await
+      .with_context(|| {
+        format!("Failed to get manifest {reference}")
+      })?;
+    let res = check_status(res).await?;
+    let digest = res
+      .headers()
+      .get("docker-content-digest")
+      .and_then(|digest| digest.to_str().ok())
+      .unwrap_or(reference)
+      .to_string();
+    let manifest = res
+      .json::<Manifest>()
+      .await
+      .context("Failed to parse image manifest")?;
+    Ok((digest, manifest))
+  }
+
+  async fn get_image_config(
+    &self,
+    repository: &str,
+    config: Option<&Descriptor>,
+  ) -> anyhow::Result<ImageConfig> {
+    let Some(config) = config else {
+      return Ok(Default::default());
+    };
+    let url = format!(
+      "{}/v2/{repository}/blobs/{}",
+      self.base_url, config.digest
+    );
+    let res = self
+      .send(Method::GET, &url, &pull_scope(repository))
+      .await
+      .context("Failed to get image config")?;
+    check_status(res)
+      .await?
+      .json::<ImageConfig>()
+      .await
+      .context("Failed to parse image config")
+  }
+
+  /// Deletes the manifest, removing every tag pointing to it.
+  pub async fn delete_manifest(
+    &self,
+    repository: &str,
+    digest: &str,
+  ) -> anyhow::Result<()> {
+    let url =
+      format!("{}/v2/{repository}/manifests/{digest}", self.base_url);
+    let res = self
+      .send(
+        Method::DELETE,
+        &url,
+        &format!("repository:{repository}:pull,delete"),
+      )
+      .await
+      .with_context(|| format!("Failed to delete {digest}"))?;
+    if res.status() == StatusCode::METHOD_NOT_ALLOWED {
+      return Err(anyhow!(
+        "Registry does not allow deleting images. For the 'registry:2' image, set REGISTRY_STORAGE_DELETE_ENABLED=true"
+      ));
+    }
+    check_status(res).await?;
+    Ok(())
+  }
+
+  /// Sends the request, handling the registry auth challenge
+  /// for the scope if required.
+  async fn send(
+    &self,
+    method: Method,
+    url: &str,
+    scope: &str,
+  ) -> anyhow::Result<Response> {
+    let token = self.tokens.lock().unwrap().get(scope).cloned();
+    let req = self.request(method.clone(), url);
+    let req = match &token {
+      Some(token) => req.bearer_auth(token),
+      None => req,
+    };
+    let res = req.send().await.context("Failed to reach registry")?;
+    if res.status() != StatusCode::UNAUTHORIZED {
+      return Ok(res);
+    }
+
+    let challenge = res
+      .headers()
+      .get(WWW_AUTHENTICATE)
+      .and_then(|challenge| challenge.to_str().ok())
+      .context("Registry returned 401 without an auth challenge")?
+      .to_string();
+
+    let req = self.request(method, url);
+    let req = if let Some(params) = challenge.strip_prefix("Bearer ")
+    {
+      let token = self.get_bearer_token(params, scope).await?;
+      self
+        .tokens
+        .lock()
+        .unwrap()
+        .insert(scope.to_string(), token.clone());
+      req.bearer_auth(token)
+    } else if let Some((username, token)) = &self.credentials {
+      req.basic_auth(username, Some(token))
+    } else {
+      return Err(anyhow!(
+        "Registry requires authentication, but no registry account is configured"
+      ));
+    };
+    req.send().await.context("Failed to reach registry")
+  }
+
+  /// Pagination links are usually relative to the registry.
+  fn absolute_url(&self, url: String) -> String {
+    if url.starts_with('/') {
+      format!("{}{url}", self.base_url)
+    } else {
+      url
+    }
+  }
+
+  fn request(&self, method: Method, url: &str) -> RequestBuilder {
+    http_client()
+      .request(method, url)
+      .header(ACCEPT, MANIFEST_ACCEPT)
+  }
+
+  /// Exchanges the account credentials for a bearer token
+  /// at the realm given in the auth challenge.
+  async fn get_bearer_token(
+    &self,
+    params: &str,
+    scope: &str,
+  ) -> anyhow::Result<String> {
+    #[derive(Deserialize)]
+    struct TokenResponse {
+      token: Option<String>,
+      access_token: Option<String>,
+    }
+    let params = parse_challenge_params(params);
+    let realm = params
+      .get("realm")
+      .context("Auth challenge is missing the realm")?;
+    let mut query = vec![("scope", scope)];
+    if let Some(service) = params.get("service") {
+      query.push(("service", service));
+    }
+    let req = http_client().get(*realm).query(&query);
+    let req = match &self.credentials {
+      Some((username, token)) => {
+        req.basic_auth(username, Some(token))
+      }
+      None => req,
+    };
+    let res = req
+      .send()
+      .await
+      .context("Failed to reach registry auth server")?;
+    let res = check_status(res)
+      .await
+      .context("Failed to authenticate with registry")?
+      .json::<TokenResponse>()
+      .await
+      .context("Failed to parse registry auth response")?;
+    res
+      .token
+      .or(res.access_token)
+      .context("Registry auth response is missing the token")
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct ImageInfo {
+  pub digest: String,
+  /// The total size of the image layers in bytes.
+  pub size: i64,
+  /// Unix timestamp in milliseconds.
+  pub created: Option<i64>,
+  /// eg. `linux/amd64`
+  pub platforms: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+  config: Option<Descriptor>,
+  #[serde(default)]
+  layers: Vec<Descriptor>,
+  manifests: Option<Vec<Descriptor>>,
+}
+
+#[derive(Deserialize)]
+struct Descriptor {
+  digest: String,
+  #[serde(default)]
+  size: i64,
+  platform: Option<Platform>,
+}
+
+#[derive(Deserialize)]
+struct Platform {
+  os: String,
+  architecture: String,
+  variant: Option<String>,
+}
+
+impl Platform {
+  /// eg. `linux/arm64/v8`.
+  /// None for the `unknown/unknown` attestation manifests.
+  fn name(self) -> Option<String> {
+    if self.os == "unknown" || self.architecture == "unknown" {
+      return None;
+    }
+    match self.variant {
+      Some(variant) => {
+        Some(format!("{}/{}/{variant}", self.os, self.architecture))
+      }
+      None => Some(format!("{}/{}", self.os, self.architecture)),
+    }
+  }
+}
+
+#[derive(Deserialize, Default)]
+struct ImageConfig {
+  created: Option<String>,
+  os: Option<String>,
+  architecture: Option<String>,
+  variant: Option<String>,
+}
+
+impl ImageConfig {
+  fn created_ms(&self) -> Option<i64> {
+    let created = self.created.as_deref()?;
+    chrono::DateTime::parse_from_rfc3339(created)
+      .ok()
+      .map(|created| created.timestamp_millis())
+  }
+
+  fn platform(self) -> Option<String> {
+    Platform {
+      os: self.os?,
+      architecture: self.architecture?,
+      variant: self.variant,
+    }
+    .name()
+  }
+}
+
+fn pull_scope(repository: &str) -> String {
+  format!("repository:{repository}:pull")
+}
+
+/// Splits the image name (without tag) into the registry
+/// domain and the repository, eg. `ghcr.io/myorg/app`
+/// -> (`ghcr.io`, `myorg/app`).
+pub fn split_image_name(image_name: &str) -> Option<(&str, &str)> {
+  let (domain, repository) = image_name.split_once('/')?;
+  // Domains contain '.' or ':', or are 'localhost'
+  if domain.contains(['.', ':']) || domain == "localhost" {
+    Some((domain, repository))
+  } else {
+    None
+  }
+}
+
+/// Like docker, registries on localhost are accessed over http.
+fn registry_base_url(domain: &str) -> String {
+  let host = domain.split(':').next().unwrap_or(domain);
+  match domain {
+    "docker.io" | "index.docker.io" => {
+      String::from("https://registry-1.docker.io")
+    }
+    _ if host == "localhost" || host == "127.0.0.1" => {
+      format!("http://{domain}")
+    }
+    _ => format!("https://{domain}"),
+  }
+}
+
+/// Parses `realm="...",service="...",scope="..."`.
+/// Quoted values may contain commas, eg. `scope="repository:app:pull,push"`.
+fn parse_challenge_params(params: &str) -> HashMap<&str, &str> {
+  let mut res = HashMap::new();
+  let mut rest = params.trim();
+  while let Some((key, after)) = rest.split_once('=') {
+    let key = key.trim().trim_start_matches(',').trim();
+    let (value, next) = match after.strip_prefix('"') {
+      Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+      None => after.split_once(',').unwrap_or((after, "")),
+    };
+    res.insert(key, value);
+    rest = next;
+  }
+  res
+}
+
+/// Parses the `Link: </v2/...>; rel="next"` pagination header.
+fn next_page_url(res: &Response) -> Option<String> {
+  let link = res.headers().get(LINK)?.to_str().ok()?;
+  let (url, rel) = link.split_once(';')?;
+  if !rel.contains("next") {
+    return None;
+  }
+  Some(
+    url
+      .trim()
+      .trim_start_matches('<')
+      .trim_end_matches('>')
+      .to_string(),
+  )
+}
+
+async fn check_status(res: Response) -> anyhow::Result<Response> {
+  let status = res.status();
+  if status.is_success() {
+    return Ok(res);
+  }
+  let body = res.text().await.unwrap_or_default();
+  Err(anyhow!("Registry responded with {status} | {body}"))
+}
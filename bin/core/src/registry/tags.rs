@@ -0,0 +1,88 @@
+//! Matches registry tags against the tags pushed by Builds.
+
+use komodo_client::entities::{Version, build::Build};
+
+/// The image tag postfix of each image variant pushed by the Build.
+pub fn image_tag_postfixes(build: &Build) -> Vec<String> {
+  let postfix = |image_tag: &str| {
+    if image_tag.is_empty() {
+      String::new()
+    } else {
+      format!("-{image_tag}")
+    }
+  };
+  if build.config.matrix.is_empty() {
+    return vec![postfix(&build.config.image_tag)];
+  }
+  build
+    .config
+    .matrix
+    .iter()
+    .map(|entry| postfix(&build.get_matrix_image_tag(entry)))
+    .collect()
+}
+
+/// Parses full version tags, eg `1.19.5` / `1.19.5-postfix`.
+pub fn parse_version_tag(
+  tag: &str,
+  postfix: &str,
+) -> Option<Version> {
+  let version = tag.strip_suffix(postfix)?;
+  if version.split('.').count() != 3 {
+    return None;
+  }
+  Version::try_from(version).ok()
+}
+
+/// Commit hash tags, eg `a6f8e83` / `a6f8e83-postfix`.
+pub fn is_commit_tag(build: &Build, tag: &str) -> bool {
+  image_tag_postfixes(build).iter().any(|postfix| {
+    tag.strip_suffix(postfix.as_str()).is_some_and(|hash| {
+      (7..=40).contains(&hash.len())
+        && hash.chars().all(|c| c.is_ascii_hexdigit())
+    })
+  })
+}
+
+/// Whether the tag has the form of one pushed by the Build,
+/// eg `latest`, `1.19.5`, `1.19`, `1`, `a6f8e83`,
+/// or the per platform `1.19.5-linux-arm64`.
+/// Each may include the image tag postfix.
+pub fn is_build_tag(build: &Build, tag: &str) -> bool {
+  if is_commit_tag(build, tag) {
+    return true;
+  }
+  let postfixes = image_tag_postfixes(build);
+  for postfix in &postfixes {
+    // Pure image tag passthrough
+    if !postfix.is_empty() && tag == &postfix[1..] {
+      return true;
+    }
+    let Some(base) = tag.strip_suffix(postfix.as_str()) else {
+      continue;
+    };
+    if base == "latest" || is_partial_version(base) {
+      return true;
+    }
+  }
+  let version = build.config.version.to_string();
+  build.get_platform_builder_groups().into_iter().any(
+    |(_, platforms)| {
+      let platform_tag = build.get_platform_image_tag(&platforms);
+      let suffix =
+        platform_tag.strip_prefix(&version).unwrap_or(&platform_tag);
+      tag.strip_suffix(suffix).is_some_and(|version| {
+        parse_version_tag(version, "").is_some()
+      })
+    },
+  )
+}
+
+/// Matches `1`, `1.19`, and `1.19.5`.
+fn is_partial_version(tag: &str) -> bool {
+  let parts = tag.split('.').collect::<Vec<_>>();
+  (1..=3).contains(&parts.len())
+    && parts.iter().all(|part| {
+      !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())
+    })
+}
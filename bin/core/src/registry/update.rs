@@ -0,0 +1,281 @@
+//! Finds newer version tags in the registry,
+//! for the image update policies.
+
+use std::cmp::Ordering;
+
+use anyhow::Context;
+use komodo_client::entities::ImageUpdatePolicy;
+use regex::Regex;
+
+use super::{
+  RegistryClient, split_image_name, usage::normalize_image_name,
+};
+
+/// Checks the registry for a newer version tag of the image
+/// allowed by the policy. Returns the image with the new tag,
+/// or None if there is no newer tag, or the policy is `digest`.
+pub async fn check_tag_update(
+  image: &str,
+  account: &str,
+  policy: ImageUpdatePolicy,
+  tag_filter: &str,
+) -> anyhow::Result<Option<String>> {
+  if policy == ImageUpdatePolicy::Digest || image.contains('@') {
+    return Ok(None);
+  }
+  // Images without a tag use 'latest', which has no version.
+  let Some((name, tag)) =
+    image.rsplit_once(':').filter(|(_, tag)| !tag.contains('/'))
+  else {
+    return Ok(None);
+  };
+  let tag_filter = if tag_filter.is_empty() {
+    None
+  } else {
+    Some(
+      Regex::new(tag_filter)
+        .context("Failed to parse update tag filter regex")?,
+    )
+  };
+  let normalized = normalize_image_name(name);
+  let (domain, repository) = split_image_name(&normalized)
+    .with_context(|| {
+      format!("Failed to get registry domain for image {image}")
+    })?;
+  let tags = RegistryClient::new(domain, account)
+    .await?
+    .list_tags(repository)
+    .await
+    .with_context(|| format!("Failed to list tags for {image}"))?;
+  Ok(
+    newest_allowed_tag(tag, &tags, policy, tag_filter.as_ref())
+      .map(|tag| format!("{name}:{tag}")),
+  )
+}
+
+/// Picks the highest version tag newer than `current`
+/// allowed by the policy. Only tags with the same form as
+/// the current tag are considered, eg `16.2-alpine` only
+/// moves to other `X.Y-alpine` tags.
+fn newest_allowed_tag<'a>(
+  current: &str,
+  tags: &'a [String],
+  policy: ImageUpdatePolicy,
+  tag_filter: Option<&Regex>,
+) -> Option<&'a str> {
+  let current = VersionTag::parse(current)?;
+  tags
+    .iter()
+    .filter(|tag| {
+      tag_filter.is_none_or(|filter| filter.is_match(tag))
+    })
+    .filter_map(|tag| {
+      VersionTag::parse(tag).map(|version| (tag.as_str(), version))
+    })
+    .filter(|(_, version)| current.allows(version, policy))
+    .max_by(|(_, a), (_, b)| a.cmp_parts(b))
+    .map(|(tag, _)| tag)
+}
+
+/// Tags like `16`, `16.2`, `v1.19.5`, `16.2-alpine`.
+struct VersionTag<'a> {
+  prefix: bool,
+  parts: Vec<u64>,
+  suffix: &'a str,
+}
+
+impl<'a> VersionTag<'a> {
+  fn parse(tag: &'a str) -> Option<VersionTag<'a>> {
+    let (prefix, tag) = match tag.strip_prefix('v') {
+      Some(tag) => (true, tag),
+      None => (false, tag),
+    };
+    let (version, suffix) = tag.split_once('-').unwrap_or((tag, ""));
+    let parts = version
+      .split('.')
+      .map(|part| {
+        if part.is_empty()
+          || !part.chars().all(|c| c.is_ascii_digit())
+        {
+          return None;
+        }
+        part.parse().ok()
+      })
+      .collect::<Option<Vec<u64>>>()?;
+    if !(1..=3).contains(&parts.len()) {
+      return None;
+    }
+    Some(VersionTag {
+      prefix,
+      parts,
+      suffix,
+    })
+  }
+
+  /// Whether `other` is a newer version of the same form,
+  /// within the policy level.
+  fn allows(
+    &self,
+    other: &VersionTag,
+    policy: ImageUpdatePolicy,
+  ) -> bool {
+    if self.prefix != other.prefix
+      || self.suffix != other.suffix
+      || self.parts.len() != other.parts.len()
+      || other.cmp_parts(self) != Ordering::Greater
+    {
+      return false;
+    }
+    // The number of leading parts which must stay the same.
+    let fixed = match policy {
+      ImageUpdatePolicy::Digest => return false,
+      ImageUpdatePolicy::Patch => 2,
+      ImageUpdatePolicy::Minor => 1,
+      ImageUpdatePolicy::Major => 0,
+    };
+    let fixed = fixed.min(self.parts.len());
+    self.parts[..fixed] == other.parts[..fixed]
+  }
+
+  fn cmp_parts(&self, other: &VersionTag) -> Ordering {
+    self.parts.cmp(&other.parts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn newest(
+    current: &str,
+    tags: &[&str],
+    policy: ImageUpdatePolicy,
+  ) -> Option<String> {
+    let tags =
+      tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+    newest_allowed_tag(current, &tags, policy, None)
+      .map(str::to_string)
+  }
+
+  const TAGS: &[&str] = &[
+    "latest",
+    "1.2.3",
+    "1.2.4",
+    "1.2.10",
+    "1.3.0",
+    "1.10.1",
+    "2.0.0",
+    "2.1.0-rc1",
+    "1.2",
+    "1.3",
+    "1",
+    "2",
+  ];
+
+  #[test]
+  fn respects_policy_level() {
+    for (policy, expected) in [
+      (ImageUpdatePolicy::Patch, Some("1.2.10")),
+      (ImageUpdatePolicy::Minor, Some("1.10.1")),
+      (ImageUpdatePolicy::Major, Some("2.0.0")),
+      (ImageUpdatePolicy::Digest, None),
+    ] {
+      assert_eq!(
+        newest("1.2.3", TAGS, policy).as_deref(),
+        expected,
+        "policy: {policy:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn keeps_tag_form() {
+    // Partial versions only move to partial versions
+    assert_eq!(
+      newest("1.2", TAGS, ImageUpdatePolicy::Minor).as_deref(),
+      Some("1.3")
+    );
+    assert_eq!(
+      newest("1", TAGS, ImageUpdatePolicy::Major).as_deref(),
+      Some("2")
+    );
+    // The 'v' prefix must match
+    assert_eq!(
+      newest(
+        "v1.0.0",
+        &["1.0.1", "v1.0.1", "v1.1.0"],
+        ImageUpdatePolicy::Patch
+      )
+      .as_deref(),
+      Some("v1.0.1")
+    );
+  }
+
+  #[test]
+  fn matches_suffix_and_pre_release() {
+    let tags = [
+      "16.2-alpine",
+      "16.3-alpine",
+      "16.4",
+      "17.0-alpine",
+      "16.4-bookworm",
+    ];
+    assert_eq!(
+      newest("16.2-alpine", &tags, ImageUpdatePolicy::Minor)
+        .as_deref(),
+      Some("16.3-alpine")
+    );
+    assert_eq!(
+      newest("16.2-alpine", &tags, ImageUpdatePolicy::Major)
+        .as_deref(),
+      Some("17.0-alpine")
+    );
+    // Pre-releases are only considered from a pre-release
+    // with the same suffix.
+    assert_eq!(
+      newest("2.0.0", TAGS, ImageUpdatePolicy::Major).as_deref(),
+      None
+    );
+    assert_eq!(
+      newest(
+        "2.0.0-rc1",
+        &["2.0.0", "2.0.1-rc1", "2.0.1-rc2"],
+        ImageUpdatePolicy::Patch
+      )
+      .as_deref(),
+      Some("2.0.1-rc1")
+    );
+  }
+
+  #[test]
+  fn ignores_non_version_tags() {
+    assert_eq!(
+      newest("latest", TAGS, ImageUpdatePolicy::Major),
+      None
+    );
+    assert_eq!(
+      newest(
+        "1.2.3",
+        &["1.2.x", "1.2.3.4", "a1.2.4"],
+        ImageUpdatePolicy::Major
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn applies_tag_filter() {
+    let tags =
+      TAGS.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+    let filter = Regex::new(r"^1\.2\.").unwrap();
+    assert_eq!(
+      newest_allowed_tag(
+        "1.2.3",
+        &tags,
+        ImageUpdatePolicy::Major,
+        Some(&filter)
+      ),
+      Some("1.2.10")
+    );
+  }
+}
@@ -0,0 +1,141 @@
+//! Finds the image tags in use by Deployments and Stacks.
+
+use std::collections::{BTreeSet, HashMap};
+
+use komodo_client::entities::{
+  deployment::DeploymentImage, registry::RegistryTagResource,
+};
+
+use crate::state::{
+  all_resources_cache, deployment_status_cache, stack_status_cache,
+};
+
+use super::split_image_name;
+
+/// The resources using an image tag.
+#[derive(Default)]
+pub struct TagUsage {
+  pub deployments: BTreeSet<RegistryTagResource>,
+  pub stacks: BTreeSet<RegistryTagResource>,
+}
+
+/// Maps image name -> tag -> resources using the tag.
+pub type ImageUsage = HashMap<String, HashMap<String, TagUsage>>;
+
+/// Gets the image tags in use by Deployments and Stacks,
+/// from both their config and their running containers.
+pub async fn image_usage() -> ImageUsage {
+  let all = all_resources_cache().load_full();
+  let mut usage = ImageUsage::new();
+
+  for deployment in all.deployments.values() {
+    let mut images = Vec::<String>::new();
+    match &deployment.config.image {
+      DeploymentImage::Image { image } => images.push(image.clone()),
+      DeploymentImage::Build { build_id, version } => {
+        if let Some(build) = all.builds.get(build_id) {
+          let version = if version.is_none() {
+            build.config.version
+          } else {
+            *version
+          };
+          let tag = build.get_deployment_image_tag(version);
+          for image_name in build.get_image_names() {
+            images.push(format!("{image_name}:{tag}"));
+          }
+        }
+      }
+    }
+    if let Some(status) =
+      deployment_status_cache().get(&deployment.id).await
+    {
+      images.extend(
+        status
+          .curr
+          .container
+          .as_ref()
+          .and_then(|container| container.image.clone()),
+      );
+      images.extend(
+        status
+          .curr
+          .service
+          .as_ref()
+          .and_then(|service| service.image.clone()),
+      );
+    }
+    let resource = RegistryTagResource {
+      name: deployment.name.clone(),
+      id: deployment.id.clone(),
+    };
+    for image in images {
+      if let Some((name, tag)) = split_image_tag(&image) {
+        usage
+          .entry(name)
+          .or_default()
+          .entry(tag)
+          .or_default()
+          .deployments
+          .insert(resource.clone());
+      }
+    }
+  }
+
+  for stack in all.stacks.values() {
+    let mut images = stack
+      .info
+      .deployed_services
+      .iter()
+      .flatten()
+      .chain(&stack.info.latest_services)
+      .map(|service| service.image.clone())
+      .collect::<Vec<_>>();
+    if let Some(status) = stack_status_cache().get(&stack.id).await {
+      images
+        .extend(status.curr.services.iter().map(|s| s.image.clone()));
+    }
+    let resource = RegistryTagResource {
+      name: stack.name.clone(),
+      id: stack.id.clone(),
+    };
+    for image in images {
+      if let Some((name, tag)) = split_image_tag(&image) {
+        usage
+          .entry(name)
+          .or_default()
+          .entry(tag)
+          .or_default()
+          .stacks
+          .insert(resource.clone());
+      }
+    }
+  }
+
+  usage
+}
+
+/// Splits the image into the fully qualified name and the tag,
+/// eg. `myorg/app` -> (`docker.io/myorg/app`, `latest`).
+pub fn split_image_tag(image: &str) -> Option<(String, String)> {
+  let image = image.split('@').next()?.trim();
+  if image.is_empty() {
+    return None;
+  }
+  let (name, tag) = match image.rsplit_once(':') {
+    Some((name, tag)) if !tag.contains('/') => (name, tag),
+    _ => (image, "latest"),
+  };
+  Some((normalize_image_name(name), tag.to_string()))
+}
+
+/// Fully qualifies Docker Hub image names,
+/// eg. `myorg/app` -> `docker.io/myorg/app`.
+pub fn normalize_image_name(name: &str) -> String {
+  if split_image_name(name).is_some() {
+    name.to_string()
+  } else if name.contains('/') {
+    format!("docker.io/{name}")
+  } else {
+    format!("docker.io/library/{name}")
+  }
+}
@@ -1,6 +1,6 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
-use anyhow::Context;
+use anyhow::{Context, anyhow};
 use database::mungos::{
   find::find_collect,
   mongodb::{Collection, bson::doc, options::FindOneOptions},
@@ -12,11 +12,15 @@ use komodo_client::entities::{
     ActionListItemInfo, ActionQuerySpecifics, ActionState,
     PartialActionConfig,
   },
+  permission::PermissionLevel,
   resource::Resource,
+  server::Server,
   update::Update,
   user::User,
 };
 
+use sha2::{Digest, Sha256};
+
 use crate::{
   helpers::query::{get_action_state, get_last_run_at},
   schedule::{
@@ -67,6 +71,7 @@ impl super::KomodoResource for Action {
         last_run_at: last_run_at.unwrap_or(None),
         next_scheduled_run,
         schedule_error,
+        library: action.config.library,
       },
     }
   }
@@ -92,13 +97,13 @@ impl super::KomodoResource for Action {
 
   async fn validate_create_config(
     config: &mut Self::PartialConfig,
-    _user: &User,
+    user: &User,
   ) -> anyhow::Result<()> {
     if config.file_contents.is_none() {
       config.file_contents =
         Some(DEFAULT_ACTION_FILE_CONTENTS.to_string());
     }
-    Ok(())
+    validate_config(None, config, user).await
   }
 
   async fn post_create(
@@ -117,17 +122,30 @@ impl super::KomodoResource for Action {
   }
 
   async fn validate_update_config(
-    _id: &str,
-    _config: &mut Self::PartialConfig,
-    _user: &User,
+    id: &str,
+    config: &mut Self::PartialConfig,
+    user: &User,
   ) -> anyhow::Result<()> {
-    Ok(())
+    validate_config(Some(id), config, user).await
   }
 
   async fn post_update(
     updated: &Self,
     update: &mut Update,
   ) -> anyhow::Result<()> {
+    if updated.config.library {
+      let dependents = library_dependents(&updated.id).await?;
+      if !dependents.is_empty() {
+        update.push_simple_log(
+          "Dependent Actions",
+          format!(
+            "Library version: {}\n\nActions using this library on their next run:\n{}",
+            library_version(&updated.config.file_contents),
+            dependents.join("\n")
+          ),
+        );
+      }
+    }
     Self::post_create(updated, update).await
   }
 
@@ -144,9 +162,17 @@ impl super::KomodoResource for Action {
   }
 
   async fn pre_delete(
-    _resource: &Resource<Self::Config, Self::Info>,
+    resource: &Resource<Self::Config, Self::Info>,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    db_client()
+      .actions
+      .update_many(
+        doc! { "config.libraries": &resource.id },
+        doc! { "$pull": { "config.libraries": &resource.id } },
+      )
+      .await
+      .context("Failed to detach library from actions")?;
     Ok(())
   }
 
@@ -160,6 +186,118 @@ impl super::KomodoResource for Action {
   }
 }
 
+#[instrument("ValidateActionConfig", skip_all)]
+async fn validate_config(
+  id: Option<&str>,
+  config: &mut PartialActionConfig,
+  user: &User,
+) -> anyhow::Result<()> {
+  if let Some(server_id) = &config.server_id
+    && !server_id.is_empty()
+  {
+    let server = super::get_check_permissions::<Server>(
+      server_id,
+      user,
+      PermissionLevel::Read.attach(),
+    )
+    .await
+    .context("Cannot attach Action to this Server")?;
+    config.server_id = Some(server.id);
+  }
+  if let Some(libraries) = &mut config.libraries {
+    for library_id in libraries.iter_mut() {
+      let library = super::get_check_permissions::<Action>(
+        library_id,
+        user,
+        PermissionLevel::Read.into(),
+      )
+      .await
+      .with_context(|| {
+        format!("Cannot import Action library '{library_id}'")
+      })?;
+      if !library.config.library {
+        return Err(anyhow!(
+          "Action '{}' is not a library",
+          library.name
+        ));
+      }
+      if Some(library.id.as_str()) == id {
+        return Err(anyhow!("Action cannot import itself"));
+      }
+      *library_id = library.id;
+    }
+  }
+  if let Some(schema) = &config.argument_schema {
+    let mut names = HashSet::new();
+    for argument in schema {
+      if argument.name.is_empty() {
+        return Err(anyhow!("Action argument name cannot be empty"));
+      }
+      if !names.insert(&argument.name) {
+        return Err(anyhow!(
+          "Action argument '{}' is declared more than once",
+          argument.name
+        ));
+      }
+      for choice in &argument.choices {
+        argument.kind.convert(choice.clone()).with_context(|| {
+          format!(
+            "Choice {choice} of Action argument '{}' is not of type '{}'",
+            argument.name, argument.kind
+          )
+        })?;
+      }
+      if let Some(default) = &argument.default {
+        argument.validate(default.clone()).with_context(|| {
+          format!(
+            "Invalid default for Action argument '{}'",
+            argument.name
+          )
+        })?;
+      }
+    }
+  }
+  Ok(())
+}
+
+/// The names of the Actions importing the library,
+/// directly or through other libraries.
+async fn library_dependents(id: &str) -> anyhow::Result<Vec<String>> {
+  let actions = find_collect(
+    &db_client().actions,
+    doc! { "config.libraries.0": { "$exists": true } },
+    None,
+  )
+  .await
+  .context("Failed to get Actions from db")?;
+  let mut dependents = Vec::new();
+  let mut seen = HashSet::from([id.to_string()]);
+  let mut queue = vec![id.to_string()];
+  while let Some(library) = queue.pop() {
+    for action in &actions {
+      if action.config.libraries.contains(&library)
+        && seen.insert(action.id.clone())
+      {
+        if action.config.library {
+          queue.push(action.id.clone());
+        } else {
+          dependents.push(action.name.clone());
+        }
+      }
+    }
+  }
+  dependents.sort();
+  Ok(dependents)
+}
+
+/// Identifies the library contents used by a run,
+/// the first 8 characters of the contents sha256 hash.
+pub fn library_version(contents: &str) -> String {
+  let mut version = hex::encode(Sha256::digest(contents));
+  version.truncate(8);
+  version
+}
+
 pub fn spawn_action_state_refresh_loop() {
   tokio::spawn(async move {
     loop {
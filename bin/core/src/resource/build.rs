@@ -1,6 +1,6 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
-use anyhow::Context;
+use anyhow::{Context, anyhow};
 use database::mungos::{
   find::find_collect,
   mongodb::{Collection, bson::doc, options::FindOptions},
@@ -156,7 +156,7 @@ impl super::KomodoResource for Build {
     config: &mut Self::PartialConfig,
     user: &User,
   ) -> anyhow::Result<()> {
-    validate_config(config, user).await
+    validate_config(None, config, user).await
   }
 
   async fn post_create(
@@ -187,11 +187,11 @@ impl super::KomodoResource for Build {
   }
 
   async fn validate_update_config(
-    _id: &str,
+    id: &str,
     config: &mut Self::PartialConfig,
     user: &User,
   ) -> anyhow::Result<()> {
-    validate_config(config, user).await
+    validate_config(Some(id), config, user).await
   }
 
   async fn post_update(
@@ -225,6 +225,11 @@ impl super::KomodoResource for Build {
     _update: &mut Update,
   ) -> anyhow::Result<()> {
     build_state_cache().remove(&resource.id).await;
+    db_client()
+      .build_sboms
+      .delete_many(doc! { "build_id": &resource.id })
+      .await
+      .context("Failed to delete build sboms")?;
     Ok(())
   }
 }
@@ -258,6 +263,7 @@ pub async fn refresh_build_state_cache() {
 
 #[instrument("ValidateBuildConfig", skip_all)]
 async fn validate_config(
+  id: Option<&str>,
   config: &mut PartialBuildConfig,
   user: &User,
 ) -> anyhow::Result<()> {
@@ -273,6 +279,75 @@ async fn validate_config(
     .context("Cannot attach Build to this Builder")?;
     config.builder_id = Some(builder.id)
   }
+  if let Some(platform_builders) = &mut config.platform_builders {
+    for platform_builder in platform_builders {
+      if platform_builder.builder_id.is_empty() {
+        continue;
+      }
+      let builder = super::get_check_permissions::<Builder>(
+        &platform_builder.builder_id,
+        user,
+        PermissionLevel::Read.attach(),
+      )
+      .await
+      .with_context(|| {
+        format!(
+          "Cannot attach Build to the {} Builder",
+          platform_builder.platform
+        )
+      })?;
+      platform_builder.builder_id = builder.id;
+    }
+  }
+  if let Some(matrix) = &mut config.matrix {
+    // Variants push to their image tags,
+    // so they must be set and unique.
+    let mut image_tags = HashSet::new();
+    for entry in matrix.iter_mut() {
+      entry.image_tag = entry.image_tag.trim().to_string();
+      if entry.image_tag.is_empty() {
+        return Err(anyhow!(
+          "Build matrix variants must have an image tag"
+        ));
+      }
+      if !image_tags.insert(entry.image_tag.clone()) {
+        return Err(anyhow!(
+          "Duplicate image tag for build matrix variants: {}",
+          entry.image_tag
+        ));
+      }
+    }
+    for entry in matrix {
+      environment_vars_from_str(&entry.build_args).with_context(
+        || {
+          format!(
+            "Invalid build_args for matrix variant {}",
+            entry.image_tag
+          )
+        },
+      )?;
+      if entry.builder_id.is_empty() {
+        continue;
+      }
+      let builder = super::get_check_permissions::<Builder>(
+        &entry.builder_id,
+        user,
+        PermissionLevel::Read.attach(),
+      )
+      .await
+      .with_context(|| {
+        format!(
+          "Cannot attach Build to the Builder for matrix variant {}",
+          entry.image_tag
+        )
+      })?;
+      entry.builder_id = builder.id;
+    }
+  }
+  validate_matrix_platform_builders(id, config).await?;
+  if let Some(platforms) = &mut config.platforms {
+    platforms.retain(|v| !empty_or_only_spaces(v))
+  }
   if let Some(linked_repo) = &config.linked_repo
     && !linked_repo.is_empty()
   {
@@ -300,6 +375,33 @@ async fn validate_config(
   Ok(())
 }
 
+/// Matrix variants are each built by a single Builder,
+/// so the matrix can't be combined with `platform_builders`.
+/// On update, the field not being updated is taken from the Build.
+async fn validate_matrix_platform_builders(
+  id: Option<&str>,
+  config: &PartialBuildConfig,
+) -> anyhow::Result<()> {
+  if config.matrix.is_none() && config.platform_builders.is_none() {
+    return Ok(());
+  }
+  let current = match id {
+    Some(id) => super::get::<Build>(id).await?.config,
+    None => Default::default(),
+  };
+  let matrix = config.matrix.as_ref().unwrap_or(&current.matrix);
+  let platform_builders = config
+    .platform_builders
+    .as_ref()
+    .unwrap_or(&current.platform_builders);
+  if !matrix.is_empty() && !platform_builders.is_empty() {
+    return Err(anyhow!(
+      "The build matrix can't be combined with platform builders"
+    ));
+  }
+  Ok(())
+}
+
 /// The Build state as computed for the build list items,
 /// from the in memory action states / state cache.
 pub async fn get_build_state(id: &String) -> BuildState {
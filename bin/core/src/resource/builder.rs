@@ -158,6 +158,25 @@ impl super::KomodoResource for Builder {
       )
       .await
       .context("failed to update_many builds on database")?;
+    db_client()
+      .builds
+      .update_many(
+        doc! { "config.platform_builders.builder_id": &resource.id },
+        doc! { "$pull": {
+          "config.platform_builders": { "builder_id": &resource.id }
+        } },
+      )
+      .await
+      .context("failed to update_many builds on database")?;
+    db_client()
+      .builds
+      .update_many(
+        doc! { "config.matrix.builder_id": &resource.id },
+        doc! { "$set": { "config.matrix.$[entry].builder_id": "" } },
+      )
+      .array_filters(vec![doc! { "entry.builder_id": &resource.id }])
+      .await
+      .context("failed to update_many builds on database")?;
     db_client()
       .repos
       .update_many(
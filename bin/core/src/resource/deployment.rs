@@ -32,11 +32,13 @@ use periphery_client::api::{
 use crate::{
   config::core_config,
   helpers::{
+    allocation::{deployment_allocations, log_allocation_conflicts},
     empty_or_only_spaces, periphery_client,
     query::{get_deployment_state, get_swarm_or_server},
     swarm::swarm_request,
   },
   monitor::{refresh_server_cache, refresh_swarm_cache},
+  schedule::delete_scheduled_deploy,
   state::{
     action_states, all_resources_cache, db_client,
     deployment_status_cache,
@@ -110,6 +112,12 @@ impl super::KomodoResource for Deployment {
       .get(&deployment.config.swarm_id)
       .map(|swarm| swarm.name.clone())
       .unwrap_or_default();
+    // Ignore the newer tag once the config has moved to it.
+    let latest_image = optional_string(deployment.info.latest_image)
+      .filter(|latest| match &deployment.config.image {
+        DeploymentImage::Image { image } => latest != image,
+        DeploymentImage::Build { .. } => false,
+      });
     let (build_image, build_id) = match deployment.config.image {
       DeploymentImage::Build { build_id, version } => {
         let (build_name, build_id, build_version) = all
@@ -163,6 +171,7 @@ impl super::KomodoResource for Deployment {
           .update_available(current_digests)
       })
       .unwrap_or_default();
+    let update_available = update_available || latest_image.is_some();
     DeploymentListItem {
       name: deployment.name,
       id: deployment.id,
@@ -178,6 +187,7 @@ impl super::KomodoResource for Deployment {
           .unwrap_or(deployment.config.custom_name),
         image,
         update_available,
+        latest_image,
         swarm_id: deployment.config.swarm_id,
         swarm_name,
         server_id: deployment.config.server_id,
@@ -215,7 +225,7 @@ impl super::KomodoResource for Deployment {
 
   async fn post_create(
     created: &Resource<Self::Config, Self::Info>,
-    _update: &mut Update,
+    update: &mut Update,
   ) -> anyhow::Result<()> {
     if created.config.swarm_id.is_empty()
       && created.config.server_id.is_empty()
@@ -241,6 +251,13 @@ impl super::KomodoResource for Deployment {
       }
       SwarmOrServer::Server(server) => {
         refresh_server_cache(&server, true).await;
+        log_allocation_conflicts(
+          &server.id,
+          &ResourceTarget::Deployment(created.id.clone()),
+          &deployment_allocations(created),
+          update,
+        )
+        .await;
       }
       SwarmOrServer::None => {}
     }
@@ -388,6 +405,10 @@ impl super::KomodoResource for Deployment {
     _update: &mut Update,
   ) -> anyhow::Result<()> {
     deployment_status_cache().remove(&resource.id).await;
+    delete_scheduled_deploy(&ResourceTarget::Deployment(
+      resource.id.clone(),
+    ))
+    .await;
     Ok(())
   }
 }
@@ -62,13 +62,15 @@ mod deployment;
 mod procedure;
 mod refresh;
 mod repo;
+mod revision;
 mod server;
 mod stack;
 mod swarm;
 mod sync;
 
 pub use action::{
-  refresh_action_state_cache, spawn_action_state_refresh_loop,
+  library_version, refresh_action_state_cache,
+  spawn_action_state_refresh_loop,
 };
 pub use build::{
   get_build_state, refresh_build_state_cache,
@@ -87,6 +89,9 @@ pub use repo::{
   get_repo_state, refresh_repo_state_cache,
   spawn_repo_state_refresh_loop,
 };
+pub use revision::{
+  config_object, diff_configs, parse_config, revision_filter,
+};
 pub use server::{rotate_server_keys, update_server_public_key};
 
 /// Implement on each Komodo resource for common methods
@@ -770,6 +775,8 @@ pub async fn update<T: KomodoResource>(
   let config: T::PartialConfig = diff.into();
 
   let id = resource.id.clone();
+  let prev_config = resource.config.clone();
+  let prev_ts = resource.updated_at;
 
   let config_doc = T::update_document(resource, config)
     .context("failed to serialize config to bson document")?;
@@ -817,7 +824,24 @@ pub async fn update<T: KomodoResource>(
   refresh_all_resources_cache().await;
 
   update.finalize();
-  add_update(update).await?;
+  let update_id = add_update(update).await?;
+
+  if let Err(e) = revision::add_revision::<T>(
+    resource_target::<T>(updated.id.clone()),
+    &prev_config,
+    prev_ts,
+    &updated.config,
+    user,
+    update_id,
+  )
+  .await
+  {
+    warn!(
+      "Failed to save {} {} config revision | {e:#}",
+      T::resource_type(),
+      updated.name
+    );
+  }
 
   Ok(updated)
 }
@@ -1043,7 +1067,8 @@ pub async fn delete<T: KomodoResource>(
           .push_error_log("Post delete", format_serror(&e.into()));
       }
     },
-    delete_from_alerters::<T>(&resource.id)
+    delete_from_alerters::<T>(&resource.id),
+    delete_revisions(&target),
   );
 
   refresh_all_resources_cache().await;
@@ -1054,6 +1079,16 @@ pub async fn delete<T: KomodoResource>(
   Ok(resource)
 }
 
+async fn delete_revisions(target: &ResourceTarget) {
+  if let Err(e) = db_client()
+    .resource_revisions
+    .delete_many(revision::revision_filter(target))
+    .await
+  {
+    warn!("Failed to delete resource revisions | {e:#}");
+  }
+}
+
 async fn delete_from_alerters<T: KomodoResource>(id: &str) {
   let target_bson = doc! {
     "type": T::resource_type().as_ref(),
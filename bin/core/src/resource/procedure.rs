@@ -278,6 +278,7 @@ async fn validate_config(
           // Build
           (RunBuild, Build, build),
           (CancelBuild, Build, build),
+          (CleanupBuildImages, Build, build),
           // Deployment
           (Deploy, Deployment, deployment),
           (PullDeployment, Deployment, deployment),
@@ -313,6 +314,7 @@ async fn validate_config(
           (PruneVolumes, Server, server),
           (PruneDockerBuilders, Server, server),
           (PruneBuildx, Server, server),
+          (PruneBuildCache, Server, server),
           (PruneSystem, Server, server),
           // Resource Sync
           (RunSync, ResourceSync, sync),
@@ -0,0 +1,102 @@
+use anyhow::{Context, anyhow};
+use database::mungos::mongodb::bson::{Document, doc};
+use komodo_client::entities::{
+  I64, JsonObject, JsonValue, ResourceTarget, komodo_timestamp,
+  revision::{ResourceRevision, RevisionFieldDiff},
+  user::User,
+};
+use partial_derive2::{Diff, FieldDiff, PartialDiff};
+use serde::Serialize;
+
+use crate::state::db_client;
+
+use super::KomodoResource;
+
+pub fn revision_filter(target: &ResourceTarget) -> Document {
+  let (variant, id) = target.extract_variant_id();
+  doc! { "target.type": variant.as_ref(), "target.id": id }
+}
+
+/// Saves the updated resource config as a new revision.
+/// Resources with no revisions yet were last changed before
+/// revisions were tracked, so the previous config
+/// is saved first as the baseline.
+pub async fn add_revision<T: KomodoResource>(
+  target: ResourceTarget,
+  prev_config: &T::Config,
+  prev_ts: I64,
+  config: &T::Config,
+  user: &User,
+  update_id: String,
+) -> anyhow::Result<()> {
+  let revisions = &db_client().resource_revisions;
+  let has_baseline = revisions
+    .find_one(revision_filter(&target))
+    .await
+    .context("Failed to query db for revisions")?
+    .is_some();
+  let mut new = Vec::with_capacity(2);
+  if !has_baseline {
+    new.push(ResourceRevision {
+      id: Default::default(),
+      target: target.clone(),
+      ts: prev_ts,
+      operator: Default::default(),
+      username: Default::default(),
+      update_id: Default::default(),
+      config: config_object(prev_config)?,
+    });
+  }
+  new.push(ResourceRevision {
+    id: Default::default(),
+    target,
+    ts: komodo_timestamp(),
+    operator: user.id.clone(),
+    username: user.username.clone(),
+    update_id,
+    config: config_object(config)?,
+  });
+  revisions
+    .insert_many(new)
+    .await
+    .context("Failed to add revision to db")?;
+  Ok(())
+}
+
+/// The field by field diff of the configs,
+/// using the resource `PartialDiff`.
+pub fn diff_configs<T: KomodoResource>(
+  from: JsonObject,
+  to: JsonObject,
+) -> anyhow::Result<Vec<RevisionFieldDiff>> {
+  let from = parse_config::<T>(from)?;
+  let to = parse_config::<T>(to)?;
+  let diffs = from
+    .partial_diff(to.into())
+    .iter_field_diffs()
+    .map(|FieldDiff { field, from, to }| RevisionFieldDiff {
+      field: field.to_string(),
+      from,
+      to,
+    })
+    .collect();
+  Ok(diffs)
+}
+
+pub fn parse_config<T: KomodoResource>(
+  config: JsonObject,
+) -> anyhow::Result<T::Config> {
+  serde_json::from_value(JsonValue::Object(config))
+    .context("Failed to parse revision config")
+}
+
+pub fn config_object(
+  config: &impl Serialize,
+) -> anyhow::Result<JsonObject> {
+  match serde_json::to_value(config)
+    .context("Failed to serialize resource config")?
+  {
+    JsonValue::Object(config) => Ok(config),
+    _ => Err(anyhow!("Resource config is not an object")),
+  }
+}
@@ -244,6 +244,14 @@ impl super::KomodoResource for Server {
       .await
       .context("failed to detach server from repos")?;
 
+    db.actions
+      .update_many(
+        doc! { "config.server_id": &id },
+        doc! { "$set": { "config.server_id": "" } },
+      )
+      .await
+      .context("failed to detach server from actions")?;
+
     db.alerts
       .update_many(
         doc! { "target.type": "Server", "target.id": &id },
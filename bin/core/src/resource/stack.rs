@@ -30,6 +30,7 @@ use crate::{
   api::write::WriteArgs,
   config::core_config,
   helpers::{
+    allocation::{log_allocation_conflicts, stack_allocations},
     periphery_client,
     query::{
       get_cached_stack_state, get_stack_state, get_swarm_or_server,
@@ -38,6 +39,8 @@ use crate::{
     swarm::swarm_request,
   },
   monitor::{refresh_server_cache, refresh_swarm_cache},
+  schedule::delete_scheduled_deploy,
+  stack::preview::cleanup_deleted_stack,
   state::{
     action_states, all_resources_cache, db_client,
     server_status_cache, stack_status_cache,
@@ -134,11 +137,23 @@ impl super::KomodoResource for Stack {
                 })
               })
               .unwrap_or_default();
+            // A newer version tag allowed by the service update policy,
+            // until the service is running it.
+            let latest_tag_image = stack
+              .info
+              .latest_images
+              .iter()
+              .find(|latest| {
+                latest.service == current_service.service
+                  && latest.image != current_service.image
+              })
+              .map(|latest| latest.image.clone());
             StackServiceWithUpdate {
               service: current_service.service.clone(),
               image: current_service.image.clone(),
-              latest_image,
-              update_available,
+              update_available: update_available
+                || latest_tag_image.is_some(),
+              latest_image: latest_tag_image.or(latest_image),
             }
           })
           .collect::<Vec<_>>()
@@ -318,6 +333,17 @@ impl super::KomodoResource for Stack {
       }
       SwarmOrServer::Server(server) => {
         refresh_server_cache(&server, true).await;
+        // Reload for the remote contents from the cache refresh.
+        let stack = super::get::<Stack>(&created.id)
+          .await
+          .unwrap_or_else(|_| created.clone());
+        log_allocation_conflicts(
+          &server.id,
+          &ResourceTarget::Stack(stack.id.clone()),
+          &stack_allocations(&stack),
+          update,
+        )
+        .await;
       }
       SwarmOrServer::None => {}
     }
@@ -464,9 +490,14 @@ impl super::KomodoResource for Stack {
 
   async fn post_delete(
     resource: &Resource<Self::Config, Self::Info>,
-    _update: &mut Update,
+    update: &mut Update,
   ) -> anyhow::Result<()> {
     stack_status_cache().remove(&resource.id).await;
+    delete_scheduled_deploy(&ResourceTarget::Stack(
+      resource.id.clone(),
+    ))
+    .await;
+    cleanup_deleted_stack(&resource.id, update).await;
     Ok(())
   }
 }
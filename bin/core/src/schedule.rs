@@ -7,16 +7,20 @@ use anyhow::{Context, anyhow};
 use async_timing_util::Timelength;
 use chrono::Local;
 use croner::parser::CronParser;
-use database::mungos::find::find_collect;
+use database::mungos::{
+  find::find_collect,
+  mongodb::bson::{Document, doc},
+};
 use formatting::format_serror;
 use komodo_client::{
-  api::execute::{RunAction, RunProcedure},
+  api::execute::{Deploy, DeployStack, RunAction, RunProcedure},
   entities::{
     ResourceTarget, ResourceTargetVariant, ScheduleFormat,
     action::Action,
     alert::{Alert, AlertData, SeverityLevel},
     komodo_timestamp,
     procedure::Procedure,
+    schedule::ScheduledDeploy,
     user::{action_user, procedure_user},
   },
 };
@@ -25,9 +29,9 @@ use uuid::Uuid;
 
 use crate::{
   alert::send_alerts,
-  api::execute::{ExecuteArgs, ExecuteRequest},
+  api::execute::{self, ExecuteArgs, ExecuteRequest},
   config::core_config,
-  helpers::update::init_execution_update,
+  helpers::{query::get_user, update::init_execution_update},
   state::db_client,
 };
 
@@ -191,6 +195,10 @@ pub fn spawn_schedule_executor() {
 
                   update_schedule(&procedure);
                 }
+                ResourceTarget::Deployment(_)
+                | ResourceTarget::Stack(_) => {
+                  run_scheduled_deploy(target).await;
+                }
                 _ => unreachable!(),
               }
             });
@@ -229,9 +237,10 @@ pub fn cancel_schedule(target: &ResourceTarget) {
 }
 
 pub async fn update_schedules() {
-  let (procedures, actions) = tokio::join!(
+  let (procedures, actions, scheduled_deploys) = tokio::join!(
     find_collect(&db_client().procedures, None, None),
     find_collect(&db_client().actions, None, None),
+    find_collect(&db_client().scheduled_deploys, None, None),
   );
   let procedures = match procedures
     .context("failed to get all procedures from db")
@@ -250,6 +259,17 @@ pub async fn update_schedules() {
         Vec::new()
       }
     };
+  let scheduled_deploys = match scheduled_deploys
+    .context("failed to get all scheduled deploys from db")
+  {
+    Ok(scheduled_deploys) => scheduled_deploys,
+    Err(e) => {
+      error!(
+        "failed to get scheduled deploys for schedule update | {e:#}"
+      );
+      Vec::new()
+    }
+  };
   // clear out any schedules which don't match to existing resources
   {
     let mut lock = schedules().write().unwrap();
@@ -260,6 +280,11 @@ pub async fn update_schedules() {
       ResourceTarget::Procedure(id) => {
         procedures.iter().any(|procedure| &procedure.id == id)
       }
+      ResourceTarget::Deployment(_) | ResourceTarget::Stack(_) => {
+        scheduled_deploys
+          .iter()
+          .any(|scheduled| &scheduled.target == target)
+      }
       _ => unreachable!(),
     });
   }
@@ -269,6 +294,87 @@ pub async fn update_schedules() {
   for action in actions {
     update_schedule(&action);
   }
+  for scheduled in scheduled_deploys {
+    add_scheduled_deploy(&scheduled);
+  }
+}
+
+pub fn scheduled_deploy_filter(target: &ResourceTarget) -> Document {
+  let (variant, id) = target.extract_variant_id();
+  doc! { "target.type": variant.as_ref(), "target.id": id }
+}
+
+/// Removes any scheduled deploy for the deleted Deployment / Stack.
+pub async fn delete_scheduled_deploy(target: &ResourceTarget) {
+  cancel_schedule(target);
+  if let Err(e) = db_client()
+    .scheduled_deploys
+    .delete_many(scheduled_deploy_filter(target))
+    .await
+  {
+    warn!("Failed to delete scheduled deploy | {e:#}");
+  }
+}
+
+/// Spawns the one-off schedule for the scheduled deploy,
+/// replacing any existing one for the resource.
+pub fn add_scheduled_deploy(scheduled: &ScheduledDeploy) {
+  schedules()
+    .write()
+    .unwrap()
+    .insert(scheduled.target.clone(), Ok(scheduled.run_at));
+}
+
+/// Runs the scheduled Deploy / DeployStack as the user who scheduled it,
+/// removing it from the database so it only runs once.
+async fn run_scheduled_deploy(target: ResourceTarget) {
+  let (variant, id) = target.extract_variant_id();
+  let id = id.clone();
+  let scheduled = match db_client()
+    .scheduled_deploys
+    .find_one_and_delete(scheduled_deploy_filter(&target))
+    .await
+  {
+    Ok(Some(scheduled)) => scheduled,
+    // Cancelled before it ran
+    Ok(None) => return,
+    Err(e) => {
+      error!(
+        "Failed to get scheduled deploy for {variant} {id} from db | {e:#}"
+      );
+      return;
+    }
+  };
+  let user = match get_user(&scheduled.operator).await {
+    Ok(user) => user,
+    Err(e) => {
+      warn!(
+        "Scheduled deploy on {variant} {id} failed | failed to get user | {e:#}"
+      );
+      return;
+    }
+  };
+  let request = match target {
+    ResourceTarget::Deployment(deployment) => {
+      ExecuteRequest::Deploy(Deploy {
+        deployment,
+        stop_signal: None,
+        stop_time: None,
+      })
+    }
+    ResourceTarget::Stack(stack) => {
+      ExecuteRequest::DeployStack(DeployStack {
+        stack,
+        services: Vec::new(),
+        stop_time: None,
+        commit: None,
+      })
+    }
+    _ => unreachable!(),
+  };
+  if let Err(e) = execute::inner_handler(request, user).await {
+    warn!("Scheduled deploy on {variant} {id} failed | {e:#}");
+  }
 }
 
 /// Re/spawns the schedule for the given procedure
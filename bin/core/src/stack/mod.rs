@@ -11,8 +11,10 @@ use crate::{
 };
 
 pub mod execute;
+pub mod preview;
 pub mod remote;
 pub mod services;
+pub mod validate;
 
 pub async fn setup_stack_execution(
   stack: &str,
@@ -0,0 +1,270 @@
+use std::{collections::HashMap, pin::Pin};
+
+use anyhow::{Context, anyhow};
+use database::mungos::{find::find_collect, mongodb::bson::doc};
+use formatting::format_serror;
+use interpolate::Interpolator;
+use komodo_client::entities::{
+  komodo_timestamp,
+  repo::Repo,
+  stack::{Stack, StackConfig},
+  update::Update,
+  user::git_webhook_user,
+};
+
+use crate::{
+  resource,
+  state::{all_resources_cache, db_client},
+};
+
+/// Creates the preview Stack for the pull request from the template,
+/// or updates the existing one to match the template.
+/// Returns the preview Stack, ready to deploy.
+pub async fn upsert_stack_preview(
+  template: &Stack,
+  pr_number: i64,
+  branch: &str,
+) -> anyhow::Result<Stack> {
+  let config = preview_config(template, pr_number, branch).await?;
+
+  let existing = db_client()
+    .stack_previews
+    .find_one(
+      doc! { "stack_id": &template.id, "pr_number": pr_number },
+    )
+    .await
+    .context("Failed to query db for stack preview")?;
+  let existing = match existing {
+    Some(preview) => {
+      resource::get::<Stack>(&preview.preview_stack_id).await.ok()
+    }
+    None => None,
+  };
+
+  let preview = match existing {
+    Some(preview) => resource::update::<Stack>(
+      &preview.id,
+      config.into(),
+      git_webhook_user(),
+    )
+    .await
+    .context("Failed to update preview stack")?,
+    None => resource::create::<Stack>(
+      &format!("{}-pr-{pr_number}", template.name),
+      config.into(),
+      None,
+      git_webhook_user(),
+    )
+    .await
+    .map_err(|e| e.error)
+    .context("Failed to create preview stack")?,
+  };
+
+  let ts = komodo_timestamp();
+  db_client()
+    .stack_previews
+    .update_one(
+      doc! { "stack_id": &template.id, "pr_number": pr_number },
+      doc! {
+        "$set": {
+          "preview_stack_id": &preview.id,
+          "branch": branch,
+          "updated_at": ts,
+        },
+        "$setOnInsert": { "created_at": ts },
+      },
+    )
+    .upsert(true)
+    .await
+    .context("Failed to record stack preview on db")?;
+
+  Ok(preview)
+}
+
+/// Destroys and deletes the preview Stack for the pull request,
+/// if one exists.
+pub async fn delete_stack_preview(
+  template_id: &str,
+  pr_number: i64,
+) -> anyhow::Result<()> {
+  let Some(preview) = db_client()
+    .stack_previews
+    .find_one(
+      doc! { "stack_id": template_id, "pr_number": pr_number },
+    )
+    .await
+    .context("Failed to query db for stack preview")?
+  else {
+    return Ok(());
+  };
+  delete_preview_stack(&preview.preview_stack_id).await
+}
+
+/// Called after a Stack is deleted. If the Stack was a template,
+/// destroys and deletes its previews. If the Stack was a preview,
+/// removes its record.
+pub async fn cleanup_deleted_stack(
+  stack_id: &str,
+  update: &mut Update,
+) {
+  let previews = match find_collect(
+    &db_client().stack_previews,
+    doc! { "stack_id": stack_id },
+    None,
+  )
+  .await
+  .context("Failed to query db for stack previews")
+  {
+    Ok(previews) => previews,
+    Err(e) => {
+      update
+        .push_error_log("Delete Previews", format_serror(&e.into()));
+      return;
+    }
+  };
+  for preview in previews {
+    if let Err(e) =
+      delete_preview_stack(&preview.preview_stack_id).await
+    {
+      update.push_error_log(
+        "Delete Preview",
+        format_serror(
+          &e.context(format!(
+            "Failed to delete preview for PR #{}",
+            preview.pr_number
+          ))
+          .into(),
+        ),
+      );
+    }
+  }
+  if let Err(e) = db_client()
+    .stack_previews
+    .delete_many(doc! {
+      "$or": [
+        { "stack_id": stack_id },
+        { "preview_stack_id": stack_id },
+      ]
+    })
+    .await
+  {
+    warn!("Failed to remove stack preview records | {e:#}");
+  }
+}
+
+/// Garbage collects previews whose template or preview Stack
+/// was removed without cleaning up, eg. directly on the database.
+pub async fn prune_stack_previews() -> anyhow::Result<()> {
+  let previews =
+    find_collect(&db_client().stack_previews, None, None)
+      .await
+      .context("Failed to query db for stack previews")?;
+  if previews.is_empty() {
+    return Ok(());
+  }
+  let all = all_resources_cache().load_full();
+  for preview in previews {
+    if !all.stacks.contains_key(&preview.preview_stack_id) {
+      db_client()
+        .stack_previews
+        .delete_one(
+          doc! { "preview_stack_id": &preview.preview_stack_id },
+        )
+        .await
+        .context("Failed to remove stack preview record")?;
+      info!(
+        "Removed record of deleted preview stack for PR #{}",
+        preview.pr_number
+      );
+    } else if !all.stacks.contains_key(&preview.stack_id)
+      && let Err(e) =
+        delete_preview_stack(&preview.preview_stack_id).await
+    {
+      warn!(
+        "Failed to delete orphaned preview stack {} | {e:#}",
+        preview.preview_stack_id
+      );
+    }
+  }
+  Ok(())
+}
+
+/// Deleting the Stack also destroys it,
+/// and removes the preview record.
+///
+/// Boxed as this recurses through the Stack `post_delete`.
+fn delete_preview_stack(
+  id: &str,
+) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
+  Box::pin(async move {
+    resource::delete::<Stack>(id, git_webhook_user())
+      .await
+      .map(|_| ())
+  })
+}
+
+/// The template config, adjusted to deploy
+/// the pull request alongside the template.
+async fn preview_config(
+  template: &Stack,
+  pr_number: i64,
+  branch: &str,
+) -> anyhow::Result<StackConfig> {
+  let mut config = template.config.clone();
+
+  if config.files_on_host {
+    return Err(anyhow!(
+      "Previews are not supported with 'files_on_host'"
+    ));
+  }
+
+  // Previews use the pull request branch,
+  // so can't follow the linked repo branch.
+  if !config.linked_repo.is_empty() {
+    let repo = resource::get::<Repo>(&config.linked_repo)
+      .await
+      .context("Failed to find 'linked_repo'")?;
+    config.linked_repo = String::new();
+    config.git_provider = repo.config.git_provider;
+    config.git_https = repo.config.git_https;
+    config.git_account = repo.config.git_account;
+    config.repo = repo.config.repo;
+  }
+
+  if config.repo.is_empty() {
+    return Err(anyhow!(
+      "Previews require the Stack to use a git repo"
+    ));
+  }
+
+  config.branch = branch.to_string();
+  config.commit = String::new();
+  // Use the default clone path for the preview stack name
+  config.clone_path = String::new();
+  config.project_name =
+    format!("{}-pr-{pr_number}", template.project_name(true))
+      .to_lowercase();
+  // The preview is managed by the template webhook.
+  config.preview_enabled = false;
+  config.webhook_enabled = false;
+
+  // These are not secrets, so are interpolated
+  // even with 'skip_secret_interp' enabled.
+  // Other [[VARIABLES]] are left as is.
+  let variables = HashMap::from([
+    (String::from("PR_NUMBER"), pr_number.to_string()),
+    (String::from("PR_BRANCH"), branch.to_string()),
+  ]);
+  let secrets = HashMap::new();
+  Interpolator::new(Some(&variables), &secrets)
+    .interpolate_string(&mut config.file_contents)?
+    .interpolate_string(&mut config.environment)?
+    .interpolate_string(&mut config.pre_deploy.command)?
+    .interpolate_string(&mut config.post_deploy.command)?
+    .interpolate_string(&mut config.compose_cmd_wrapper)?
+    .interpolate_extra_args(&mut config.extra_args)?
+    .interpolate_extra_args(&mut config.build_extra_args)
+    .context("Failed to interpolate pull request variables")?;
+
+  Ok(config)
+}
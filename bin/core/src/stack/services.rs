@@ -73,3 +73,40 @@ pub fn extract_services_into_res(
 
   Ok(())
 }
+
+/// Rewrites the `image:` values matching a current image
+/// to the new image, keeping the indentation and quotes of the line.
+/// Takes (current image, new image) pairs.
+/// Returns None if no image matches.
+pub fn replace_compose_images(
+  compose_contents: &str,
+  images: &[(String, String)],
+) -> Option<String> {
+  let mut replaced = false;
+  let contents = compose_contents
+    .split('\n')
+    .map(|line| {
+      let trimmed = line.trim_start();
+      let Some(value) = trimmed.strip_prefix("image:") else {
+        return line.to_string();
+      };
+      let value = value.trim();
+      let Some((_, new)) = images.iter().find(|(current, _)| {
+        current == value.trim_matches(['"', '\''])
+      }) else {
+        return line.to_string();
+      };
+      replaced = true;
+      let indent = &line[..line.len() - trimmed.len()];
+      let quote = if value.starts_with(['"', '\'']) {
+        &value[..1]
+      } else {
+        ""
+      };
+      let cr = if line.ends_with('\r') { "\r" } else { "" };
+      format!("{indent}image: {quote}{new}{quote}{cr}")
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+  replaced.then_some(contents)
+}
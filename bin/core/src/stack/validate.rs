@@ -0,0 +1,422 @@
+//! Validates Stacks before deploy, see `ValidateStack`.
+
+use std::{
+  collections::{HashMap, HashSet},
+  sync::OnceLock,
+};
+
+use formatting::format_serror;
+use interpolate::Interpolator;
+use komodo_client::entities::{
+  ResourceTarget, SwarmOrServer,
+  repo::Repo,
+  server::Server,
+  stack::{
+    Stack, StackDiagnostic, StackDiagnosticKind,
+    StackDiagnosticLevel, StackServiceNames,
+  },
+  update::Update,
+};
+use periphery_client::api::{
+  DeployStackResponse, compose::ComposeConfig,
+};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+  helpers::{
+    allocation::{compose_port, server_allocations},
+    periphery_client,
+    query::{VariablesAndSecrets, get_variables_and_secrets},
+    stack_git_token,
+  },
+  registry::{
+    RegistryClient, split_image_name, usage::split_image_tag,
+  },
+  resource,
+  state::server_status_cache,
+};
+
+pub struct StackValidation {
+  /// Whether no errors were found.
+  pub valid: bool,
+  /// The problems found.
+  pub diagnostics: Vec<StackDiagnostic>,
+  /// The output of `docker compose config`, if it succeeded.
+  pub merged_config: Option<String>,
+}
+
+/// Interpolates the Stack, runs `docker compose config` on the Server,
+/// and checks the images, variables and published ports.
+pub async fn validate_stack(
+  mut stack: Stack,
+  swarm_or_server: &SwarmOrServer,
+) -> anyhow::Result<StackValidation> {
+  let mut diagnostics = Vec::new();
+
+  let mut repo = if !stack.config.files_on_host
+    && !stack.config.linked_repo.is_empty()
+  {
+    resource::get::<Repo>(&stack.config.linked_repo)
+      .await?
+      .into()
+  } else {
+    None
+  };
+
+  let git_token = stack_git_token(&mut stack, repo.as_mut()).await?;
+
+  let secret_replacers = if !stack.config.skip_secret_interp {
+    let VariablesAndSecrets { variables, secrets } =
+      get_variables_and_secrets().await?;
+    let mut interpolator =
+      Interpolator::new(Some(&variables), &secrets);
+    interpolator.interpolate_stack(&mut stack)?;
+    if let Some(repo) = repo.as_mut()
+      && !repo.config.skip_secret_interp
+    {
+      interpolator.interpolate_repo(repo)?;
+    }
+    interpolator.secret_replacers
+  } else {
+    Default::default()
+  };
+
+  // With interpolation skipped, [[VARIABLES]] are left as is.
+  if !stack.config.skip_secret_interp {
+    unset_komodo_variables(&stack, &mut diagnostics);
+  }
+
+  let (services, merged_config) = match swarm_or_server {
+    SwarmOrServer::Server(server) => {
+      let res = periphery_client(server)
+        .await?
+        .request(ComposeConfig {
+          stack: stack.clone(),
+          repo,
+          git_token,
+          replacers: secret_replacers.into_iter().collect(),
+        })
+        .await?;
+      compose_config_diagnostics(&res, &mut diagnostics);
+      (res.services, res.merged_config)
+    }
+    SwarmOrServer::Swarm(_) | SwarmOrServer::None => {
+      diagnostics.push(StackDiagnostic {
+        level: StackDiagnosticLevel::Warning,
+        kind: StackDiagnosticKind::ComposeConfig,
+        service: None,
+        message: String::from(
+          "The compose config is only checked for Stacks deployed to a Server",
+        ),
+      });
+      (stack.info.latest_services.clone(), None)
+    }
+  };
+
+  let compose = merged_config.as_deref().and_then(|config| {
+    serde_yaml_ng::from_str::<MergedCompose>(config).ok()
+  });
+
+  let server = match swarm_or_server {
+    SwarmOrServer::Server(server) => Some(server),
+    _ => None,
+  };
+
+  check_images(
+    &stack,
+    server,
+    &services,
+    compose.as_ref(),
+    &mut diagnostics,
+  )
+  .await;
+
+  if let (Some(server), Some(compose)) = (server, &compose) {
+    check_port_conflicts(&stack, server, compose, &mut diagnostics)
+      .await;
+  }
+
+  Ok(StackValidation {
+    valid: !diagnostics
+      .iter()
+      .any(|d| d.level == StackDiagnosticLevel::Error),
+    diagnostics,
+    merged_config,
+  })
+}
+
+/// Adds the validation result to the Update logs.
+/// Errors found fail the Update.
+pub fn push_validation_log(
+  update: &mut Update,
+  res: anyhow::Result<StackValidation>,
+) {
+  match res {
+    Ok(res) if res.valid => update.push_simple_log(
+      "Validate Stack",
+      if res.diagnostics.is_empty() {
+        String::from("No problems found")
+      } else {
+        format_diagnostics(&res.diagnostics)
+      },
+    ),
+    Ok(res) => update.push_error_log(
+      "Validate Stack",
+      format_diagnostics(&res.diagnostics),
+    ),
+    Err(e) => update.push_error_log(
+      "Validate Stack",
+      format_serror(&e.context("Failed to validate stack").into()),
+    ),
+  }
+}
+
+/// Formats the diagnostics for the Update logs.
+fn format_diagnostics(diagnostics: &[StackDiagnostic]) -> String {
+  diagnostics
+    .iter()
+    .map(|d| match &d.service {
+      Some(service) => {
+        format!(
+          "{} | {} | {service} | {}",
+          d.level, d.kind, d.message
+        )
+      }
+      None => format!("{} | {} | {}", d.level, d.kind, d.message),
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Komodo variables left after interpolation are not defined.
+fn unset_komodo_variables(
+  stack: &Stack,
+  diagnostics: &mut Vec<StackDiagnostic>,
+) {
+  static REGEX: OnceLock<Regex> = OnceLock::new();
+  let regex = REGEX
+    .get_or_init(|| Regex::new(r"\[\[([A-Za-z0-9_]+)\]\]").unwrap());
+  let mut unset = HashSet::new();
+  for target in
+    [&stack.config.file_contents, &stack.config.environment]
+  {
+    for capture in regex.captures_iter(target) {
+      let variable = &capture[1];
+      if unset.insert(variable.to_string()) {
+        diagnostics.push(StackDiagnostic {
+          level: StackDiagnosticLevel::Warning,
+          kind: StackDiagnosticKind::UnsetVariable,
+          service: None,
+          message: format!(
+            "Variable [[{variable}]] is not defined in Core, it must be a Periphery secret"
+          ),
+        });
+      }
+    }
+  }
+}
+
+fn compose_config_diagnostics(
+  res: &DeployStackResponse,
+  diagnostics: &mut Vec<StackDiagnostic>,
+) {
+  for file in &res.missing_files {
+    diagnostics.push(StackDiagnostic {
+      level: StackDiagnosticLevel::Error,
+      kind: StackDiagnosticKind::MissingFile,
+      service: None,
+      message: format!("Missing file: {file}"),
+    });
+  }
+  for error in &res.remote_errors {
+    diagnostics.push(StackDiagnostic {
+      level: StackDiagnosticLevel::Error,
+      kind: StackDiagnosticKind::MissingFile,
+      service: None,
+      message: format!(
+        "Failed to read {}: {}",
+        error.path, error.contents
+      ),
+    });
+  }
+  static REGEX: OnceLock<Regex> = OnceLock::new();
+  let regex = REGEX.get_or_init(|| {
+    Regex::new(r#"The \\?"(\w+)\\?" variable is not set"#).unwrap()
+  });
+  let mut unset = HashSet::new();
+  for log in &res.logs {
+    // Missing / unreadable files are reported above.
+    if !log.success
+      && !matches!(
+        log.stage.as_str(),
+        "Validate Files" | "Read Compose File"
+      )
+    {
+      diagnostics.push(StackDiagnostic {
+        level: StackDiagnosticLevel::Error,
+        kind: StackDiagnosticKind::ComposeConfig,
+        service: None,
+        message: format!(
+          "{}: {}",
+          log.stage,
+          if log.stderr.is_empty() {
+            &log.stdout
+          } else {
+            &log.stderr
+          }
+          .trim()
+        ),
+      });
+    }
+    for capture in regex.captures_iter(&log.stderr) {
+      let variable = &capture[1];
+      if unset.insert(variable.to_string()) {
+        diagnostics.push(StackDiagnostic {
+          level: StackDiagnosticLevel::Warning,
+          kind: StackDiagnosticKind::UnsetVariable,
+          service: None,
+          message: format!(
+            "Variable ${{{variable}}} is not set, defaulting to a blank string"
+          ),
+        });
+      }
+    }
+  }
+}
+
+/// Checks the service images exist on the Server or in the registry.
+/// Services with a `build` are skipped.
+async fn check_images(
+  stack: &Stack,
+  server: Option<&Server>,
+  services: &[StackServiceNames],
+  compose: Option<&MergedCompose>,
+  diagnostics: &mut Vec<StackDiagnostic>,
+) {
+  let local_images = match server {
+    Some(server) => local_images(server).await,
+    None => HashSet::new(),
+  };
+  let mut checked = HashSet::new();
+  for service in services {
+    let built = compose
+      .and_then(|compose| compose.services.get(&service.service_name))
+      .is_some_and(|service| service.build.is_some());
+    if built
+      // Images with a hardcoded digest are checked on pull.
+      || service.image.contains('@')
+      || !checked.insert(service.image.clone())
+    {
+      continue;
+    }
+    let Some((name, tag)) = split_image_tag(&service.image) else {
+      continue;
+    };
+    if local_images.contains(&(name.clone(), tag.clone())) {
+      continue;
+    }
+    let Some((domain, repository)) = split_image_name(&name) else {
+      continue;
+    };
+    let account = if stack.config.registry_provider == domain {
+      stack.config.registry_account.as_str()
+    } else {
+      ""
+    };
+    let res = match RegistryClient::new(domain, account).await {
+      Ok(client) => client.image_exists(repository, &tag).await,
+      Err(e) => Err(e),
+    };
+    let (level, message) = match res {
+      Ok(true) => continue,
+      Ok(false) => (
+        StackDiagnosticLevel::Error,
+        format!("Image {} not found in the registry", service.image),
+      ),
+      Err(e) => (
+        StackDiagnosticLevel::Warning,
+        format!(
+          "Failed to check image {} in the registry | {e:#}",
+          service.image
+        ),
+      ),
+    };
+    diagnostics.push(StackDiagnostic {
+      level,
+      kind: StackDiagnosticKind::MissingImage,
+      service: Some(service.service_name.clone()),
+      message,
+    });
+  }
+}
+
+/// The (name, tag) of the images already on the Server.
+async fn local_images(server: &Server) -> HashSet<(String, String)> {
+  let Some(status) = server_status_cache().get(&server.id).await
+  else {
+    return HashSet::new();
+  };
+  let Some(docker) = &status.docker else {
+    return HashSet::new();
+  };
+  docker
+    .images
+    .iter()
+    .flat_map(|image| &image.tags)
+    .filter_map(|tag| split_image_tag(tag))
+    .collect()
+}
+
+/// Checks the published ports against the ports allocated
+/// on the Server by other resources and containers.
+/// Ports published by running containers fail validation,
+/// ports only configured by other resources are warnings.
+async fn check_port_conflicts(
+  stack: &Stack,
+  server: &Server,
+  compose: &MergedCompose,
+  diagnostics: &mut Vec<StackDiagnostic>,
+) {
+  let allocations = server_allocations(&server.id).await;
+  let target = ResourceTarget::Stack(stack.id.clone());
+
+  let mut services = compose.services.iter().collect::<Vec<_>>();
+  services.sort_by_key(|(name, _)| *name);
+
+  for (service_name, service) in services {
+    let ports = service
+      .ports
+      .iter()
+      .filter_map(compose_port)
+      .collect::<Vec<_>>();
+    for conflict in allocations.port_conflicts(Some(&target), &ports)
+    {
+      diagnostics.push(StackDiagnostic {
+        level: if conflict.running {
+          StackDiagnosticLevel::Error
+        } else {
+          StackDiagnosticLevel::Warning
+        },
+        kind: StackDiagnosticKind::PortConflict,
+        service: Some(service_name.clone()),
+        message: conflict.message,
+      });
+    }
+  }
+}
+
+/// The parts of the `docker compose config` output
+/// used for validation. The ports are always in the long syntax.
+#[derive(Deserialize)]
+struct MergedCompose {
+  #[serde(default)]
+  services: HashMap<String, MergedService>,
+}
+
+#[derive(Deserialize)]
+struct MergedService {
+  build: Option<serde_yaml_ng::Value>,
+  #[serde(default)]
+  ports: Vec<serde_yaml_ng::Value>,
+}
@@ -25,7 +25,25 @@ pub trait ReplaceIds: KomodoResource {
 
 // These have no linked resource ids to replace
 impl ReplaceIds for Server {}
-impl ReplaceIds for Action {}
+impl ReplaceIds for Action {
+  fn replace_ids(config: &mut Self::Config) {
+    let all = all_resources_cache().load();
+    config.server_id.clone_from(
+      all
+        .servers
+        .get(&config.server_id)
+        .map(|s| &s.name)
+        .unwrap_or(&String::new()),
+    );
+    config.libraries.iter_mut().for_each(|library_id| {
+      *library_id = all
+        .actions
+        .get(library_id)
+        .map(|a| a.name.clone())
+        .unwrap_or_default();
+    });
+  }
+}
 
 impl ReplaceIds for ResourceSync {
   fn replace_ids(config: &mut Self::Config) {
@@ -137,6 +155,24 @@ impl ReplaceIds for Build {
         .map(|s| &s.name)
         .unwrap_or(&String::new()),
     );
+    for platform_builder in &mut config.platform_builders {
+      platform_builder.builder_id.clone_from(
+        all
+          .builders
+          .get(&platform_builder.builder_id)
+          .map(|s| &s.name)
+          .unwrap_or(&String::new()),
+      );
+    }
+    for entry in &mut config.matrix {
+      entry.builder_id.clone_from(
+        all
+          .builders
+          .get(&entry.builder_id)
+          .map(|s| &s.name)
+          .unwrap_or(&String::new()),
+      );
+    }
     config.linked_repo.clone_from(
       all
         .repos
@@ -0,0 +1,305 @@
+use std::{path::Path, process::Stdio, sync::Arc, time::Duration};
+
+use anyhow::{Context, anyhow};
+use command::{
+  CommandOptions, run_komodo_standard_command, run_standard_command,
+};
+use komodo_client::entities::{
+  NoData, komodo_timestamp, update::Log,
+};
+use mogh_resolver::Resolve;
+use periphery_client::api::action::{
+  ActionLibrary, CancelAction, GetActionRun, GetActionRunResponse,
+  RunAction,
+};
+use tokio::{
+  fs,
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  process::Command,
+  sync::Mutex,
+};
+use uuid::Uuid;
+
+use crate::{
+  config::periphery_config,
+  state::{ActionRun, action_runs},
+};
+
+/// Used when Deno is not installed on the host.
+const DENO_IMAGE: &str = "denoland/deno";
+
+/// How long a finished run is kept for Core to get the output.
+const FINISHED_RUN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+impl Resolve<crate::api::Args> for RunAction {
+  #[instrument(
+    "RunAction",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      run_id = self.id,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<NoData> {
+    let RunAction {
+      id,
+      contents,
+      permission_flags,
+      reload,
+      libraries,
+    } = self;
+
+    if periphery_config().disable_actions {
+      return Err(anyhow!(
+        "Actions are disabled in the Periphery config"
+      ));
+    }
+
+    let dir = periphery_config().action_dir();
+    fs::create_dir_all(&dir).await.with_context(|| {
+      format!("Failed to create action directory at {dir:?}")
+    })?;
+    let name = Uuid::new_v4().to_string();
+    let path = dir.join(format!("{name}.ts"));
+    // The contents include the Action API key.
+    mogh_secret_file::write_async(&path, &contents)
+      .await
+      .with_context(|| {
+        format!("Failed to write action file to {path:?}")
+      })?;
+
+    // The libraries are written to a directory named after the run
+    let libraries_dir = dir.join(&name);
+    if !libraries.is_empty() {
+      write_libraries(&libraries_dir, &libraries).await?;
+    }
+
+    let reload = if reload { " --reload" } else { "" };
+    let deno_installed = run_standard_command(
+      "deno --version",
+      CommandOptions::default(),
+    )
+    .await
+    .success();
+    let (command, container) = if deno_installed {
+      let import_map = if libraries.is_empty() {
+        String::new()
+      } else {
+        format!(
+          " --import-map={}",
+          libraries_dir.join("import_map.json").display()
+        )
+      };
+      (
+        format!(
+          "deno run{permission_flags}{import_map}{reload} {}",
+          path.display()
+        ),
+        None,
+      )
+    } else {
+      let container = format!("komodo-action-{name}");
+      let (libraries_mount, import_map) = if libraries.is_empty() {
+        (String::new(), "")
+      } else {
+        (
+          format!(" -v {}:/libraries:ro", libraries_dir.display()),
+          " --import-map=/libraries/import_map.json",
+        )
+      };
+      (
+        format!(
+          "docker run --rm --name {container} --network host -v {}:/action.ts:ro{libraries_mount} {DENO_IMAGE} run{permission_flags}{import_map}{reload} /action.ts",
+          path.display()
+        ),
+        Some(container),
+      )
+    };
+
+    let run = Arc::new(ActionRun::default());
+    action_runs().insert(id.clone(), run.clone()).await;
+
+    tokio::spawn(async move {
+      let start_ts = komodo_timestamp();
+      let res = stream_command(&command, &run).await;
+      if run.cancel.is_cancelled()
+        && let Some(container) = container
+      {
+        // Killing the docker client doesn't stop the container.
+        run_komodo_standard_command(
+          "Stop Action Container",
+          format!("docker rm -f {container}"),
+          CommandOptions::default(),
+        )
+        .await;
+      }
+      if let Err(e) = fs::remove_file(&path).await {
+        warn!(
+          "Failed to delete action file after action execution | {e:#}"
+        );
+      }
+      if fs::try_exists(&libraries_dir).await.unwrap_or_default()
+        && let Err(e) = fs::remove_dir_all(&libraries_dir).await
+      {
+        warn!(
+          "Failed to delete action libraries after action execution | {e:#}"
+        );
+      }
+      let mut stderr = run.stderr.lock().await.clone();
+      let success = match res {
+        Ok(success) => success,
+        Err(e) => {
+          stderr.push_str(&format!("{e:#}"));
+          false
+        }
+      };
+      *run.log.lock().await = Some(Log {
+        stage: String::from("Execute Action"),
+        command,
+        stdout: run.stdout.lock().await.clone(),
+        stderr,
+        success,
+        start_ts,
+        end_ts: komodo_timestamp(),
+      });
+      // The run is removed once Core gets the final log.
+      // Drop it if Core stops polling, eg. after losing the connection.
+      tokio::time::sleep(FINISHED_RUN_TIMEOUT).await;
+      action_runs().remove(&id).await;
+    });
+
+    Ok(NoData {})
+  }
+}
+
+/// Writes the library files and their import map to the directory.
+async fn write_libraries(
+  dir: &Path,
+  libraries: &[ActionLibrary],
+) -> anyhow::Result<()> {
+  fs::create_dir_all(dir).await.with_context(|| {
+    format!("Failed to create action libraries directory at {dir:?}")
+  })?;
+  for library in libraries {
+    let path = dir.join(library.file_name());
+    mogh_secret_file::write_async(&path, &library.contents)
+      .await
+      .with_context(|| {
+        format!(
+          "Failed to write action library {} to {path:?}",
+          library.name
+        )
+      })?;
+  }
+  let path = dir.join("import_map.json");
+  fs::write(&path, ActionLibrary::import_map(libraries))
+    .await
+    .with_context(|| {
+      format!("Failed to write action import map to {path:?}")
+    })
+}
+
+/// Runs the command, appending the output to the run as it arrives.
+/// Returns whether the command was successful.
+async fn stream_command(
+  command: &str,
+  run: &ActionRun,
+) -> anyhow::Result<bool> {
+  let args = shlex::split(command)
+    .filter(|args| !args.is_empty())
+    .context("Command lexed into empty args")?;
+  let mut child = Command::new(&args[0])
+    .args(&args[1..])
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .context("Failed to spawn action command")?;
+  let stdout = child.stdout.take().context("Missing stdout")?;
+  let stderr = child.stderr.take().context("Missing stderr")?;
+
+  // The child is moved in, so it is killed when dropped on cancel.
+  let wait = async move {
+    let (status, _, _) = tokio::join!(
+      child.wait(),
+      read_lines(stdout, &run.stdout),
+      read_lines(stderr, &run.stderr),
+    );
+    status
+  };
+
+  tokio::select! {
+    status = wait => {
+      Ok(status.context("Failed to wait on action command")?.success())
+    }
+    _ = run.cancel.cancelled() => {
+      Err(anyhow::anyhow!("Action cancelled"))
+    }
+  }
+}
+
+async fn read_lines(
+  output: impl AsyncRead + Unpin,
+  buffer: &Mutex<String>,
+) {
+  let mut lines = BufReader::new(output).lines();
+  while let Ok(Some(line)) = lines.next_line().await {
+    let mut buffer = buffer.lock().await;
+    buffer.push_str(&line);
+    buffer.push('\n');
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for GetActionRun {
+  async fn resolve(
+    self,
+    _: &crate::api::Args,
+  ) -> anyhow::Result<GetActionRunResponse> {
+    let run = action_runs()
+      .get(&self.id)
+      .await
+      .context("No action run found")?;
+    let log = run.log.lock().await.clone();
+    if log.is_some() {
+      action_runs().remove(&self.id).await;
+    }
+    Ok(GetActionRunResponse {
+      stdout: run.stdout.lock().await.clone(),
+      stderr: run.stderr.lock().await.clone(),
+      log,
+    })
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for CancelAction {
+  #[instrument(
+    "CancelAction",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      run_id = self.id,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<NoData> {
+    action_runs()
+      .get(&self.id)
+      .await
+      .context("No running action found")?
+      .cancel
+      .cancel();
+    Ok(NoData {})
+  }
+}
@@ -6,10 +6,17 @@ use std::{
 use anyhow::{Context, anyhow};
 use formatting::format_serror;
 use komodo_client::{
-  entities::{EnvironmentVar, update::Log},
+  entities::{
+    EnvironmentVar,
+    build::{Build, BuildCacheBackend, BuildConfig},
+    to_path_compatible_name,
+    update::Log,
+  },
   parsers::QUOTE_PATTERN,
 };
 
+use crate::config::periphery_config;
+
 pub async fn write_dockerfile(
   build_path: &Path,
   dockerfile_path: &str,
@@ -101,3 +108,79 @@ pub async fn parse_secret_args(
   }
   Ok(res)
 }
+
+/// Buildx `--cache-from` / `--cache-to` args for the build cache backend.
+pub fn parse_cache_args(
+  build: &Build,
+  should_push: bool,
+) -> anyhow::Result<String> {
+  let BuildConfig {
+    use_buildx,
+    cache_backend,
+    cache_ref,
+    cache_mode_max,
+    ..
+  } = &build.config;
+  let mode = if *cache_mode_max { ",mode=max" } else { "" };
+  match cache_backend {
+    BuildCacheBackend::None => Ok(String::new()),
+    _ if !use_buildx => Err(anyhow!(
+      "The '{cache_backend}' build cache backend requires 'use_buildx'"
+    )),
+    BuildCacheBackend::Registry => {
+      let cache_ref = build.get_registry_cache_ref().context(
+        "The 'Registry' build cache backend requires an image registry, or 'cache_ref' to be set",
+      )?;
+      // Only export if logged in to push
+      let cache_to = if should_push {
+        format!(" --cache-to type=registry,ref={cache_ref}{mode}")
+      } else {
+        String::new()
+      };
+      Ok(format!(
+        " --cache-from type=registry,ref={cache_ref}{cache_to}"
+      ))
+    }
+    BuildCacheBackend::Local => {
+      let cache_dir = if cache_ref.is_empty() {
+        periphery_config()
+          .build_dir()
+          .join(".buildcache")
+          .join(to_path_compatible_name(&build.name))
+      } else {
+        PathBuf::from(cache_ref)
+      };
+      let cache_dir = cache_dir.display();
+      Ok(format!(
+        " --cache-from type=local,src={cache_dir} --cache-to type=local,dest={cache_dir}{mode}"
+      ))
+    }
+  }
+}
+
+/// The `--platform` arg for the target platforms.
+pub fn parse_platform_args(build: &Build) -> anyhow::Result<String> {
+  let BuildConfig {
+    use_buildx,
+    platforms,
+    ..
+  } = &build.config;
+  match platforms.len() {
+    0 => Ok(String::new()),
+    1 => Ok(format!(" --platform {}", platforms[0])),
+    _ if !use_buildx => Err(anyhow!(
+      "Building for multiple platforms requires 'use_buildx'"
+    )),
+    _ => Ok(format!(" --platform {}", platforms.join(","))),
+  }
+}
+
+/// The space reclaimed, from the `Total:` line of `docker buildx prune`.
+pub fn parse_reclaimed_space(stdout: &str) -> Option<String> {
+  stdout.lines().rev().find_map(|line| {
+    line
+      .trim()
+      .strip_prefix("Total:")
+      .map(|total| total.trim().to_string())
+  })
+}
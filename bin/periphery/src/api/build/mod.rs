@@ -7,6 +7,7 @@ use anyhow::{Context, anyhow};
 use command::{
   CommandOptions, KomodoCommandMode,
   run_komodo_command_with_sanitization, run_komodo_standard_command,
+  run_standard_command,
 };
 use formatting::format_serror;
 use interpolate::Interpolator;
@@ -19,10 +20,12 @@ use komodo_client::entities::{
 };
 use mogh_resolver::Resolve;
 use periphery_client::api::build::{
-  self, CancelBuild, GetDockerfileContentsOnHost,
-  GetDockerfileContentsOnHostResponse, PruneBuilders, PruneBuildx,
-  WriteDockerfileContentsToHost,
+  self, BuilderCachePrune, CancelBuild, GetDockerfileContentsOnHost,
+  GetDockerfileContentsOnHostResponse, PruneBuildCache,
+  PruneBuildCacheResponse, PruneBuilders, PruneBuildx, PushImage,
+  PushManifestList, WriteDockerfileContentsToHost,
 };
+use serde::Deserialize;
 use tokio::fs;
 use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
@@ -35,6 +38,8 @@ use crate::{
 };
 
 mod helpers;
+mod sbom;
+
 use helpers::*;
 
 impl Resolve<crate::api::Args> for GetDockerfileContentsOnHost {
@@ -151,6 +156,7 @@ impl Resolve<crate::api::Args> for build::Build {
       mut replacers,
       commit_hash,
       additional_tags,
+      skip_push,
     } = self;
 
     let mut logs = Vec::new();
@@ -336,11 +342,22 @@ impl Resolve<crate::api::Args> for build::Build {
         )
         .context("Failed to parse image tags into command")?;
 
-      let maybe_push = if should_push { " --push" } else { "" };
+      let platform_args = parse_platform_args(&build)?;
+
+      let cache_args = parse_cache_args(&build, should_push)?;
+
+      let maybe_push = if skip_push {
+        // Make sure the image is available locally to scan
+        if *use_buildx { " --load" } else { "" }
+      } else if should_push {
+        " --push"
+      } else {
+        ""
+      };
 
       // Construct command
       let command = format!(
-        "docker{buildx} build{build_args}{command_secret_args}{extra_args}{labels}{image_tags}{maybe_push} -f {dockerfile_path} .",
+        "docker{buildx} build{build_args}{command_secret_args}{extra_args}{platform_args}{cache_args}{labels}{image_tags}{maybe_push} -f {dockerfile_path} .",
       );
 
       anyhow::Ok(command)
@@ -403,6 +420,159 @@ impl Resolve<crate::api::Args> for CancelBuild {
 
 //
 
+impl Resolve<crate::api::Args> for PushImage {
+  #[instrument(
+    "PushImage",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      build_id = self.build.id,
+      build_name = self.build.name,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<Vec<Log>> {
+    let PushImage {
+      build,
+      registry_tokens,
+      commit_hash,
+    } = self;
+
+    let registry_tokens = registry_tokens
+      .iter()
+      .map(|(domain, account, token)| {
+        ((domain.as_str(), account.as_str()), token.as_str())
+      })
+      .collect::<HashMap<_, _>>();
+
+    for (domain, account) in build
+      .config
+      .image_registry
+      .iter()
+      .map(|r| (r.domain.as_str(), r.account.as_str()))
+      .collect::<HashSet<_>>()
+    {
+      docker_login(
+        domain,
+        account,
+        registry_tokens.get(&(domain, account)).copied(),
+      )
+      .await
+      .context("Failed to login to docker registry")?;
+    }
+
+    let mut logs = Vec::new();
+
+    for tag in build.get_image_tags(
+      &build.get_image_names(),
+      commit_hash.as_deref(),
+      &[],
+    ) {
+      let log = run_komodo_standard_command(
+        "Push Image",
+        format!("docker push {tag}"),
+        CommandOptions::default(),
+      )
+      .await;
+      let success = log.success;
+      logs.push(log);
+      if !success {
+        break;
+      }
+    }
+
+    Ok(logs)
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for PushManifestList {
+  #[instrument(
+    "PushManifestList",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      build_id = self.build.id,
+      build_name = self.build.name,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<Vec<Log>> {
+    let PushManifestList {
+      build,
+      registry_tokens,
+      commit_hash,
+      platform_groups,
+    } = self;
+
+    let registry_tokens = registry_tokens
+      .iter()
+      .map(|(domain, account, token)| {
+        ((domain.as_str(), account.as_str()), token.as_str())
+      })
+      .collect::<HashMap<_, _>>();
+
+    for (domain, account) in build
+      .config
+      .image_registry
+      .iter()
+      .map(|r| (r.domain.as_str(), r.account.as_str()))
+      .collect::<HashSet<_>>()
+    {
+      docker_login(
+        domain,
+        account,
+        registry_tokens.get(&(domain, account)).copied(),
+      )
+      .await
+      .context("Failed to login to docker registry")?;
+    }
+
+    let mut logs = Vec::new();
+
+    for image_name in build.get_image_names() {
+      let mut command =
+        String::from("docker buildx imagetools create");
+      for tag in build.get_image_tags(
+        std::slice::from_ref(&image_name),
+        commit_hash.as_deref(),
+        &[],
+      ) {
+        command.push_str(" -t ");
+        command.push_str(&tag);
+      }
+      for platforms in &platform_groups {
+        command.push(' ');
+        command.push_str(&image_name);
+        command.push(':');
+        command.push_str(&build.get_platform_image_tag(platforms));
+      }
+      let log = run_komodo_standard_command(
+        "Push Manifest List",
+        command,
+        CommandOptions::default(),
+      )
+      .await;
+      let success = log.success;
+      logs.push(log);
+      if !success {
+        break;
+      }
+    }
+
+    Ok(logs)
+  }
+}
+
+//
+
 impl Resolve<crate::api::Args> for PruneBuilders {
   #[instrument(
     "PruneBuilders",
@@ -454,3 +624,67 @@ impl Resolve<crate::api::Args> for PruneBuildx {
     )
   }
 }
+
+//
+
+impl Resolve<crate::api::Args> for PruneBuildCache {
+  #[instrument(
+    "PruneBuildCache",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<PruneBuildCacheResponse> {
+    let mut res = Vec::new();
+    for builder in list_buildx_builders().await? {
+      let log = run_komodo_standard_command(
+        &format!("Prune Build Cache | {builder}"),
+        format!("docker buildx prune --builder {builder} -a -f"),
+        CommandOptions::default(),
+      )
+      .await;
+      res.push(BuilderCachePrune {
+        reclaimed: parse_reclaimed_space(&log.stdout),
+        builder,
+        log,
+      });
+    }
+    Ok(res)
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BuildxBuilder {
+  name: String,
+}
+
+/// Lists the buildx builder names, using
+/// `docker buildx ls --format json` (buildx v0.14+).
+async fn list_buildx_builders() -> anyhow::Result<Vec<String>> {
+  let output = run_standard_command(
+    "docker buildx ls --format json",
+    CommandOptions::default(),
+  )
+  .await;
+  if !output.success() {
+    return Err(anyhow!("{}", output.stderr.replace('\n', " | ")))
+      .context("Failed to list buildx builders");
+  }
+  let mut builders = Vec::<String>::new();
+  for line in output.stdout.lines().filter(|line| !line.is_empty()) {
+    let builder = serde_json::from_str::<BuildxBuilder>(line)
+      .with_context(|| {
+        format!("Failed to parse buildx builder {line}")
+      })?;
+    if !builders.contains(&builder.name) {
+      builders.push(builder.name);
+    }
+  }
+  Ok(builders)
+}
@@ -0,0 +1,184 @@
+use anyhow::{Context, anyhow};
+use command::{CommandOptions, run_standard_command};
+use komodo_client::entities::{
+  komodo_timestamp,
+  sbom::{SbomFormat, SbomPackage, SbomReport},
+  update::Log,
+};
+use mogh_resolver::Resolve;
+use periphery_client::api::build::{
+  GenerateSbom, GenerateSbomResponse,
+};
+use serde::Deserialize;
+use shell_escape::unix::escape;
+
+use crate::state::docker_client;
+
+impl Resolve<crate::api::Args> for GenerateSbom {
+  #[instrument(
+    "GenerateSbom",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      image = self.image,
+      format = self.format.as_ref(),
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<GenerateSbomResponse> {
+    let GenerateSbom { image, format } = self;
+    let output_format = match format {
+      SbomFormat::None => {
+        return Err(anyhow!("Must specify an SBOM format"));
+      }
+      SbomFormat::Spdx => "spdx-json",
+      SbomFormat::CycloneDx => "cyclonedx-json",
+    };
+    let start_ts = komodo_timestamp();
+    let command = format!(
+      "syft {} --quiet --output {output_format}",
+      escape(image.as_str().into())
+    );
+    let output =
+      run_standard_command(&command, CommandOptions::default()).await;
+
+    let packages = if output.success() {
+      match format {
+        SbomFormat::Spdx => parse_spdx(&output.stdout),
+        SbomFormat::CycloneDx => parse_cyclonedx(&output.stdout),
+        SbomFormat::None => unreachable!(),
+      }
+    } else {
+      Err(anyhow!("{}", output.stderr.trim())).context(
+        "Failed to generate SBOM. Ensure 'syft' is installed on the builder.",
+      )
+    };
+
+    let mut packages = match packages {
+      Ok(packages) => packages,
+      Err(e) => {
+        return Ok(GenerateSbomResponse {
+          log: Log {
+            stage: String::from("Generate SBOM"),
+            command,
+            stderr: format!("{e:#}"),
+            success: false,
+            start_ts,
+            end_ts: komodo_timestamp(),
+            ..Default::default()
+          },
+          sbom: None,
+        });
+      }
+    };
+    packages.sort_by(|a, b| {
+      a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version))
+    });
+
+    Ok(GenerateSbomResponse {
+      log: Log {
+        stage: String::from("Generate SBOM"),
+        command,
+        stdout: format!(
+          "Generated {format} SBOM listing {} packages for {image}",
+          packages.len()
+        ),
+        success: true,
+        start_ts,
+        end_ts: komodo_timestamp(),
+        ..Default::default()
+      },
+      sbom: Some(SbomReport {
+        format,
+        digest: image_digest(&image).await.unwrap_or_default(),
+        packages,
+        document: output.stdout,
+      }),
+    })
+  }
+}
+
+/// Gets the image repo digest from the local docker daemon.
+/// This is only available if the image was pushed.
+async fn image_digest(image: &str) -> Option<String> {
+  let client = docker_client().load();
+  let client = client.iter().next()?;
+  client
+    .inspect_image(image)
+    .await
+    .ok()?
+    .repo_digests?
+    .into_iter()
+    .next()
+    .and_then(|digest| {
+      digest.split_once('@').map(|(_, digest)| digest.to_string())
+    })
+}
+
+// ====
+// SPDX
+// ====
+
+#[derive(Deserialize)]
+struct SpdxDocument {
+  #[serde(default)]
+  packages: Vec<SpdxPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+  name: String,
+  #[serde(default)]
+  version_info: String,
+  #[serde(default)]
+  external_refs: Vec<SpdxExternalRef>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxExternalRef {
+  reference_type: String,
+  reference_locator: String,
+}
+
+fn parse_spdx(document: &str) -> anyhow::Result<Vec<SbomPackage>> {
+  let document = serde_json::from_str::<SpdxDocument>(document)
+    .context("Failed to parse SPDX json document")?;
+  let packages = document
+    .packages
+    .into_iter()
+    .map(|package| SbomPackage {
+      purl: package
+        .external_refs
+        .into_iter()
+        .find(|r| r.reference_type == "purl")
+        .map(|r| r.reference_locator)
+        .unwrap_or_default(),
+      name: package.name,
+      version: package.version_info,
+    })
+    .collect();
+  Ok(packages)
+}
+
+// =========
+// CYCLONEDX
+// =========
+
+#[derive(Deserialize)]
+struct CycloneDxDocument {
+  #[serde(default)]
+  components: Vec<SbomPackage>,
+}
+
+fn parse_cyclonedx(
+  document: &str,
+) -> anyhow::Result<Vec<SbomPackage>> {
+  let document = serde_json::from_str::<CycloneDxDocument>(document)
+    .context("Failed to parse CycloneDX json document")?;
+  Ok(document.components)
+}
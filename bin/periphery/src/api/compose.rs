@@ -1,5 +1,8 @@
 use std::{
-  borrow::Cow, fmt::Write, path::PathBuf, sync::LazyLock,
+  borrow::Cow,
+  fmt::Write,
+  path::{Path, PathBuf},
+  sync::LazyLock,
   time::Duration,
 };
 
@@ -28,7 +31,10 @@ use tracing::Instrument;
 
 use crate::{
   config::periphery_config,
-  docker::compose::{docker_compose, parse_compose_services},
+  docker::{
+    compose::{docker_compose, parse_compose_services},
+    signature::{VERIFIED_IMAGES_FILE, verify_stack_images},
+  },
   helpers::{format_extra_args, format_log_grep},
   stack::{
     maybe_login_registry, pull_or_clone_stack, validate_files,
@@ -425,6 +431,107 @@ impl Resolve<crate::api::Args> for ComposePull {
 
 //
 
+impl Resolve<crate::api::Args> for ComposeConfig {
+  #[instrument(
+    "ComposeConfig",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      stack = &self.stack.name,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<DeployStackResponse> {
+    let ComposeConfig {
+      mut stack,
+      repo,
+      git_token,
+      mut replacers,
+    } = self;
+
+    let mut res = DeployStackResponse::default();
+
+    let mut interpolator =
+      Interpolator::new(None, &periphery_config().secrets);
+    // Only interpolate Stack. Repo interpolation will be handled
+    // by the CloneRepo / PullOrCloneRepo call.
+    interpolator
+      .interpolate_stack(&mut stack)?
+      .push_logs(&mut res.logs);
+    replacers.extend(interpolator.secret_replacers);
+
+    let (run_directory, env_file_path) = match write_stack(
+      &stack,
+      repo.as_ref(),
+      git_token,
+      replacers.clone(),
+      &mut res,
+      args,
+    )
+    .await
+    {
+      Ok(res) => res,
+      Err(e) => {
+        res
+          .logs
+          .push(Log::error("Write Stack", format_serror(&e.into())));
+        return Ok(res);
+      }
+    };
+
+    // Canonicalize the path to ensure it exists, and is the cleanest path to the run directory.
+    let run_directory = run_directory.canonicalize().with_context(||
+      format!("Failed to validate run directory on host after stack write (canonicalize error), path={}", run_directory.to_string_lossy()),
+    )?;
+
+    validate_files(&stack, &run_directory, &mut res).await;
+    if !all_logs_success(&res.logs) {
+      return Ok(res);
+    }
+
+    let docker_compose = docker_compose();
+    let file_args = stack.compose_file_paths().join(" -f ");
+    let env_file_args = env_file_args(
+      env_file_path,
+      &stack.config.additional_env_files,
+    )?;
+    let project_name = stack.project_name(true);
+
+    let compose_cmd_wrapper =
+      parse_multiline_command(&stack.config.compose_cmd_wrapper);
+    // If wrapper_include is empty but wrapper is set, use default ["up"] for backward compatibility
+    let default_include = vec![String::from("up")];
+    let wrapper_include =
+      if stack.config.compose_cmd_wrapper_include.is_empty()
+        && !compose_cmd_wrapper.is_empty()
+      {
+        &default_include
+      } else {
+        &stack.config.compose_cmd_wrapper_include
+      };
+
+    compose_config(
+      format!(
+        "{docker_compose} -p {project_name} -f {file_args}{env_file_args} config",
+      ),
+      &compose_cmd_wrapper,
+      wrapper_include,
+      &run_directory,
+      &project_name,
+      &replacers,
+      &mut res,
+    )
+    .await;
+
+    Ok(res)
+  }
+}
+
+//
+
 impl Resolve<crate::api::Args> for ComposeUp {
   #[instrument(
     "ComposeUp",
@@ -558,71 +665,20 @@ impl Resolve<crate::api::Args> for ComposeUp {
 
     // Uses 'docker compose config' command to extract services (including image)
     // after performing interpolation
-    {
-      let command = format!(
+    if !compose_config(
+      format!(
         "{docker_compose} -p {project_name} -f {file_args}{env_file_args} config",
-      );
-      let (command, wrapped) = match maybe_wrap_command(
-        command,
-        &compose_cmd_wrapper,
-        wrapper_include,
-        "config",
-      ) {
-        Ok(result) => result,
-        Err(log) => {
-          res.logs.push(log);
-          return Ok(res);
-        }
-      };
-      let span = info_span!("GetComposeConfig", command);
-      let mut config_log = if wrapped {
-        run_komodo_shell_command(
-          "Compose Config",
-          command,
-          CommandOptions::default().path(run_directory.as_path()),
-        )
-        .instrument(span)
-        .await
-      } else {
-        run_komodo_standard_command(
-          "Compose Config",
-          command,
-          CommandOptions::default().path(run_directory.as_path()),
-        )
-        .instrument(span)
-        .await
-      };
-
-      if !config_log.success {
-        config_log.sanitize(&replacers);
-        res.logs.push(config_log);
-        return Ok(res);
-      }
-
-      // attach sanitized merged config in any case.
-      res.merged_config =
-        svi::replace_in_string(&config_log.stdout, &replacers).into();
-
-      if let Err(e) = parse_compose_services(
-        &config_log.stdout,
-        &project_name,
-        &mut res.services,
-      ) {
-        config_log.sanitize(&replacers);
-        res.logs.push(config_log);
-        res.logs.push(Log::error(
-          "Parse Compose Services",
-          format_serror(&e.into()),
-        ));
-        // early return with error log
-        // including sanitized config log
-        // and parse error for clear view
-        // of what the issue might be.
-        return Ok(res);
-      }
-
-      config_log.sanitize(&replacers);
-      res.logs.push(config_log);
+      ),
+      &compose_cmd_wrapper,
+      wrapper_include,
+      &run_directory,
+      &project_name,
+      &replacers,
+      &mut res,
+    )
+    .await
+    {
+      return Ok(res);
     }
 
     if stack.config.run_build {
@@ -710,6 +766,26 @@ impl Resolve<crate::api::Args> for ComposeUp {
       }
     }
 
+    // Verify image signatures before taking anything down.
+    // The services are then pinned to the verified digests.
+    let mut file_args = file_args;
+    if !stack.config.signature_keys.is_empty() {
+      let pinned = verify_stack_images(
+        &res.services,
+        &services,
+        &stack.config.signature_keys,
+        &run_directory,
+        &mut res.logs,
+      )
+      .await;
+      if !all_logs_success(&res.logs) {
+        return Ok(res);
+      }
+      if pinned {
+        file_args = format!("{file_args} -f {VERIFIED_IMAGES_FILE}");
+      }
+    }
+
     if stack.config.destroy_before_deploy
       // Also check if project name changed, which also requires taking down.
       || last_project_name != project_name
@@ -1057,6 +1133,83 @@ fn env_file_args(
   Ok(res)
 }
 
+/// Runs `docker compose config` to validate the compose files,
+/// and extract the services (including image) after interpolation.
+/// Attaches the sanitized merged config and services to the response.
+/// Returns whether the config succeeded.
+async fn compose_config(
+  command: String,
+  compose_cmd_wrapper: &str,
+  wrapper_include: &[String],
+  run_directory: &Path,
+  project_name: &str,
+  replacers: &[(String, String)],
+  res: &mut DeployStackResponse,
+) -> bool {
+  let (command, wrapped) = match maybe_wrap_command(
+    command,
+    compose_cmd_wrapper,
+    wrapper_include,
+    "config",
+  ) {
+    Ok(result) => result,
+    Err(log) => {
+      res.logs.push(log);
+      return false;
+    }
+  };
+  let span = info_span!("GetComposeConfig", command);
+  let mut config_log = if wrapped {
+    run_komodo_shell_command(
+      "Compose Config",
+      command,
+      CommandOptions::default().path(run_directory),
+    )
+    .instrument(span)
+    .await
+  } else {
+    run_komodo_standard_command(
+      "Compose Config",
+      command,
+      CommandOptions::default().path(run_directory),
+    )
+    .instrument(span)
+    .await
+  };
+
+  if !config_log.success {
+    config_log.sanitize(replacers);
+    res.logs.push(config_log);
+    return false;
+  }
+
+  // attach sanitized merged config in any case.
+  res.merged_config =
+    svi::replace_in_string(&config_log.stdout, replacers).into();
+
+  if let Err(e) = parse_compose_services(
+    &config_log.stdout,
+    project_name,
+    &mut res.services,
+  ) {
+    config_log.sanitize(replacers);
+    res.logs.push(config_log);
+    res.logs.push(Log::error(
+      "Parse Compose Services",
+      format_serror(&e.into()),
+    ));
+    // early return with error log
+    // including sanitized config log
+    // and parse error for clear view
+    // of what the issue might be.
+    return false;
+  }
+
+  config_log.sanitize(replacers);
+  res.logs.push(config_log);
+  true
+}
+
 /// Apply compose_cmd_wrapper to command if the subcommand is in wrapper_include list.
 /// Returns Ok((command, wrapped)) where `wrapped` indicates if wrapper was applied.
 /// Returns Err(Log) if wrapper is invalid (missing placeholder).
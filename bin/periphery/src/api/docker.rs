@@ -17,7 +17,13 @@ use mogh_resolver::Resolve;
 use periphery_client::api::docker::*;
 
 use crate::{
-  docker::{docker_login, image::get_image_digest_from_registry},
+  config::periphery_config,
+  docker::{
+    docker_login,
+    image::get_image_digest_from_registry,
+    scan::scan_image,
+    signature::{sign_image, verify_image_digest},
+  },
   state::docker_client,
 };
 
@@ -80,6 +86,102 @@ impl Resolve<crate::api::Args> for GetLatestImageDigest {
 
 //
 
+impl Resolve<crate::api::Args> for ScanImage {
+  #[instrument(
+    "ScanImage",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      image = self.name,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<ScanImageResponse> {
+    Ok(scan_image(&self.name).await)
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for SignImage {
+  #[instrument(
+    "SignImage",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      image = self.image,
+      key = self.key_name,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<Log> {
+    let SignImage {
+      image,
+      key_name,
+      key,
+      password_name,
+      password,
+    } = self;
+    let secrets = &periphery_config().secrets;
+    let key = match key {
+      Some(key) => key,
+      None => secrets.get(&key_name).cloned().with_context(|| {
+        format!("Did not find signing key '{key_name}' in Core or Periphery secrets")
+      })?,
+    };
+    let password = match password {
+      Some(password) => Some(password),
+      None if password_name.is_empty() => None,
+      None => Some(secrets.get(&password_name).cloned().with_context(|| {
+        format!("Did not find signing key password '{password_name}' in Core or Periphery secrets")
+      })?),
+    };
+    sign_image(&image, &key, password.as_deref()).await
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for VerifyImageSignature {
+  #[instrument(
+    "VerifyImageSignature",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      image = self.image,
+      account = self.account,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<VerifyImageSignatureResponse> {
+    let VerifyImageSignature {
+      image,
+      keys,
+      account,
+      token,
+    } = self;
+    docker_login(
+      &extract_registry_domain(&image)?,
+      account.as_deref().unwrap_or_default(),
+      token.as_deref(),
+    )
+    .await?;
+    let (log, image) = verify_image_digest(&image, &keys).await;
+    Ok(VerifyImageSignatureResponse { log, image })
+  }
+}
+
+//
+
 /// Wait this long after a pull to allow another pull through
 const PULL_TIMEOUT: i64 = 5_000;
 
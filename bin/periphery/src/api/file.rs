@@ -0,0 +1,493 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, anyhow};
+use command::{
+  CommandOptions, run_komodo_standard_command, run_standard_command,
+};
+use data_encoding::BASE64;
+use komodo_client::entities::{
+  file::{FileDownload, FileEntry, FileEntryKind},
+  update::Log,
+};
+use mogh_resolver::Resolve;
+use periphery_client::api::file::*;
+use shell_escape::unix::escape;
+use uuid::Uuid;
+
+use crate::{config::periphery_config, state::docker_client};
+
+impl Resolve<crate::api::Args> for ListContainerDirectory {
+  async fn resolve(
+    self,
+    _: &crate::api::Args,
+  ) -> anyhow::Result<Vec<FileEntry>> {
+    check_container_file_transfers_enabled()?;
+    let path = container_path(&self.path)?;
+    let command = format!(
+      "docker exec {} find {} -mindepth 1 -maxdepth 1 -exec stat -c '%F|%s|%Y|%n' {{}} +",
+      escape(self.container.into()),
+      escape(path.into()),
+    );
+    let output =
+      run_standard_command(&command, CommandOptions::default()).await;
+    if !output.success() {
+      return Err(anyhow!("{}", output.stderr.trim())).context(
+        "Failed to list container directory. The container must have 'find' and 'stat' available.",
+      );
+    }
+    let mut entries = output
+      .stdout
+      .lines()
+      .filter_map(parse_stat_line)
+      .collect::<Vec<_>>();
+    sort_entries(&mut entries);
+    Ok(entries)
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for ReadContainerFile {
+  #[instrument(
+    "ReadContainerFile",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      container = self.container,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<FileDownload> {
+    check_container_file_transfers_enabled()?;
+    let path = container_path(&self.path)?;
+
+    // Check the type and size before copying anything
+    // out of the container, as `docker cp` copies whole directories.
+    let stat = {
+      let client = docker_client().load();
+      let client = client
+        .iter()
+        .next()
+        .context("Could not connect to docker client")?;
+      client.container_path_stat(&self.container, &path).await?
+    };
+    if stat.file_mode & GO_MODE_TYPE != 0 {
+      return Err(anyhow!("{path} is not a file"));
+    }
+    check_size(stat.size.max(0) as u64, self.max_size)?;
+
+    let transfer_dir = TransferDir::create().await?;
+    let target = transfer_dir.path.join("file");
+    let command = format!(
+      "docker cp {}:{} {}",
+      escape(self.container.as_str().into()),
+      escape(path.as_str().into()),
+      escape(target.to_string_lossy()),
+    );
+    let output =
+      run_standard_command(&command, CommandOptions::default()).await;
+    if !output.success() {
+      return Err(anyhow!("{}", output.stderr.trim()))
+        .context("Failed to copy file out of container");
+    }
+
+    read_file(&target, path, self.max_size).await
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for WriteContainerFile {
+  #[instrument(
+    "WriteContainerFile",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      container = self.container,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<Log> {
+    check_container_file_transfers_enabled()?;
+    let path = container_path(&self.path)?;
+    let contents = BASE64
+      .decode(self.contents.as_bytes())
+      .context("File contents are not valid base64")?;
+
+    let transfer_dir = TransferDir::create().await?;
+    let source = transfer_dir.path.join("file");
+    tokio::fs::write(&source, contents)
+      .await
+      .context("Failed to write file to transfer directory")?;
+
+    let command = format!(
+      "docker cp {} {}:{}",
+      escape(source.to_string_lossy()),
+      escape(self.container.into()),
+      escape(path.into()),
+    );
+    Ok(
+      run_komodo_standard_command(
+        "Copy File To Container",
+        command,
+        CommandOptions::default(),
+      )
+      .await,
+    )
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for ListHostDirectory {
+  async fn resolve(
+    self,
+    _: &crate::api::Args,
+  ) -> anyhow::Result<Vec<FileEntry>> {
+    check_host_file_transfers_enabled()?;
+    let path = host_path(&self.path).await?;
+    let mut dir =
+      tokio::fs::read_dir(&path).await.with_context(|| {
+        format!("Failed to read directory {path:?}")
+      })?;
+    let mut entries = Vec::new();
+    while let Some(entry) = dir
+      .next_entry()
+      .await
+      .context("Failed to read directory entry")?
+    {
+      let Ok(metadata) = entry.metadata().await else {
+        continue;
+      };
+      let file_type = metadata.file_type();
+      let kind = if file_type.is_symlink() {
+        FileEntryKind::Symlink
+      } else if file_type.is_dir() {
+        FileEntryKind::Directory
+      } else if file_type.is_file() {
+        FileEntryKind::File
+      } else {
+        FileEntryKind::Other
+      };
+      let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| {
+          modified.duration_since(std::time::UNIX_EPOCH).ok()
+        })
+        .map(|modified| modified.as_millis() as i64)
+        .unwrap_or_default();
+      entries.push(FileEntry {
+        name: entry.file_name().to_string_lossy().to_string(),
+        path: entry.path().to_string_lossy().to_string(),
+        kind,
+        size: metadata.len(),
+        modified,
+      });
+    }
+    sort_entries(&mut entries);
+    Ok(entries)
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for ReadHostFile {
+  #[instrument(
+    "ReadHostFile",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<FileDownload> {
+    check_host_file_transfers_enabled()?;
+    let path = host_path(&self.path).await?;
+    let display = path.to_string_lossy().to_string();
+    read_file(&path, display, self.max_size).await
+  }
+}
+
+//
+
+impl Resolve<crate::api::Args> for WriteHostFile {
+  #[instrument(
+    "WriteHostFile",
+    skip_all,
+    fields(
+      id = args.id.to_string(),
+      core = args.core,
+      path = self.path,
+    )
+  )]
+  async fn resolve(
+    self,
+    args: &crate::api::Args,
+  ) -> anyhow::Result<Log> {
+    check_host_file_transfers_enabled()?;
+    let path = host_write_path(&self.path).await?;
+    let contents = BASE64
+      .decode(self.contents.as_bytes())
+      .context("File contents are not valid base64")?;
+    let size = contents.len();
+    tokio::fs::write(&path, contents)
+      .await
+      .with_context(|| format!("Failed to write file to {path:?}"))?;
+    Ok(Log::simple(
+      "Write File To Host",
+      format!("Wrote {size} bytes to {path:?}"),
+    ))
+  }
+}
+
+//
+
+/// The file type bits of a Go `os.FileMode`, as returned by the
+/// docker archive stat. None are set for regular files.
+const GO_MODE_TYPE: u32 = (1 << 31) // Dir
+  | (1 << 27) // Symlink
+  | (1 << 26) // Device
+  | (1 << 25) // Named pipe
+  | (1 << 24) // Socket
+  | (1 << 21) // Char device
+  | (1 << 19); // Irregular
+
+/// Host file transfers give the same access as a host terminal,
+/// so are also disabled along with the terminals.
+fn check_host_file_transfers_enabled() -> anyhow::Result<()> {
+  let config = periphery_config();
+  if config.disable_file_transfers {
+    Err(anyhow!(
+      "File transfers are disabled in the Periphery config"
+    ))
+  } else if config.disable_terminals {
+    Err(anyhow!(
+      "Host file transfers are disabled along with Terminals in the Periphery config"
+    ))
+  } else {
+    Ok(())
+  }
+}
+
+/// Container file transfers give the same access as container exec,
+/// so are also disabled along with the container terminals.
+fn check_container_file_transfers_enabled() -> anyhow::Result<()> {
+  let config = periphery_config();
+  if config.disable_file_transfers {
+    Err(anyhow!(
+      "File transfers are disabled in the Periphery config"
+    ))
+  } else if config.disable_container_terminals {
+    Err(anyhow!(
+      "Container file transfers are disabled along with Container Terminals in the Periphery config"
+    ))
+  } else {
+    Ok(())
+  }
+}
+
+fn check_size(size: u64, max_size: u64) -> anyhow::Result<()> {
+  if size > max_size {
+    Err(anyhow!(
+      "File is {size} bytes, which is over the maximum of {max_size} bytes"
+    ))
+  } else {
+    Ok(())
+  }
+}
+
+async fn read_file(
+  file: &Path,
+  path: String,
+  max_size: u64,
+) -> anyhow::Result<FileDownload> {
+  let metadata = tokio::fs::metadata(file)
+    .await
+    .with_context(|| format!("Failed to read metadata for {path}"))?;
+  if !metadata.is_file() {
+    return Err(anyhow!("{path} is not a file"));
+  }
+  check_size(metadata.len(), max_size)?;
+  let contents = tokio::fs::read(file)
+    .await
+    .with_context(|| format!("Failed to read file {path}"))?;
+  Ok(FileDownload {
+    path,
+    size: contents.len() as u64,
+    contents: BASE64.encode(&contents),
+  })
+}
+
+/// Container paths must be absolute, and are normalized
+/// to not contain any `..` components.
+fn container_path(path: &str) -> anyhow::Result<String> {
+  let path = Path::new(path);
+  if !path.is_absolute() {
+    return Err(anyhow!("Container path must be absolute"));
+  }
+  if path.components().any(|c| c == Component::ParentDir) {
+    return Err(anyhow!("Container path cannot contain '..'"));
+  }
+  Ok(
+    path
+      .components()
+      .collect::<PathBuf>()
+      .to_string_lossy()
+      .to_string(),
+  )
+}
+
+/// Resolves an existing path on the host,
+/// ensuring it is inside `root_directory` after following symlinks.
+async fn host_path(path: &str) -> anyhow::Result<PathBuf> {
+  let (root, path) = join_root(path).await?;
+  let path = tokio::fs::canonicalize(&path)
+    .await
+    .with_context(|| format!("Path {path:?} does not exist"))?;
+  if !path.starts_with(&root) {
+    return Err(anyhow!("Path must be inside {root:?}"));
+  }
+  Ok(path)
+}
+
+/// Resolves a file path to write on the host, creating the parent directory.
+/// The existing part of the parent is checked to be inside `root_directory`
+/// after following symlinks before creating anything, and an existing
+/// symlink at the file path is refused rather than followed.
+async fn host_write_path(path: &str) -> anyhow::Result<PathBuf> {
+  let (root, path) = join_root(path).await?;
+  let (Some(parent), Some(file_name)) =
+    (path.parent(), path.file_name())
+  else {
+    return Err(anyhow!("Path {path:?} is not a file path"));
+  };
+  // The deepest existing ancestor, the root at least exists.
+  let mut existing = None;
+  for ancestor in parent.ancestors() {
+    if let Ok(canonical) = tokio::fs::canonicalize(ancestor).await {
+      existing = Some((ancestor, canonical));
+      break;
+    }
+  }
+  let Some((ancestor, canonical)) = existing else {
+    return Err(anyhow!("Failed to resolve directory {parent:?}"));
+  };
+  if !canonical.starts_with(&root) {
+    return Err(anyhow!("Path must be inside {root:?}"));
+  }
+  // Components are checked to not contain '..' by join_root.
+  let parent = canonical.join(
+    parent
+      .strip_prefix(ancestor)
+      .context("Failed to resolve directory")?,
+  );
+  tokio::fs::create_dir_all(&parent).await.with_context(|| {
+    format!("Failed to create directory {parent:?}")
+  })?;
+  // Check again in case the directories were swapped for symlinks.
+  let parent =
+    tokio::fs::canonicalize(&parent).await.with_context(|| {
+      format!("Failed to resolve directory {parent:?}")
+    })?;
+  if !parent.starts_with(&root) {
+    return Err(anyhow!("Path must be inside {root:?}"));
+  }
+  let path = parent.join(file_name);
+  if tokio::fs::symlink_metadata(&path)
+    .await
+    .is_ok_and(|metadata| metadata.file_type().is_symlink())
+  {
+    return Err(anyhow!("Path {path:?} is a symlink"));
+  }
+  Ok(path)
+}
+
+/// Returns the canonical root directory,
+/// and the path joined onto it.
+async fn join_root(path: &str) -> anyhow::Result<(PathBuf, PathBuf)> {
+  let root = &periphery_config().root_directory;
+  let root =
+    tokio::fs::canonicalize(root).await.with_context(|| {
+      format!("Failed to resolve root directory {root:?}")
+    })?;
+  let path = Path::new(path);
+  if path.components().any(|c| c == Component::ParentDir) {
+    return Err(anyhow!("Path cannot contain '..'"));
+  }
+  let path = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    root.join(path)
+  };
+  Ok((root, path.components().collect()))
+}
+
+/// Parses a line output by `stat -c '%F|%s|%Y|%n'`
+fn parse_stat_line(line: &str) -> Option<FileEntry> {
+  let mut split = line.splitn(4, '|');
+  let kind = FileEntryKind::from_stat(split.next()?);
+  let size = split.next()?.parse().ok()?;
+  let modified = split.next()?.parse::<i64>().ok()? * 1000;
+  let path = split.next()?.to_string();
+  let name =
+    Path::new(&path).file_name()?.to_string_lossy().to_string();
+  Some(FileEntry {
+    name,
+    path,
+    kind,
+    size,
+    modified,
+  })
+}
+
+/// Directories first, then by name.
+fn sort_entries(entries: &mut [FileEntry]) {
+  entries.sort_by(|a, b| {
+    (b.kind == FileEntryKind::Directory)
+      .cmp(&(a.kind == FileEntryKind::Directory))
+      .then_with(|| a.name.cmp(&b.name))
+  });
+}
+
+/// A temporary directory used to stage files copied
+/// to / from containers. Removed on drop.
+struct TransferDir {
+  path: PathBuf,
+}
+
+impl TransferDir {
+  async fn create() -> anyhow::Result<TransferDir> {
+    let path = periphery_config()
+      .root_directory
+      .join(".transfer")
+      .join(Uuid::new_v4().to_string());
+    tokio::fs::create_dir_all(&path).await.with_context(|| {
+      format!("Failed to create transfer directory {path:?}")
+    })?;
+    Ok(TransferDir { path })
+  }
+}
+
+impl Drop for TransferDir {
+  fn drop(&mut self) {
+    if let Err(e) = std::fs::remove_dir_all(&self.path) {
+      warn!(
+        "Failed to clean up transfer directory {:?} | {e:?}",
+        self.path
+      );
+    }
+  }
+}
@@ -7,8 +7,8 @@ use komodo_client::entities::{
 };
 use mogh_resolver::Resolve;
 use periphery_client::api::{
-  build::*, compose::*, container::*, docker::*, git::*, keys::*,
-  poll::*, stats::*, swarm::*, terminal::*, *,
+  action::*, build::*, compose::*, container::*, docker::*, file::*,
+  git::*, keys::*, poll::*, stats::*, swarm::*, terminal::*, *,
 };
 use serde::{Deserialize, Serialize};
 use strum::EnumDiscriminants;
@@ -18,10 +18,12 @@ use crate::{config::periphery_config, state::stats_client};
 
 pub mod terminal;
 
+mod action;
 mod build;
 mod compose;
 mod container;
 mod docker;
+mod file;
 mod git;
 mod keys;
 mod poll;
@@ -69,8 +71,17 @@ pub enum PeripheryRequest {
   WriteDockerfileContentsToHost(WriteDockerfileContentsToHost),
   Build(Build),
   CancelBuild(CancelBuild),
+  PushImage(PushImage),
+  PushManifestList(PushManifestList),
+  GenerateSbom(GenerateSbom),
   PruneBuilders(PruneBuilders),
   PruneBuildx(PruneBuildx),
+  PruneBuildCache(PruneBuildCache),
+
+  // Action
+  RunAction(RunAction),
+  GetActionRun(GetActionRun),
+  CancelAction(CancelAction),
 
   // Compose (Read)
   GetComposeContentsOnHost(GetComposeContentsOnHost),
@@ -81,6 +92,7 @@ pub enum PeripheryRequest {
   WriteComposeContentsToHost(WriteComposeContentsToHost),
   WriteCommitComposeContents(WriteCommitComposeContents),
   ComposePull(ComposePull),
+  ComposeConfig(ComposeConfig),
   ComposeUp(ComposeUp),
   ComposeExecution(ComposeExecution),
   ComposeRun(ComposeRun),
@@ -121,9 +133,12 @@ pub enum PeripheryRequest {
   InspectImage(InspectImage),
   ImageHistory(ImageHistory),
   GetLatestImageDigest(GetLatestImageDigest),
+  ScanImage(ScanImage),
 
   // Image (Write)
   PullImage(PullImage),
+  SignImage(SignImage),
+  VerifyImageSignature(VerifyImageSignature),
   DeleteImage(DeleteImage),
   PruneImages(PruneImages),
 
@@ -164,6 +179,16 @@ pub enum PeripheryRequest {
   RotateSwarmSecret(RotateSwarmSecret),
   RemoveSwarmSecrets(RemoveSwarmSecrets),
 
+  // Files (Read)
+  ListContainerDirectory(ListContainerDirectory),
+  ReadContainerFile(ReadContainerFile),
+  ListHostDirectory(ListHostDirectory),
+  ReadHostFile(ReadHostFile),
+
+  // Files (Write)
+  WriteContainerFile(WriteContainerFile),
+  WriteHostFile(WriteHostFile),
+
   // Terminal
   ListTerminals(ListTerminals),
   CreateServerTerminal(CreateServerTerminal),
@@ -28,6 +28,7 @@ use tracing::Instrument as _;
 
 use crate::{
   config::periphery_config,
+  docker::signature::{VERIFIED_IMAGES_FILE, verify_stack_images},
   helpers::push_extra_args,
   stack::{maybe_login_registry, validate_files, write::write_stack},
   state::docker_client,
@@ -287,6 +288,26 @@ impl Resolve<crate::api::Args> for DeploySwarmStack {
       }
     }
 
+    // Verify image signatures before taking anything down.
+    // The services are then pinned to the verified digests.
+    let mut file_args = file_args;
+    if !stack.config.signature_keys.is_empty() {
+      let pinned = verify_stack_images(
+        &res.services,
+        &[],
+        &stack.config.signature_keys,
+        &run_directory,
+        &mut res.logs,
+      )
+      .await;
+      if !all_logs_success(&res.logs) {
+        return Ok(res);
+      }
+      if pinned {
+        file_args = format!("{file_args} -c {VERIFIED_IMAGES_FILE}");
+      }
+    }
+
     if stack.config.destroy_before_deploy
       // Also check if project name changed, which also requires taking down.
       || last_project_name != project_name
@@ -117,6 +117,12 @@ pub fn periphery_config() -> &'static PeripheryConfig {
       disable_container_terminals: env
         .periphery_disable_container_terminals
         .unwrap_or(config.disable_container_terminals),
+      disable_file_transfers: env
+        .periphery_disable_file_transfers
+        .unwrap_or(config.disable_file_transfers),
+      disable_actions: env
+        .periphery_disable_actions
+        .unwrap_or(config.disable_actions),
       stats_polling_rate: env
         .periphery_stats_polling_rate
         .unwrap_or(config.stats_polling_rate),
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
 use anyhow::Context;
-use bollard::query_parameters::{
-  InspectContainerOptions, ListContainersOptions,
+use bollard::{
+  container::PathStatResponse,
+  query_parameters::{
+    ContainerArchiveInfoOptions, InspectContainerOptions,
+    ListContainersOptions,
+  },
 };
 use komodo_client::entities::docker::{container::*, *};
 
@@ -107,6 +111,27 @@ impl DockerClient {
     Ok(containers)
   }
 
+  /// Stats the path in the container filesystem.
+  /// Unlike `docker exec stat`, this works for containers
+  /// without `stat` available, or which are not running.
+  pub async fn container_path_stat(
+    &self,
+    container_name: &str,
+    path: &str,
+  ) -> anyhow::Result<PathStatResponse> {
+    self
+      .docker
+      .get_container_archive_info(
+        container_name,
+        ContainerArchiveInfoOptions {
+          path: path.to_string(),
+        }
+        .into(),
+      )
+      .await
+      .with_context(|| format!("Failed to stat {path}"))
+  }
+
   pub async fn inspect_container(
     &self,
     container_name: &str,
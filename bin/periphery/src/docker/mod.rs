@@ -13,7 +13,9 @@ use shell_escape::unix::escape;
 pub mod compose;
 pub mod config;
 pub mod image;
+pub mod scan;
 pub mod secret;
+pub mod signature;
 pub mod stack;
 pub mod stats;
 
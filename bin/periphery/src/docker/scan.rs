@@ -0,0 +1,287 @@
+use anyhow::{Context, anyhow};
+use command::{CommandOptions, run_standard_command};
+use komodo_client::entities::{
+  komodo_timestamp,
+  scan::{
+    ImageScanReport, ImageScanner, Vulnerability,
+    VulnerabilityCounts, VulnerabilitySeverity,
+  },
+  update::Log,
+};
+use periphery_client::api::docker::ScanImageResponse;
+use serde::Deserialize;
+use shell_escape::unix::escape;
+
+/// Scans the image with the first scanner found on the host.
+/// The JSON output is parsed into the report,
+/// while the log only contains the summary.
+pub async fn scan_image(image: &str) -> ScanImageResponse {
+  let start_ts = komodo_timestamp();
+  let Some(scanner) = find_scanner().await else {
+    return ScanImageResponse {
+      log: Log::error(
+        "Image Scan",
+        String::from(
+          "No image scanner found on the host. Install 'trivy' or 'grype' to enable image scanning.",
+        ),
+      ),
+      report: None,
+    };
+  };
+
+  let command = match scanner {
+    ImageScanner::Trivy => format!(
+      "trivy image --quiet --scanners vuln --format json {}",
+      escape(image.into())
+    ),
+    ImageScanner::Grype => {
+      format!("grype {} --quiet --output json", escape(image.into()))
+    }
+  };
+
+  let output =
+    run_standard_command(&command, CommandOptions::default()).await;
+
+  let report = if output.success() {
+    match scanner {
+      ImageScanner::Trivy => parse_trivy(&output.stdout),
+      ImageScanner::Grype => parse_grype(&output.stdout),
+    }
+  } else {
+    Err(anyhow!("{}", output.stderr.trim()))
+      .with_context(|| format!("Failed to scan image with {scanner}"))
+  };
+
+  match report {
+    Ok(report) => ScanImageResponse {
+      log: Log {
+        stage: String::from("Image Scan"),
+        command,
+        stdout: format!(
+          "{scanner} found {} vulnerabilities in {image}\n{}",
+          report.counts.total(),
+          report.counts
+        ),
+        success: true,
+        start_ts,
+        end_ts: komodo_timestamp(),
+        ..Default::default()
+      },
+      report: Some(report),
+    },
+    Err(e) => ScanImageResponse {
+      log: Log {
+        stage: String::from("Image Scan"),
+        command,
+        stderr: format!("{e:#}"),
+        success: false,
+        start_ts,
+        end_ts: komodo_timestamp(),
+        ..Default::default()
+      },
+      report: None,
+    },
+  }
+}
+
+async fn find_scanner() -> Option<ImageScanner> {
+  for (scanner, command) in [
+    (ImageScanner::Trivy, "trivy --version"),
+    (ImageScanner::Grype, "grype version"),
+  ] {
+    if run_standard_command(command, CommandOptions::default())
+      .await
+      .success()
+    {
+      return Some(scanner);
+    }
+  }
+  None
+}
+
+fn finish_report(
+  scanner: ImageScanner,
+  digest: String,
+  mut findings: Vec<Vulnerability>,
+) -> ImageScanReport {
+  // The same vulnerability can be reported
+  // multiple times for the same package.
+  findings.sort_by(|a, b| {
+    b.severity
+      .cmp(&a.severity)
+      .then_with(|| a.id.cmp(&b.id))
+      .then_with(|| a.package.cmp(&b.package))
+  });
+  findings.dedup_by(|a, b| a.id == b.id && a.package == b.package);
+  let mut counts = VulnerabilityCounts::default();
+  for finding in &findings {
+    counts.add(finding.severity);
+  }
+  ImageScanReport {
+    scanner,
+    digest,
+    counts,
+    findings,
+  }
+}
+
+/// Extracts the digest from a repo digest, eg `image@sha256:...`
+fn repo_digest(repo_digests: &[String]) -> Option<String> {
+  repo_digests
+    .first()
+    .and_then(|digest| digest.split_once('@'))
+    .map(|(_, digest)| digest.to_string())
+}
+
+fn parse_severity(severity: &str) -> VulnerabilitySeverity {
+  // Grype uses 'Negligible' for the lowest severity
+  if severity.eq_ignore_ascii_case("negligible") {
+    return VulnerabilitySeverity::Low;
+  }
+  severity.parse().unwrap_or_default()
+}
+
+// =====
+// TRIVY
+// =====
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyOutput {
+  #[serde(default)]
+  metadata: TrivyMetadata,
+  #[serde(default)]
+  results: Vec<TrivyResult>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyMetadata {
+  #[serde(default)]
+  repo_digests: Vec<String>,
+  #[serde(default, rename = "ImageID")]
+  image_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyResult {
+  #[serde(default)]
+  vulnerabilities: Vec<TrivyVulnerability>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyVulnerability {
+  #[serde(rename = "VulnerabilityID")]
+  vulnerability_id: String,
+  pkg_name: String,
+  #[serde(default)]
+  installed_version: String,
+  #[serde(default)]
+  fixed_version: String,
+  #[serde(default)]
+  severity: String,
+  #[serde(default)]
+  title: String,
+}
+
+fn parse_trivy(output: &str) -> anyhow::Result<ImageScanReport> {
+  let TrivyOutput { metadata, results } =
+    serde_json::from_str::<TrivyOutput>(output)
+      .context("Failed to parse trivy json output")?;
+  let digest =
+    repo_digest(&metadata.repo_digests).unwrap_or(metadata.image_id);
+  let findings = results
+    .into_iter()
+    .flat_map(|result| result.vulnerabilities)
+    .map(|v| Vulnerability {
+      id: v.vulnerability_id,
+      severity: parse_severity(&v.severity),
+      package: v.pkg_name,
+      installed_version: v.installed_version,
+      fixed_version: v.fixed_version,
+      title: v.title,
+    })
+    .collect();
+  Ok(finish_report(ImageScanner::Trivy, digest, findings))
+}
+
+// =====
+// GRYPE
+// =====
+
+#[derive(Deserialize)]
+struct GrypeOutput {
+  #[serde(default)]
+  matches: Vec<GrypeMatch>,
+  source: Option<GrypeSource>,
+}
+
+#[derive(Deserialize)]
+struct GrypeMatch {
+  vulnerability: GrypeVulnerability,
+  artifact: GrypeArtifact,
+}
+
+#[derive(Deserialize)]
+struct GrypeVulnerability {
+  id: String,
+  #[serde(default)]
+  severity: String,
+  #[serde(default)]
+  description: String,
+  #[serde(default)]
+  fix: GrypeFix,
+}
+
+#[derive(Deserialize, Default)]
+struct GrypeFix {
+  #[serde(default)]
+  versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GrypeArtifact {
+  name: String,
+  #[serde(default)]
+  version: String,
+}
+
+#[derive(Deserialize)]
+struct GrypeSource {
+  target: Option<GrypeTarget>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GrypeTarget {
+  #[serde(default)]
+  repo_digests: Vec<String>,
+  #[serde(default, rename = "imageID")]
+  image_id: String,
+}
+
+fn parse_grype(output: &str) -> anyhow::Result<ImageScanReport> {
+  let GrypeOutput { matches, source } =
+    serde_json::from_str::<GrypeOutput>(output)
+      .context("Failed to parse grype json output")?;
+  let digest = source
+    .and_then(|source| source.target)
+    .map(|target| {
+      repo_digest(&target.repo_digests).unwrap_or(target.image_id)
+    })
+    .unwrap_or_default();
+  let findings = matches
+    .into_iter()
+    .map(|m| Vulnerability {
+      id: m.vulnerability.id,
+      severity: parse_severity(&m.vulnerability.severity),
+      package: m.artifact.name,
+      installed_version: m.artifact.version,
+      fixed_version: m.vulnerability.fix.versions.join(", "),
+      title: m.vulnerability.description,
+    })
+    .collect();
+  Ok(finish_report(ImageScanner::Grype, digest, findings))
+}
@@ -0,0 +1,244 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use command::{
+  CommandOptions, run_komodo_standard_command, run_standard_command,
+};
+use formatting::format_serror;
+use komodo_client::entities::{
+  komodo_timestamp, stack::StackServiceNames, update::Log,
+};
+use shell_escape::unix::escape;
+use uuid::Uuid;
+
+use crate::{
+  config::periphery_config,
+  docker::image::get_image_digest_from_registry,
+};
+
+/// Signs the image (and so its digest) using `cosign`
+/// with the given private key.
+pub async fn sign_image(
+  image: &str,
+  key: &str,
+  password: Option<&str>,
+) -> anyhow::Result<Log> {
+  let key_file = KeyFile::create(key).await?;
+  let command = format!(
+    "cosign sign --yes --key {} {}",
+    escape(key_file.path.to_string_lossy()),
+    escape(image.into()),
+  );
+  // cosign reads the key password from stdin when it isn't a terminal.
+  Ok(
+    run_komodo_standard_command(
+      "Sign Image",
+      command,
+      CommandOptions::default().stdin(password.unwrap_or_default()),
+    )
+    .await,
+  )
+}
+
+/// Verifies the image has a valid `cosign` signature
+/// from at least one of the given public keys.
+pub async fn verify_image_signature(
+  image: &str,
+  keys: &[String],
+) -> Log {
+  let start_ts = komodo_timestamp();
+  let mut errors = Vec::new();
+  for (i, key) in keys.iter().enumerate() {
+    let key_file = match KeyFile::create(key).await {
+      Ok(key_file) => key_file,
+      Err(e) => {
+        errors.push(format!("key {i}: {e:#}"));
+        continue;
+      }
+    };
+    let command = format!(
+      "cosign verify --key {} {}",
+      escape(key_file.path.to_string_lossy()),
+      escape(image.into()),
+    );
+    let output =
+      run_standard_command(&command, CommandOptions::default()).await;
+    if output.success() {
+      return Log {
+        stage: String::from("Verify Image Signature"),
+        command: format!("cosign verify {image}"),
+        stdout: format!(
+          "Verified signature for {image} using key {i}\n{}",
+          output.stderr.trim()
+        ),
+        success: true,
+        start_ts,
+        end_ts: komodo_timestamp(),
+        ..Default::default()
+      };
+    }
+    errors.push(format!("key {i}: {}", output.stderr.trim()));
+  }
+  Log {
+    stage: String::from("Verify Image Signature"),
+    command: format!("cosign verify {image}"),
+    stderr: format!(
+      "No valid signature found for {image} using the {} configured key/s\n{}",
+      keys.len(),
+      errors.join("\n")
+    ),
+    success: false,
+    start_ts,
+    end_ts: komodo_timestamp(),
+    ..Default::default()
+  }
+}
+
+/// Resolves the image tag to its digest on the registry,
+/// and verifies the signature of that digest.
+/// Returns the image pinned to the verified digest,
+/// so the deploy can't use different contents pushed to the tag
+/// after verification.
+pub async fn verify_image_digest(
+  image: &str,
+  keys: &[String],
+) -> (Log, Option<String>) {
+  let digest = match get_image_digest_from_registry(image).await {
+    Ok(digest) => digest,
+    Err(e) => {
+      return (
+        Log::error(
+          "Verify Image Signature",
+          format_serror(
+            &e.context(format!("Failed to get digest for {image}"))
+              .into(),
+          ),
+        ),
+        None,
+      );
+    }
+  };
+  let pinned = format!("{}@{digest}", image_repository(image));
+  let log = verify_image_signature(&pinned, keys).await;
+  let pinned = log.success.then_some(pinned);
+  (log, pinned)
+}
+
+/// The file pinning the stack services to their verified digests,
+/// passed after the compose files so it takes precedence.
+pub const VERIFIED_IMAGES_FILE: &str = ".komodo-verified-images.yaml";
+
+/// Verifies the images of the stack services by digest,
+/// stopping at the first one which fails.
+/// Services with no image (built locally) are skipped.
+///
+/// On success, writes [VERIFIED_IMAGES_FILE] to the run directory,
+/// and returns whether it should be passed to the deploy.
+pub async fn verify_stack_images(
+  services: &[StackServiceNames],
+  filter: &[String],
+  keys: &[String],
+  run_directory: &Path,
+  logs: &mut Vec<Log>,
+) -> bool {
+  let mut pinned = BTreeMap::new();
+  for service in services {
+    if service.image.is_empty()
+      || (!filter.is_empty()
+        && !filter.contains(&service.service_name))
+    {
+      continue;
+    }
+    let (log, image) =
+      verify_image_digest(&service.image, keys).await;
+    logs.push(log);
+    let Some(image) = image else {
+      return false;
+    };
+    pinned.insert(
+      service.service_name.as_str(),
+      BTreeMap::from([("image", image)]),
+    );
+  }
+  if pinned.is_empty() {
+    return false;
+  }
+  let path = run_directory.join(VERIFIED_IMAGES_FILE);
+  let contents = BTreeMap::from([("services", pinned)]);
+  let res = serde_yaml_ng::to_string(&contents)
+    .context("Failed to serialize verified images");
+  let res = match res {
+    Ok(contents) => {
+      tokio::fs::write(&path, contents).await.with_context(|| {
+        format!("Failed to write verified images to {path:?}")
+      })
+    }
+    Err(e) => Err(e),
+  };
+  if let Err(e) = res {
+    logs.push(Log::error(
+      "Verify Image Signature",
+      format_serror(&e.into()),
+    ));
+    return false;
+  }
+  true
+}
+
+/// The image name without the tag or digest,
+/// eg. `ghcr.io/org/app:1.2.3` -> `ghcr.io/org/app`.
+fn image_repository(image: &str) -> &str {
+  let image = image
+    .split_once('@')
+    .map(|(repository, _)| repository)
+    .unwrap_or(image);
+  // A ':' after the last '/' starts the tag,
+  // otherwise it is part of the registry host.
+  match image.rfind(':') {
+    Some(i) if !image[i..].contains('/') => &image[..i],
+    _ => image,
+  }
+}
+
+/// A key written to disk for use with cosign, removed on drop.
+struct KeyFile {
+  path: PathBuf,
+}
+
+impl KeyFile {
+  async fn create(contents: &str) -> anyhow::Result<KeyFile> {
+    let dir = periphery_config().root_directory.join(".cosign");
+    tokio::fs::create_dir_all(&dir).await.with_context(|| {
+      format!("Failed to create key directory {dir:?}")
+    })?;
+    let path = dir.join(Uuid::new_v4().to_string());
+    let key_file = KeyFile { path };
+    write_private(&key_file.path, contents)
+      .await
+      .context("Failed to write key file")?;
+    Ok(key_file)
+  }
+}
+
+impl Drop for KeyFile {
+  fn drop(&mut self) {
+    if let Err(e) = std::fs::remove_file(&self.path) {
+      warn!("Failed to clean up key file {:?} | {e:?}", self.path);
+    }
+  }
+}
+
+async fn write_private(
+  path: &std::path::Path,
+  contents: &str,
+) -> std::io::Result<()> {
+  use tokio::io::AsyncWriteExt;
+  let mut options = tokio::fs::OpenOptions::new();
+  options.write(true).create_new(true).mode(0o600);
+  let mut file = options.open(path).await?;
+  file.write_all(contents.as_bytes()).await?;
+  file.flush().await
+}
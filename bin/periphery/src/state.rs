@@ -8,6 +8,7 @@ use anyhow::{Context, anyhow};
 use arc_swap::ArcSwap;
 use komodo_client::entities::{
   docker::container::ContainerStats, terminal::TerminalStdinMessage,
+  update::Log,
 };
 use mogh_cache::{CloneCache, CloneVecCache};
 use mogh_pki::{PkiKind, RotatableKeyPair, SpkiPublicKey};
@@ -349,3 +350,20 @@ pub fn build_cancel_cache() -> &'static CancelCache {
   static BUILD_CANCEL_CACHE: OnceLock<CancelCache> = OnceLock::new();
   BUILD_CANCEL_CACHE.get_or_init(Default::default)
 }
+
+/// An Action run started by Core.
+#[derive(Debug, Default)]
+pub struct ActionRun {
+  pub stdout: Mutex<String>,
+  pub stderr: Mutex<String>,
+  /// Set once the run finishes.
+  pub log: Mutex<Option<Log>>,
+  pub cancel: CancellationToken,
+}
+
+/// Maps action run id => ActionRun
+pub fn action_runs() -> &'static CloneCache<String, Arc<ActionRun>> {
+  static ACTION_RUNS: OnceLock<CloneCache<String, Arc<ActionRun>>> =
+    OnceLock::new();
+  ACTION_RUNS.get_or_init(Default::default)
+}
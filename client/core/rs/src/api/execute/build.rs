@@ -3,7 +3,7 @@ use mogh_resolver::Resolve;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
-use crate::entities::update::Update;
+use crate::entities::{Version, update::Update};
 
 use super::{BatchExecutionResponse, KomodoExecuteRequest};
 
@@ -46,6 +46,14 @@ pub fn run_build() {}
 pub struct RunBuild {
   /// Can be build id or name
   pub build: String,
+  /// Build at this commit hash or tag for this run only,
+  /// rather than the configured branch / commit.
+  #[arg(long)]
+  pub commit: Option<String>,
+  /// Build with this version for this run only. The configured
+  /// version is not auto incremented or updated.
+  #[arg(long)]
+  pub version: Option<Version>,
 }
 
 //
@@ -121,3 +129,36 @@ pub struct CancelBuild {
   /// Can be id or name
   pub build: String,
 }
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/CleanupBuildImages",
+  description = "Deletes old image tags of the build from its registries.",
+  request_body(content = CleanupBuildImages),
+  responses(
+    (status = 200, description = "The update", body = Update),
+  ),
+)]
+pub fn cleanup_build_images() {}
+
+/// Deletes old image tags of the target build from its image registries,
+/// according to the build retention policy.
+/// Uses the Docker Registry HTTP API v2 with the registry account credentials.
+/// Versions deployed by any Deployment or Stack are always kept.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, PartialEq, Resolve, Parser,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct CleanupBuildImages {
+  /// Can be id or name
+  pub build: String,
+}
@@ -122,6 +122,7 @@ pub enum Execution {
   RunBuild(RunBuild),
   BatchRunBuild(BatchRunBuild),
   CancelBuild(CancelBuild),
+  CleanupBuildImages(CleanupBuildImages),
 
   // REPO
   /// Clone the target repo
@@ -182,6 +183,7 @@ pub enum Execution {
   PruneVolumes(PruneVolumes),
   PruneDockerBuilders(PruneDockerBuilders),
   PruneBuildx(PruneBuildx),
+  PruneBuildCache(PruneBuildCache),
   PruneSystem(PruneSystem),
 
   // SWARM
@@ -37,6 +37,7 @@ use crate::api::execute;
     execute::prune_volumes,
     execute::prune_docker_builders,
     execute::prune_buildx,
+    execute::prune_build_cache,
     execute::prune_system,
     // stack
     execute::deploy_stack,
@@ -45,6 +46,7 @@ use crate::api::execute;
     execute::batch_deploy_stack_if_changed,
     execute::pull_stack,
     execute::batch_pull_stack,
+    execute::validate_stack,
     execute::start_stack,
     execute::restart_stack,
     execute::pause_stack,
@@ -68,6 +70,7 @@ use crate::api::execute;
     execute::run_build,
     execute::batch_run_build,
     execute::cancel_build,
+    execute::cleanup_build_images,
     // repo
     execute::clone_repo,
     execute::batch_clone_repo,
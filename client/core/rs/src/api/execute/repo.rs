@@ -42,6 +42,10 @@ pub fn clone_repo() {}
 pub struct CloneRepo {
   /// Id or name
   pub repo: String,
+  /// Check out this commit hash or tag for this run only,
+  /// rather than the configured branch / commit.
+  #[arg(long)]
+  pub commit: Option<String>,
 }
 
 //
@@ -119,6 +123,10 @@ pub fn pull_repo() {}
 pub struct PullRepo {
   /// Id or name
   pub repo: String,
+  /// Check out this commit hash or tag for this run only,
+  /// rather than the configured branch / commit.
+  #[arg(long)]
+  pub commit: Option<String>,
 }
 
 //
@@ -200,6 +208,10 @@ pub fn build_repo() {}
 pub struct BuildRepo {
   /// Id or name
   pub repo: String,
+  /// Check out this commit hash or tag for this run only,
+  /// rather than the configured branch / commit.
+  #[arg(long)]
+  pub commit: Option<String>,
 }
 
 //
@@ -649,6 +649,39 @@ pub struct PruneBuildx {
 
 //
 
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/PruneBuildCache",
+  description = "Prunes the build cache of each buildx builder on the target server.",
+  request_body(content = PruneBuildCache),
+  responses(
+    (status = 200, description = "The update", body = Update),
+  ),
+)]
+pub fn prune_build_cache() {}
+
+/// Prunes the build cache of each buildx builder on the target server,
+/// logging the space reclaimed from each. Response: [Update].
+///
+/// 1. Runs `docker buildx prune --builder <builder> -a -f` for each builder
+///    listed by `docker buildx ls`.
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, PartialEq, Resolve, Parser,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct PruneBuildCache {
+  /// Id or name
+  pub server: String,
+}
+
+//
+
 #[cfg(feature = "utoipa")]
 #[utoipa::path(
   post,
@@ -43,6 +43,10 @@ pub struct DeployStack {
   /// Override the default termination max time.
   /// Only used if the stack needs to be taken down first.
   pub stop_time: Option<i32>,
+  /// Deploy this commit hash or tag for this run only,
+  /// rather than the configured branch / commit.
+  #[arg(long)]
+  pub commit: Option<String>,
 }
 
 //
@@ -243,6 +247,39 @@ pub struct BatchPullStack {
 
 //
 
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ValidateStack",
+  description = "Validates the target stack without deploying.",
+  request_body(content = ValidateStack),
+  responses(
+    (status = 200, description = "The update", body = Update),
+  ),
+)]
+pub fn validate_stack() {}
+
+/// Validates the target stack without deploying.
+/// Writes the stack files to the host and runs `docker compose config`,
+/// checks the service images exist in the registry, and flags
+/// unset variables and published ports already used on the Server.
+/// The problems found are logged on the Update. Response: [Update]
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Resolve, Parser,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct ValidateStack {
+  /// Id or name
+  pub stack: String,
+}
+
+//
+
 #[cfg(feature = "utoipa")]
 #[utoipa::path(
   post,
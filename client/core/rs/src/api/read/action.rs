@@ -3,10 +3,10 @@ use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
 use crate::entities::{
-  U64,
+  JsonValue, U64,
   action::{
-    Action, ActionActionState, ActionListItem, ActionQuery,
-    ActionSortBy,
+    Action, ActionActionState, ActionArgument, ActionListItem,
+    ActionQuery, ActionSortBy,
   },
 };
 
@@ -174,6 +174,73 @@ pub type GetActionActionStateResponse = ActionActionState;
 
 //
 
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetActionArguments",
+  description = "Get the declared arguments of the action.",
+  request_body(content = GetActionArguments),
+  responses(
+    (status = 200, description = "The action arguments", body = GetActionArgumentsResponse),
+  ),
+)]
+pub fn get_action_arguments() {}
+
+/// Get the arguments declared in the action `argument_schema`,
+/// for prompting for values before a run.
+/// Response: [GetActionArgumentsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetActionArgumentsResponse)]
+#[error(mogh_error::Error)]
+pub struct GetActionArguments {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub action: String,
+}
+
+#[typeshare]
+pub type GetActionArgumentsResponse = Vec<ActionArgument>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetActionOutput",
+  description = "Get the JSON output of an action run.",
+  request_body(content = GetActionOutput),
+  responses(
+    (status = 200, description = "The action run output", body = serde_json::Value),
+  ),
+)]
+pub fn get_action_output() {}
+
+/// Get the JSON output set by an action run using `setOutput`.
+/// Response: [GetActionOutputResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetActionOutputResponse)]
+#[error(mogh_error::Error)]
+pub struct GetActionOutput {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub action: String,
+  /// The id of the `RunAction` update.
+  /// If not provided, uses the latest run.
+  pub update_id: Option<String>,
+}
+
+/// The output of the run, or `null` if no output was set.
+#[typeshare]
+pub type GetActionOutputResponse = Option<JsonValue>;
+
+//
+
 #[cfg(feature = "utoipa")]
 #[utoipa::path(
   post,
@@ -0,0 +1,53 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::backup::BackupItem;
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListBackupItems",
+  description = "**Admin only.** List the restorable items in a database backup.",
+  request_body(content = ListBackupItems),
+  responses(
+    (status = 200, description = "The backup items", body = ListBackupItemsResponse),
+    (status = 403, description = "Not admin", body = mogh_error::Serror),
+    (status = 500, description = "Failed", body = mogh_error::Serror),
+  ),
+)]
+pub fn list_backup_items() {}
+
+/// **Admin only.** List the resources, variables and user groups
+/// contained in a database backup, each compared against
+/// the current database contents.
+/// Response: [ListBackupItemsResponse].
+///
+/// Use with [RestoreBackupItems][crate::api::write::RestoreBackupItems]
+/// to restore only specific items.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListBackupItemsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListBackupItems {
+  /// The dated backup folder to read, eg `2025-08-01_05-04-53`.
+  /// If not provided, will use the most recent backup.
+  pub backup: Option<String>,
+}
+
+/// Response for [ListBackupItems].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ListBackupItemsResponse {
+  /// The dated backup folder which was read.
+  pub backup: String,
+  /// The restorable items in the backup.
+  pub items: Vec<BackupItem>,
+}
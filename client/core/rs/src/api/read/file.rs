@@ -0,0 +1,154 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::file::{FileDownload, FileEntry};
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListContainerDirectory",
+  description = "List the entries of a directory inside a container.",
+  request_body(content = ListContainerDirectory),
+  responses(
+    (status = 200, description = "The directory entries", body = ListContainerDirectoryResponse),
+  ),
+)]
+pub fn list_container_directory() {}
+
+/// List the entries of a directory inside a container.
+/// Requires the container to have `find` and `stat` available.
+/// Requires minimum Read + Inspect permission on the Server.
+/// Response: [ListContainerDirectoryResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListContainerDirectoryResponse)]
+#[error(mogh_error::Error)]
+pub struct ListContainerDirectory {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The container name
+  pub container: String,
+  /// The absolute path of the directory inside the container.
+  pub path: String,
+}
+
+#[typeshare]
+pub type ListContainerDirectoryResponse = Vec<FileEntry>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetContainerFile",
+  description = "Download a file from inside a container.",
+  request_body(content = GetContainerFile),
+  responses(
+    (status = 200, description = "The file", body = GetContainerFileResponse),
+  ),
+)]
+pub fn get_container_file() {}
+
+/// Download a file from inside a container, like `docker cp`.
+/// The file must be smaller than the Core `max_file_transfer_size_mb`.
+/// Requires minimum Read + Inspect permission on the Server.
+/// Response: [FileDownload].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetContainerFileResponse)]
+#[error(mogh_error::Error)]
+pub struct GetContainerFile {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The container name
+  pub container: String,
+  /// The absolute path of the file inside the container.
+  pub path: String,
+}
+
+#[typeshare]
+pub type GetContainerFileResponse = FileDownload;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListServerDirectory",
+  description = "List the entries of a directory on the server host.",
+  request_body(content = ListServerDirectory),
+  responses(
+    (status = 200, description = "The directory entries", body = ListServerDirectoryResponse),
+  ),
+)]
+pub fn list_server_directory() {}
+
+/// List the entries of a directory on the server host.
+/// The path must be inside the Periphery `root_directory`.
+/// Requires minimum Read + Inspect permission on the Server.
+/// Response: [ListServerDirectoryResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListServerDirectoryResponse)]
+#[error(mogh_error::Error)]
+pub struct ListServerDirectory {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The path of the directory, relative to the Periphery `root_directory`.
+  /// Leave empty to list the `root_directory` itself.
+  #[serde(default)]
+  pub path: String,
+}
+
+#[typeshare]
+pub type ListServerDirectoryResponse = Vec<FileEntry>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetServerFile",
+  description = "Download a file from the server host.",
+  request_body(content = GetServerFile),
+  responses(
+    (status = 200, description = "The file", body = GetServerFileResponse),
+  ),
+)]
+pub fn get_server_file() {}
+
+/// Download a file from the server host.
+/// The path must be inside the Periphery `root_directory`,
+/// and the file smaller than the Core `max_file_transfer_size_mb`.
+/// Requires minimum Read + Inspect permission on the Server.
+/// Response: [FileDownload].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetServerFileResponse)]
+#[error(mogh_error::Error)]
+pub struct GetServerFile {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The path of the file, relative to the Periphery `root_directory`.
+  pub path: String,
+}
+
+#[typeshare]
+pub type GetServerFileResponse = FileDownload;
@@ -5,15 +5,21 @@ use typeshare::typeshare;
 mod action;
 mod alert;
 mod alerter;
+mod backup;
 mod build;
 mod builder;
 mod deployment;
 mod docker;
+mod file;
 mod onboarding_key;
 mod permission;
 mod procedure;
 mod provider;
+mod registry;
 mod repo;
+mod revision;
+mod sbom;
+mod scan;
 mod schedule;
 mod server;
 mod stack;
@@ -30,15 +36,21 @@ mod variable;
 pub use action::*;
 pub use alert::*;
 pub use alerter::*;
+pub use backup::*;
 pub use build::*;
 pub use builder::*;
 pub use deployment::*;
 pub use docker::*;
+pub use file::*;
 pub use onboarding_key::*;
 pub use permission::*;
 pub use procedure::*;
 pub use provider::*;
+pub use registry::*;
 pub use repo::*;
+pub use revision::*;
+pub use sbom::*;
+pub use scan::*;
 pub use schedule::*;
 pub use server::*;
 pub use stack::*;
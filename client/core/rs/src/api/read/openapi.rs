@@ -18,6 +18,8 @@ use crate::api::read;
     read::get_image_registry_account,
     read::list_image_registry_accounts,
     read::list_image_registries_from_config,
+    read::list_registry_repositories,
+    read::list_registry_tags,
     // swarm
     read::list_swarms,
     read::list_full_swarms,
@@ -48,6 +50,7 @@ use crate::api::read;
     read::get_servers_summary,
     read::get_server_state,
     read::get_periphery_information,
+    read::list_server_port_allocations,
     read::get_system_information,
     read::get_system_stats,
     read::list_system_processes,
@@ -67,6 +70,7 @@ use crate::api::read;
     read::search_stack_log,
     read::list_common_stack_extra_args,
     read::list_common_stack_build_extra_args,
+    read::list_stack_previews,
     // deployment
     read::list_deployments,
     read::list_full_deployments,
@@ -88,6 +92,9 @@ use crate::api::read;
     read::get_builds_summary,
     read::get_build_monthly_stats,
     read::list_build_versions,
+    read::list_build_sboms,
+    read::get_build_sbom,
+    read::diff_build_sboms,
     read::list_common_build_extra_args,
     // repo
     read::list_repos,
@@ -106,6 +113,8 @@ use crate::api::read;
     read::list_full_actions,
     read::get_action,
     read::get_action_action_state,
+    read::get_action_arguments,
+    read::get_action_output,
     read::get_actions_summary,
     // builder
     read::list_builders,
@@ -142,6 +151,15 @@ use crate::api::read;
     read::list_image_history,
     read::list_volumes,
     read::inspect_volume,
+    // file
+    read::list_container_directory,
+    read::get_container_file,
+    read::list_server_directory,
+    read::get_server_file,
+    // revision
+    read::list_resource_revisions,
+    read::get_resource_revision,
+    read::diff_resource_revisions,
     // toml
     read::export_all_resources_to_toml,
     read::export_resources_to_toml,
@@ -156,6 +174,9 @@ use crate::api::read;
     // alert
     read::list_alerts,
     read::get_alert,
+    // image scan
+    read::list_image_scans,
+    read::get_image_scan,
     // user
     read::list_api_keys,
     read::list_api_keys_for_service_user,
@@ -169,6 +190,8 @@ use crate::api::read;
     // user group
     read::list_user_groups,
     read::get_user_group,
+    // backup
+    read::list_backup_items,
   ),
 )]
 pub struct KomodoReadApi;
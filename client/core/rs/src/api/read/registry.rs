@@ -0,0 +1,83 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::registry::RegistryTag;
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListRegistryRepositories",
+  description = "List the repositories in an image registry using the registry catalog. Admin only.",
+  request_body(content = ListRegistryRepositories),
+  responses(
+    (status = 200, description = "The list of repositories", body = ListRegistryRepositoriesResponse),
+  ),
+)]
+pub fn list_registry_repositories() {}
+
+/// List the repositories in an image registry
+/// using the registry catalog. Admin only.
+/// Response: [ListRegistryRepositoriesResponse].
+///
+/// Note. Some registries, such as Docker Hub,
+/// don't support listing repositories.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListRegistryRepositoriesResponse)]
+#[error(mogh_error::Error)]
+pub struct ListRegistryRepositories {
+  /// The registry domain, eg. `ghcr.io`
+  pub domain: String,
+  /// The registry account to authenticate with.
+  /// Uses anonymous access if empty.
+  #[serde(default)]
+  pub account: String,
+}
+
+#[typeshare]
+pub type ListRegistryRepositoriesResponse = Vec<String>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListRegistryTags",
+  description = "List the tags of an image registry repository, along with the Builds, Deployments, and Stacks using each tag. Admin only.",
+  request_body(content = ListRegistryTags),
+  responses(
+    (status = 200, description = "The list of tags", body = ListRegistryTagsResponse),
+  ),
+)]
+pub fn list_registry_tags() {}
+
+/// List the tags of an image registry repository,
+/// along with the Builds, Deployments, and Stacks using each tag.
+/// Admin only.
+/// Response: [ListRegistryTagsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListRegistryTagsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListRegistryTags {
+  /// The registry domain, eg. `ghcr.io`
+  pub domain: String,
+  /// The registry account to authenticate with.
+  /// Uses anonymous access if empty.
+  #[serde(default)]
+  pub account: String,
+  /// The repository, eg. `moghtech/komodo-core`
+  pub repository: String,
+}
+
+#[typeshare]
+pub type ListRegistryTagsResponse = Vec<RegistryTag>;
@@ -0,0 +1,105 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{
+  I64, ResourceTarget,
+  revision::{ResourceRevision, RevisionFieldDiff},
+};
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListResourceRevisions",
+  description = "List the config revisions of a resource, sorted by most recent first.",
+  request_body(content = ListResourceRevisions),
+  responses(
+    (status = 200, description = "The list of revisions", body = ListResourceRevisionsResponse),
+  ),
+)]
+pub fn list_resource_revisions() {}
+
+/// List the config revisions of a resource, sorted by most recent first.
+/// The config is not included, use [GetResourceRevision] to get it.
+/// Response: [ListResourceRevisionsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListResourceRevisionsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListResourceRevisions {
+  /// The target resource.
+  pub target: ResourceTarget,
+  /// Limit the number of included results. Default is no limit.
+  pub limit: Option<I64>,
+}
+
+#[typeshare]
+pub type ListResourceRevisionsResponse = Vec<ResourceRevision>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetResourceRevision",
+  description = "Get a resource config revision, including the full config.",
+  request_body(content = GetResourceRevision),
+  responses(
+    (status = 200, description = "The revision", body = GetResourceRevisionResponse),
+  ),
+)]
+pub fn get_resource_revision() {}
+
+/// Get a resource config revision, including the full config.
+/// Response: [ResourceRevision].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetResourceRevisionResponse)]
+#[error(mogh_error::Error)]
+pub struct GetResourceRevision {
+  pub id: String,
+}
+
+#[typeshare]
+pub type GetResourceRevisionResponse = ResourceRevision;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/DiffResourceRevisions",
+  description = "Compare the configs of two revisions of a resource.",
+  request_body(content = DiffResourceRevisions),
+  responses(
+    (status = 200, description = "The changed config fields", body = DiffResourceRevisionsResponse),
+  ),
+)]
+pub fn diff_resource_revisions() {}
+
+/// Compare the configs of two revisions of the same resource.
+/// Response: [DiffResourceRevisionsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(DiffResourceRevisionsResponse)]
+#[error(mogh_error::Error)]
+pub struct DiffResourceRevisions {
+  /// The id of the earlier revision.
+  pub from: String,
+  /// The id of the later revision.
+  /// If not provided, compares against the current config.
+  pub to: Option<String>,
+}
+
+#[typeshare]
+pub type DiffResourceRevisionsResponse = Vec<RevisionFieldDiff>;
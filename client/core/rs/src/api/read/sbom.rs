@@ -0,0 +1,109 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{
+  I64, Version,
+  sbom::{BuildSbom, SbomDiff},
+};
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListBuildSboms",
+  description = "List the SBOMs generated for a build, sorted by most recent first.",
+  request_body(content = ListBuildSboms),
+  responses(
+    (status = 200, description = "The list of SBOMs", body = ListBuildSbomsResponse),
+  ),
+)]
+pub fn list_build_sboms() {}
+
+/// List the SBOMs generated for a build, sorted by most recent first.
+/// The packages and document are not included, use [GetBuildSbom] to get them.
+/// Response: [ListBuildSbomsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListBuildSbomsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListBuildSboms {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub build: String,
+  /// Limit the number of included results. Default is no limit.
+  pub limit: Option<I64>,
+}
+
+#[typeshare]
+pub type ListBuildSbomsResponse = Vec<BuildSbom>;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetBuildSbom",
+  description = "Get an SBOM, including the packages and full document.",
+  request_body(content = GetBuildSbom),
+  responses(
+    (status = 200, description = "The SBOM", body = GetBuildSbomResponse),
+  ),
+)]
+pub fn get_build_sbom() {}
+
+/// Get an SBOM, including the packages and full document.
+/// Response: [BuildSbom].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetBuildSbomResponse)]
+#[error(mogh_error::Error)]
+pub struct GetBuildSbom {
+  pub id: String,
+}
+
+#[typeshare]
+pub type GetBuildSbomResponse = BuildSbom;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/DiffBuildSboms",
+  description = "Compare the packages in the SBOMs of two build versions.",
+  request_body(content = DiffBuildSboms),
+  responses(
+    (status = 200, description = "The package differences", body = DiffBuildSbomsResponse),
+  ),
+)]
+pub fn diff_build_sboms() {}
+
+/// Compare the packages in the SBOMs of two build versions.
+/// Uses the most recent SBOM generated for each version.
+/// Response: [SbomDiff].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(DiffBuildSbomsResponse)]
+#[error(mogh_error::Error)]
+pub struct DiffBuildSboms {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub build: String,
+  /// The older version
+  pub from: Version,
+  /// The newer version
+  pub to: Version,
+}
+
+#[typeshare]
+pub type DiffBuildSbomsResponse = SbomDiff;
@@ -0,0 +1,92 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{I64, MongoDocument, U64, scan::ImageScan};
+
+use super::KomodoReadRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListImageScans",
+  description = "Get a paginated list of image scans sorted by timestamp descending.",
+  request_body(content = ListImageScans),
+  responses(
+    (status = 200, description = "The paginated list of image scans", body = ListImageScansResponse),
+  ),
+)]
+pub fn list_image_scans() {}
+
+/// Get a paginated list of image scans sorted by timestamp descending.
+/// The individual findings are not included, use [GetImageScan] to get them.
+/// Response: [ListImageScansResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListImageScansResponse)]
+#[error(mogh_error::Error)]
+pub struct ListImageScans {
+  /// Pass a custom mongo query to filter the image scans.
+  ///
+  /// ## Example JSON
+  /// ```json
+  /// {
+  ///   "target": {
+  ///     "type": "Deployment",
+  ///     "id": "6608bf89cb2a12b257ab6c09"
+  ///   },
+  ///   "counts.critical": { "$gt": 0 }
+  /// }
+  /// ```
+  /// This will filter to only include scans of the Deployment with critical findings.
+  #[cfg_attr(feature = "utoipa", schema(value_type = serde_json::Value))]
+  pub query: Option<MongoDocument>,
+  /// Retrieve older results by incrementing the page.
+  /// `page: 0` is default, and returns the most recent results.
+  #[serde(default)]
+  pub page: U64,
+}
+
+/// Response for [ListImageScans].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ListImageScansResponse {
+  pub scans: Vec<ImageScan>,
+  /// If more scans exist, the next page will be given here.
+  /// Otherwise it will be `null`
+  pub next_page: Option<I64>,
+}
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/GetImageScan",
+  description = "Get an image scan, including the individual findings.",
+  request_body(content = GetImageScan),
+  responses(
+    (status = 200, description = "The image scan", body = GetImageScanResponse),
+  ),
+)]
+pub fn get_image_scan() {}
+
+/// Get an image scan, including the individual findings.
+/// Response: [ImageScan].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetImageScanResponse)]
+#[error(mogh_error::Error)]
+pub struct GetImageScan {
+  pub id: String,
+}
+
+#[typeshare]
+pub type GetImageScanResponse = ImageScan;
@@ -6,7 +6,7 @@ use crate::entities::{
   I64, Timelength, U64,
   server::{
     PeripheryInformation, Server, ServerActionState, ServerListItem,
-    ServerQuery, ServerSortBy, ServerState,
+    ServerPortAllocation, ServerQuery, ServerSortBy, ServerState,
   },
   stats::{
     SystemInformation, SystemProcess, SystemStats, SystemStatsRecord,
@@ -428,3 +428,36 @@ pub struct GetServersSummaryResponse {
   /// The number of disabled servers.
   pub disabled: I64,
 }
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListServerPortAllocations",
+  description = "List the host ports allocated on the server.",
+  request_body(content = ListServerPortAllocations),
+  responses(
+    (status = 200, description = "The port allocations", body = ListServerPortAllocationsResponse),
+  ),
+)]
+pub fn list_server_port_allocations() {}
+
+/// List the host ports allocated on the server,
+/// by the Deployment / Stack configs and the running containers.
+/// Response: [ListServerPortAllocationsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListServerPortAllocationsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListServerPortAllocations {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+}
+
+#[typeshare]
+pub type ListServerPortAllocationsResponse =
+  Vec<ServerPortAllocation>;
@@ -7,6 +7,7 @@ use crate::entities::{
   docker::{
     container::Container, service::SwarmService, stack::SwarmStack,
   },
+  preview::StackPreview,
   stack::{
     Stack, StackActionState, StackListItem, StackQuery, StackService,
     StackServiceState, StackSortBy,
@@ -493,6 +494,38 @@ pub type ListCommonStackBuildExtraArgsResponse = Vec<String>;
 
 //
 
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ListStackPreviews",
+  description = "List the pull request preview environments deployed from a stack.",
+  request_body(content = ListStackPreviews),
+  responses(
+    (status = 200, description = "The list of stack previews", body = ListStackPreviewsResponse),
+  ),
+)]
+pub fn list_stack_previews() {}
+
+/// List the pull request preview environments deployed from a stack,
+/// sorted by pull request number.
+/// Response: [ListStackPreviewsResponse].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListStackPreviewsResponse)]
+#[error(mogh_error::Error)]
+pub struct ListStackPreviews {
+  /// Id or name of the template stack
+  #[serde(alias = "id", alias = "name")]
+  pub stack: String,
+}
+
+#[typeshare]
+pub type ListStackPreviewsResponse = Vec<StackPreview>;
+
+//
+
 #[cfg(feature = "utoipa")]
 #[utoipa::path(
   post,
@@ -0,0 +1,42 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{backup::BackupItemTarget, update::Update};
+
+use super::KomodoWriteRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/RestoreBackupItems",
+  description = "**Admin only.** Restore selected items from a database backup.",
+  request_body(content = RestoreBackupItems),
+  responses(
+    (status = 200, description = "The update", body = Update),
+    (status = 403, description = "Not admin", body = mogh_error::Serror),
+    (status = 500, description = "Failed", body = mogh_error::Serror),
+  ),
+)]
+pub fn restore_backup_items() {}
+
+/// **Admin only.** Restore only the selected resources, variables
+/// and user groups from a database backup, leaving the rest of
+/// the database untouched. The diff of each item against
+/// the current database is recorded on the Update.
+/// Response: [Update].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct RestoreBackupItems {
+  /// The dated backup folder to restore from, eg `2025-08-01_05-04-53`.
+  /// If not provided, will use the most recent backup.
+  pub backup: Option<String>,
+  /// The items to restore.
+  pub items: Vec<BackupItemTarget>,
+}
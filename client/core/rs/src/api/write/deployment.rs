@@ -240,6 +240,10 @@ pub struct CheckDeploymentForUpdateResponse {
   pub deployment: String,
   /// Whether update is available
   pub update_available: bool,
+  /// The image with the newer version tag,
+  /// if found using the update policy.
+  #[serde(default)]
+  pub latest_image: Option<String>,
 }
 
 //
@@ -0,0 +1,79 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::update::Update;
+
+use super::KomodoWriteRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/WriteContainerFile",
+  description = "Upload a file into a container.",
+  request_body(content = WriteContainerFile),
+  responses(
+    (status = 200, description = "The update", body = Update),
+  ),
+)]
+pub fn write_container_file() {}
+
+/// Upload a file into a container, like `docker cp`.
+/// Overwrites the file if it already exists.
+/// The file must be smaller than the Core `max_file_transfer_size_mb`.
+/// Requires minimum Read + Terminal permission on the Server.
+/// Response: [Update].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct WriteContainerFile {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The container name
+  pub container: String,
+  /// The absolute path of the file inside the container.
+  pub path: String,
+  /// The file contents, base64 encoded.
+  pub contents: String,
+}
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/WriteServerFile",
+  description = "Upload a file to the server host.",
+  request_body(content = WriteServerFile),
+  responses(
+    (status = 200, description = "The update", body = Update),
+  ),
+)]
+pub fn write_server_file() {}
+
+/// Upload a file to the server host, creating parent directories as needed.
+/// The path must be inside the Periphery `root_directory`,
+/// and the file smaller than the Core `max_file_transfer_size_mb`.
+/// Requires minimum Read + Terminal permission on the Server.
+/// Response: [Update].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(mogh_error::Error)]
+pub struct WriteServerFile {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The path of the file, relative to the Periphery `root_directory`.
+  pub path: String,
+  /// The file contents, base64 encoded.
+  pub contents: String,
+}
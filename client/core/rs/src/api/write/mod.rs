@@ -2,15 +2,18 @@ mod action;
 mod alert;
 mod alerter;
 mod api_key;
+mod backup;
 mod build;
 mod builder;
 mod deployment;
+mod file;
 mod onboarding_key;
 mod permissions;
 mod procedure;
 mod provider;
 mod repo;
 mod resource;
+mod schedule;
 mod server;
 mod stack;
 mod swarm;
@@ -25,15 +28,18 @@ pub use action::*;
 pub use alert::*;
 pub use alerter::*;
 pub use api_key::*;
+pub use backup::*;
 pub use build::*;
 pub use builder::*;
 pub use deployment::*;
+pub use file::*;
 pub use onboarding_key::*;
 pub use permissions::*;
 pub use procedure::*;
 pub use provider::*;
 pub use repo::*;
 pub use resource::*;
+pub use schedule::*;
 pub use server::*;
 pub use stack::*;
 pub use swarm::*;
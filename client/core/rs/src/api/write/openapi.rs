@@ -11,6 +11,10 @@ use crate::api::write;
     write::delete_onboarding_key,
     // resource
     write::update_resource_meta,
+    write::restore_resource_revision,
+    // schedule
+    write::schedule_deploy,
+    write::cancel_scheduled_deploy,
     // swarm
     write::create_swarm,
     write::copy_swarm,
@@ -102,6 +106,9 @@ use crate::api::write;
     write::delete_terminal,
     write::delete_all_terminals,
     write::batch_delete_all_terminals,
+    // file
+    write::write_container_file,
+    write::write_server_file,
     // alert
     write::close_alert,
     // tags
@@ -139,6 +146,8 @@ use crate::api::write;
     write::create_image_registry_account,
     write::update_image_registry_account,
     write::delete_image_registry_account,
+    // backup
+    write::restore_backup_items,
   ),
 )]
 pub struct KomodoWriteApi;
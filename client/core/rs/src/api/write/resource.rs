@@ -47,3 +47,35 @@ pub struct UpdateResourceMeta {
 
 #[typeshare]
 pub type UpdateResourceMetaResponse = NoData;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/RestoreResourceRevision",
+  description = "Restore a resource config to a previous revision.",
+  request_body(content = RestoreResourceRevision),
+  responses(
+    (status = 200, description = "Resource config restored.", body = RestoreResourceRevisionResponse),
+  ),
+)]
+pub fn restore_resource_revision() {}
+
+/// Restore a resource config to a previous revision.
+/// This is applied as a normal config update,
+/// so it is recorded as a new revision and can itself be reverted.
+/// Response: [NoData].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(RestoreResourceRevisionResponse)]
+#[error(mogh_error::Error)]
+pub struct RestoreResourceRevision {
+  /// The id of the revision to restore.
+  pub id: String,
+}
+
+#[typeshare]
+pub type RestoreResourceRevisionResponse = NoData;
@@ -0,0 +1,76 @@
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{
+  I64, NoData, ResourceTarget, schedule::ScheduledDeploy,
+};
+
+use super::KomodoWriteRequest;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/ScheduleDeploy",
+  description = "Schedule a one-off deploy of a Deployment or Stack.",
+  request_body(content = ScheduleDeploy),
+  responses(
+    (status = 200, description = "The scheduled deploy", body = ScheduleDeployResponse),
+  ),
+)]
+pub fn schedule_deploy() {}
+
+/// Schedule a one-off Deploy / DeployStack to run at a specific time.
+/// Replaces any deploy already scheduled for the resource.
+/// The scheduled deploy is listed in [ListSchedules][crate::api::read::ListSchedules].
+/// Requires Execute permission on the resource.
+/// Response: [ScheduledDeploy].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(ScheduleDeployResponse)]
+#[error(mogh_error::Error)]
+pub struct ScheduleDeploy {
+  /// The Deployment or Stack to deploy.
+  pub target: ResourceTarget,
+  /// Unix timestamp in milliseconds to run the deploy.
+  /// Must be in the future.
+  pub run_at: I64,
+}
+
+#[typeshare]
+pub type ScheduleDeployResponse = ScheduledDeploy;
+
+//
+
+#[cfg(feature = "utoipa")]
+#[utoipa::path(
+  post,
+  path = "/CancelScheduledDeploy",
+  description = "Cancel the scheduled deploy of a Deployment or Stack.",
+  request_body(content = CancelScheduledDeploy),
+  responses(
+    (status = 200, description = "Scheduled deploy cancelled.", body = CancelScheduledDeployResponse),
+  ),
+)]
+pub fn cancel_scheduled_deploy() {}
+
+/// Cancel the scheduled deploy of a Deployment or Stack.
+/// Requires Execute permission on the resource.
+/// Response: [NoData].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[empty_traits(KomodoWriteRequest)]
+#[response(CancelScheduledDeployResponse)]
+#[error(mogh_error::Error)]
+pub struct CancelScheduledDeploy {
+  /// The Deployment or Stack with the scheduled deploy.
+  pub target: ResourceTarget,
+}
+
+#[typeshare]
+pub type CancelScheduledDeployResponse = NoData;
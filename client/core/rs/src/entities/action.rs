@@ -1,16 +1,18 @@
+use anyhow::{Context, anyhow};
 use bson::{Document, doc};
 use derive_builder::Builder;
 use derive_default_builder::DefaultBuilder;
 use partial_derive2::Partial;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumString};
 use typeshare::typeshare;
 
 use crate::{
   deserializers::{
     file_contents_deserializer, option_file_contents_deserializer,
+    option_string_list_deserializer, string_list_deserializer,
   },
-  entities::{FileFormat, I64, NoData},
+  entities::{FileFormat, I64, JsonObject, JsonValue, NoData},
 };
 
 use super::{
@@ -35,6 +37,8 @@ pub struct ActionListItemInfo {
   /// If there is an error parsing schedule expression,
   /// it will be given here.
   pub schedule_error: Option<String>,
+  /// Whether the Action is a shared library.
+  pub library: bool,
 }
 
 #[typeshare]
@@ -156,12 +160,105 @@ pub struct ActionConfig {
   #[builder(default)]
   pub webhook_secret: String,
 
+  /// Optionally run the Action on this Server's Periphery agent,
+  /// instead of on the Core host. Periphery uses Deno if installed,
+  /// otherwise runs the script in the `denoland/deno` container.
+  #[serde(default, alias = "server")]
+  #[partial_attr(serde(alias = "server"))]
+  #[cfg_attr(
+    feature = "schemars",
+    partial_attr(schemars(rename = "server"))
+  )]
+  #[builder(default)]
+  pub server_id: String,
+
   /// Whether deno will be instructed to reload all dependencies,
   /// this can usually be kept false outside of development.
   #[serde(default)]
   #[builder(default)]
   pub reload_deno_deps: bool,
 
+  /// Run the Action with all Deno permissions (`--allow-all`),
+  /// ignoring the granular permissions below.
+  /// Only takes effect when the Action was last configured by an admin.
+  #[serde(default = "default_allow_all")]
+  #[builder(default = "default_allow_all()")]
+  #[partial_default(default_allow_all())]
+  pub allow_all: bool,
+
+  /// Network hosts the Action may connect to (`--allow-net`),
+  /// eg. `api.github.com`, `10.0.0.5:8080`. Use `*` to allow any host.
+  /// Komodo Core is always allowed.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub allow_net: Vec<String>,
+
+  /// Paths the Action may read (`--allow-read`).
+  /// Use `*` to allow any path.
+  /// Only takes effect when the Action was last configured by an admin.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub allow_read: Vec<String>,
+
+  /// Paths the Action may write (`--allow-write`).
+  /// Use `*` to allow any path.
+  /// Only takes effect when the Action was last configured by an admin.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub allow_write: Vec<String>,
+
+  /// Environment variables the Action may read (`--allow-env`).
+  /// Use `*` to allow all.
+  /// Only takes effect when the Action was last configured by an admin.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub allow_env: Vec<String>,
+
+  /// Executables the Action may run as subprocesses (`--allow-run`).
+  /// Subprocesses are not sandboxed. Use `*` to allow any.
+  /// Only takes effect when the Action was last configured by an admin.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub allow_run: Vec<String>,
+
+  /// Make this Action a shared library of Typescript helpers.
+  /// Libraries can't be run. Other Actions list the library in `libraries`
+  /// and import its exports with `import { .. } from "@lib/<name>"`.
+  #[serde(default)]
+  #[builder(default)]
+  pub library: bool,
+
+  /// Library Actions (id or name) this Action imports from.
+  /// The library files are written alongside the script for each run,
+  /// including the libraries they import themselves.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub libraries: Vec<String>,
+
   /// Typescript file contents using pre-initialized `komodo` client.
   /// Supports variable / secret interpolation.
   #[serde(default, deserialize_with = "file_contents_deserializer")]
@@ -186,6 +283,14 @@ pub struct ActionConfig {
   ))]
   #[builder(default)]
   pub arguments: String,
+
+  /// Optionally declare the arguments the Action accepts.
+  /// `RunAction` args are validated against these before the run,
+  /// and clients can use them to prompt for the values.
+  /// Args not declared here are passed through unchecked.
+  #[serde(default)]
+  #[builder(default)]
+  pub argument_schema: Vec<ActionArgument>,
 }
 
 fn default_schedule_enabled() -> bool {
@@ -208,10 +313,40 @@ fn default_webhook_enabled() -> bool {
   true
 }
 
+fn default_allow_all() -> bool {
+  true
+}
+
 impl ActionConfig {
   pub fn builder() -> ActionConfigBuilder {
     ActionConfigBuilder::default()
   }
+
+  /// Validates the args against the `argument_schema`,
+  /// filling in defaults and converting string values
+  /// (eg. from `key_value` arguments) to the declared types.
+  pub fn validate_arguments(
+    &self,
+    args: &mut JsonObject,
+  ) -> anyhow::Result<()> {
+    for argument in &self.argument_schema {
+      let value = args
+        .remove(&argument.name)
+        .filter(|value| !value.is_null())
+        .or_else(|| argument.default.clone());
+      let Some(value) = value else {
+        if argument.required {
+          return Err(anyhow!(
+            "Missing required argument '{}'",
+            argument.name
+          ));
+        }
+        continue;
+      };
+      args.insert(argument.name.clone(), argument.validate(value)?);
+    }
+    Ok(())
+  }
 }
 
 impl Default for ActionConfig {
@@ -226,10 +361,155 @@ impl Default for ActionConfig {
       failure_alert: default_failure_alert(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
+      server_id: Default::default(),
       reload_deno_deps: Default::default(),
+      allow_all: default_allow_all(),
+      allow_net: Default::default(),
+      allow_read: Default::default(),
+      allow_write: Default::default(),
+      allow_env: Default::default(),
+      allow_run: Default::default(),
+      library: Default::default(),
+      libraries: Default::default(),
       arguments_format: Default::default(),
       file_contents: Default::default(),
       arguments: Default::default(),
+      argument_schema: Default::default(),
+    }
+  }
+}
+
+/// Declares an argument the Action accepts at `ARGS.<name>`.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ActionArgument {
+  /// The argument name.
+  pub name: String,
+  /// The type of the argument value. Default: `string`
+  #[serde(default, rename = "type")]
+  pub kind: ActionArgumentType,
+  /// A description to show when prompting for the value.
+  #[serde(default)]
+  pub description: String,
+  /// Fail the run if no value is given and there is no default.
+  #[serde(default)]
+  pub required: bool,
+  /// The value to use when none is given.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default: Option<JsonValue>,
+  /// Restrict the value to one of these choices.
+  #[serde(default)]
+  pub choices: Vec<JsonValue>,
+  /// The value is sensitive. It is hidden from the run output,
+  /// and clients should not echo it when prompting.
+  #[serde(default)]
+  pub secret: bool,
+}
+
+impl ActionArgument {
+  /// Checks the value matches the type and choices,
+  /// returning the value converted to the type.
+  pub fn validate(
+    &self,
+    value: JsonValue,
+  ) -> anyhow::Result<JsonValue> {
+    let value = self.kind.convert(value).with_context(|| {
+      format!(
+        "Argument '{}' must be of type '{}'",
+        self.name, self.kind
+      )
+    })?;
+    if !self.choices.is_empty()
+      && !self.choices.iter().any(|choice| {
+        self.kind.convert(choice.clone()).as_ref() == Some(&value)
+      })
+    {
+      return Err(anyhow!(
+        "Argument '{}' must be one of {}",
+        self.name,
+        JsonValue::Array(self.choices.clone())
+      ));
+    }
+    Ok(value)
+  }
+}
+
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Display,
+  EnumString,
+  Serialize,
+  Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ActionArgumentType {
+  #[default]
+  String,
+  Number,
+  Integer,
+  Boolean,
+  Array,
+  Object,
+}
+
+impl ActionArgumentType {
+  /// Converts the value to this type. Strings are parsed for
+  /// the other types, and numbers / booleans are accepted as strings.
+  /// Returns `None` if the value can't be converted.
+  pub fn convert(self, value: JsonValue) -> Option<JsonValue> {
+    use ActionArgumentType::*;
+    match (self, value) {
+      (String, value @ JsonValue::String(_))
+      | (Number, value @ JsonValue::Number(_))
+      | (Boolean, value @ JsonValue::Bool(_))
+      | (Array, value @ JsonValue::Array(_))
+      | (Object, value @ JsonValue::Object(_)) => Some(value),
+      (Integer, JsonValue::Number(number))
+        if number.is_i64() || number.is_u64() =>
+      {
+        Some(JsonValue::Number(number))
+      }
+      (String, JsonValue::Number(number)) => {
+        Some(JsonValue::String(number.to_string()))
+      }
+      (String, JsonValue::Bool(value)) => {
+        Some(JsonValue::String(value.to_string()))
+      }
+      (Number, JsonValue::String(value)) => {
+        serde_json::from_str::<serde_json::Number>(value.trim())
+          .ok()
+          .map(JsonValue::Number)
+      }
+      (Integer, JsonValue::String(value)) => {
+        value.trim().parse::<i64>().ok().map(JsonValue::from)
+      }
+      (Boolean, JsonValue::String(value)) => {
+        value.trim().parse::<bool>().ok().map(JsonValue::Bool)
+      }
+      (Array, JsonValue::String(value)) => {
+        serde_json::from_str::<Vec<JsonValue>>(&value)
+          .ok()
+          .map(JsonValue::Array)
+      }
+      (Object, JsonValue::String(value)) => {
+        serde_json::from_str::<JsonObject>(&value)
+          .ok()
+          .map(JsonValue::Object)
+      }
+      _ => None,
     }
   }
 }
@@ -8,7 +8,8 @@ use crate::entities::{I64, MongoId};
 
 use super::{
   _Serror, ResourceTarget, ResourceTargetVariant, Version,
-  deployment::DeploymentState, stack::StackState,
+  deployment::DeploymentState, scan::VulnerabilityCounts,
+  stack::StackState,
 };
 
 /// Representation of an alert in the system.
@@ -320,6 +321,21 @@ pub enum AlertData {
     name: String,
   },
 
+  /// An image scan found vulnerabilities
+  /// exceeding the configured threshold.
+  ImageVulnerabilities {
+    /// The type of resource using the image
+    resource_type: ResourceTargetVariant,
+    /// The id of the resource
+    id: String,
+    /// The name of the resource
+    name: String,
+    /// The scanned image
+    image: String,
+    /// The number of findings at each severity
+    counts: VulnerabilityCounts,
+  },
+
   /// A procedure has failed
   ProcedureFailed {
     /// The id of the procedure
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString, IntoStaticStr};
+use typeshare::typeshare;
+
+/// The types of items which can be selectively
+/// restored out of a database backup.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Hash,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+  AsRefStr,
+  IntoStaticStr,
+)]
+#[strum(ascii_case_insensitive)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum BackupItemVariant {
+  Swarm,
+  Server,
+  Stack,
+  Deployment,
+  Build,
+  Repo,
+  Procedure,
+  Action,
+  Builder,
+  Alerter,
+  ResourceSync,
+  Variable,
+  UserGroup,
+}
+
+impl BackupItemVariant {
+  pub const ALL: [BackupItemVariant; 13] = [
+    BackupItemVariant::Swarm,
+    BackupItemVariant::Server,
+    BackupItemVariant::Stack,
+    BackupItemVariant::Deployment,
+    BackupItemVariant::Build,
+    BackupItemVariant::Repo,
+    BackupItemVariant::Procedure,
+    BackupItemVariant::Action,
+    BackupItemVariant::Builder,
+    BackupItemVariant::Alerter,
+    BackupItemVariant::ResourceSync,
+    BackupItemVariant::Variable,
+    BackupItemVariant::UserGroup,
+  ];
+
+  /// The database collection holding items of this type.
+  /// This is also the backup file name, minus the `.gz` extension.
+  pub fn collection(self) -> &'static str {
+    self.into()
+  }
+}
+
+/// Identifies a single item inside a backup.
+/// Items are selected by name, which is unique for each item type.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct BackupItemTarget {
+  /// The type of the item.
+  pub variant: BackupItemVariant,
+  /// The name of the item.
+  pub name: String,
+}
+
+/// Parses targets in the form `Variant:name`, eg `Stack:my-stack`.
+/// The variant is case insensitive.
+impl FromStr for BackupItemTarget {
+  type Err = anyhow::Error;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (variant, name) = s.split_once(':').with_context(|| {
+      format!("Item '{s}' must be in the form 'Variant:name'")
+    })?;
+    let variant = variant.trim().parse().map_err(|_| {
+      anyhow!(
+        "Unrecognized item type '{variant}'. Options: {}",
+        BackupItemVariant::ALL
+          .iter()
+          .map(|v| v.as_ref())
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+    })?;
+    let name = name.trim();
+    if name.is_empty() {
+      return Err(anyhow!("Item '{s}' is missing the name"));
+    }
+    Ok(BackupItemTarget {
+      variant,
+      name: name.to_string(),
+    })
+  }
+}
+
+/// An item found in a backup, compared against
+/// the current state of the database.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct BackupItem {
+  /// The type of the item.
+  pub variant: BackupItemVariant,
+  /// The database id of the item in the backup.
+  /// Empty for Variables, which are identified by name.
+  pub id: String,
+  /// The name of the item in the backup.
+  pub name: String,
+  /// How the backup contents compare to the current database.
+  pub state: BackupItemState,
+  /// The fields which would change on restore.
+  /// Only populated for [BackupItemState::Changed].
+  pub diff: Vec<BackupItemFieldDiff>,
+}
+
+/// How an item in the backup compares against the current database.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  AsRefStr,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum BackupItemState {
+  /// The item is the same in the backup and the database.
+  Unchanged,
+  /// The item exists in both, but the contents differ.
+  Changed,
+  /// The item exists in the backup, but not in the database.
+  /// Restoring it will recreate it.
+  Missing,
+}
+
+/// A single field difference between the current item and the backup.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct BackupItemFieldDiff {
+  /// The dot separated path to the field, eg `config.file_contents`.
+  pub field: String,
+  /// The current value, serialized as JSON. Empty if not set.
+  pub current: String,
+  /// The value in the backup, serialized as JSON. Empty if not set.
+  pub backup: String,
+}
@@ -5,7 +5,7 @@ use derive_builder::Builder;
 use derive_default_builder::DefaultBuilder;
 use partial_derive2::Partial;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumString};
 use typeshare::typeshare;
 
 use crate::{
@@ -19,8 +19,10 @@ use crate::{
 };
 
 use super::{
-  SystemCommand, Version,
+  SystemCommand, Version, WebhookTrigger,
   resource::{Resource, ResourceListItem, ResourceQuery},
+  sbom::SbomFormat,
+  scan::VulnerabilityThreshold,
 };
 
 #[cfg(feature = "utoipa")]
@@ -155,6 +157,118 @@ impl Build {
     }
   }
 
+  /// The image ref used with the `Registry` build cache backend.
+  /// Uses `cache_ref` if set, otherwise `{image}:buildcache`
+  /// on the first image registry.
+  /// Returns `None` if there is no image registry to derive it from.
+  pub fn get_registry_cache_ref(&self) -> Option<String> {
+    if !self.config.cache_ref.is_empty() {
+      return Some(self.config.cache_ref.clone());
+    }
+    let registry = self.config.image_registry.first()?;
+    if registry.domain.is_empty() || registry.account.is_empty() {
+      return None;
+    }
+    let image_tag_postfix = if self.config.image_tag.is_empty() {
+      String::new()
+    } else {
+      format!("-{}", self.config.image_tag)
+    };
+    Some(format!(
+      "{}:buildcache{image_tag_postfix}",
+      self.get_deployment_image_name()
+    ))
+  }
+
+  /// The tag pushed by a single builder when the platforms are
+  /// fanned out across `platform_builders`, before being stitched
+  /// into the manifest list. Eg `1.19.5-linux-arm64`.
+  pub fn get_platform_image_tag(
+    &self,
+    platforms: &[String],
+  ) -> String {
+    let image_tag_postfix = if self.config.image_tag.is_empty() {
+      String::new()
+    } else {
+      format!("-{}", self.config.image_tag)
+    };
+    let platforms = platforms
+      .iter()
+      .map(|platform| platform.replace(['/', ':'], "-"))
+      .collect::<Vec<_>>()
+      .join("-");
+    format!("{}{image_tag_postfix}-{platforms}", self.config.version)
+  }
+
+  /// Groups the target `platforms` by the Builder which builds them.
+  /// Platforms without an entry in `platform_builders`
+  /// are built on the main `builder_id`.
+  /// Returns an empty list if the build isn't fanned out.
+  pub fn get_platform_builder_groups(
+    &self,
+  ) -> Vec<(String, Vec<String>)> {
+    let BuildConfig {
+      builder_id,
+      platforms,
+      platform_builders,
+      ..
+    } = &self.config;
+    if platform_builders.is_empty() {
+      return Vec::new();
+    }
+    let mut groups = Vec::<(String, Vec<String>)>::new();
+    for platform in platforms {
+      let builder = platform_builders
+        .iter()
+        .find(|pb| &pb.platform == platform)
+        .map(|pb| &pb.builder_id)
+        .filter(|builder| !builder.is_empty())
+        .unwrap_or(builder_id);
+      match groups.iter_mut().find(|(id, _)| id == builder) {
+        Some((_, platforms)) => platforms.push(platform.clone()),
+        None => {
+          groups.push((builder.clone(), vec![platform.clone()]))
+        }
+      }
+    }
+    groups
+  }
+
+  /// The image tag of a `matrix` variant,
+  /// the variant tag appended to the Build image tag.
+  pub fn get_matrix_image_tag(
+    &self,
+    entry: &BuildMatrixEntry,
+  ) -> String {
+    match (
+      self.config.image_tag.is_empty(),
+      entry.image_tag.is_empty(),
+    ) {
+      (_, true) => self.config.image_tag.clone(),
+      (true, false) => entry.image_tag.clone(),
+      (false, false) => {
+        format!("{}-{}", self.config.image_tag, entry.image_tag)
+      }
+    }
+  }
+
+  /// The build adjusted to build the `matrix` variant.
+  /// The variant build args are added after the Build build args,
+  /// so take precedence.
+  pub fn get_matrix_build(&self, entry: &BuildMatrixEntry) -> Build {
+    let mut build = self.clone();
+    build.config.image_tag = self.get_matrix_image_tag(entry);
+    if !entry.build_args.is_empty() {
+      build.config.build_args =
+        format!("{}\n{}", self.config.build_args, entry.build_args);
+    }
+    if !entry.builder_id.is_empty() {
+      build.config.builder_id.clone_from(&entry.builder_id);
+    }
+    build.config.matrix = Vec::new();
+    build
+  }
+
   /// Used in build -> deployment flow to choose the
   /// associated latest tag.
   ///
@@ -171,11 +285,18 @@ impl Build {
           include_commit_tag,
           repo,
           linked_repo,
+          matrix,
           ..
         },
       ..
     } = self;
 
+    // Matrix builds deploy the first variant
+    let image_tag = match matrix.first() {
+      Some(entry) => self.get_matrix_image_tag(entry),
+      None => image_tag.clone(),
+    };
+
     let image_tag_postfix = if image_tag.is_empty() {
       format_args!("")
     } else {
@@ -376,6 +497,21 @@ pub struct BuildConfig {
   #[partial_default(default_include_tag())]
   pub include_commit_tag: bool,
 
+  /// Retention policy for `CleanupBuildImages`.
+  /// Keep at least the latest N versions in the image registries.
+  /// 0 means the number of versions is not limited.
+  #[serde(default)]
+  #[builder(default)]
+  pub retain_versions: I64,
+
+  /// Retention policy for `CleanupBuildImages`.
+  /// Keep versions pushed within the last N days,
+  /// even if outside `retain_versions`.
+  /// 0 means versions are not kept by age.
+  #[serde(default)]
+  #[builder(default)]
+  pub retain_days: I64,
+
   /// Configure quick links that are displayed in the resource header
   #[serde(default, deserialize_with = "string_list_deserializer")]
   #[partial_attr(serde(
@@ -441,6 +577,54 @@ pub struct BuildConfig {
   #[builder(default)]
   pub webhook_secret: String,
 
+  /// Report the result of webhook triggered executions
+  /// to the git provider as a commit status, linking to the Update.
+  /// Supports Github, Gitea / Forgejo, and Gitlab.
+  /// Requires `git_account` to be configured.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_commit_status: bool,
+
+  /// Only trigger incoming webhooks when a changed file matches
+  /// one of these paths. Supports wildcard syntax, eg `services/api/**`.
+  /// If empty, any change triggers the webhook.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_include_paths: Vec<String>,
+
+  /// Don't trigger incoming webhooks when all the changed files
+  /// match these paths. Supports wildcard syntax, eg `docs/**`.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_exclude_paths: Vec<String>,
+
+  /// Whether incoming webhooks trigger on pushes to the branch,
+  /// or on pushed tags matching `webhook_tag_pattern`.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_trigger: WebhookTrigger,
+
+  /// With the `Tag` webhook trigger, only pushed tags matching
+  /// this pattern trigger the webhook. The pushed tag is set as the `commit`.
+  /// Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_tag_pattern: String,
+
+  /// With the `Tag` webhook trigger, set the build `version`
+  /// from the semver in the pushed tag, eg `v1.2.3` -> `1.2.3`.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_tag_version: bool,
+
   /// If this is checked, the build will source the files on the host.
   /// Use `build_path` and `dockerfile_path` to specify the path on the host.
   /// This is useful for those who wish to setup their files on the host,
@@ -482,6 +666,96 @@ pub struct BuildConfig {
   #[builder(default)]
   pub use_buildx: bool,
 
+  /// The platforms to build the image for, eg `linux/amd64`, `linux/arm64`.
+  /// Passed to the build with `--platform`.
+  /// More than one platform requires `use_buildx`.
+  /// If empty, builds for the platform of the builder.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub platforms: Vec<String>,
+
+  /// Build some of the `platforms` on different Builders,
+  /// eg. an arm64 Server builder alongside an amd64 AWS builder.
+  /// The Builders run in parallel, each pushing a platform specific tag,
+  /// which are then combined into a manifest list on the main builder.
+  /// Platforms without an entry here are built on the main builder.
+  /// Requires an image registry to push to.
+  #[serde(default)]
+  #[builder(default)]
+  pub platform_builders: Vec<PlatformBuilder>,
+
+  /// Build variants of the image, in parallel across Builders,
+  /// eg. for different base images.
+  /// Each variant pushes the usual tags, postfixed with the variant `image_tag`.
+  /// Deployments attached to the Build use the first variant.
+  #[serde(default)]
+  #[builder(default)]
+  pub matrix: Vec<BuildMatrixEntry>,
+
+  /// Import / export the build cache using buildx
+  /// `--cache-from` / `--cache-to`, so builds on
+  /// fresh builders (eg. AWS) don't start cold.
+  /// Requires `use_buildx`. Default: `None`
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_backend: BuildCacheBackend,
+
+  /// The cache location. For the `Registry` backend, an image ref,
+  /// eg `ghcr.io/myorg/app:buildcache`. For the `Local` backend,
+  /// a directory on the builder.
+  /// If empty, it is derived for the build: `{image}:buildcache` on the
+  /// first image registry, or `{build_dir}/.buildcache/{build}`.
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_ref: String,
+
+  /// Export the layers of all build stages (`mode=max`),
+  /// not just the final image layers. Caches multi-stage builds
+  /// better, at the cost of a larger cache.
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_mode_max: bool,
+
+  /// Scan the built image for vulnerabilities after the build.
+  /// Requires `trivy` or `grype` to be installed on the builder.
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_image: bool,
+
+  /// Fail the build if the scan finds vulnerabilities
+  /// at or above this severity. When set, single platform images
+  /// are only pushed once they pass the scan. Default: `None`
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_threshold: VulnerabilityThreshold,
+
+  /// Generate an SBOM in this format for the built image.
+  /// Requires `syft` to be installed on the builder.
+  /// Default: `None`
+  #[serde(default)]
+  #[builder(default)]
+  pub sbom_format: SbomFormat,
+
+  /// The name of the secret holding a `cosign` private key
+  /// used to sign the image after it is pushed.
+  /// The secret is looked up in the Core secrets,
+  /// then in the builder's Periphery secrets.
+  /// Requires `cosign` to be installed on the builder.
+  /// Leave empty to skip signing.
+  #[serde(default)]
+  #[builder(default)]
+  pub signing_key: String,
+
+  /// The name of the secret holding the password
+  /// for the `signing_key`, if it is encrypted.
+  #[serde(default)]
+  #[builder(default)]
+  pub signing_key_password: String,
+
   /// Any extra docker cli arguments to be included in the build command
   #[serde(default, deserialize_with = "string_list_deserializer")]
   #[partial_attr(serde(
@@ -611,6 +885,8 @@ impl Default for BuildConfig {
       include_latest_tag: default_include_tag(),
       include_version_tags: default_include_tag(),
       include_commit_tag: default_include_tag(),
+      retain_versions: Default::default(),
+      retain_days: Default::default(),
       links: Default::default(),
       linked_repo: Default::default(),
       git_provider: default_git_provider(),
@@ -627,9 +903,26 @@ impl Default for BuildConfig {
       labels: Default::default(),
       extra_args: Default::default(),
       use_buildx: Default::default(),
+      platforms: Default::default(),
+      platform_builders: Default::default(),
+      matrix: Default::default(),
+      cache_backend: Default::default(),
+      cache_ref: Default::default(),
+      cache_mode_max: Default::default(),
+      scan_image: Default::default(),
+      scan_threshold: Default::default(),
+      sbom_format: Default::default(),
+      signing_key: Default::default(),
+      signing_key_password: Default::default(),
       image_registry: Default::default(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
+      webhook_commit_status: Default::default(),
+      webhook_include_paths: Default::default(),
+      webhook_exclude_paths: Default::default(),
+      webhook_trigger: Default::default(),
+      webhook_tag_pattern: Default::default(),
+      webhook_tag_version: Default::default(),
       dockerfile: Default::default(),
       files_on_host: Default::default(),
     }
@@ -647,6 +940,74 @@ impl utoipa::PartialSchema for PartialBuildConfig {
 #[cfg(feature = "utoipa")]
 impl utoipa::ToSchema for PartialBuildConfig {}
 
+/// Builds a target platform on a specific Builder.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlatformBuilder {
+  /// The target platform, eg `linux/arm64`.
+  /// Should also be included in `platforms`.
+  pub platform: String,
+  /// The Builder (id or name) to build the platform on.
+  #[serde(default, alias = "builder")]
+  #[cfg_attr(feature = "schemars", schemars(rename = "builder"))]
+  pub builder_id: String,
+}
+
+/// A variant of the image built by the Build `matrix`.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BuildMatrixEntry {
+  /// Postfixed to the image tags of the variant,
+  /// eg. `alpine` pushes `:1.19.5-alpine` and `:latest-alpine`.
+  /// Required, and must be unique across the matrix.
+  pub image_tag: String,
+  /// Build args for the variant, in addition to the Build `build_args`.
+  /// Args with the same name override the Build `build_args`.
+  #[serde(default, deserialize_with = "env_vars_deserializer")]
+  pub build_args: String,
+  /// Build the variant on a different Builder (id or name).
+  /// Uses the Build `builder_id` if empty.
+  #[serde(default, alias = "builder")]
+  #[cfg_attr(feature = "schemars", schemars(rename = "builder"))]
+  pub builder_id: String,
+}
+
+/// Where buildx imports / exports the build cache.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum BuildCacheBackend {
+  /// Only use the builder's own cache.
+  #[default]
+  None,
+  /// Push the cache to an image registry (`type=registry`).
+  /// Shares the cache between builders.
+  Registry,
+  /// Write the cache to a directory on the builder (`type=local`).
+  /// Requires a buildx builder using the `docker-container` driver.
+  Local,
+}
+
 /// Configuration for an image registry
 #[typeshare]
 #[derive(
@@ -706,6 +1067,7 @@ impl ImageRegistryConfig {
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct BuildActionState {
   pub building: bool,
+  pub cleaning_images: bool,
 }
 
 #[typeshare]
@@ -27,6 +27,18 @@ pub enum DatabaseCommand {
     /// Example: `2025-08-01_05-04-53`
     #[arg(long, short = 'r')]
     restore_folder: Option<PathBuf>,
+    /// Only restore the selected resources / variables / user groups,
+    /// leaving the rest of the database untouched.
+    /// A diff against the target database is shown before applying.
+    /// Pass multiple times to select multiple items.
+    ///
+    /// Format: `Variant:name`, eg. `--only Stack:my-stack --only Variable:MY_VAR`
+    #[arg(long, short = 'o')]
+    only: Vec<String>,
+    /// List the resources / variables / user groups in the backup,
+    /// and how they compare to the target database, without restoring.
+    #[arg(long, short = 'l', default_value_t = false)]
+    list: bool,
     /// Whether to index the target database. Default: true
     #[arg(long, short = 'i', default_value_t = true)]
     index: bool,
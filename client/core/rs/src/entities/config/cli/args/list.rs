@@ -121,6 +121,10 @@ pub enum ListCommand {
   /// List Alerters (aliases: `alerter`, `alrt`)
   #[clap(alias = "alerter", alias = "alrt")]
   Alerters(ResourceFilters),
+  /// List the repositories in an image registry,
+  /// or the tags of a repository (aliases: `reg`, `rg`)
+  #[clap(alias = "reg", alias = "rg")]
+  Registry(RegistryFilters),
 }
 
 #[derive(Debug, Clone, clap::Parser)]
@@ -177,3 +181,19 @@ pub struct ResourceFilters {
   #[arg(long, short = 'f', default_value_t = super::CliFormat::Table)]
   pub format: super::CliFormat,
 }
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RegistryFilters {
+  /// The registry domain, eg. `ghcr.io`
+  pub domain: String,
+  /// List the tags of this repository, eg. `moghtech/komodo-core`.
+  /// If not provided, lists the registry repositories.
+  pub repository: Option<String>,
+  /// The registry account configured in Komodo to authenticate with.
+  /// (alias `u`)
+  #[arg(long, short = 'u', default_value_t = String::new())]
+  pub account: String,
+  /// Specify the format of the output.
+  #[arg(long, short = 'f', default_value_t = super::CliFormat::Table)]
+  pub format: super::CliFormat,
+}
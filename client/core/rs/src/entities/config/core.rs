@@ -109,6 +109,8 @@ pub struct Env {
   pub komodo_keep_stats_for_days: Option<u64>,
   /// Override `keep_alerts_for_days`
   pub komodo_keep_alerts_for_days: Option<u64>,
+  /// Override `keep_revisions_for_days`
+  pub komodo_keep_revisions_for_days: Option<u64>,
   /// Override `webhook_secret`
   pub komodo_webhook_secret: Option<String>,
   /// Override `webhook_secret` with file
@@ -311,6 +313,10 @@ pub struct Env {
   pub komodo_repo_directory: Option<PathBuf>,
   /// Override `action_directory`
   pub komodo_action_directory: Option<PathBuf>,
+  /// Override `backups_folder`
+  pub komodo_backups_folder: Option<PathBuf>,
+  /// Override `max_file_transfer_size_mb`
+  pub komodo_max_file_transfer_size_mb: Option<u64>,
 }
 
 fn default_core_config_paths() -> Vec<PathBuf> {
@@ -702,6 +708,12 @@ pub struct CoreConfig {
   #[serde(default = "default_prune_days")]
   pub keep_alerts_for_days: u64,
 
+  /// Number of days to keep resource revisions, or 0 to disable pruning.
+  /// Revisions older than this number of days are deleted on a daily cycle
+  /// Default: 90
+  #[serde(default = "default_keep_revisions_days")]
+  pub keep_revisions_for_days: u64,
+
   // ==================
   // = Poll Intervals =
   // ==================
@@ -817,6 +829,19 @@ pub struct CoreConfig {
   #[serde(default = "default_action_directory")]
   pub action_directory: PathBuf,
 
+  /// Specify the folder containing the dated database backups,
+  /// used to selectively restore items. Should point to the same
+  /// folder the database backups are written to.
+  /// Default: `/backups`
+  #[serde(default = "default_backups_folder")]
+  pub backups_folder: PathBuf,
+
+  /// The maximum size in megabytes of files which can be
+  /// downloaded from / uploaded to containers and server hosts.
+  /// Default: 10
+  #[serde(default = "default_max_file_transfer_size_mb")]
+  pub max_file_transfer_size_mb: u64,
+
   /// The path to the built ui folder.
   #[serde(default = "default_ui_path")]
   pub ui_path: String,
@@ -908,10 +933,22 @@ fn default_action_directory() -> PathBuf {
   PathBuf::from("/action-cache")
 }
 
+fn default_backups_folder() -> PathBuf {
+  PathBuf::from("/backups")
+}
+
+fn default_max_file_transfer_size_mb() -> u64 {
+  10
+}
+
 fn default_prune_days() -> u64 {
   14
 }
 
+fn default_keep_revisions_days() -> u64 {
+  90
+}
+
 fn default_poll_interval() -> Timelength {
   Timelength::OneHour
 }
@@ -992,6 +1029,7 @@ impl Default for CoreConfig {
       unsafe_unsanitized_startup_config: Default::default(),
       keep_stats_for_days: default_prune_days(),
       keep_alerts_for_days: default_prune_days(),
+      keep_revisions_for_days: default_keep_revisions_days(),
       resource_poll_interval: default_poll_interval(),
       monitoring_interval: default_monitoring_interval(),
       aws: Default::default(),
@@ -1008,6 +1046,8 @@ impl Default for CoreConfig {
       sync_directory: default_sync_directory(),
       repo_directory: default_repo_directory(),
       action_directory: default_action_directory(),
+      backups_folder: default_backups_folder(),
+      max_file_transfer_size_mb: default_max_file_transfer_size_mb(),
     }
   }
 }
@@ -1038,6 +1078,7 @@ impl CoreConfig {
       monitoring_interval: config.monitoring_interval,
       keep_stats_for_days: config.keep_stats_for_days,
       keep_alerts_for_days: config.keep_alerts_for_days,
+      keep_revisions_for_days: config.keep_revisions_for_days,
       logging: config.logging,
       pretty_startup_config: config.pretty_startup_config,
       unsafe_unsanitized_startup_config: config
@@ -1156,9 +1197,16 @@ impl CoreConfig {
       repo_directory: config.repo_directory,
       action_directory: config.action_directory,
       sync_directory: config.sync_directory,
+      backups_folder: config.backups_folder,
+      max_file_transfer_size_mb: config.max_file_transfer_size_mb,
     }
   }
 
+  /// The maximum file transfer size in bytes.
+  pub fn max_file_transfer_size(&self) -> u64 {
+    self.max_file_transfer_size_mb * 1024 * 1024
+  }
+
   pub fn oidc_enabled(&self) -> bool {
     self.oidc_enabled
       && !self.oidc_provider.is_empty()
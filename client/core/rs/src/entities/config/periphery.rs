@@ -181,6 +181,10 @@ pub struct Env {
   /// Override `disable_container_terminals`
   #[serde(alias = "periphery_disable_container_exec")]
   pub periphery_disable_container_terminals: Option<bool>,
+  /// Override `disable_file_transfers`
+  pub periphery_disable_file_transfers: Option<bool>,
+  /// Override `disable_actions`
+  pub periphery_disable_actions: Option<bool>,
   /// Override `stats_polling_rate`
   pub periphery_stats_polling_rate: Option<Timelength>,
   /// Override `container_stats_polling_rate`
@@ -379,6 +383,21 @@ pub struct PeripheryConfig {
   #[serde(default, alias = "disable_container_exec")]
   pub disable_container_terminals: bool,
 
+  /// Whether to disable the file browser api
+  /// and disallow listing, downloading and uploading
+  /// files on containers and the host.
+  /// Host / container file transfers are also disabled
+  /// by `disable_terminals` / `disable_container_terminals`.
+  /// Default: false
+  #[serde(default)]
+  pub disable_file_transfers: bool,
+
+  /// Whether to disable running Actions on this host,
+  /// for Actions configured with this Server.
+  /// Default: false
+  #[serde(default)]
+  pub disable_actions: bool,
+
   /// The rate at which the system stats will be polled to update the cache.
   /// Options: https://docs.rs/komodo_client/latest/komodo_client/entities/enum.Timelength.html
   /// Default: `5-sec`
@@ -484,6 +503,8 @@ impl Default for PeripheryConfig {
       default_terminal_command: default_default_terminal_command(),
       disable_terminals: Default::default(),
       disable_container_terminals: Default::default(),
+      disable_file_transfers: Default::default(),
+      disable_actions: Default::default(),
       stats_polling_rate: default_stats_polling_rate(),
       container_stats_polling_rate:
         default_container_stats_polling_rate(),
@@ -535,6 +556,8 @@ impl PeripheryConfig {
       default_terminal_command: self.default_terminal_command.clone(),
       disable_terminals: self.disable_terminals,
       disable_container_terminals: self.disable_container_terminals,
+      disable_file_transfers: self.disable_file_transfers,
+      disable_actions: self.disable_actions,
       stats_polling_rate: self.stats_polling_rate,
       container_stats_polling_rate: self.container_stats_polling_rate,
       legacy_compose_cli: self.legacy_compose_cli,
@@ -630,6 +653,11 @@ impl PeripheryConfig {
     }
   }
 
+  /// Action scripts are written here while they run.
+  pub fn action_dir(&self) -> PathBuf {
+    self.root_directory.join("actions")
+  }
+
   pub fn build_dir(&self) -> PathBuf {
     if let Some(dir) = &self.build_dir {
       dir.to_owned()
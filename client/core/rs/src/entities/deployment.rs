@@ -10,8 +10,8 @@ use typeshare::typeshare;
 use crate::{
   deserializers::*,
   entities::{
-    EnvironmentVar, ImageDigest, environment_vars_from_str,
-    optional_str,
+    EnvironmentVar, ImageDigest, ImageUpdatePolicy,
+    MaintenanceWindow, environment_vars_from_str, optional_str,
   },
   parsers::parse_key_value_list,
 };
@@ -20,6 +20,7 @@ use super::{
   TerminationSignal, Version,
   docker::container::ContainerStateStatusEnum,
   resource::{Resource, ResourceListItem, ResourceQuery},
+  scan::VulnerabilityThreshold,
 };
 
 #[cfg(feature = "utoipa")]
@@ -84,8 +85,12 @@ pub struct DeploymentListItemInfo {
   pub custom_name: String,
   /// The image attached to the deployment.
   pub image: String,
-  /// Whether there is a newer image available at the same tag.
+  /// Whether there is a newer image available at the same tag,
+  /// or a newer version tag allowed by the update policy.
   pub update_available: bool,
+  /// The image with the newer version tag, if available.
+  #[serde(default)]
+  pub latest_image: Option<String>,
   /// The swarm that deployment is deployed on, when in Swarm mode.
   pub swarm_id: String,
   /// The name of the swarm that deployment is deployed on, when in Swarm mode.
@@ -108,6 +113,10 @@ pub struct DeploymentInfo {
   /// This includes both the image name / tag, and the specific digest hash.
   #[serde(default)]
   pub latest_image_digest: ImageDigest,
+  /// The image with the newest version tag allowed by the update policy.
+  /// Empty with the `digest` policy, or if there is no newer tag.
+  #[serde(default)]
+  pub latest_image: String,
   /// The container / service name used at the time of the last deploy.
   /// Kept to match the Deployment to its container / service
   /// even if the name configuration changes before the next deploy.
@@ -192,6 +201,20 @@ pub struct DeploymentConfig {
   #[builder(default)]
   pub poll_for_updates: bool,
 
+  /// How image updates are detected.
+  /// - `digest`: A newer image pushed to the same tag. Default.
+  /// - `patch` / `minor` / `major`: Newer version tags in the registry,
+  ///   up to the given semver level.
+  #[serde(default)]
+  #[builder(default)]
+  pub update_policy: ImageUpdatePolicy,
+
+  /// Only consider registry tags matching this regex
+  /// for version tag updates.
+  #[serde(default)]
+  #[builder(default)]
+  pub update_tag_filter: String,
+
   /// Whether to automatically redeploy when
   /// newer a image is found. Will implicitly
   /// enable `poll_for_updates`, you don't need to
@@ -200,12 +223,47 @@ pub struct DeploymentConfig {
   #[builder(default)]
   pub auto_update: bool,
 
+  /// Restrict auto updates to these windows.
+  /// Updates found outside of the windows are queued,
+  /// and deployed once a window opens.
+  #[serde(default)]
+  #[builder(default)]
+  pub auto_update_windows: Vec<MaintenanceWindow>,
+
+  /// Also allow auto updates during the maintenance windows
+  /// of the attached Server / Swarm. If no windows are
+  /// configured at all, updates are deployed immediately.
+  #[serde(default)]
+  #[builder(default)]
+  pub auto_update_in_maintenance: bool,
+
   /// Whether to send ContainerStateChange alerts for this deployment.
   #[serde(default = "default_send_alerts")]
   #[builder(default = "default_send_alerts()")]
   #[partial_default(default_send_alerts())]
   pub send_alerts: bool,
 
+  /// Scan the deployed image for vulnerabilities after every deploy.
+  /// Requires `trivy` or `grype` to be installed on the server.
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_image: bool,
+
+  /// Send an `ImageVulnerabilities` alert if the scan finds vulnerabilities
+  /// at or above this severity. Default: `None`
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_threshold: VulnerabilityThreshold,
+
+  /// Require the image to have a valid `cosign` signature
+  /// from at least one of these public keys before deploying.
+  /// The verified digest is deployed, rather than the tag.
+  /// Requires `cosign` to be installed on the server.
+  /// Leave empty to skip verification.
+  #[serde(default)]
+  #[builder(default)]
+  pub signature_keys: Vec<String>,
+
   /// Configure quick links that are displayed in the resource header
   #[serde(default)]
   #[builder(default)]
@@ -353,8 +411,15 @@ impl Default for DeploymentConfig {
       skip_secret_interp: Default::default(),
       redeploy_on_build: Default::default(),
       poll_for_updates: Default::default(),
+      update_policy: Default::default(),
+      update_tag_filter: Default::default(),
       auto_update: Default::default(),
+      auto_update_windows: Default::default(),
+      auto_update_in_maintenance: Default::default(),
       send_alerts: default_send_alerts(),
+      scan_image: Default::default(),
+      scan_threshold: Default::default(),
+      signature_keys: Default::default(),
       links: Default::default(),
       network: default_network(),
       restart: Default::default(),
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{I64, U64};
+
+/// An entry in a directory listing,
+/// either inside a container or on the server host.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FileEntry {
+  /// The file name.
+  pub name: String,
+  /// The full path to the file.
+  pub path: String,
+  /// The type of the entry.
+  pub kind: FileEntryKind,
+  /// The size of the file in bytes.
+  pub size: U64,
+  /// When the file was last modified.
+  /// Unix timestamp milliseconds.
+  pub modified: I64,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum FileEntryKind {
+  File,
+  Directory,
+  Symlink,
+  #[default]
+  Other,
+}
+
+impl FileEntryKind {
+  /// Parses the file type as output by `stat -c %F`.
+  pub fn from_stat(file_type: &str) -> FileEntryKind {
+    match file_type {
+      "regular file" | "regular empty file" => FileEntryKind::File,
+      "directory" => FileEntryKind::Directory,
+      "symbolic link" => FileEntryKind::Symlink,
+      _ => FileEntryKind::Other,
+    }
+  }
+}
+
+/// A file downloaded from a container or server host.
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FileDownload {
+  /// The full path to the file.
+  pub path: String,
+  /// The size of the file in bytes.
+  pub size: U64,
+  /// The file contents, base64 encoded.
+  pub contents: String,
+}
@@ -29,6 +29,8 @@ pub mod alert;
 pub mod alerter;
 /// Subtypes of [ApiKey][api_key::ApiKey].
 pub mod api_key;
+/// Subtypes of [BackupItem][backup::BackupItem].
+pub mod backup;
 /// Subtypes of [Build][build::Build].
 pub mod build;
 /// Subtypes of [Builder][builder::Builder].
@@ -39,22 +41,34 @@ pub mod config;
 pub mod deployment;
 /// Networks, Images, Containers.
 pub mod docker;
+/// Subtypes of [FileEntry][file::FileEntry].
+pub mod file;
 /// Subtypes of [LogConfig][logger::LogConfig].
 pub mod logger;
 /// Subtypes of [CreationKey][creation_key::CreationKey]
 pub mod onboarding_key;
 /// Subtypes of [Permission][permission::Permission].
 pub mod permission;
+/// Subtypes of [StackPreview][preview::StackPreview]
+pub mod preview;
 /// Subtypes of [Procedure][procedure::Procedure].
 pub mod procedure;
 /// Subtypes of [GitProviderAccount][provider::GitProviderAccount] and [DockerRegistryAccount][provider::DockerRegistryAccount]
 pub mod provider;
+/// Subtypes of [RegistryTag][registry::RegistryTag]
+pub mod registry;
 /// Subtypes of [Repo][repo::Repo].
 pub mod repo;
 /// Subtypes of [CoreReport][core_report::CoreReport]
 pub mod report;
 /// Subtypes of [Resource][resource::Resource].
 pub mod resource;
+/// Subtypes of [ResourceRevision][revision::ResourceRevision]
+pub mod revision;
+/// Subtypes of [BuildSbom][sbom::BuildSbom]
+pub mod sbom;
+/// Subtypes of [ImageScan][scan::ImageScan]
+pub mod scan;
 /// Subtypes of [Schedule][schedule::Schedule]
 pub mod schedule;
 /// Subtypes of [Server][server::Server].
@@ -388,6 +402,14 @@ impl TryFrom<&str> for Version {
   }
 }
 
+impl std::str::FromStr for Version {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Version::try_from(s)
+  }
+}
+
 impl Version {
   pub fn increment(&mut self) {
     self.patch += 1;
@@ -524,6 +546,38 @@ impl ImageDigest {
   }
 }
 
+/// How image updates are detected when polling for updates.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ImageUpdatePolicy {
+  /// A newer image pushed to the same tag. Default.
+  #[default]
+  Digest,
+  /// Newer version tags with the same major and minor version,
+  /// eg `16.2.1` -> `16.2.3`.
+  Patch,
+  /// Newer version tags with the same major version,
+  /// eg `16.2` -> `16.3`.
+  Minor,
+  /// Any newer version tag, eg `16.2` -> `17.0`.
+  Major,
+}
+
 /// Represents a scheduled maintenance window
 #[typeshare]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -568,6 +622,31 @@ fn default_enabled() -> bool {
   true
 }
 
+/// The git event which triggers incoming webhooks.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Default,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WebhookTrigger {
+  /// Trigger on pushes to the configured branch.
+  #[default]
+  Branch,
+  /// Trigger on pushed tags matching the tag pattern,
+  /// checking out the pushed tag.
+  Tag,
+}
+
 #[typeshare]
 #[derive(
   Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
@@ -1246,7 +1325,10 @@ pub enum Operation {
   PruneVolumes,
   PruneDockerBuilders,
   PruneBuildx,
+  PruneBuildCache,
   PruneSystem,
+  WriteContainerFile,
+  WriteServerFile,
 
   // Stack
   CreateStack,
@@ -1256,6 +1338,7 @@ pub enum Operation {
   WriteStackContents,
   RefreshStackCache,
   PullStack,
+  ValidateStack,
   DeployStack,
   StartStack,
   RestartStack,
@@ -1298,6 +1381,7 @@ pub enum Operation {
   DeleteBuild,
   RunBuild,
   CancelBuild,
+  CleanupBuildImages,
   WriteDockerfile,
 
   // Repo
@@ -1355,6 +1439,7 @@ pub enum Operation {
   GlobalAutoUpdate,
   RotateAllServerKeys,
   RotateCoreKeys,
+  RestoreBackupItems,
 
   // Variable
   CreateVariable,
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::{I64, MongoId};
+
+/// A preview environment, a temporary Stack deployed
+/// for an open pull request from a template Stack
+/// with `preview_enabled`.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", unique_doc_index({ "stack_id": 1, "pr_number": 1 }))]
+pub struct StackPreview {
+  /// The Mongo ID of the preview.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized StackPreview) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// Unix timestamp in milliseconds the preview was created
+  pub created_at: I64,
+
+  /// Unix timestamp in milliseconds the preview was last deployed
+  pub updated_at: I64,
+
+  /// The id of the template Stack
+  #[cfg_attr(feature = "mongo", index)]
+  pub stack_id: String,
+
+  /// The id of the preview Stack
+  #[cfg_attr(feature = "mongo", index)]
+  pub preview_stack_id: String,
+
+  /// The pull request number
+  pub pr_number: I64,
+
+  /// The pull request source branch
+  pub branch: String,
+}
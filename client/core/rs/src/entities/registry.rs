@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::I64;
+
+/// An image tag in an image registry repository.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RegistryTag {
+  /// The tag, eg. `1.19.5`
+  pub tag: String,
+  /// The manifest digest the tag points to.
+  pub digest: String,
+  /// The total size of the image layers in bytes.
+  /// For multi-platform images, this is the total across all platforms.
+  pub size: I64,
+  /// Unix timestamp in milliseconds the image was created,
+  /// if it could be determined.
+  pub created: Option<I64>,
+  /// The image platforms, eg. `linux/amd64`.
+  pub platforms: Vec<String>,
+  /// The Builds which push this image tag.
+  pub builds: Vec<RegistryTagResource>,
+  /// The Deployments which currently use this image tag,
+  /// either in their config or in their running container.
+  pub deployments: Vec<RegistryTagResource>,
+  /// The Stacks which currently use this image tag,
+  /// either in their compose files or in their running services.
+  pub stacks: Vec<RegistryTagResource>,
+}
+
+/// A resource related to a [RegistryTag].
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Default,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RegistryTagResource {
+  pub name: String,
+  pub id: String,
+}
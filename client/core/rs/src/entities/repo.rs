@@ -16,7 +16,8 @@ use crate::{
 };
 
 use super::{
-  EnvironmentVar, SystemCommand, environment_vars_from_str,
+  EnvironmentVar, SystemCommand, WebhookTrigger,
+  environment_vars_from_str,
   resource::{Resource, ResourceListItem, ResourceQuery},
 };
 
@@ -213,6 +214,48 @@ pub struct RepoConfig {
   #[builder(default)]
   pub webhook_secret: String,
 
+  /// Report the result of webhook triggered executions
+  /// to the git provider as a commit status, linking to the Update.
+  /// Supports Github, Gitea / Forgejo, and Gitlab.
+  /// Requires `git_account` to be configured.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_commit_status: bool,
+
+  /// Only trigger incoming webhooks when a changed file matches
+  /// one of these paths. Supports wildcard syntax, eg `services/api/**`.
+  /// If empty, any change triggers the webhook.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_include_paths: Vec<String>,
+
+  /// Don't trigger incoming webhooks when all the changed files
+  /// match these paths. Supports wildcard syntax, eg `docs/**`.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_exclude_paths: Vec<String>,
+
+  /// Whether incoming webhooks trigger on pushes to the branch,
+  /// or on pushed tags matching `webhook_tag_pattern`.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_trigger: WebhookTrigger,
+
+  /// With the `Tag` webhook trigger, only pushed tags matching
+  /// this pattern trigger the webhook. The pushed tag is set as the `commit`.
+  /// Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_tag_pattern: String,
+
   /// Command to be run after the repo is cloned.
   /// The path is relative to the root of the repo.
   #[serde(default)]
@@ -312,6 +355,11 @@ impl Default for RepoConfig {
       skip_secret_interp: Default::default(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
+      webhook_commit_status: Default::default(),
+      webhook_include_paths: Default::default(),
+      webhook_exclude_paths: Default::default(),
+      webhook_trigger: Default::default(),
+      webhook_tag_pattern: Default::default(),
     }
   }
 }
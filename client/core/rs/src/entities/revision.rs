@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::{I64, JsonObject, MongoId, ResourceTarget};
+
+/// A snapshot of a resource config,
+/// saved each time the resource config is updated.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", doc_index({ "target.type": 1, "target.id": 1 }))]
+pub struct ResourceRevision {
+  /// The Mongo ID of the revision.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized ResourceRevision) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// The resource the config belongs to.
+  pub target: ResourceTarget,
+
+  /// Unix timestamp in milliseconds the config was saved.
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+
+  /// The id of the user who made the change.
+  /// Empty for the baseline revision, the config from before
+  /// the first change made with revisions tracked.
+  #[serde(default)]
+  pub operator: String,
+
+  /// The username of the user who made the change.
+  #[serde(default)]
+  pub username: String,
+
+  /// The id of the config Update, if any.
+  #[serde(default)]
+  pub update_id: String,
+
+  /// The full resource config.
+  /// Not included in list responses.
+  #[serde(default)]
+  #[cfg_attr(feature = "utoipa", schema(value_type = std::collections::HashMap<String, serde_json::Value>))]
+  pub config: JsonObject,
+}
+
+/// A config field which differs between two revisions.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RevisionFieldDiff {
+  /// The config field name.
+  pub field: String,
+  /// The field value in the earlier revision.
+  pub from: String,
+  /// The field value in the later revision.
+  pub to: String,
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+use typeshare::typeshare;
+
+use super::{I64, MongoId, Version};
+
+/// A software bill of materials generated for a built image.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+pub struct BuildSbom {
+  /// The Mongo ID of the SBOM.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized BuildSbom) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// Unix timestamp in milliseconds the SBOM was generated
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+
+  /// The id of the Build
+  #[cfg_attr(feature = "mongo", index)]
+  pub build_id: String,
+
+  /// The id of the RunBuild Update which generated the SBOM
+  pub update_id: String,
+
+  /// The build version
+  pub version: Version,
+
+  /// The commit hash of the build, if built from a repo.
+  #[serde(default)]
+  pub commit_hash: String,
+
+  /// The image the SBOM was generated for, eg `mbecker2020/komodo:1.2.3`
+  pub image: String,
+
+  /// The image digest, if it could be determined.
+  #[cfg_attr(feature = "mongo", index)]
+  pub digest: String,
+
+  /// The SBOM document format
+  pub format: SbomFormat,
+
+  /// The packages contained in the image, sorted by name.
+  /// Not included in list responses.
+  #[serde(default)]
+  pub packages: Vec<SbomPackage>,
+
+  /// The full SBOM json document.
+  /// Not included in list responses.
+  #[serde(default)]
+  pub document: String,
+
+  /// The gzip compressed SBOM json document, which is how
+  /// the document is stored on the database to keep
+  /// large SBOMs within the document size limit.
+  /// Decompressed into `document` by GetBuildSbom.
+  #[typeshare(skip)]
+  #[serde(
+    default,
+    with = "serde_bytes",
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub document_gzip: Vec<u8>,
+}
+
+/// The SBOM returned from Periphery.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SbomReport {
+  /// The SBOM document format
+  pub format: SbomFormat,
+  /// The image digest, if it could be determined.
+  pub digest: String,
+  /// The packages contained in the image, sorted by name.
+  pub packages: Vec<SbomPackage>,
+  /// The full SBOM json document.
+  pub document: String,
+}
+
+/// The SBOM document format to generate.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  Display,
+  EnumString,
+  AsRefStr,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum SbomFormat {
+  /// Don't generate an SBOM.
+  #[default]
+  None,
+  /// https://spdx.dev
+  Spdx,
+  /// https://cyclonedx.org
+  CycloneDx,
+}
+
+impl SbomFormat {
+  pub fn is_none(self) -> bool {
+    matches!(self, SbomFormat::None)
+  }
+}
+
+/// A package listed in an SBOM.
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SbomPackage {
+  /// The package name
+  pub name: String,
+  /// The package version
+  #[serde(default)]
+  pub version: String,
+  /// The package url, eg `pkg:deb/debian/openssl@3.0.11`
+  #[serde(default)]
+  pub purl: String,
+}
+
+/// The differences in packages between two SBOMs.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SbomDiff {
+  /// Packages only in the newer SBOM.
+  pub added: Vec<SbomPackage>,
+  /// Packages only in the older SBOM.
+  pub removed: Vec<SbomPackage>,
+  /// Packages in both SBOMs with a different version.
+  pub changed: Vec<SbomPackageChange>,
+}
+
+/// A package with a different version between two SBOMs.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SbomPackageChange {
+  /// The package name
+  pub name: String,
+  /// The version in the older SBOM
+  pub from: String,
+  /// The version in the newer SBOM
+  pub to: String,
+}
+
+impl SbomDiff {
+  /// Packages are matched by name. If a package name is listed
+  /// multiple times, the versions are compared as a set.
+  pub fn new(from: &[SbomPackage], to: &[SbomPackage]) -> SbomDiff {
+    let from = group_versions(from);
+    let to = group_versions(to);
+    let mut diff = SbomDiff::default();
+    for (name, (versions, packages)) in &to {
+      match from.get(name) {
+        None => diff.added.extend(packages.iter().cloned().cloned()),
+        Some((from_versions, _)) if from_versions != versions => {
+          diff.changed.push(SbomPackageChange {
+            name: name.to_string(),
+            from: from_versions.join(", "),
+            to: versions.join(", "),
+          })
+        }
+        Some(_) => {}
+      }
+    }
+    for (name, (_, packages)) in &from {
+      if !to.contains_key(name) {
+        diff.removed.extend(packages.iter().cloned().cloned());
+      }
+    }
+    diff
+  }
+}
+
+/// Maps package name -> (sorted versions, packages)
+fn group_versions(
+  packages: &[SbomPackage],
+) -> BTreeMap<&str, (Vec<&str>, Vec<&SbomPackage>)> {
+  let mut res =
+    BTreeMap::<&str, (Vec<&str>, Vec<&SbomPackage>)>::new();
+  for package in packages {
+    let (versions, packages) =
+      res.entry(package.name.as_str()).or_default();
+    versions.push(package.version.as_str());
+    packages.push(package);
+  }
+  for (versions, _) in res.values_mut() {
+    versions.sort();
+    versions.dedup();
+  }
+  res
+}
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+use typeshare::typeshare;
+
+use super::{I64, MongoId, ResourceTarget, U64};
+
+/// The summarized results of scanning an image for vulnerabilities.
+/// Stored per target resource and image digest.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", doc_index({ "target.type": 1 }))]
+#[cfg_attr(feature = "mongo", doc_index({ "target.id": 1 }))]
+pub struct ImageScan {
+  /// The Mongo ID of the image scan.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized ImageScan) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// Unix timestamp in milliseconds of the scan
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+
+  /// The resource (Build / Deployment / Stack) the image belongs to.
+  pub target: ResourceTarget,
+
+  /// The scanned image, eg `mbecker2020/komodo:latest`
+  #[cfg_attr(feature = "mongo", index)]
+  pub image: String,
+
+  /// The scanned image digest, if it could be determined.
+  #[cfg_attr(feature = "mongo", index)]
+  pub digest: String,
+
+  /// The scanner used.
+  pub scanner: ImageScanner,
+
+  /// The number of findings at each severity.
+  pub counts: VulnerabilityCounts,
+
+  /// The individual findings, sorted by severity descending.
+  /// Not included in list responses.
+  #[serde(default)]
+  pub findings: Vec<Vulnerability>,
+}
+
+/// The results of an image scan returned from Periphery.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ImageScanReport {
+  /// The scanner used.
+  pub scanner: ImageScanner,
+  /// The scanned image digest, if it could be determined.
+  pub digest: String,
+  /// The number of findings at each severity.
+  pub counts: VulnerabilityCounts,
+  /// The individual findings, sorted by severity descending.
+  pub findings: Vec<Vulnerability>,
+}
+
+/// The supported vulnerability scanner CLIs.
+/// The first one found on the Periphery host is used.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Display,
+  EnumString,
+  AsRefStr,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ImageScanner {
+  /// https://github.com/aquasecurity/trivy
+  #[default]
+  Trivy,
+  /// https://github.com/anchore/grype
+  Grype,
+}
+
+/// A single vulnerability found in an image.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Vulnerability {
+  /// The vulnerability id, eg `CVE-2024-12345`
+  pub id: String,
+  /// The severity
+  pub severity: VulnerabilitySeverity,
+  /// The affected package
+  pub package: String,
+  /// The installed package version
+  pub installed_version: String,
+  /// The version the vulnerability is fixed in, if one exists.
+  #[serde(default)]
+  pub fixed_version: String,
+  /// A short description, if provided by the scanner.
+  #[serde(default)]
+  pub title: String,
+}
+
+/// Vulnerability severity, ordered from least to most severe.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Display,
+  EnumString,
+  AsRefStr,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[strum(ascii_case_insensitive)]
+pub enum VulnerabilitySeverity {
+  #[default]
+  Unknown,
+  Low,
+  Medium,
+  High,
+  Critical,
+}
+
+/// The number of findings at each severity.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct VulnerabilityCounts {
+  pub critical: U64,
+  pub high: U64,
+  pub medium: U64,
+  pub low: U64,
+  pub unknown: U64,
+}
+
+impl VulnerabilityCounts {
+  pub fn add(&mut self, severity: VulnerabilitySeverity) {
+    match severity {
+      VulnerabilitySeverity::Critical => self.critical += 1,
+      VulnerabilitySeverity::High => self.high += 1,
+      VulnerabilitySeverity::Medium => self.medium += 1,
+      VulnerabilitySeverity::Low => self.low += 1,
+      VulnerabilitySeverity::Unknown => self.unknown += 1,
+    }
+  }
+
+  pub fn total(&self) -> U64 {
+    self.critical + self.high + self.medium + self.low + self.unknown
+  }
+
+  /// The number of findings at or above the given severity.
+  pub fn at_or_above(&self, severity: VulnerabilitySeverity) -> U64 {
+    let mut count = self.critical;
+    if severity <= VulnerabilitySeverity::High {
+      count += self.high;
+    }
+    if severity <= VulnerabilitySeverity::Medium {
+      count += self.medium;
+    }
+    if severity <= VulnerabilitySeverity::Low {
+      count += self.low;
+    }
+    if severity == VulnerabilitySeverity::Unknown {
+      count += self.unknown;
+    }
+    count
+  }
+}
+
+impl std::fmt::Display for VulnerabilityCounts {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "critical: {} | high: {} | medium: {} | low: {} | unknown: {}",
+      self.critical, self.high, self.medium, self.low, self.unknown
+    )
+  }
+}
+
+/// Configure when image scan findings should be acted on.
+/// Builds will fail, while Deployments and Stacks will send an alert.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  Display,
+  EnumString,
+  AsRefStr,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum VulnerabilityThreshold {
+  /// Only record the findings.
+  #[default]
+  None,
+  /// Any finding at Low severity or above.
+  Low,
+  /// Any finding at Medium severity or above.
+  Medium,
+  /// Any finding at High severity or above.
+  High,
+  /// Any finding at Critical severity.
+  Critical,
+}
+
+impl VulnerabilityThreshold {
+  /// The minimum severity which exceeds the threshold,
+  /// or None if the threshold is disabled.
+  pub fn severity(self) -> Option<VulnerabilitySeverity> {
+    match self {
+      VulnerabilityThreshold::None => None,
+      VulnerabilityThreshold::Low => Some(VulnerabilitySeverity::Low),
+      VulnerabilityThreshold::Medium => {
+        Some(VulnerabilitySeverity::Medium)
+      }
+      VulnerabilityThreshold::High => {
+        Some(VulnerabilitySeverity::High)
+      }
+      VulnerabilityThreshold::Critical => {
+        Some(VulnerabilitySeverity::Critical)
+      }
+    }
+  }
+
+  /// The number of findings exceeding the threshold.
+  pub fn exceeded_by(self, counts: &VulnerabilityCounts) -> U64 {
+    self
+      .severity()
+      .map(|severity| counts.at_or_above(severity))
+      .unwrap_or_default()
+  }
+}
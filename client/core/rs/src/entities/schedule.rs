@@ -1,14 +1,16 @@
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
-use crate::entities::{I64, ResourceTarget, ScheduleFormat};
+use crate::entities::{I64, MongoId, ResourceTarget, ScheduleFormat};
 
-/// A scheduled Action / Procedure run.
+/// A scheduled Action / Procedure run,
+/// or a one-off [ScheduledDeploy].
 #[typeshare]
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Schedule {
-  /// Procedure or Alerter
+  /// Action, Procedure, or the Deployment / Stack
+  /// of a scheduled deploy.
   pub target: ResourceTarget,
   /// Readable name of the target resource
   pub name: String,
@@ -47,3 +49,39 @@ pub enum ScheduleSortBy {
   /// Sort by enabled.
   Enabled,
 }
+
+/// A one-off Deploy / DeployStack, scheduled to run at a specific time.
+/// Each Deployment / Stack can have one scheduled deploy at a time.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", unique_doc_index({ "target.type": 1, "target.id": 1 }))]
+pub struct ScheduledDeploy {
+  /// The Mongo ID of the scheduled deploy.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized ScheduledDeploy) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// The Deployment or Stack to deploy.
+  pub target: ResourceTarget,
+
+  /// Unix timestamp in milliseconds to run the deploy.
+  pub run_at: I64,
+
+  /// The id of the user who scheduled the deploy.
+  /// The deploy is run as this user.
+  pub operator: String,
+
+  /// Unix timestamp in milliseconds the deploy was scheduled.
+  pub created_at: I64,
+}
@@ -11,7 +11,8 @@ use crate::{
     option_string_list_deserializer, string_list_deserializer,
   },
   entities::{
-    _Serror, MaintenanceWindow, Timelength, stats::MinimalSystemStats,
+    _Serror, MaintenanceWindow, ResourceTarget, Timelength,
+    stats::MinimalSystemStats,
   },
 };
 
@@ -450,6 +451,30 @@ pub struct PeripheryInformation {
   pub public_ip: Option<String>,
 }
 
+/// A host port (or port range) published on a Server,
+/// either configured on a Deployment / Stack,
+/// or published by a running container.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ServerPortAllocation {
+  /// The published host port, or the start of the range.
+  pub port: u16,
+  /// The end of the published range.
+  /// Same as `port` for a single port.
+  pub end_port: u16,
+  /// The protocol, eg. `tcp`, `udp`.
+  pub protocol: String,
+  /// The host ip the port is bound to.
+  /// Empty when bound on all interfaces.
+  pub host_ip: String,
+  /// The Deployment / Stack allocating the port,
+  /// or null for containers not managed by Komodo.
+  pub resource: Option<ResourceTarget>,
+  /// The running container publishing the port, if any.
+  pub container: Option<String>,
+}
+
 /// Current pending actions on the server.
 #[typeshare]
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
@@ -467,6 +492,8 @@ pub struct ServerActionState {
   pub pruning_builders: bool,
   /// Server currently pruning builx cache
   pub pruning_buildx: bool,
+  /// Server currently pruning the build cache of each buildx builder
+  pub pruning_build_cache: bool,
   /// Server currently pruning system
   pub pruning_system: bool,
   /// Server currently starting containers.
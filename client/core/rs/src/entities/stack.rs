@@ -21,7 +21,8 @@ use crate::{
     option_string_list_deserializer, string_list_deserializer,
   },
   entities::{
-    EnvironmentVar, ImageDigest,
+    EnvironmentVar, ImageDigest, ImageUpdatePolicy,
+    MaintenanceWindow,
     docker::{
       container::ContainerStateStatusEnum,
       service::SwarmServiceListItem,
@@ -32,9 +33,10 @@ use crate::{
 };
 
 use super::{
-  FileContents, SystemCommand,
+  FileContents, SystemCommand, WebhookTrigger,
   docker::container::ContainerListItem,
   resource::{Resource, ResourceListItem, ResourceQuery},
+  scan::VulnerabilityThreshold,
 };
 
 #[cfg(feature = "utoipa")]
@@ -307,6 +309,11 @@ pub struct StackInfo {
   pub latest_hash: Option<String>,
   /// Latest commit message, or null
   pub latest_message: Option<String>,
+
+  /// The images with the newest version tag allowed by
+  /// the service update policies, for services with a newer tag.
+  #[serde(default)]
+  pub latest_images: Vec<StackServiceImage>,
 }
 
 #[typeshare(serialized_as = "Partial<StackConfig>")]
@@ -386,11 +393,24 @@ pub struct StackConfig {
   #[builder(default)]
   pub run_build: bool,
 
+  /// Whether to validate the Stack before deploying,
+  /// failing the deploy if any errors are found. See `ValidateStack`.
+  #[serde(default)]
+  #[builder(default)]
+  pub validate_before_deploy: bool,
+
   /// Whether to poll for any updates to the images.
   #[serde(default)]
   #[builder(default)]
   pub poll_for_updates: bool,
 
+  /// How image updates are detected for specific services.
+  /// Services not listed use the `digest` policy,
+  /// a newer image pushed to the same tag.
+  #[serde(default)]
+  #[builder(default)]
+  pub update_policies: Vec<StackServiceUpdatePolicy>,
+
   /// Whether to automatically redeploy when
   /// newer images are found. Will implicitly
   /// enable `poll_for_updates`, you don't need to
@@ -418,6 +438,20 @@ pub struct StackConfig {
   #[builder(default)]
   pub auto_update_skip_services: Vec<String>,
 
+  /// Restrict auto updates to these windows.
+  /// Updates found outside of the windows are queued,
+  /// and deployed once a window opens.
+  #[serde(default)]
+  #[builder(default)]
+  pub auto_update_windows: Vec<MaintenanceWindow>,
+
+  /// Also allow auto updates during the maintenance windows
+  /// of the attached Server / Swarm. If no windows are
+  /// configured at all, updates are deployed immediately.
+  #[serde(default)]
+  #[builder(default)]
+  pub auto_update_in_maintenance: bool,
+
   /// Whether to run `docker compose down` before `compose up`.
   #[serde(default)]
   #[builder(default)]
@@ -502,6 +536,61 @@ pub struct StackConfig {
   #[builder(default)]
   pub webhook_force_deploy: bool,
 
+  /// Report the result of webhook triggered executions
+  /// to the git provider as a commit status, linking to the Update.
+  /// Supports Github, Gitea / Forgejo, and Gitlab.
+  /// Requires `git_account` to be configured.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_commit_status: bool,
+
+  /// Only trigger incoming webhooks when a changed file matches
+  /// one of these paths. Supports wildcard syntax, eg `services/api/**`.
+  /// If empty, any change triggers the webhook.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_include_paths: Vec<String>,
+
+  /// Don't trigger incoming webhooks when all the changed files
+  /// match these paths. Supports wildcard syntax, eg `docs/**`.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_exclude_paths: Vec<String>,
+
+  /// Whether incoming webhooks trigger on pushes to the branch,
+  /// or on pushed tags matching `webhook_tag_pattern`.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_trigger: WebhookTrigger,
+
+  /// With the `Tag` webhook trigger, only pushed tags matching
+  /// this pattern trigger the webhook. The pushed tag is set as the `commit`.
+  /// Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_tag_pattern: String,
+
+  /// Deploy a preview environment for each open pull request
+  /// targeting the branch, triggered by the `preview` webhook.
+  /// A temporary Stack is created from this one, using the pull request branch
+  /// and project name `{project_name}-pr-{number}`, and is destroyed and
+  /// deleted when the pull request is closed.
+  /// `[[PR_NUMBER]]` and `[[PR_BRANCH]]` are interpolated into the preview
+  /// compose files, environment, and commands,
+  /// even when `skip_secret_interp` is enabled.
+  /// Supports Github, Gitea / Forgejo, and Gitlab.
+  #[serde(default)]
+  #[builder(default)]
+  pub preview_enabled: bool,
+
   /// If this is checked, the stack will source the files on the host.
   /// Use `run_directory` and `file_paths` to specify the path on the host.
   /// This is useful for those who wish to setup their files on the host,
@@ -563,6 +652,27 @@ pub struct StackConfig {
   #[partial_default(default_send_alerts())]
   pub send_alerts: bool,
 
+  /// Scan the service images for vulnerabilities after every deploy.
+  /// Requires `trivy` or `grype` to be installed on the server.
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_images: bool,
+
+  /// Send an `ImageVulnerabilities` alert if the scan finds vulnerabilities
+  /// at or above this severity. Default: `None`
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_threshold: VulnerabilityThreshold,
+
+  /// Require the service images to have a valid `cosign` signature
+  /// from at least one of these public keys before deploying.
+  /// The verified digest is deployed, rather than the tag.
+  /// Requires `cosign` to be installed on the server.
+  /// Leave empty to skip verification.
+  #[serde(default)]
+  #[builder(default)]
+  pub signature_keys: Vec<String>,
+
   /// Used with `registry_account` to login to a registry before docker compose up.
   #[serde(default)]
   #[builder(default)]
@@ -733,9 +843,12 @@ impl Default for StackConfig {
       file_contents: Default::default(),
       auto_pull: default_auto_pull(),
       poll_for_updates: Default::default(),
+      update_policies: Default::default(),
       auto_update: Default::default(),
       auto_update_all_services: Default::default(),
       auto_update_skip_services: Default::default(),
+      auto_update_windows: Default::default(),
+      auto_update_in_maintenance: Default::default(),
       ignore_services: Default::default(),
       pre_deploy: Default::default(),
       post_deploy: Default::default(),
@@ -745,6 +858,7 @@ impl Default for StackConfig {
       additional_env_files: Default::default(),
       config_files: Default::default(),
       run_build: Default::default(),
+      validate_before_deploy: Default::default(),
       destroy_before_deploy: Default::default(),
       build_extra_args: Default::default(),
       compose_cmd_wrapper: Default::default(),
@@ -762,7 +876,16 @@ impl Default for StackConfig {
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
       webhook_force_deploy: Default::default(),
+      webhook_commit_status: Default::default(),
+      webhook_include_paths: Default::default(),
+      webhook_exclude_paths: Default::default(),
+      webhook_trigger: Default::default(),
+      webhook_tag_pattern: Default::default(),
+      preview_enabled: Default::default(),
       send_alerts: default_send_alerts(),
+      scan_images: Default::default(),
+      scan_threshold: Default::default(),
+      signature_keys: Default::default(),
       links: Default::default(),
     }
   }
@@ -1093,6 +1216,85 @@ pub enum StackFileRequires {
   None,
 }
 
+/// The image update policy of a Stack service.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StackServiceUpdatePolicy {
+  /// The service name.
+  pub service: String,
+  /// How image updates are detected.
+  /// - `digest`: A newer image pushed to the same tag. Default.
+  /// - `patch` / `minor` / `major`: Newer version tags in the registry,
+  ///   up to the given semver level.
+  #[serde(default)]
+  pub policy: ImageUpdatePolicy,
+  /// Only consider registry tags matching this regex
+  /// for version tag updates.
+  #[serde(default)]
+  pub tag_filter: String,
+}
+
+/// A service image, eg. with a newer version tag.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct StackServiceImage {
+  /// The service name.
+  pub service: String,
+  /// The image, including the tag.
+  pub image: String,
+}
+
+/// A problem found when validating a Stack before deploy.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct StackDiagnostic {
+  /// Errors will fail the deploy, warnings are only reported.
+  pub level: StackDiagnosticLevel,
+  /// The type of problem.
+  pub kind: StackDiagnosticKind,
+  /// The service the problem relates to, if any.
+  #[serde(default)]
+  pub service: Option<String>,
+  /// Describes the problem.
+  pub message: String,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum StackDiagnosticLevel {
+  Error,
+  Warning,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum StackDiagnosticKind {
+  /// Failed to write the stack files or run `docker compose config`.
+  ComposeConfig,
+  /// A compose / env / config file is missing.
+  MissingFile,
+  /// A variable used in the compose files or environment is not set.
+  UnsetVariable,
+  /// A service image doesn't exist in the registry.
+  MissingImage,
+  /// A published port is already used on the Server.
+  PortConflict,
+}
+
 /// Additional env file configuration for Stack.
 /// Supports backward compatibility with string-only format.
 #[typeshare]
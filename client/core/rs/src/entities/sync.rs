@@ -252,6 +252,35 @@ pub struct ResourceSyncConfig {
   #[builder(default)]
   pub webhook_secret: String,
 
+  /// Report the result of webhook triggered executions
+  /// to the git provider as a commit status, linking to the Update.
+  /// Supports Github, Gitea / Forgejo, and Gitlab.
+  /// Requires `git_account` to be configured.
+  #[serde(default)]
+  #[builder(default)]
+  pub webhook_commit_status: bool,
+
+  /// Only trigger incoming webhooks when a changed file matches
+  /// one of these paths. Supports wildcard syntax, eg `services/api/**`.
+  /// If empty, any change triggers the webhook.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_include_paths: Vec<String>,
+
+  /// Don't trigger incoming webhooks when all the changed files
+  /// match these paths. Supports wildcard syntax, eg `docs/**`.
+  #[serde(default, deserialize_with = "string_list_deserializer")]
+  #[partial_attr(serde(
+    default,
+    deserialize_with = "option_string_list_deserializer"
+  ))]
+  #[builder(default)]
+  pub webhook_exclude_paths: Vec<String>,
+
   /// Files are available on the Komodo Core host.
   /// Specify the file / folder with [ResourceSyncConfig::resource_path].
   #[serde(default)]
@@ -392,6 +421,9 @@ impl Default for ResourceSyncConfig {
       delete: Default::default(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
+      webhook_commit_status: Default::default(),
+      webhook_include_paths: Default::default(),
+      webhook_exclude_paths: Default::default(),
       pending_alert: default_pending_alert(),
     }
   }
@@ -205,7 +205,7 @@ impl Log {
   }
 
   #[cfg(feature = "svi")]
-  pub fn sanitize(&mut self, replacers: &Vec<(String, String)>) {
+  pub fn sanitize(&mut self, replacers: &[(String, String)]) {
     self.command = svi::replace_in_string(&self.command, replacers);
     self.stdout = svi::replace_in_string(&self.stdout, replacers);
     self.stderr = svi::replace_in_string(&self.stderr, replacers);
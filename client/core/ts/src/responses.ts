@@ -35,6 +35,7 @@ export type ReadResponses = {
   GetServer: Types.GetServerResponse;
   GetServerState: Types.GetServerStateResponse;
   GetPeripheryInformation: Types.GetPeripheryInformationResponse;
+  ListServerPortAllocations: Types.ListServerPortAllocationsResponse;
   GetServerActionState: Types.GetServerActionStateResponse;
   ListServers: Types.ListServersResponse;
   ListFullServers: Types.ListFullServersResponse;
@@ -58,6 +59,10 @@ export type ReadResponses = {
   ListImageHistory: Types.ListImageHistoryResponse;
   ListVolumes: Types.ListVolumesResponse;
   InspectVolume: Types.InspectVolumeResponse;
+  ListContainerDirectory: Types.ListContainerDirectoryResponse;
+  GetContainerFile: Types.GetContainerFileResponse;
+  ListServerDirectory: Types.ListServerDirectoryResponse;
+  GetServerFile: Types.GetServerFileResponse;
 
   // ==== SERVER STATS ====
   GetSystemInformation: Types.GetSystemInformationResponse;
@@ -79,6 +84,7 @@ export type ReadResponses = {
   ListAllStackServices: Types.ListAllStackServicesResponse;
   ListCommonStackExtraArgs: Types.ListCommonStackExtraArgsResponse;
   ListCommonStackBuildExtraArgs: Types.ListCommonStackBuildExtraArgsResponse;
+  ListStackPreviews: Types.ListStackPreviewsResponse;
 
   // ==== DEPLOYMENT ====
   GetDeploymentsSummary: Types.GetDeploymentsSummaryResponse;
@@ -102,6 +108,9 @@ export type ReadResponses = {
   ListBuilds: Types.ListBuildsResponse;
   ListFullBuilds: Types.ListFullBuildsResponse;
   ListBuildVersions: Types.ListBuildVersionsResponse;
+  ListBuildSboms: Types.ListBuildSbomsResponse;
+  GetBuildSbom: Types.GetBuildSbomResponse;
+  DiffBuildSboms: Types.DiffBuildSbomsResponse;
   ListCommonBuildExtraArgs: Types.ListCommonBuildExtraArgsResponse;
 
   // ==== REPO ====
@@ -122,6 +131,8 @@ export type ReadResponses = {
   GetActionsSummary: Types.GetActionsSummaryResponse;
   GetAction: Types.GetActionResponse;
   GetActionActionState: Types.GetActionActionStateResponse;
+  GetActionArguments: Types.GetActionArgumentsResponse;
+  GetActionOutput: Types.GetActionOutputResponse;
   ListActions: Types.ListActionsResponse;
   ListFullActions: Types.ListFullActionsResponse;
 
@@ -146,6 +157,9 @@ export type ReadResponses = {
   GetAlerter: Types.GetAlerterResponse;
   ListAlerters: Types.ListAlertersResponse;
   ListFullAlerters: Types.ListFullAlertersResponse;
+  ListResourceRevisions: Types.ListResourceRevisionsResponse;
+  GetResourceRevision: Types.GetResourceRevisionResponse;
+  DiffResourceRevisions: Types.DiffResourceRevisionsResponse;
 
   // ==== TOML ====
   ExportAllResourcesToToml: Types.ExportAllResourcesToTomlResponse;
@@ -176,6 +190,8 @@ export type ReadResponses = {
   // ==== ALERT ====
   ListAlerts: Types.ListAlertsResponse;
   GetAlert: Types.GetAlertResponse;
+  ListImageScans: Types.ListImageScansResponse;
+  GetImageScan: Types.GetImageScanResponse;
 
   // ==== VARIABLE ====
   GetVariable: Types.GetVariableResponse;
@@ -186,14 +202,20 @@ export type ReadResponses = {
   ListGitProviderAccounts: Types.ListGitProviderAccountsResponse;
   GetImageRegistryAccount: Types.GetImageRegistryAccountResponse;
   ListImageRegistryAccounts: Types.ListImageRegistryAccountsResponse;
+  ListRegistryRepositories: Types.ListRegistryRepositoriesResponse;
+  ListRegistryTags: Types.ListRegistryTagsResponse;
 
   // ==== ONBOARDING KEY ====
   ListOnboardingKeys: Types.ListOnboardingKeysResponse;
+  ListBackupItems: Types.ListBackupItemsResponse;
 };
 
 export type WriteResponses = {
   // ==== RESOURCE ====
   UpdateResourceMeta: Types.UpdateResourceMetaResponse;
+  RestoreResourceRevision: Types.RestoreResourceRevisionResponse;
+  ScheduleDeploy: Types.ScheduleDeployResponse;
+  CancelScheduledDeploy: Types.CancelScheduledDeployResponse;
 
   // ==== SWARM ====
   CreateSwarm: Types.Swarm;
@@ -217,6 +239,8 @@ export type WriteResponses = {
   DeleteTerminal: Types.NoData;
   DeleteAllTerminals: Types.NoData;
   BatchDeleteAllTerminals: Types.NoData;
+  WriteContainerFile: Types.Update;
+  WriteServerFile: Types.Update;
 
   // ==== STACK ====
   CreateStack: Types.Stack;
@@ -349,6 +373,7 @@ export type WriteResponses = {
 
   // ==== ALERT ====
   CloseAlert: Types.NoData;
+  RestoreBackupItems: Types.Update;
 };
 
 export type ExecuteResponses = {
@@ -359,6 +384,7 @@ export type ExecuteResponses = {
   BatchDeployStackIfChanged: Types.BatchExecutionResponse;
   PullStack: Types.Update;
   BatchPullStack: Types.BatchExecutionResponse;
+  ValidateStack: Types.Update;
   StartStack: Types.Update;
   RestartStack: Types.Update;
   StopStack: Types.Update;
@@ -384,6 +410,7 @@ export type ExecuteResponses = {
   RunBuild: Types.Update;
   BatchRunBuild: Types.BatchExecutionResponse;
   CancelBuild: Types.Update;
+  CleanupBuildImages: Types.Update;
 
   // ==== REPO ====
   CloneRepo: Types.Update;
@@ -432,6 +459,7 @@ export type ExecuteResponses = {
   PruneVolumes: Types.Update;
   PruneDockerBuilders: Types.Update;
   PruneBuildx: Types.Update;
+  PruneBuildCache: Types.Update;
   PruneSystem: Types.Update;
 
   // ==== SWARM ====
@@ -70,6 +70,36 @@ export enum FileFormat {
 	Json = "json",
 }
 
+export enum ActionArgumentType {
+	String = "string",
+	Number = "number",
+	Integer = "integer",
+	Boolean = "boolean",
+	Array = "array",
+	Object = "object",
+}
+
+/** Declares an argument the Action accepts at `ARGS.<name>`. */
+export interface ActionArgument {
+	/** The argument name. */
+	name: string;
+	/** The type of the argument value. Default: `string` */
+	type?: ActionArgumentType;
+	/** A description to show when prompting for the value. */
+	description?: string;
+	/** Fail the run if no value is given and there is no default. */
+	required?: boolean;
+	/** The value to use when none is given. */
+	default?: JsonValue;
+	/** Restrict the value to one of these choices. */
+	choices?: JsonValue[];
+	/**
+	 * The value is sensitive. It is hidden from the run output,
+	 * and clients should not echo it when prompting.
+	 */
+	secret?: boolean;
+}
+
 export interface ActionConfig {
 	/** Whether this action should run at startup. */
 	run_at_startup: boolean;
@@ -115,11 +145,65 @@ export interface ActionConfig {
 	 * If its an empty string, use the default secret from the config.
 	 */
 	webhook_secret?: string;
+	/**
+	 * Optionally run the Action on this Server's Periphery agent,
+	 * instead of on the Core host. Periphery uses Deno if installed,
+	 * otherwise runs the script in the `denoland/deno` container.
+	 */
+	server_id?: string;
 	/**
 	 * Whether deno will be instructed to reload all dependencies,
 	 * this can usually be kept false outside of development.
 	 */
 	reload_deno_deps?: boolean;
+	/**
+	 * Run the Action with all Deno permissions (`--allow-all`),
+	 * ignoring the granular permissions below.
+	 * Only takes effect when the Action was last configured by an admin.
+	 */
+	allow_all: boolean;
+	/**
+	 * Network hosts the Action may connect to (`--allow-net`),
+	 * eg. `api.github.com`, `10.0.0.5:8080`. Use `*` to allow any host.
+	 * Komodo Core is always allowed.
+	 */
+	allow_net?: string[];
+	/**
+	 * Paths the Action may read (`--allow-read`).
+	 * Use `*` to allow any path.
+	 * Only takes effect when the Action was last configured by an admin.
+	 */
+	allow_read?: string[];
+	/**
+	 * Paths the Action may write (`--allow-write`).
+	 * Use `*` to allow any path.
+	 * Only takes effect when the Action was last configured by an admin.
+	 */
+	allow_write?: string[];
+	/**
+	 * Environment variables the Action may read (`--allow-env`).
+	 * Use `*` to allow all.
+	 * Only takes effect when the Action was last configured by an admin.
+	 */
+	allow_env?: string[];
+	/**
+	 * Executables the Action may run as subprocesses (`--allow-run`).
+	 * Subprocesses are not sandboxed. Use `*` to allow any.
+	 * Only takes effect when the Action was last configured by an admin.
+	 */
+	allow_run?: string[];
+	/**
+	 * Make this Action a shared library of Typescript helpers.
+	 * Libraries can't be run. Other Actions list the library in `libraries`
+	 * and import its exports with `import { .. } from "@lib/<name>"`.
+	 */
+	library?: boolean;
+	/**
+	 * Library Actions (id or name) this Action imports from.
+	 * The library files are written alongside the script for each run,
+	 * including the libraries they import themselves.
+	 */
+	libraries?: string[];
 	/**
 	 * Typescript file contents using pre-initialized `komodo` client.
 	 * Supports variable / secret interpolation.
@@ -132,6 +216,13 @@ export interface ActionConfig {
 	arguments_format?: FileFormat;
 	/** Default arguments to give to the Action for use in the script at `ARGS`. */
 	arguments?: string;
+	/**
+	 * Optionally declare the arguments the Action accepts.
+	 * `RunAction` args are validated against these before the run,
+	 * and clients can use them to prompt for the values.
+	 * Args not declared here are passed through unchecked.
+	 */
+	argument_schema?: ActionArgument[];
 }
 
 /** Represents an empty json object: `{}` */
@@ -181,6 +272,8 @@ export interface ActionListItemInfo {
 	 * it will be given here.
 	 */
 	schedule_error?: string;
+	/** Whether the Action is a shared library. */
+	library: boolean;
 }
 
 export type ActionListItem = ResourceListItem<ActionListItemInfo>;
@@ -360,6 +453,11 @@ export interface CheckDeploymentForUpdateResponse {
 	deployment: string;
 	/** Whether update is available */
 	update_available: boolean;
+	/**
+	 * The image with the newer version tag,
+	 * if found using the update policy.
+	 */
+	latest_image?: string;
 }
 
 export type BatchCheckDeploymentForUpdateResponse = CheckDeploymentForUpdateResponse[];
@@ -389,6 +487,112 @@ export type BatchExecutionResponseItem =
 
 export type BatchExecutionResponse = BatchExecutionResponseItem[];
 
+/**
+ * The types of items which can be selectively
+ * restored out of a database backup.
+ */
+export enum BackupItemVariant {
+	Swarm = "Swarm",
+	Server = "Server",
+	Stack = "Stack",
+	Deployment = "Deployment",
+	Build = "Build",
+	Repo = "Repo",
+	Procedure = "Procedure",
+	Action = "Action",
+	Builder = "Builder",
+	Alerter = "Alerter",
+	ResourceSync = "ResourceSync",
+	Variable = "Variable",
+	UserGroup = "UserGroup",
+}
+
+/**
+ * Identifies a single item inside a backup.
+ * Items are selected by name, which is unique for each item type.
+ */
+export interface BackupItemTarget {
+	/** The type of the item. */
+	variant: BackupItemVariant;
+	/** The name of the item. */
+	name: string;
+}
+
+/**
+ * **Admin only.** Restore only the selected resources, variables
+ * and user groups from a database backup, leaving the rest of
+ * the database untouched. The diff of each item against
+ * the current database is recorded on the Update.
+ * Response: [Update].
+ */
+export interface RestoreBackupItems {
+	/**
+	 * The dated backup folder to restore from, eg `2025-08-01_05-04-53`.
+	 * If not provided, will use the most recent backup.
+	 */
+	backup?: string;
+	/** The items to restore. */
+	items: BackupItemTarget[];
+}
+
+/**
+ * Upload a file into a container, like `docker cp`.
+ * Overwrites the file if it already exists.
+ * The file must be smaller than the Core `max_file_transfer_size_mb`.
+ * Requires minimum Read + Terminal permission on the Server.
+ * Response: [Update].
+ */
+export interface WriteContainerFile {
+	/** Id or name */
+	server: string;
+	/** The container name */
+	container: string;
+	/** The absolute path of the file inside the container. */
+	path: string;
+	/** The file contents, base64 encoded. */
+	contents: string;
+}
+
+/**
+ * Upload a file to the server host, creating parent directories as needed.
+ * The path must be inside the Periphery `root_directory`,
+ * and the file smaller than the Core `max_file_transfer_size_mb`.
+ * Requires minimum Read + Terminal permission on the Server.
+ * Response: [Update].
+ */
+export interface WriteServerFile {
+	/** Id or name */
+	server: string;
+	/** The path of the file, relative to the Periphery `root_directory`. */
+	path: string;
+	/** The file contents, base64 encoded. */
+	contents: string;
+}
+
+/**
+ * Prunes the build cache of each buildx builder on the target server,
+ * logging the space reclaimed from each. Response: [Update].
+ * 
+ * 1. Runs `docker buildx prune --builder <builder> -a -f` for each builder
+ * listed by `docker buildx ls`.
+ */
+export interface PruneBuildCache {
+	/** Id or name */
+	server: string;
+}
+
+/**
+ * Deletes old image tags of the target build from its image registries,
+ * according to the build retention policy.
+ * Uses the Docker Registry HTTP API v2 with the registry account credentials.
+ * Versions deployed by any Deployment or Stack are always kept.
+ * Response: [Update]
+ */
+export interface CleanupBuildImages {
+	/** Can be id or name */
+	build: string;
+}
+
 export enum Operation {
 	None = "None",
 	CreateSwarm = "CreateSwarm",
@@ -431,7 +635,10 @@ export enum Operation {
 	PruneVolumes = "PruneVolumes",
 	PruneDockerBuilders = "PruneDockerBuilders",
 	PruneBuildx = "PruneBuildx",
+	PruneBuildCache = "PruneBuildCache",
 	PruneSystem = "PruneSystem",
+	WriteContainerFile = "WriteContainerFile",
+	WriteServerFile = "WriteServerFile",
 	CreateStack = "CreateStack",
 	UpdateStack = "UpdateStack",
 	RenameStack = "RenameStack",
@@ -439,6 +646,7 @@ export enum Operation {
 	WriteStackContents = "WriteStackContents",
 	RefreshStackCache = "RefreshStackCache",
 	PullStack = "PullStack",
+	ValidateStack = "ValidateStack",
 	DeployStack = "DeployStack",
 	StartStack = "StartStack",
 	RestartStack = "RestartStack",
@@ -475,6 +683,7 @@ export enum Operation {
 	DeleteBuild = "DeleteBuild",
 	RunBuild = "RunBuild",
 	CancelBuild = "CancelBuild",
+	CleanupBuildImages = "CleanupBuildImages",
 	WriteDockerfile = "WriteDockerfile",
 	CreateRepo = "CreateRepo",
 	UpdateRepo = "UpdateRepo",
@@ -518,6 +727,7 @@ export enum Operation {
 	GlobalAutoUpdate = "GlobalAutoUpdate",
 	RotateAllServerKeys = "RotateAllServerKeys",
 	RotateCoreKeys = "RotateCoreKeys",
+	RestoreBackupItems = "RestoreBackupItems",
 	CreateVariable = "CreateVariable",
 	UpdateVariableValue = "UpdateVariableValue",
 	DeleteVariable = "DeleteVariable",
@@ -635,6 +845,91 @@ export interface SystemCommand {
 	shell_mode?: boolean;
 }
 
+/**
+ * Configure when image scan findings should be acted on.
+ * Builds will fail, while Deployments and Stacks will send an alert.
+ */
+export enum VulnerabilityThreshold {
+	/** Only record the findings. */
+	None = "None",
+	/** Any finding at Low severity or above. */
+	Low = "Low",
+	/** Any finding at Medium severity or above. */
+	Medium = "Medium",
+	/** Any finding at High severity or above. */
+	High = "High",
+	/** Any finding at Critical severity. */
+	Critical = "Critical",
+}
+
+/** The SBOM document format to generate. */
+export enum SbomFormat {
+	/** Don't generate an SBOM. */
+	None = "None",
+	/** https://spdx.dev */
+	Spdx = "Spdx",
+	/** https://cyclonedx.org */
+	CycloneDx = "CycloneDx",
+}
+
+/** The git event which triggers incoming webhooks. */
+export enum WebhookTrigger {
+	/** Trigger on pushes to the configured branch. */
+	Branch = "Branch",
+	/**
+	 * Trigger on pushed tags matching the tag pattern,
+	 * checking out the pushed tag.
+	 */
+	Tag = "Tag",
+}
+
+/** Where buildx imports / exports the build cache. */
+export enum BuildCacheBackend {
+	/** Only use the builder's own cache. */
+	None = "None",
+	/**
+	 * Push the cache to an image registry (`type=registry`).
+	 * Shares the cache between builders.
+	 */
+	Registry = "Registry",
+	/**
+	 * Write the cache to a directory on the builder (`type=local`).
+	 * Requires a buildx builder using the `docker-container` driver.
+	 */
+	Local = "Local",
+}
+
+/** Builds a target platform on a specific Builder. */
+export interface PlatformBuilder {
+	/**
+	 * The target platform, eg `linux/arm64`.
+	 * Should also be included in `platforms`.
+	 */
+	platform: string;
+	/** The Builder (id or name) to build the platform on. */
+	builder_id?: string;
+}
+
+/** A variant of the image built by the Build `matrix`. */
+export interface BuildMatrixEntry {
+	/**
+	 * Postfixed to the image tags of the variant,
+	 * eg. `alpine` pushes `:1.19.5-alpine` and `:latest-alpine`.
+	 * Required, and must be unique across the matrix.
+	 */
+	image_tag: string;
+	/**
+	 * Build args for the variant, in addition to the Build `build_args`.
+	 * Args with the same name override the Build `build_args`.
+	 */
+	build_args?: string;
+	/**
+	 * Build the variant on a different Builder (id or name).
+	 * Uses the Build `builder_id` if empty.
+	 */
+	builder_id?: string;
+}
+
 /** The build configuration. */
 export interface BuildConfig {
 	/** Which builder is used to build the image. */
@@ -671,6 +966,19 @@ export interface BuildConfig {
 	include_version_tags: boolean;
 	/** Push commit hash `:a6v8h83` / `:a6v8h83-image_tag` tags. */
 	include_commit_tag: boolean;
+	/**
+	 * Retention policy for `CleanupBuildImages`.
+	 * Keep at least the latest N versions in the image registries.
+	 * 0 means the number of versions is not limited.
+	 */
+	retain_versions?: I64;
+	/**
+	 * Retention policy for `CleanupBuildImages`.
+	 * Keep versions pushed within the last N days,
+	 * even if outside `retain_versions`.
+	 * 0 means versions are not kept by age.
+	 */
+	retain_days?: I64;
 	/** Configure quick links that are displayed in the resource header */
 	links?: string[];
 	/** Choose a Komodo Repo (Resource) to source the build files. */
@@ -704,6 +1012,40 @@ export interface BuildConfig {
 	 * If its an empty string, use the default secret from the config.
 	 */
 	webhook_secret?: string;
+	/**
+	 * Report the result of webhook triggered executions
+	 * to the git provider as a commit status, linking to the Update.
+	 * Supports Github, Gitea / Forgejo, and Gitlab.
+	 * Requires `git_account` to be configured.
+	 */
+	webhook_commit_status?: boolean;
+	/**
+	 * Only trigger incoming webhooks when a changed file matches
+	 * one of these paths. Supports wildcard syntax, eg `services/api/**`.
+	 * If empty, any change triggers the webhook.
+	 */
+	webhook_include_paths?: string[];
+	/**
+	 * Don't trigger incoming webhooks when all the changed files
+	 * match these paths. Supports wildcard syntax, eg `docs/**`.
+	 */
+	webhook_exclude_paths?: string[];
+	/**
+	 * Whether incoming webhooks trigger on pushes to the branch,
+	 * or on pushed tags matching `webhook_tag_pattern`.
+	 */
+	webhook_trigger?: WebhookTrigger;
+	/**
+	 * With the `Tag` webhook trigger, only pushed tags matching
+	 * this pattern trigger the webhook. The pushed tag is set as the `commit`.
+	 * Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+	 */
+	webhook_tag_pattern?: string;
+	/**
+	 * With the `Tag` webhook trigger, set the build `version`
+	 * from the semver in the pushed tag, eg `v1.2.3` -> `1.2.3`.
+	 */
+	webhook_tag_version?: boolean;
 	/**
 	 * If this is checked, the build will source the files on the host.
 	 * Use `build_path` and `dockerfile_path` to specify the path on the host.
@@ -727,6 +1069,81 @@ export interface BuildConfig {
 	skip_secret_interp?: boolean;
 	/** Whether to use buildx to build (eg `docker buildx build ...`) */
 	use_buildx?: boolean;
+	/**
+	 * The platforms to build the image for, eg `linux/amd64`, `linux/arm64`.
+	 * Passed to the build with `--platform`.
+	 * More than one platform requires `use_buildx`.
+	 * If empty, builds for the platform of the builder.
+	 */
+	platforms?: string[];
+	/**
+	 * Build some of the `platforms` on different Builders,
+	 * eg. an arm64 Server builder alongside an amd64 AWS builder.
+	 * The Builders run in parallel, each pushing a platform specific tag,
+	 * which are then combined into a manifest list on the main builder.
+	 * Platforms without an entry here are built on the main builder.
+	 * Requires an image registry to push to.
+	 */
+	platform_builders?: PlatformBuilder[];
+	/**
+	 * Build variants of the image, in parallel across Builders,
+	 * eg. for different base images.
+	 * Each variant pushes the usual tags, postfixed with the variant `image_tag`.
+	 * Deployments attached to the Build use the first variant.
+	 */
+	matrix?: BuildMatrixEntry[];
+	/**
+	 * Import / export the build cache using buildx
+	 * `--cache-from` / `--cache-to`, so builds on
+	 * fresh builders (eg. AWS) don't start cold.
+	 * Requires `use_buildx`. Default: `None`
+	 */
+	cache_backend?: BuildCacheBackend;
+	/**
+	 * The cache location. For the `Registry` backend, an image ref,
+	 * eg `ghcr.io/myorg/app:buildcache`. For the `Local` backend,
+	 * a directory on the builder.
+	 * If empty, it is derived for the build: `{image}:buildcache` on the
+	 * first image registry, or `{build_dir}/.buildcache/{build}`.
+	 */
+	cache_ref?: string;
+	/**
+	 * Export the layers of all build stages (`mode=max`),
+	 * not just the final image layers. Caches multi-stage builds
+	 * better, at the cost of a larger cache.
+	 */
+	cache_mode_max?: boolean;
+	/**
+	 * Scan the built image for vulnerabilities after the build.
+	 * Requires `trivy` or `grype` to be installed on the builder.
+	 */
+	scan_image?: boolean;
+	/**
+	 * Fail the build if the scan finds vulnerabilities
+	 * at or above this severity. When set, single platform images
+	 * are only pushed once they pass the scan. Default: `None`
+	 */
+	scan_threshold?: VulnerabilityThreshold;
+	/**
+	 * Generate an SBOM in this format for the built image.
+	 * Requires `syft` to be installed on the builder.
+	 * Default: `None`
+	 */
+	sbom_format?: SbomFormat;
+	/**
+	 * The name of the secret holding a `cosign` private key
+	 * used to sign the image after it is pushed.
+	 * The secret is looked up in the Core secrets,
+	 * then in the builder's Periphery secrets.
+	 * Requires `cosign` to be installed on the builder.
+	 * Leave empty to skip signing.
+	 */
+	signing_key?: string;
+	/**
+	 * The name of the secret holding the password
+	 * for the `signing_key`, if it is encrypted.
+	 */
+	signing_key_password?: string;
 	/** Any extra docker cli arguments to be included in the build command */
 	extra_args?: string[];
 	/** The optional command run after repo clone and before docker build. */
@@ -919,6 +1336,7 @@ export type Execution =
 	| { type: "RunBuild", params: RunBuild }
 	| { type: "BatchRunBuild", params: BatchRunBuild }
 	| { type: "CancelBuild", params: CancelBuild }
+	| { type: "CleanupBuildImages", params: CleanupBuildImages }
 	/** Clone the target repo */
 	| { type: "CloneRepo", params: CloneRepo }
 	| { type: "BatchCloneRepo", params: BatchCloneRepo }
@@ -961,6 +1379,7 @@ export type Execution =
 	| { type: "PruneVolumes", params: PruneVolumes }
 	| { type: "PruneDockerBuilders", params: PruneDockerBuilders }
 	| { type: "PruneBuildx", params: PruneBuildx }
+	| { type: "PruneBuildCache", params: PruneBuildCache }
 	| { type: "PruneSystem", params: PruneSystem }
 	| { type: "RemoveSwarmNodes", params: RemoveSwarmNodes }
 	| { type: "UpdateSwarmNode", params: UpdateSwarmNode }
@@ -1327,6 +1746,24 @@ export enum TerminationSignal {
 	SigTerm = "SIGTERM",
 }
 
+/** How image updates are detected when polling for updates. */
+export enum ImageUpdatePolicy {
+	/** A newer image pushed to the same tag. Default. */
+	Digest = "digest",
+	/**
+	 * Newer version tags with the same major and minor version,
+	 * eg `16.2.1` -> `16.2.3`.
+	 */
+	Patch = "patch",
+	/**
+	 * Newer version tags with the same major version,
+	 * eg `16.2` -> `16.3`.
+	 */
+	Minor = "minor",
+	/** Any newer version tag, eg `16.2` -> `17.0`. */
+	Major = "major",
+}
+
 export interface DeploymentConfig {
 	/**
 	 * The Swarm to deploy the Deployment on (as a Swarm Service), setting the Deployment into Swarm mode.
@@ -1367,6 +1804,18 @@ export interface DeploymentConfig {
 	redeploy_on_build?: boolean;
 	/** Whether to poll for any updates to the image. */
 	poll_for_updates?: boolean;
+	/**
+	 * How image updates are detected.
+	 * - `digest`: A newer image pushed to the same tag. Default.
+	 * - `patch` / `minor` / `major`: Newer version tags in the registry,
+	 * up to the given semver level.
+	 */
+	update_policy?: ImageUpdatePolicy;
+	/**
+	 * Only consider registry tags matching this regex
+	 * for version tag updates.
+	 */
+	update_tag_filter?: string;
 	/**
 	 * Whether to automatically redeploy when
 	 * newer a image is found. Will implicitly
@@ -1374,8 +1823,38 @@ export interface DeploymentConfig {
 	 * enable both.
 	 */
 	auto_update?: boolean;
+	/**
+	 * Restrict auto updates to these windows.
+	 * Updates found outside of the windows are queued,
+	 * and deployed once a window opens.
+	 */
+	auto_update_windows?: MaintenanceWindow[];
+	/**
+	 * Also allow auto updates during the maintenance windows
+	 * of the attached Server / Swarm. If no windows are
+	 * configured at all, updates are deployed immediately.
+	 */
+	auto_update_in_maintenance?: boolean;
 	/** Whether to send ContainerStateChange alerts for this deployment. */
 	send_alerts: boolean;
+	/**
+	 * Scan the deployed image for vulnerabilities after every deploy.
+	 * Requires `trivy` or `grype` to be installed on the server.
+	 */
+	scan_image?: boolean;
+	/**
+	 * Send an `ImageVulnerabilities` alert if the scan finds vulnerabilities
+	 * at or above this severity. Default: `None`
+	 */
+	scan_threshold?: VulnerabilityThreshold;
+	/**
+	 * Require the image to have a valid `cosign` signature
+	 * from at least one of these public keys before deploying.
+	 * The verified digest is deployed, rather than the tag.
+	 * Requires `cosign` to be installed on the server.
+	 * Leave empty to skip verification.
+	 */
+	signature_keys?: string[];
 	/** Configure quick links that are displayed in the resource header */
 	links?: string[];
 	/**
@@ -1446,6 +1925,11 @@ export interface DeploymentInfo {
 	 * This includes both the image name / tag, and the specific digest hash.
 	 */
 	latest_image_digest?: ImageDigest;
+	/**
+	 * The image with the newest version tag allowed by the update policy.
+	 * Empty with the `digest` policy, or if there is no newer tag.
+	 */
+	latest_image?: string;
 	/**
 	 * The container / service name used at the time of the last deploy.
 	 * Kept to match the Deployment to its container / service
@@ -1504,8 +1988,13 @@ export interface DeploymentListItemInfo {
 	custom_name: string;
 	/** The image attached to the deployment. */
 	image: string;
-	/** Whether there is a newer image available at the same tag. */
+	/**
+	 * Whether there is a newer image available at the same tag,
+	 * or a newer version tag allowed by the update policy.
+	 */
 	update_available: boolean;
+	/** The image with the newer version tag, if available. */
+	latest_image?: string;
 	/** The swarm that deployment is deployed on, when in Swarm mode. */
 	swarm_id: string;
 	/** The name of the swarm that deployment is deployed on, when in Swarm mode. */
@@ -1610,6 +2099,15 @@ export enum SeverityLevel {
 	Critical = "CRITICAL",
 }
 
+/** The number of findings at each severity. */
+export interface VulnerabilityCounts {
+	critical: U64;
+	high: U64;
+	medium: U64;
+	low: U64;
+	unknown: U64;
+}
+
 /** The variants of data related to the alert. */
 export type AlertData = 
 	/** A null alert */
@@ -1837,6 +2335,22 @@ export type AlertData =
 	id: string;
 	/** The name of the repo */
 	name: string;
+}}
+	/**
+	 * An image scan found vulnerabilities
+	 * exceeding the configured threshold.
+	 */
+	| { type: "ImageVulnerabilities", data: {
+	/** The type of resource using the image */
+	resource_type: ResourceTarget["type"];
+	/** The id of the resource */
+	id: string;
+	/** The name of the resource */
+	name: string;
+	/** The scanned image */
+	image: string;
+	/** The number of findings at each severity */
+	counts: VulnerabilityCounts;
 }}
 	/** A procedure has failed */
 	| { type: "ProcedureFailed", data: {
@@ -1900,6 +2414,7 @@ export type GetAlerterResponse = Alerter;
 
 export interface BuildActionState {
 	building: boolean;
+	cleaning_images: boolean;
 }
 
 export type GetBuildActionStateResponse = BuildActionState;
@@ -2085,6 +2600,35 @@ export interface RepoConfig {
 	 * If its an empty string, use the default secret from the config.
 	 */
 	webhook_secret?: string;
+	/**
+	 * Report the result of webhook triggered executions
+	 * to the git provider as a commit status, linking to the Update.
+	 * Supports Github, Gitea / Forgejo, and Gitlab.
+	 * Requires `git_account` to be configured.
+	 */
+	webhook_commit_status?: boolean;
+	/**
+	 * Only trigger incoming webhooks when a changed file matches
+	 * one of these paths. Supports wildcard syntax, eg `services/api/**`.
+	 * If empty, any change triggers the webhook.
+	 */
+	webhook_include_paths?: string[];
+	/**
+	 * Don't trigger incoming webhooks when all the changed files
+	 * match these paths. Supports wildcard syntax, eg `docs/**`.
+	 */
+	webhook_exclude_paths?: string[];
+	/**
+	 * Whether incoming webhooks trigger on pushes to the branch,
+	 * or on pushed tags matching `webhook_tag_pattern`.
+	 */
+	webhook_trigger?: WebhookTrigger;
+	/**
+	 * With the `Tag` webhook trigger, only pushed tags matching
+	 * this pattern trigger the webhook. The pushed tag is set as the `commit`.
+	 * Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+	 */
+	webhook_tag_pattern?: string;
 	/**
 	 * Command to be run after the repo is cloned.
 	 * The path is relative to the root of the repo.
@@ -2174,6 +2718,24 @@ export interface ResourceSyncConfig {
 	 * If its an empty string, use the default secret from the config.
 	 */
 	webhook_secret?: string;
+	/**
+	 * Report the result of webhook triggered executions
+	 * to the git provider as a commit status, linking to the Update.
+	 * Supports Github, Gitea / Forgejo, and Gitlab.
+	 * Requires `git_account` to be configured.
+	 */
+	webhook_commit_status?: boolean;
+	/**
+	 * Only trigger incoming webhooks when a changed file matches
+	 * one of these paths. Supports wildcard syntax, eg `services/api/**`.
+	 * If empty, any change triggers the webhook.
+	 */
+	webhook_include_paths?: string[];
+	/**
+	 * Don't trigger incoming webhooks when all the changed files
+	 * match these paths. Supports wildcard syntax, eg `docs/**`.
+	 */
+	webhook_exclude_paths?: string[];
 	/**
 	 * Files are available on the Komodo Core host.
 	 * Specify the file / folder with [ResourceSyncConfig::resource_path].
@@ -2314,6 +2876,8 @@ export interface ServerActionState {
 	pruning_builders: boolean;
 	/** Server currently pruning builx cache */
 	pruning_buildx: boolean;
+	/** Server currently pruning the build cache of each buildx builder */
+	pruning_build_cache: boolean;
 	/** Server currently pruning system */
 	pruning_system: boolean;
 	/** Server currently starting containers. */
@@ -2484,6 +3048,24 @@ export interface StackFileDependency {
 	requires?: StackFileRequires;
 }
 
+/** The image update policy of a Stack service. */
+export interface StackServiceUpdatePolicy {
+	/** The service name. */
+	service: string;
+	/**
+	 * How image updates are detected.
+	 * - `digest`: A newer image pushed to the same tag. Default.
+	 * - `patch` / `minor` / `major`: Newer version tags in the registry,
+	 * up to the given semver level.
+	 */
+	policy?: ImageUpdatePolicy;
+	/**
+	 * Only consider registry tags matching this regex
+	 * for version tag updates.
+	 */
+	tag_filter?: string;
+}
+
 /** The compose file configuration. */
 export interface StackConfig {
 	/**
@@ -2525,8 +3107,19 @@ export interface StackConfig {
 	 * Note. Not used in Swarm mode.
 	 */
 	run_build?: boolean;
+	/**
+	 * Whether to validate the Stack before deploying,
+	 * failing the deploy if any errors are found. See `ValidateStack`.
+	 */
+	validate_before_deploy?: boolean;
 	/** Whether to poll for any updates to the images. */
 	poll_for_updates?: boolean;
+	/**
+	 * How image updates are detected for specific services.
+	 * Services not listed use the `digest` policy,
+	 * a newer image pushed to the same tag.
+	 */
+	update_policies?: StackServiceUpdatePolicy[];
 	/**
 	 * Whether to automatically redeploy when
 	 * newer images are found. Will implicitly
@@ -2547,6 +3140,18 @@ export interface StackConfig {
 	 * Manual checks still include all services.
 	 */
 	auto_update_skip_services?: string[];
+	/**
+	 * Restrict auto updates to these windows.
+	 * Updates found outside of the windows are queued,
+	 * and deployed once a window opens.
+	 */
+	auto_update_windows?: MaintenanceWindow[];
+	/**
+	 * Also allow auto updates during the maintenance windows
+	 * of the attached Server / Swarm. If no windows are
+	 * configured at all, updates are deployed immediately.
+	 */
+	auto_update_in_maintenance?: boolean;
 	/** Whether to run `docker compose down` before `compose up`. */
 	destroy_before_deploy?: boolean;
 	/** Whether to skip secret interpolation into the stack environment variables. */
@@ -2597,6 +3202,47 @@ export interface StackConfig {
 	 * If this option is enabled, will always run `DeployStack` without diffing.
 	 */
 	webhook_force_deploy?: boolean;
+	/**
+	 * Report the result of webhook triggered executions
+	 * to the git provider as a commit status, linking to the Update.
+	 * Supports Github, Gitea / Forgejo, and Gitlab.
+	 * Requires `git_account` to be configured.
+	 */
+	webhook_commit_status?: boolean;
+	/**
+	 * Only trigger incoming webhooks when a changed file matches
+	 * one of these paths. Supports wildcard syntax, eg `services/api/**`.
+	 * If empty, any change triggers the webhook.
+	 */
+	webhook_include_paths?: string[];
+	/**
+	 * Don't trigger incoming webhooks when all the changed files
+	 * match these paths. Supports wildcard syntax, eg `docs/**`.
+	 */
+	webhook_exclude_paths?: string[];
+	/**
+	 * Whether incoming webhooks trigger on pushes to the branch,
+	 * or on pushed tags matching `webhook_tag_pattern`.
+	 */
+	webhook_trigger?: WebhookTrigger;
+	/**
+	 * With the `Tag` webhook trigger, only pushed tags matching
+	 * this pattern trigger the webhook. The pushed tag is set as the `commit`.
+	 * Supports wildcard syntax, eg `v*`. If empty, any tag matches.
+	 */
+	webhook_tag_pattern?: string;
+	/**
+	 * Deploy a preview environment for each open pull request
+	 * targeting the branch, triggered by the `preview` webhook.
+	 * A temporary Stack is created from this one, using the pull request branch
+	 * and project name `{project_name}-pr-{number}`, and is destroyed and
+	 * deleted when the pull request is closed.
+	 * `[[PR_NUMBER]]` and `[[PR_BRANCH]]` are interpolated into the preview
+	 * compose files, environment, and commands,
+	 * even when `skip_secret_interp` is enabled.
+	 * Supports Github, Gitea / Forgejo, and Gitlab.
+	 */
+	preview_enabled?: boolean;
 	/**
 	 * If this is checked, the stack will source the files on the host.
 	 * Use `run_directory` and `file_paths` to specify the path on the host.
@@ -2639,6 +3285,24 @@ export interface StackConfig {
 	config_files?: StackFileDependency[];
 	/** Whether to send StackStateChange alerts for this stack. */
 	send_alerts: boolean;
+	/**
+	 * Scan the service images for vulnerabilities after every deploy.
+	 * Requires `trivy` or `grype` to be installed on the server.
+	 */
+	scan_images?: boolean;
+	/**
+	 * Send an `ImageVulnerabilities` alert if the scan finds vulnerabilities
+	 * at or above this severity. Default: `None`
+	 */
+	scan_threshold?: VulnerabilityThreshold;
+	/**
+	 * Require the service images to have a valid `cosign` signature
+	 * from at least one of these public keys before deploying.
+	 * The verified digest is deployed, rather than the tag.
+	 * Requires `cosign` to be installed on the server.
+	 * Leave empty to skip verification.
+	 */
+	signature_keys?: string[];
 	/** Used with `registry_account` to login to a registry before docker compose up. */
 	registry_provider?: string;
 	/** Used with `registry_provider` to login to a registry before docker compose up. */
@@ -2766,6 +3430,14 @@ export interface StackRemoteFileContents {
 	requires?: StackFileRequires;
 }
 
+/** A service image, eg. with a newer version tag. */
+export interface StackServiceImage {
+	/** The service name. */
+	service: string;
+	/** The image, including the tag. */
+	image: string;
+}
+
 export interface StackInfo {
 	/**
 	 * If any of the expected compose / additional files are missing in the repo,
@@ -2815,6 +3487,11 @@ export interface StackInfo {
 	latest_hash?: string;
 	/** Latest commit message, or null */
 	latest_message?: string;
+	/**
+	 * The images with the newest version tag allowed by
+	 * the service update policies, for services with a newer tag.
+	 */
+	latest_images?: StackServiceImage[];
 }
 
 export type Stack = Resource<StackConfig, StackInfo>;
@@ -5441,9 +6118,15 @@ export type ResourceSyncListItem = ResourceListItem<ResourceSyncListItemInfo>;
 
 export type ListResourceSyncsResponse = ResourceSyncListItem[];
 
-/** A scheduled Action / Procedure run. */
+/**
+ * A scheduled Action / Procedure run,
+ * or a one-off [ScheduledDeploy].
+ */
 export interface Schedule {
-	/** Procedure or Alerter */
+	/**
+	 * Action, Procedure, or the Deployment / Stack
+	 * of a scheduled deploy.
+	 */
 	target: ResourceTarget;
 	/** Readable name of the target resource */
 	name: string;
@@ -6435,6 +7118,18 @@ export interface BatchPullStack {
 	tags?: string[];
 }
 
+/**
+ * Validates the target stack without deploying.
+ * Writes the stack files to the host and runs `docker compose config`,
+ * checks the service images exist in the registry, and flags
+ * unset variables and published ports already used on the Server.
+ * The problems found are logged on the Update. Response: [Update]
+ */
+export interface ValidateStack {
+	/** Id or name */
+	stack: string;
+}
+
 /** Runs multiple Actions in parallel that match pattern. Response: [BatchExecutionResponse] */
 export interface BatchRunAction {
 	/**
@@ -6516,8 +7211,13 @@ export interface BatchRunProcedure {
 export interface BuildRepo {
 	/** Id or name */
 	repo: string;
-}
-
+	/**
+	 * Check out this commit hash or tag for this run only,
+	 * rather than the configured branch / commit.
+	 */
+	commit?: string;
+}
+
 /** Item in [GetBuildMonthlyStatsResponse] */
 export interface BuildStatsDay {
 	time: number;
@@ -6571,6 +7271,8 @@ export interface CancelRepoBuild {
 	repo: string;
 }
 
+export type CancelScheduledDeployResponse = NoData;
+
 /** Checks for newer image than what is deployed. Response: [CheckDeploymentForUpdateResponse] */
 export interface CheckDeploymentForUpdate {
 	/** Name or id */
@@ -6633,6 +7335,11 @@ export interface ClearRepoCache {
 export interface CloneRepo {
 	/** Id or name */
 	repo: string;
+	/**
+	 * Check out this commit hash or tag for this run only,
+	 * rather than the configured branch / commit.
+	 */
+	commit?: string;
 }
 
 /**
@@ -6654,6 +7361,491 @@ export interface CommitSync {
 	sync: string;
 }
 
+/** A single field difference between the current item and the backup. */
+export interface BackupItemFieldDiff {
+	/** The dot separated path to the field, eg `config.file_contents`. */
+	field: string;
+	/** The current value, serialized as JSON. Empty if not set. */
+	current: string;
+	/** The value in the backup, serialized as JSON. Empty if not set. */
+	backup: string;
+}
+
+/** How an item in the backup compares against the current database. */
+export enum BackupItemState {
+	/** The item is the same in the backup and the database. */
+	Unchanged = "Unchanged",
+	/** The item exists in both, but the contents differ. */
+	Changed = "Changed",
+	/**
+	 * The item exists in the backup, but not in the database.
+	 * Restoring it will recreate it.
+	 */
+	Missing = "Missing",
+}
+
+/**
+ * An item found in a backup, compared against
+ * the current state of the database.
+ */
+export interface BackupItem {
+	/** The type of the item. */
+	variant: BackupItemVariant;
+	/**
+	 * The database id of the item in the backup.
+	 * Empty for Variables, which are identified by name.
+	 */
+	id: string;
+	/** The name of the item in the backup. */
+	name: string;
+	/** How the backup contents compare to the current database. */
+	state: BackupItemState;
+	/**
+	 * The fields which would change on restore.
+	 * Only populated for [BackupItemState::Changed].
+	 */
+	diff: BackupItemFieldDiff[];
+}
+
+/**
+ * A host port (or port range) published on a Server,
+ * either configured on a Deployment / Stack,
+ * or published by a running container.
+ */
+export interface ServerPortAllocation {
+	/** The published host port, or the start of the range. */
+	port: number;
+	/**
+	 * The end of the published range.
+	 * Same as `port` for a single port.
+	 */
+	end_port: number;
+	/** The protocol, eg. `tcp`, `udp`. */
+	protocol: string;
+	/**
+	 * The host ip the port is bound to.
+	 * Empty when bound on all interfaces.
+	 */
+	host_ip: string;
+	/**
+	 * The Deployment / Stack allocating the port,
+	 * or null for containers not managed by Komodo.
+	 */
+	resource?: ResourceTarget;
+	/** The running container publishing the port, if any. */
+	container?: string;
+}
+
+export type ListServerPortAllocationsResponse = ServerPortAllocation[];
+
+/** A config field which differs between two revisions. */
+export interface RevisionFieldDiff {
+	/** The config field name. */
+	field: string;
+	/** The field value in the earlier revision. */
+	from: string;
+	/** The field value in the later revision. */
+	to: string;
+}
+
+export type DiffResourceRevisionsResponse = RevisionFieldDiff[];
+
+export type GetActionArgumentsResponse = ActionArgument[];
+
+/** The output of the run, or `null` if no output was set. */
+export type GetActionOutputResponse = JsonValue;
+
+/**
+ * A snapshot of a resource config,
+ * saved each time the resource config is updated.
+ */
+export interface ResourceRevision {
+	/**
+	 * The Mongo ID of the revision.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized ResourceRevision) }`
+	 */
+	_id?: MongoId;
+	/** The resource the config belongs to. */
+	target: ResourceTarget;
+	/** Unix timestamp in milliseconds the config was saved. */
+	ts: I64;
+	/**
+	 * The id of the user who made the change.
+	 * Empty for the baseline revision, the config from before
+	 * the first change made with revisions tracked.
+	 */
+	operator?: string;
+	/** The username of the user who made the change. */
+	username?: string;
+	/** The id of the config Update, if any. */
+	update_id?: string;
+	/**
+	 * The full resource config.
+	 * Not included in list responses.
+	 */
+	config?: JsonObject;
+}
+
+export type GetResourceRevisionResponse = ResourceRevision;
+
+export type ListRegistryRepositoriesResponse = string[];
+
+export type ListResourceRevisionsResponse = ResourceRevision[];
+
+/** A resource related to a [RegistryTag]. */
+export interface RegistryTagResource {
+	name: string;
+	id: string;
+}
+
+/** An image tag in an image registry repository. */
+export interface RegistryTag {
+	/** The tag, eg. `1.19.5` */
+	tag: string;
+	/** The manifest digest the tag points to. */
+	digest: string;
+	/**
+	 * The total size of the image layers in bytes.
+	 * For multi-platform images, this is the total across all platforms.
+	 */
+	size: I64;
+	/**
+	 * Unix timestamp in milliseconds the image was created,
+	 * if it could be determined.
+	 */
+	created?: I64;
+	/** The image platforms, eg. `linux/amd64`. */
+	platforms: string[];
+	/** The Builds which push this image tag. */
+	builds: RegistryTagResource[];
+	/**
+	 * The Deployments which currently use this image tag,
+	 * either in their config or in their running container.
+	 */
+	deployments: RegistryTagResource[];
+	/**
+	 * The Stacks which currently use this image tag,
+	 * either in their compose files or in their running services.
+	 */
+	stacks: RegistryTagResource[];
+}
+
+export type ListRegistryTagsResponse = RegistryTag[];
+
+export type RestoreResourceRevisionResponse = NoData;
+
+/**
+ * A one-off Deploy / DeployStack, scheduled to run at a specific time.
+ * Each Deployment / Stack can have one scheduled deploy at a time.
+ */
+export interface ScheduledDeploy {
+	/**
+	 * The Mongo ID of the scheduled deploy.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized ScheduledDeploy) }`
+	 */
+	_id?: MongoId;
+	/** The Deployment or Stack to deploy. */
+	target: ResourceTarget;
+	/** Unix timestamp in milliseconds to run the deploy. */
+	run_at: I64;
+	/**
+	 * The id of the user who scheduled the deploy.
+	 * The deploy is run as this user.
+	 */
+	operator: string;
+	/** Unix timestamp in milliseconds the deploy was scheduled. */
+	created_at: I64;
+}
+
+export type ScheduleDeployResponse = ScheduledDeploy;
+
+/**
+ * A preview environment, a temporary Stack deployed
+ * for an open pull request from a template Stack
+ * with `preview_enabled`.
+ */
+export interface StackPreview {
+	/**
+	 * The Mongo ID of the preview.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized StackPreview) }`
+	 */
+	_id?: MongoId;
+	/** Unix timestamp in milliseconds the preview was created */
+	created_at: I64;
+	/** Unix timestamp in milliseconds the preview was last deployed */
+	updated_at: I64;
+	/** The id of the template Stack */
+	stack_id: string;
+	/** The id of the preview Stack */
+	preview_stack_id: string;
+	/** The pull request number */
+	pr_number: I64;
+	/** The pull request source branch */
+	branch: string;
+}
+
+export type ListStackPreviewsResponse = StackPreview[];
+
+/** A package with a different version between two SBOMs. */
+export interface SbomPackageChange {
+	/** The package name */
+	name: string;
+	/** The version in the older SBOM */
+	from: string;
+	/** The version in the newer SBOM */
+	to: string;
+}
+
+/** A package listed in an SBOM. */
+export interface SbomPackage {
+	/** The package name */
+	name: string;
+	/** The package version */
+	version?: string;
+	/** The package url, eg `pkg:deb/debian/openssl@3.0.11` */
+	purl?: string;
+}
+
+/** The differences in packages between two SBOMs. */
+export interface SbomDiff {
+	/** Packages only in the newer SBOM. */
+	added: SbomPackage[];
+	/** Packages only in the older SBOM. */
+	removed: SbomPackage[];
+	/** Packages in both SBOMs with a different version. */
+	changed: SbomPackageChange[];
+}
+
+export type DiffBuildSbomsResponse = SbomDiff;
+
+/** A file downloaded from a container or server host. */
+export interface FileDownload {
+	/** The full path to the file. */
+	path: string;
+	/** The size of the file in bytes. */
+	size: U64;
+	/** The file contents, base64 encoded. */
+	contents: string;
+}
+
+/** A software bill of materials generated for a built image. */
+export interface BuildSbom {
+	/**
+	 * The Mongo ID of the SBOM.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized BuildSbom) }`
+	 */
+	_id?: MongoId;
+	/** Unix timestamp in milliseconds the SBOM was generated */
+	ts: I64;
+	/** The id of the Build */
+	build_id: string;
+	/** The id of the RunBuild Update which generated the SBOM */
+	update_id: string;
+	/** The build version */
+	version: Version;
+	/** The commit hash of the build, if built from a repo. */
+	commit_hash?: string;
+	/** The image the SBOM was generated for, eg `mbecker2020/komodo:1.2.3` */
+	image: string;
+	/** The image digest, if it could be determined. */
+	digest: string;
+	/** The SBOM document format */
+	format: SbomFormat;
+	/**
+	 * The packages contained in the image, sorted by name.
+	 * Not included in list responses.
+	 */
+	packages?: SbomPackage[];
+	/**
+	 * The full SBOM json document.
+	 * Not included in list responses.
+	 */
+	document?: string;
+}
+
+export type GetBuildSbomResponse = BuildSbom;
+
+export type GetContainerFileResponse = FileDownload;
+
+/**
+ * The supported vulnerability scanner CLIs.
+ * The first one found on the Periphery host is used.
+ */
+export enum ImageScanner {
+	/** https://github.com/aquasecurity/trivy */
+	Trivy = "Trivy",
+	/** https://github.com/anchore/grype */
+	Grype = "Grype",
+}
+
+export type ListBuildSbomsResponse = BuildSbom[];
+
+/** The SBOM returned from Periphery. */
+export interface SbomReport {
+	/** The SBOM document format */
+	format: SbomFormat;
+	/** The image digest, if it could be determined. */
+	digest: string;
+	/** The packages contained in the image, sorted by name. */
+	packages: SbomPackage[];
+	/** The full SBOM json document. */
+	document: string;
+}
+
+export enum StackDiagnosticKind {
+	/** Failed to write the stack files or run `docker compose config`. */
+	ComposeConfig = "ComposeConfig",
+	/** A compose / env / config file is missing. */
+	MissingFile = "MissingFile",
+	/** A variable used in the compose files or environment is not set. */
+	UnsetVariable = "UnsetVariable",
+	/** A service image doesn't exist in the registry. */
+	MissingImage = "MissingImage",
+	/** A published port is already used on the Server. */
+	PortConflict = "PortConflict",
+}
+
+export enum StackDiagnosticLevel {
+	Error = "Error",
+	Warning = "Warning",
+}
+
+/** A problem found when validating a Stack before deploy. */
+export interface StackDiagnostic {
+	/** Errors will fail the deploy, warnings are only reported. */
+	level: StackDiagnosticLevel;
+	/** The type of problem. */
+	kind: StackDiagnosticKind;
+	/** The service the problem relates to, if any. */
+	service?: string;
+	/** Describes the problem. */
+	message: string;
+}
+
+/** Vulnerability severity, ordered from least to most severe. */
+export enum VulnerabilitySeverity {
+	Unknown = "Unknown",
+	Low = "Low",
+	Medium = "Medium",
+	High = "High",
+	Critical = "Critical",
+}
+
+/** A single vulnerability found in an image. */
+export interface Vulnerability {
+	/** The vulnerability id, eg `CVE-2024-12345` */
+	id: string;
+	/** The severity */
+	severity: VulnerabilitySeverity;
+	/** The affected package */
+	package: string;
+	/** The installed package version */
+	installed_version: string;
+	/** The version the vulnerability is fixed in, if one exists. */
+	fixed_version?: string;
+	/** A short description, if provided by the scanner. */
+	title?: string;
+}
+
+/**
+ * The summarized results of scanning an image for vulnerabilities.
+ * Stored per target resource and image digest.
+ */
+export interface ImageScan {
+	/**
+	 * The Mongo ID of the image scan.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized ImageScan) }`
+	 */
+	_id?: MongoId;
+	/** Unix timestamp in milliseconds of the scan */
+	ts: I64;
+	/** The resource (Build / Deployment / Stack) the image belongs to. */
+	target: ResourceTarget;
+	/** The scanned image, eg `mbecker2020/komodo:latest` */
+	image: string;
+	/** The scanned image digest, if it could be determined. */
+	digest: string;
+	/** The scanner used. */
+	scanner: ImageScanner;
+	/** The number of findings at each severity. */
+	counts: VulnerabilityCounts;
+	/**
+	 * The individual findings, sorted by severity descending.
+	 * Not included in list responses.
+	 */
+	findings?: Vulnerability[];
+}
+
+export type GetImageScanResponse = ImageScan;
+
+export type GetServerFileResponse = FileDownload;
+
+/** The results of an image scan returned from Periphery. */
+export interface ImageScanReport {
+	/** The scanner used. */
+	scanner: ImageScanner;
+	/** The scanned image digest, if it could be determined. */
+	digest: string;
+	/** The number of findings at each severity. */
+	counts: VulnerabilityCounts;
+	/** The individual findings, sorted by severity descending. */
+	findings: Vulnerability[];
+}
+
+/** Response for [ListBackupItems]. */
+export interface ListBackupItemsResponse {
+	/** The dated backup folder which was read. */
+	backup: string;
+	/** The restorable items in the backup. */
+	items: BackupItem[];
+}
+
+export enum FileEntryKind {
+	File = "File",
+	Directory = "Directory",
+	Symlink = "Symlink",
+	Other = "Other",
+}
+
+/**
+ * An entry in a directory listing,
+ * either inside a container or on the server host.
+ */
+export interface FileEntry {
+	/** The file name. */
+	name: string;
+	/** The full path to the file. */
+	path: string;
+	/** The type of the entry. */
+	kind: FileEntryKind;
+	/** The size of the file in bytes. */
+	size: U64;
+	/**
+	 * When the file was last modified.
+	 * Unix timestamp milliseconds.
+	 */
+	modified: I64;
+}
+
+export type ListContainerDirectoryResponse = FileEntry[];
+
+/** Response for [ListImageScans]. */
+export interface ListImageScansResponse {
+	scans: ImageScan[];
+	/**
+	 * If more scans exist, the next page will be given here.
+	 * Otherwise it will be `null`
+	 */
+	next_page?: I64;
+}
+
+export type ListServerDirectoryResponse = FileEntry[];
+
 /**
  * Configures the behavior of [CreateTerminal] if the
  * specified terminal name already exists.
@@ -7651,6 +8843,11 @@ export interface DeployStack {
 	 * Only used if the stack needs to be taken down first.
 	 */
 	stop_time?: number;
+	/**
+	 * Deploy this commit hash or tag for this run only,
+	 * rather than the configured branch / commit.
+	 */
+	commit?: string;
 }
 
 /**
@@ -10422,6 +11619,11 @@ export interface PullDeployment {
 export interface PullRepo {
 	/** Id or name */
 	repo: string;
+	/**
+	 * Check out this commit hash or tag for this run only,
+	 * rather than the configured branch / commit.
+	 */
+	commit?: string;
 }
 
 /** Pulls images for the target stack. `docker compose pull`. Response: [Update] */
@@ -10891,6 +12093,16 @@ export interface RunAction {
 export interface RunBuild {
 	/** Can be build id or name */
 	build: string;
+	/**
+	 * Build at this commit hash or tag for this run only,
+	 * rather than the configured branch / commit.
+	 */
+	commit?: string;
+	/**
+	 * Build with this version for this run only. The configured
+	 * version is not auto incremented or updated.
+	 */
+	version?: Version;
 }
 
 /** Runs the target Procedure. Response: [Update] */
@@ -11817,6 +13029,7 @@ export type ExecuteRequest =
 	| { type: "BatchDeployStackIfChanged", params: BatchDeployStackIfChanged }
 	| { type: "PullStack", params: PullStack }
 	| { type: "BatchPullStack", params: BatchPullStack }
+	| { type: "ValidateStack", params: ValidateStack }
 	| { type: "StartStack", params: StartStack }
 	| { type: "RestartStack", params: RestartStack }
 	| { type: "StopStack", params: StopStack }
@@ -11838,6 +13051,7 @@ export type ExecuteRequest =
 	| { type: "RunBuild", params: RunBuild }
 	| { type: "BatchRunBuild", params: BatchRunBuild }
 	| { type: "CancelBuild", params: CancelBuild }
+	| { type: "CleanupBuildImages", params: CleanupBuildImages }
 	| { type: "CloneRepo", params: CloneRepo }
 	| { type: "BatchCloneRepo", params: BatchCloneRepo }
 	| { type: "PullRepo", params: PullRepo }
@@ -11874,6 +13088,7 @@ export type ExecuteRequest =
 	| { type: "PruneVolumes", params: PruneVolumes }
 	| { type: "PruneDockerBuilders", params: PruneDockerBuilders }
 	| { type: "PruneBuildx", params: PruneBuildx }
+	| { type: "PruneBuildCache", params: PruneBuildCache }
 	| { type: "PruneSystem", params: PruneSystem }
 	| { type: "RemoveSwarmNodes", params: RemoveSwarmNodes }
 	| { type: "UpdateSwarmNode", params: UpdateSwarmNode }
@@ -11977,6 +13192,268 @@ export enum IanaTimezone {
 	PacificKiritimati = "Pacific/Kiritimati",
 }
 
+/**
+ * **Admin only.** List the resources, variables and user groups
+ * contained in a database backup, each compared against
+ * the current database contents.
+ * Response: [ListBackupItemsResponse].
+ * 
+ * Use with [RestoreBackupItems][crate::api::write::RestoreBackupItems]
+ * to restore only specific items.
+ */
+export interface ListBackupItems {
+	/**
+	 * The dated backup folder to read, eg `2025-08-01_05-04-53`.
+	 * If not provided, will use the most recent backup.
+	 */
+	backup?: string;
+}
+
+/**
+ * Download a file from inside a container, like `docker cp`.
+ * The file must be smaller than the Core `max_file_transfer_size_mb`.
+ * Requires minimum Read + Inspect permission on the Server.
+ * Response: [FileDownload].
+ */
+export interface GetContainerFile {
+	/** Id or name */
+	server: string;
+	/** The container name */
+	container: string;
+	/** The absolute path of the file inside the container. */
+	path: string;
+}
+
+/**
+ * Download a file from the server host.
+ * The path must be inside the Periphery `root_directory`,
+ * and the file smaller than the Core `max_file_transfer_size_mb`.
+ * Requires minimum Read + Inspect permission on the Server.
+ * Response: [FileDownload].
+ */
+export interface GetServerFile {
+	/** Id or name */
+	server: string;
+	/** The path of the file, relative to the Periphery `root_directory`. */
+	path: string;
+}
+
+/**
+ * List the entries of a directory inside a container.
+ * Requires the container to have `find` and `stat` available.
+ * Requires minimum Read + Inspect permission on the Server.
+ * Response: [ListContainerDirectoryResponse].
+ */
+export interface ListContainerDirectory {
+	/** Id or name */
+	server: string;
+	/** The container name */
+	container: string;
+	/** The absolute path of the directory inside the container. */
+	path: string;
+}
+
+/**
+ * List the entries of a directory on the server host.
+ * The path must be inside the Periphery `root_directory`.
+ * Requires minimum Read + Inspect permission on the Server.
+ * Response: [ListServerDirectoryResponse].
+ */
+export interface ListServerDirectory {
+	/** Id or name */
+	server: string;
+	/**
+	 * The path of the directory, relative to the Periphery `root_directory`.
+	 * Leave empty to list the `root_directory` itself.
+	 */
+	path?: string;
+}
+
+/**
+ * Get an image scan, including the individual findings.
+ * Response: [ImageScan].
+ */
+export interface GetImageScan {
+	id: string;
+}
+
+/**
+ * Get a paginated list of image scans sorted by timestamp descending.
+ * The individual findings are not included, use [GetImageScan] to get them.
+ * Response: [ListImageScansResponse].
+ */
+export interface ListImageScans {
+	/**
+	 * Pass a custom mongo query to filter the image scans.
+	 * 
+	 * ## Example JSON
+	 * ```json
+	 * {
+	 * "target": {
+	 * "type": "Deployment",
+	 * "id": "6608bf89cb2a12b257ab6c09"
+	 * },
+	 * "counts.critical": { "$gt": 0 }
+	 * }
+	 * ```
+	 * This will filter to only include scans of the Deployment with critical findings.
+	 */
+	query?: MongoDocument;
+	/**
+	 * Retrieve older results by incrementing the page.
+	 * `page: 0` is default, and returns the most recent results.
+	 */
+	page?: U64;
+}
+
+/**
+ * Compare the packages in the SBOMs of two build versions.
+ * Uses the most recent SBOM generated for each version.
+ * Response: [SbomDiff].
+ */
+export interface DiffBuildSboms {
+	/** Id or name */
+	build: string;
+	/** The older version */
+	from: Version;
+	/** The newer version */
+	to: Version;
+}
+
+/**
+ * Get an SBOM, including the packages and full document.
+ * Response: [BuildSbom].
+ */
+export interface GetBuildSbom {
+	id: string;
+}
+
+/**
+ * List the SBOMs generated for a build, sorted by most recent first.
+ * The packages and document are not included, use [GetBuildSbom] to get them.
+ * Response: [ListBuildSbomsResponse].
+ */
+export interface ListBuildSboms {
+	/** Id or name */
+	build: string;
+	/** Limit the number of included results. Default is no limit. */
+	limit?: I64;
+}
+
+/**
+ * List the pull request preview environments deployed from a stack,
+ * sorted by pull request number.
+ * Response: [ListStackPreviewsResponse].
+ */
+export interface ListStackPreviews {
+	/** Id or name of the template stack */
+	stack: string;
+}
+
+/**
+ * List the repositories in an image registry
+ * using the registry catalog. Admin only.
+ * Response: [ListRegistryRepositoriesResponse].
+ * 
+ * Note. Some registries, such as Docker Hub,
+ * don't support listing repositories.
+ */
+export interface ListRegistryRepositories {
+	/** The registry domain, eg. `ghcr.io` */
+	domain: string;
+	/**
+	 * The registry account to authenticate with.
+	 * Uses anonymous access if empty.
+	 */
+	account?: string;
+}
+
+/**
+ * List the tags of an image registry repository,
+ * along with the Builds, Deployments, and Stacks using each tag.
+ * Admin only.
+ * Response: [ListRegistryTagsResponse].
+ */
+export interface ListRegistryTags {
+	/** The registry domain, eg. `ghcr.io` */
+	domain: string;
+	/**
+	 * The registry account to authenticate with.
+	 * Uses anonymous access if empty.
+	 */
+	account?: string;
+	/** The repository, eg. `moghtech/komodo-core` */
+	repository: string;
+}
+
+/**
+ * Get the JSON output set by an action run using `setOutput`.
+ * Response: [GetActionOutputResponse].
+ */
+export interface GetActionOutput {
+	/** Id or name */
+	action: string;
+	/**
+	 * The id of the `RunAction` update.
+	 * If not provided, uses the latest run.
+	 */
+	update_id?: string;
+}
+
+/**
+ * Get the arguments declared in the action `argument_schema`,
+ * for prompting for values before a run.
+ * Response: [GetActionArgumentsResponse].
+ */
+export interface GetActionArguments {
+	/** Id or name */
+	action: string;
+}
+
+/**
+ * Compare the configs of two revisions of the same resource.
+ * Response: [DiffResourceRevisionsResponse].
+ */
+export interface DiffResourceRevisions {
+	/** The id of the earlier revision. */
+	from: string;
+	/**
+	 * The id of the later revision.
+	 * If not provided, compares against the current config.
+	 */
+	to?: string;
+}
+
+/**
+ * Get a resource config revision, including the full config.
+ * Response: [ResourceRevision].
+ */
+export interface GetResourceRevision {
+	id: string;
+}
+
+/**
+ * List the config revisions of a resource, sorted by most recent first.
+ * The config is not included, use [GetResourceRevision] to get it.
+ * Response: [ListResourceRevisionsResponse].
+ */
+export interface ListResourceRevisions {
+	/** The target resource. */
+	target: ResourceTarget;
+	/** Limit the number of included results. Default is no limit. */
+	limit?: I64;
+}
+
+/**
+ * List the host ports allocated on the server,
+ * by the Deployment / Stack configs and the running containers.
+ * Response: [ListServerPortAllocationsResponse].
+ */
+export interface ListServerPortAllocations {
+	/** Id or name */
+	server: string;
+}
+
 export type ReadRequest = 
 	| { type: "GetVersion", params: GetVersion }
 	| { type: "GetCoreInfo", params: GetCoreInfo }
@@ -12008,6 +13485,7 @@ export type ReadRequest =
 	| { type: "GetServer", params: GetServer }
 	| { type: "GetServerState", params: GetServerState }
 	| { type: "GetPeripheryInformation", params: GetPeripheryInformation }
+	| { type: "ListServerPortAllocations", params: ListServerPortAllocations }
 	| { type: "GetServerActionState", params: GetServerActionState }
 	| { type: "ListServers", params: ListServers }
 	| { type: "ListFullServers", params: ListFullServers }
@@ -12027,6 +13505,10 @@ export type ReadRequest =
 	| { type: "ListImageHistory", params: ListImageHistory }
 	| { type: "ListVolumes", params: ListVolumes }
 	| { type: "InspectVolume", params: InspectVolume }
+	| { type: "ListContainerDirectory", params: ListContainerDirectory }
+	| { type: "GetContainerFile", params: GetContainerFile }
+	| { type: "ListServerDirectory", params: ListServerDirectory }
+	| { type: "GetServerFile", params: GetServerFile }
 	| { type: "GetSystemInformation", params: GetSystemInformation }
 	| { type: "GetSystemStats", params: GetSystemStats }
 	| { type: "GetHistoricalServerStats", params: GetHistoricalServerStats }
@@ -12044,6 +13526,7 @@ export type ReadRequest =
 	| { type: "ListAllStackServices", params: ListAllStackServices }
 	| { type: "ListCommonStackExtraArgs", params: ListCommonStackExtraArgs }
 	| { type: "ListCommonStackBuildExtraArgs", params: ListCommonStackBuildExtraArgs }
+	| { type: "ListStackPreviews", params: ListStackPreviews }
 	| { type: "GetDeploymentsSummary", params: GetDeploymentsSummary }
 	| { type: "GetDeployment", params: GetDeployment }
 	| { type: "GetDeploymentContainer", params: GetDeploymentContainer }
@@ -12061,6 +13544,9 @@ export type ReadRequest =
 	| { type: "GetBuildActionState", params: GetBuildActionState }
 	| { type: "GetBuildMonthlyStats", params: GetBuildMonthlyStats }
 	| { type: "ListBuildVersions", params: ListBuildVersions }
+	| { type: "ListBuildSboms", params: ListBuildSboms }
+	| { type: "GetBuildSbom", params: GetBuildSbom }
+	| { type: "DiffBuildSboms", params: DiffBuildSboms }
 	| { type: "ListBuilds", params: ListBuilds }
 	| { type: "ListFullBuilds", params: ListFullBuilds }
 	| { type: "ListCommonBuildExtraArgs", params: ListCommonBuildExtraArgs }
@@ -12077,6 +13563,8 @@ export type ReadRequest =
 	| { type: "GetActionsSummary", params: GetActionsSummary }
 	| { type: "GetAction", params: GetAction }
 	| { type: "GetActionActionState", params: GetActionActionState }
+	| { type: "GetActionArguments", params: GetActionArguments }
+	| { type: "GetActionOutput", params: GetActionOutput }
 	| { type: "ListActions", params: ListActions }
 	| { type: "ListFullActions", params: ListFullActions }
 	| { type: "ListSchedules", params: ListSchedules }
@@ -12093,6 +13581,9 @@ export type ReadRequest =
 	| { type: "GetAlerter", params: GetAlerter }
 	| { type: "ListAlerters", params: ListAlerters }
 	| { type: "ListFullAlerters", params: ListFullAlerters }
+	| { type: "ListResourceRevisions", params: ListResourceRevisions }
+	| { type: "GetResourceRevision", params: GetResourceRevision }
+	| { type: "DiffResourceRevisions", params: DiffResourceRevisions }
 	| { type: "ExportAllResourcesToToml", params: ExportAllResourcesToToml }
 	| { type: "ExportResourcesToToml", params: ExportResourcesToToml }
 	| { type: "GetTag", params: GetTag }
@@ -12111,13 +13602,18 @@ export type ReadRequest =
 	| { type: "ListUpdates", params: ListUpdates }
 	| { type: "ListAlerts", params: ListAlerts }
 	| { type: "GetAlert", params: GetAlert }
+	| { type: "ListImageScans", params: ListImageScans }
+	| { type: "GetImageScan", params: GetImageScan }
 	| { type: "GetVariable", params: GetVariable }
 	| { type: "ListVariables", params: ListVariables }
 	| { type: "GetGitProviderAccount", params: GetGitProviderAccount }
 	| { type: "ListGitProviderAccounts", params: ListGitProviderAccounts }
 	| { type: "GetImageRegistryAccount", params: GetImageRegistryAccount }
 	| { type: "ListImageRegistryAccounts", params: ListImageRegistryAccounts }
-	| { type: "ListOnboardingKeys", params: ListOnboardingKeys };
+	| { type: "ListRegistryRepositories", params: ListRegistryRepositories }
+	| { type: "ListRegistryTags", params: ListRegistryTags }
+	| { type: "ListOnboardingKeys", params: ListOnboardingKeys }
+	| { type: "ListBackupItems", params: ListBackupItems };
 
 export enum RepoWebhookAction {
 	Clone = "Clone",
@@ -12174,8 +13670,49 @@ export enum SyncWebhookAction {
 	Sync = "Sync",
 }
 
+/**
+ * Restore a resource config to a previous revision.
+ * This is applied as a normal config update,
+ * so it is recorded as a new revision and can itself be reverted.
+ * Response: [NoData].
+ */
+export interface RestoreResourceRevision {
+	/** The id of the revision to restore. */
+	id: string;
+}
+
+/**
+ * Cancel the scheduled deploy of a Deployment or Stack.
+ * Requires Execute permission on the resource.
+ * Response: [NoData].
+ */
+export interface CancelScheduledDeploy {
+	/** The Deployment or Stack with the scheduled deploy. */
+	target: ResourceTarget;
+}
+
+/**
+ * Schedule a one-off Deploy / DeployStack to run at a specific time.
+ * Replaces any deploy already scheduled for the resource.
+ * The scheduled deploy is listed in [ListSchedules][crate::api::read::ListSchedules].
+ * Requires Execute permission on the resource.
+ * Response: [ScheduledDeploy].
+ */
+export interface ScheduleDeploy {
+	/** The Deployment or Stack to deploy. */
+	target: ResourceTarget;
+	/**
+	 * Unix timestamp in milliseconds to run the deploy.
+	 * Must be in the future.
+	 */
+	run_at: I64;
+}
+
 export type WriteRequest = 
 	| { type: "UpdateResourceMeta", params: UpdateResourceMeta }
+	| { type: "RestoreResourceRevision", params: RestoreResourceRevision }
+	| { type: "ScheduleDeploy", params: ScheduleDeploy }
+	| { type: "CancelScheduledDeploy", params: CancelScheduledDeploy }
 	| { type: "CreateSwarm", params: CreateSwarm }
 	| { type: "CopySwarm", params: CopySwarm }
 	| { type: "DeleteSwarm", params: DeleteSwarm }
@@ -12193,6 +13730,8 @@ export type WriteRequest =
 	| { type: "DeleteTerminal", params: DeleteTerminal }
 	| { type: "DeleteAllTerminals", params: DeleteAllTerminals }
 	| { type: "BatchDeleteAllTerminals", params: BatchDeleteAllTerminals }
+	| { type: "WriteContainerFile", params: WriteContainerFile }
+	| { type: "WriteServerFile", params: WriteServerFile }
 	| { type: "CreateStack", params: CreateStack }
 	| { type: "CopyStack", params: CopyStack }
 	| { type: "DeleteStack", params: DeleteStack }
@@ -12288,7 +13827,8 @@ export type WriteRequest =
 	| { type: "CreateImageRegistryAccount", params: CreateImageRegistryAccount }
 	| { type: "UpdateImageRegistryAccount", params: UpdateImageRegistryAccount }
 	| { type: "DeleteImageRegistryAccount", params: DeleteImageRegistryAccount }
-	| { type: "CloseAlert", params: CloseAlert };
+	| { type: "CloseAlert", params: CloseAlert }
+	| { type: "RestoreBackupItems", params: RestoreBackupItems };
 
 export type WsLoginMessage = 
 	| { type: "Jwt", params: {
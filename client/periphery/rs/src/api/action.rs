@@ -0,0 +1,99 @@
+use komodo_client::entities::{
+  JsonObject, JsonValue, NoData, update::Log,
+};
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+
+//
+
+/// Start running an Action script on the host with Deno.
+/// Uses `deno` if installed, otherwise runs the script
+/// in the `denoland/deno` container.
+///
+/// Returns as soon as the run starts.
+/// Poll [GetActionRun] for the output.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(NoData)]
+#[error(anyhow::Error)]
+pub struct RunAction {
+  /// Identifies the run, used with [GetActionRun] and [CancelAction].
+  pub id: String,
+  /// The full script contents, including the komodo client setup.
+  pub contents: String,
+  /// The Deno permission flags, eg ` --allow-net=komodo.example.com`
+  pub permission_flags: String,
+  /// Reload all the Deno dependencies.
+  #[serde(default)]
+  pub reload: bool,
+  /// The shared libraries the script imports.
+  #[serde(default)]
+  pub libraries: Vec<ActionLibrary>,
+}
+
+/// A shared library module, written alongside the Action script
+/// and imported as `@lib/<name>`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionLibrary {
+  /// The library Action id, used for the file name.
+  pub id: String,
+  /// The library Action name, used in the import specifier.
+  pub name: String,
+  /// The library Typescript contents.
+  pub contents: String,
+}
+
+impl ActionLibrary {
+  pub fn file_name(&self) -> String {
+    format!("{}.ts", self.id)
+  }
+
+  /// The Deno import map for the libraries,
+  /// written in the same directory as the library files.
+  pub fn import_map(libraries: &[ActionLibrary]) -> String {
+    let imports = libraries
+      .iter()
+      .map(|library| {
+        (
+          format!("@lib/{}", library.name),
+          JsonValue::String(format!("./{}", library.file_name())),
+        )
+      })
+      .collect::<JsonObject>();
+    JsonValue::Object(JsonObject::from_iter([(
+      String::from("imports"),
+      JsonValue::Object(imports),
+    )]))
+    .to_string()
+  }
+}
+
+//
+
+/// Get the output of an Action run started with [RunAction].
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(GetActionRunResponse)]
+#[error(anyhow::Error)]
+pub struct GetActionRun {
+  pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetActionRunResponse {
+  /// The output so far.
+  pub stdout: String,
+  /// The error output so far.
+  pub stderr: String,
+  /// The final log, once the run has finished.
+  /// The run is forgotten after this is returned.
+  pub log: Option<Log>,
+}
+
+//
+
+/// Cancel an Action run started with [RunAction].
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(NoData)]
+#[error(anyhow::Error)]
+pub struct CancelAction {
+  pub id: String,
+}
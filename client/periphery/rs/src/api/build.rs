@@ -1,5 +1,8 @@
 use komodo_client::entities::{
-  FileContents, NoData, repo::Repo, update::Log,
+  FileContents, NoData,
+  repo::Repo,
+  sbom::{SbomFormat, SbomReport},
+  update::Log,
 };
 use mogh_resolver::Resolve;
 use serde::{Deserialize, Serialize};
@@ -25,12 +28,79 @@ pub struct Build {
   /// Add more tags for this build in addition to the version tags.
   #[serde(default)]
   pub additional_tags: Vec<String>,
+  /// Only build the image locally, without pushing it,
+  /// so it can be scanned before [PushImage].
+  #[serde(default)]
+  pub skip_push: bool,
 }
 
 pub type BuildResponse = Vec<Log>;
 
 //
 
+/// Push the image tags of a build made with `skip_push`
+/// to the image registries.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(PushImageResponse)]
+#[error(anyhow::Error)]
+pub struct PushImage {
+  pub build: komodo_client::entities::build::Build,
+  /// Override registry tokens with ones sent from core.
+  /// maps (domain, account) -> token.
+  #[serde(default)]
+  pub registry_tokens: Vec<(String, String, String)>,
+  /// Pass the commit hash to use with tagging
+  pub commit_hash: Option<String>,
+}
+
+pub type PushImageResponse = Vec<Log>;
+
+//
+
+/// Combine the platform specific images pushed by each builder
+/// into a multi-platform manifest list under the build tags,
+/// using `docker buildx imagetools create`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(PushManifestListResponse)]
+#[error(anyhow::Error)]
+pub struct PushManifestList {
+  pub build: komodo_client::entities::build::Build,
+  /// Override registry tokens with ones sent from core.
+  /// maps (domain, account) -> token.
+  #[serde(default)]
+  pub registry_tokens: Vec<(String, String, String)>,
+  /// Pass the commit hash to use with tagging
+  pub commit_hash: Option<String>,
+  /// The platforms built by each builder,
+  /// used to find the platform specific tags.
+  pub platform_groups: Vec<Vec<String>>,
+}
+
+pub type PushManifestListResponse = Vec<Log>;
+
+//
+
+/// Generate an SBOM for a built image using the `syft` CLI.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(GenerateSbomResponse)]
+#[error(anyhow::Error)]
+pub struct GenerateSbom {
+  /// The name of the image, including the tag.
+  pub image: String,
+  /// The SBOM document format.
+  pub format: SbomFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenerateSbomResponse {
+  /// The log of the syft command.
+  pub log: Log,
+  /// The SBOM, if it was generated successfully.
+  pub sbom: Option<SbomReport>,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
 #[response(NoData)]
 #[error(anyhow::Error)]
@@ -87,3 +157,25 @@ pub struct PruneBuilders {}
 #[response(Log)]
 #[error(anyhow::Error)]
 pub struct PruneBuildx {}
+
+//
+
+/// Prune the build cache of each buildx builder on the host,
+/// reporting the space reclaimed from each.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(PruneBuildCacheResponse)]
+#[error(anyhow::Error)]
+pub struct PruneBuildCache {}
+
+pub type PruneBuildCacheResponse = Vec<BuilderCachePrune>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuilderCachePrune {
+  /// The buildx builder name.
+  pub builder: String,
+  /// The space reclaimed, eg `1.2GB`,
+  /// if it could be parsed from the prune output.
+  pub reclaimed: Option<String>,
+  /// The log of the prune command.
+  pub log: Log,
+}
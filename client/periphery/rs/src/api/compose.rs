@@ -162,6 +162,25 @@ pub struct ComposePullResponse {
 
 //
 
+/// Rewrites the compose directory and runs docker compose config,
+/// without deploying. Response: [DeployStackResponse]
+#[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
+#[response(DeployStackResponse)]
+#[error(anyhow::Error)]
+pub struct ComposeConfig {
+  /// The stack to validate
+  pub stack: Stack,
+  /// The linked repo, if it exists.
+  pub repo: Option<Repo>,
+  /// If provided, use it to login in. Otherwise check periphery local git providers.
+  pub git_token: Option<String>,
+  /// Propogate any secret replacers from core interpolation.
+  #[serde(default)]
+  pub replacers: Vec<(String, String)>,
+}
+
+//
+
 /// docker compose up.
 #[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
 #[response(DeployStackResponse)]
@@ -4,6 +4,7 @@ use komodo_client::entities::{
     network::Network,
     volume::Volume,
   },
+  scan::ImageScanReport,
   update::Log,
 };
 use mogh_resolver::Resolve;
@@ -52,6 +53,85 @@ pub struct GetLatestImageDigestResponse {
 
 //
 
+/// Scan an image for vulnerabilities using
+/// the first scanner CLI (trivy, grype) found on the host.
+#[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
+#[response(ScanImageResponse)]
+#[error(anyhow::Error)]
+pub struct ScanImage {
+  /// The name of the image, including the tag.
+  pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanImageResponse {
+  /// The log of the scan command.
+  pub log: Log,
+  /// The scan results, if the scan was successful.
+  pub report: Option<ImageScanReport>,
+}
+
+//
+
+/// Sign an image with `cosign`.
+#[derive(Clone, Serialize, Deserialize, Resolve)]
+#[response(Log)]
+#[error(anyhow::Error)]
+pub struct SignImage {
+  /// The name of the image, including the tag.
+  pub image: String,
+  /// The name of the secret holding the private key.
+  /// Used to find the key in the Periphery secrets
+  /// if it isn't sent from Core.
+  pub key_name: String,
+  /// The private key, if found in the Core secrets.
+  pub key: Option<String>,
+  /// The name of the secret holding the key password, if any.
+  pub password_name: String,
+  /// The key password, if found in the Core secrets.
+  pub password: Option<String>,
+}
+
+impl std::fmt::Debug for SignImage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SignImage")
+      .field("image", &self.image)
+      .field("key_name", &self.key_name)
+      .field("password_name", &self.password_name)
+      .finish()
+  }
+}
+
+//
+
+/// Verify the image digest the tag currently points to
+/// has a valid `cosign` signature from at least one of the public keys.
+#[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
+#[response(VerifyImageSignatureResponse)]
+#[error(anyhow::Error)]
+pub struct VerifyImageSignature {
+  /// The name of the image, including the tag.
+  pub image: String,
+  /// The public keys to verify the signature with.
+  pub keys: Vec<String>,
+  /// Optional account to use to access the registry
+  pub account: Option<String>,
+  /// Override registry token for account with one sent from core.
+  pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyImageSignatureResponse {
+  /// The log of the verification.
+  pub log: Log,
+  /// The image pinned to the verified digest, eg. `image@sha256:...`,
+  /// if the verification was successful. Deploy this to make sure
+  /// the verified contents are deployed.
+  pub image: Option<String>,
+}
+
+//
+
 #[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
 #[response(Log)]
 #[error(anyhow::Error)]
@@ -0,0 +1,92 @@
+use komodo_client::entities::{
+  file::{FileDownload, FileEntry},
+  update::Log,
+};
+use mogh_resolver::Resolve;
+use serde::{Deserialize, Serialize};
+
+/// List the entries of a directory inside a container.
+/// Requires `find` and `stat` to be available in the container.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<FileEntry>)]
+#[error(anyhow::Error)]
+pub struct ListContainerDirectory {
+  /// The container name
+  pub container: String,
+  /// The absolute path of the directory inside the container.
+  pub path: String,
+}
+
+//
+
+/// Download a file from a container, like `docker cp`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(FileDownload)]
+#[error(anyhow::Error)]
+pub struct ReadContainerFile {
+  /// The container name
+  pub container: String,
+  /// The absolute path of the file inside the container.
+  pub path: String,
+  /// Fail if the file is larger than this many bytes.
+  pub max_size: u64,
+}
+
+//
+
+/// Upload a file into a container, like `docker cp`.
+/// Overwrites the file if it already exists.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Log)]
+#[error(anyhow::Error)]
+pub struct WriteContainerFile {
+  /// The container name
+  pub container: String,
+  /// The absolute path of the file inside the container.
+  pub path: String,
+  /// The file contents, base64 encoded.
+  pub contents: String,
+}
+
+//
+
+/// List the entries of a directory on the host.
+/// The path must be inside Periphery's `root_directory`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<FileEntry>)]
+#[error(anyhow::Error)]
+pub struct ListHostDirectory {
+  /// The path of the directory, relative to `root_directory`.
+  /// Absolute paths are also accepted if they are inside `root_directory`.
+  pub path: String,
+}
+
+//
+
+/// Download a file from the host.
+/// The path must be inside Periphery's `root_directory`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(FileDownload)]
+#[error(anyhow::Error)]
+pub struct ReadHostFile {
+  /// The path of the file, relative to `root_directory`.
+  /// Absolute paths are also accepted if they are inside `root_directory`.
+  pub path: String,
+  /// Fail if the file is larger than this many bytes.
+  pub max_size: u64,
+}
+
+//
+
+/// Upload a file to the host, creating parent directories as needed.
+/// The path must be inside Periphery's `root_directory`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Log)]
+#[error(anyhow::Error)]
+pub struct WriteHostFile {
+  /// The path of the file, relative to `root_directory`.
+  /// Absolute paths are also accepted if they are inside `root_directory`.
+  pub path: String,
+  /// The file contents, base64 encoded.
+  pub contents: String,
+}
@@ -7,10 +7,12 @@ use komodo_client::entities::{
 use mogh_resolver::Resolve;
 use serde::{Deserialize, Serialize};
 
+pub mod action;
 pub mod build;
 pub mod compose;
 pub mod container;
 pub mod docker;
+pub mod file;
 pub mod git;
 pub mod keys;
 pub mod poll;
@@ -12,9 +12,14 @@ use komodo_client::entities::{
   deployment::Deployment,
   onboarding_key::OnboardingKey,
   permission::Permission,
+  preview::StackPreview,
   procedure::Procedure,
   provider::{GitProviderAccount, ImageRegistryAccount},
   repo::Repo,
+  revision::ResourceRevision,
+  sbom::BuildSbom,
+  scan::ImageScan,
+  schedule::ScheduledDeploy,
   server::Server,
   stack::Stack,
   stats::SystemStatsRecord,
@@ -56,6 +61,11 @@ pub struct Client {
   pub updates: Collection<Update>,
   pub alerts: Collection<Alert>,
   pub stats: Collection<SystemStatsRecord>,
+  pub image_scans: Collection<ImageScan>,
+  pub build_sboms: Collection<BuildSbom>,
+  pub stack_previews: Collection<StackPreview>,
+  pub resource_revisions: Collection<ResourceRevision>,
+  pub scheduled_deploys: Collection<ScheduledDeploy>,
   // RESOURCES
   pub swarms: Collection<Swarm>,
   pub servers: Collection<Server>,
@@ -94,6 +104,12 @@ impl Client {
       updates: mongo_indexed::collection(&db, true).await?,
       alerts: mongo_indexed::collection(&db, true).await?,
       stats: mongo_indexed::collection(&db, true).await?,
+      image_scans: mongo_indexed::collection(&db, true).await?,
+      build_sboms: mongo_indexed::collection(&db, true).await?,
+      stack_previews: mongo_indexed::collection(&db, true).await?,
+      resource_revisions: mongo_indexed::collection(&db, true)
+        .await?,
+      scheduled_deploys: mongo_indexed::collection(&db, true).await?,
       // RESOURCES
       swarms: resource_collection(&db, "Swarm").await?,
       servers: resource_collection(&db, "Server").await?,
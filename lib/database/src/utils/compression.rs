@@ -0,0 +1,29 @@
+use anyhow::Context;
+use async_compression::tokio::{
+  bufread::GzipDecoder, write::GzipEncoder,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Gzip compresses the bytes in memory.
+pub async fn gzip(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+  let mut encoder = GzipEncoder::new(Vec::new());
+  encoder
+    .write_all(bytes)
+    .await
+    .context("Failed to compress bytes")?;
+  encoder
+    .shutdown()
+    .await
+    .context("Failed to finish compression")?;
+  Ok(encoder.into_inner())
+}
+
+/// Decompresses bytes compressed with [gzip].
+pub async fn gunzip(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+  let mut res = Vec::new();
+  GzipDecoder::new(bytes)
+    .read_to_end(&mut res)
+    .await
+    .context("Failed to decompress bytes")?;
+  Ok(res)
+}
@@ -1,7 +1,13 @@
 mod backup;
+mod compression;
 mod copy;
 mod restore;
+mod restore_items;
 
 pub use backup::backup;
+pub use compression::{gunzip, gzip};
 pub use copy::copy;
 pub use restore::restore;
+pub use restore_items::{
+  BackupContents, list_backup_items, restore_backup_items,
+};
@@ -22,13 +22,8 @@ pub async fn restore(
   restore_folder: Option<&Path>,
 ) -> anyhow::Result<()> {
   // Get the specific dated folder to restore contents of
-  let restore_folder = if let Some(restore_folder) = restore_folder {
-    backups_folder.join(restore_folder)
-  } else {
-    latest_restore_folder(backups_folder).await?
-  }
-  .components()
-  .collect::<PathBuf>();
+  let restore_folder =
+    get_restore_folder(backups_folder, restore_folder).await?;
 
   info!("Restore folder: {restore_folder:?}");
 
@@ -129,6 +124,22 @@ pub async fn restore(
   Ok(())
 }
 
+/// Resolves the dated backup folder to restore from,
+/// defaulting to the most recent one.
+pub(super) async fn get_restore_folder(
+  backups_folder: &Path,
+  restore_folder: Option<&Path>,
+) -> anyhow::Result<PathBuf> {
+  let restore_folder = if let Some(restore_folder) = restore_folder {
+    backups_folder.join(restore_folder)
+  } else {
+    latest_restore_folder(backups_folder).await?
+  }
+  .components()
+  .collect::<PathBuf>();
+  Ok(restore_folder)
+}
+
 async fn latest_restore_folder(
   backups_folder: &Path,
 ) -> anyhow::Result<PathBuf> {
@@ -0,0 +1,586 @@
+use std::{
+  collections::{BTreeSet, HashMap},
+  path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use async_compression::tokio::bufread::GzipDecoder;
+use futures_util::TryStreamExt;
+use komodo_client::entities::backup::{
+  BackupItem, BackupItemFieldDiff, BackupItemState, BackupItemTarget,
+  BackupItemVariant,
+};
+use mungos::mongodb::{
+  Database,
+  bson::{Document, doc},
+};
+use serde_json::Value;
+use tokio::io::BufReader;
+use tokio_util::codec::{FramedRead, LinesCodec};
+use tracing::{info, warn};
+
+use super::restore::get_restore_folder;
+
+/// These top level fields are either bookkeeping or
+/// derived from external state, and are left out of the diff.
+const IGNORED_DIFF_FIELDS: [&str; 3] = ["_id", "updated_at", "info"];
+
+pub struct BackupContents {
+  /// The dated backup folder which was read.
+  pub restore_folder: PathBuf,
+  /// The resources, variables and user groups in the backup.
+  pub items: Vec<BackupItem>,
+}
+
+/// Lists the resources, variables and user groups contained
+/// in a backup, comparing each against the current database.
+/// Nothing is written to the database.
+pub async fn list_backup_items(
+  db: &Database,
+  backups_folder: &Path,
+  restore_folder: Option<&Path>,
+) -> anyhow::Result<BackupContents> {
+  let restore_folder =
+    get_restore_folder(backups_folder, restore_folder).await?;
+  let mut items = Vec::new();
+  for variant in BackupItemVariant::ALL {
+    let loaded = load_items(db, &restore_folder, variant).await?;
+    items.extend(loaded.into_iter().map(|(item, _)| item));
+  }
+  Ok(BackupContents {
+    restore_folder,
+    items,
+  })
+}
+
+/// Restores only the targeted items out of a backup,
+/// leaving everything else in the database untouched.
+///
+/// All targets are validated to exist in the backup before
+/// anything is written. Returns the restored items, with the
+/// state / diff they had against the database before the restore.
+pub async fn restore_backup_items(
+  db: &Database,
+  backups_folder: &Path,
+  restore_folder: Option<&Path>,
+  targets: &[BackupItemTarget],
+) -> anyhow::Result<Vec<BackupItem>> {
+  if targets.is_empty() {
+    return Err(anyhow!("No items selected to restore"));
+  }
+
+  let restore_folder =
+    get_restore_folder(backups_folder, restore_folder).await?;
+
+  info!("Restore folder: {restore_folder:?}");
+
+  let variants = targets
+    .iter()
+    .map(|target| target.variant)
+    .collect::<BTreeSet<_>>();
+
+  let mut to_restore = Vec::with_capacity(targets.len());
+  for variant in variants {
+    let mut loaded = load_items(db, &restore_folder, variant)
+      .await?
+      .into_iter()
+      .map(|(item, document)| (item.name.clone(), (item, document)))
+      .collect::<HashMap<_, _>>();
+    for target in targets.iter().filter(|t| t.variant == variant) {
+      let item = loaded.remove(&target.name).with_context(|| {
+        format!(
+          "{} '{}' does not exist in backup {restore_folder:?}",
+          target.variant, target.name
+        )
+      })?;
+      to_restore.push(item);
+    }
+  }
+
+  let mut restored = Vec::with_capacity(to_restore.len());
+  for (item, document) in to_restore {
+    if item.state == BackupItemState::Unchanged {
+      info!(
+        "[{}]: '{}' is unchanged, skipping",
+        item.variant, item.name
+      );
+      restored.push(item);
+      continue;
+    }
+    restore_item(db, &item, document).await.with_context(|| {
+      format!("Failed to restore {} '{}'", item.variant, item.name)
+    })?;
+    info!("[{}]: Restored '{}'", item.variant, item.name);
+    restored.push(item);
+  }
+
+  Ok(restored)
+}
+
+async fn restore_item(
+  db: &Database,
+  item: &BackupItem,
+  mut document: Document,
+) -> anyhow::Result<()> {
+  let collection =
+    db.collection::<Document>(item.variant.collection());
+  // Variables are unique by name, and may have been
+  // recreated with a different _id since the backup.
+  let query = if item.variant == BackupItemVariant::Variable {
+    document.remove("_id");
+    doc! { "name": &item.name }
+  } else {
+    let id = document
+      .get_object_id("_id")
+      .context("Backup document is missing _id")?;
+    doc! { "_id": id }
+  };
+  collection
+    .replace_one(query, document)
+    .upsert(true)
+    .await
+    .context("Failed to upsert document")?;
+  Ok(())
+}
+
+/// Loads all items of the given type from the backup,
+/// along with their raw documents to restore.
+async fn load_items(
+  db: &Database,
+  restore_folder: &Path,
+  variant: BackupItemVariant,
+) -> anyhow::Result<Vec<(BackupItem, Document)>> {
+  let collection = variant.collection();
+  let file = restore_folder.join(format!("{collection}.gz"));
+
+  if !tokio::fs::try_exists(&file).await.unwrap_or_default() {
+    return Ok(Vec::new());
+  }
+
+  let backup = read_backup_file(&file).await?;
+
+  let current = db
+    .collection::<Document>(collection)
+    .find(Document::new())
+    .await
+    .with_context(|| format!("Failed to query {collection} on db"))?
+    .try_collect::<Vec<_>>()
+    .await
+    .with_context(|| format!("Failed to collect {collection} on db"))?
+    .into_iter()
+    .filter_map(|document| {
+      Some((item_key(variant, &document)?, document))
+    })
+    .collect::<HashMap<_, _>>();
+
+  let mut items = backup
+    .into_iter()
+    .filter_map(|document| {
+      let name = document.get_str("name").ok()?.to_string();
+      let id = if variant == BackupItemVariant::Variable {
+        String::new()
+      } else {
+        document.get_object_id("_id").ok()?.to_hex()
+      };
+      let (state, diff) =
+        match current.get(&item_key(variant, &document)?) {
+          Some(current) => {
+            let diff = diff_documents(current, &document);
+            if diff.is_empty() {
+              (BackupItemState::Unchanged, diff)
+            } else {
+              (BackupItemState::Changed, diff)
+            }
+          }
+          None => (BackupItemState::Missing, Vec::new()),
+        };
+      let item = BackupItem {
+        variant,
+        id,
+        name,
+        state,
+        diff,
+      };
+      Some((item, document))
+    })
+    .collect::<Vec<_>>();
+
+  items.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+  Ok(items)
+}
+
+/// Variables are matched by name, everything else by _id.
+fn item_key(
+  variant: BackupItemVariant,
+  document: &Document,
+) -> Option<String> {
+  if variant == BackupItemVariant::Variable {
+    document.get_str("name").ok().map(str::to_string)
+  } else {
+    document.get_object_id("_id").ok().map(|id| id.to_hex())
+  }
+}
+
+async fn read_backup_file(
+  file: &Path,
+) -> anyhow::Result<Vec<Document>> {
+  let handle = tokio::fs::File::open(file)
+    .await
+    .with_context(|| format!("Failed to open file {file:?}"))?;
+
+  let mut reader = FramedRead::new(
+    GzipDecoder::new(BufReader::new(handle)),
+    LinesCodec::new(),
+  );
+
+  let mut documents = Vec::new();
+
+  while let Some(line) =
+    reader.try_next().await.context("Failed to get next line")?
+  {
+    if line.is_empty() {
+      continue;
+    }
+    match serde_json::from_str::<Document>(&line)
+      .context("Failed to deserialize line")
+    {
+      Ok(document) => documents.push(document),
+      Err(e) => warn!("{e:#}"),
+    }
+  }
+
+  Ok(documents)
+}
+
+fn diff_documents(
+  current: &Document,
+  backup: &Document,
+) -> Vec<BackupItemFieldDiff> {
+  let (Ok(Value::Object(mut current)), Ok(Value::Object(mut backup))) =
+    (serde_json::to_value(current), serde_json::to_value(backup))
+  else {
+    return Vec::new();
+  };
+  for field in IGNORED_DIFF_FIELDS {
+    current.remove(field);
+    backup.remove(field);
+  }
+  let mut diff = Vec::new();
+  diff_values(
+    "",
+    Some(&Value::Object(current)),
+    Some(&Value::Object(backup)),
+    &mut diff,
+  );
+  diff
+}
+
+fn diff_values(
+  path: &str,
+  current: Option<&Value>,
+  backup: Option<&Value>,
+  diff: &mut Vec<BackupItemFieldDiff>,
+) {
+  // Missing and null fields are equivalent
+  let current = current.filter(|v| !v.is_null());
+  let backup = backup.filter(|v| !v.is_null());
+  match (current, backup) {
+    (Some(Value::Object(current)), Some(Value::Object(backup))) => {
+      let keys =
+        current.keys().chain(backup.keys()).collect::<BTreeSet<_>>();
+      for key in keys {
+        let path = if path.is_empty() {
+          key.to_string()
+        } else {
+          format!("{path}.{key}")
+        };
+        diff_values(&path, current.get(key), backup.get(key), diff);
+      }
+    }
+    (current, backup) if current == backup => {}
+    (current, backup) => diff.push(BackupItemFieldDiff {
+      field: path.to_string(),
+      current: current.map(Value::to_string).unwrap_or_default(),
+      backup: backup.map(Value::to_string).unwrap_or_default(),
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use async_compression::tokio::write::GzipEncoder;
+  use komodo_client::entities::config::DatabaseConfig;
+  use mungos::mongodb::bson::{Bson, oid::ObjectId};
+  use tokio::io::AsyncWriteExt;
+
+  use super::*;
+
+  fn temp_folder() -> PathBuf {
+    std::env::temp_dir()
+      .join(format!("komodo-restore-items-{}", ObjectId::new()))
+  }
+
+  async fn write_backup_file(file: &Path, lines: &[String]) {
+    let mut encoder =
+      GzipEncoder::new(tokio::fs::File::create(file).await.unwrap());
+    for line in lines {
+      encoder.write_all(line.as_bytes()).await.unwrap();
+      encoder.write_all(b"\n").await.unwrap();
+    }
+    encoder.shutdown().await.unwrap();
+  }
+
+  async fn write_backup(
+    folder: &Path,
+    collection: &str,
+    docs: &[Document],
+  ) {
+    let lines = docs
+      .iter()
+      .map(|doc| serde_json::to_string(doc).unwrap())
+      .collect::<Vec<_>>();
+    write_backup_file(
+      &folder.join(format!("{collection}.gz")),
+      &lines,
+    )
+    .await;
+  }
+
+  fn target(
+    variant: BackupItemVariant,
+    name: &str,
+  ) -> BackupItemTarget {
+    BackupItemTarget {
+      variant,
+      name: name.to_string(),
+    }
+  }
+
+  #[test]
+  fn diffs_nested_fields() {
+    let current = doc! {
+      "_id": ObjectId::new(),
+      "name": "stack",
+      "updated_at": 2,
+      "info": { "state": "running" },
+      "config": {
+        "file_contents": "new",
+        "environment": "A=1",
+        "links": ["a"],
+        "branch": null,
+      },
+    };
+    let backup = doc! {
+      "_id": ObjectId::new(),
+      "name": "stack",
+      "updated_at": 1,
+      "info": { "state": "down" },
+      "config": {
+        "file_contents": "old",
+        "environment": "A=1",
+        "links": ["a", "b"],
+        "server_id": "server",
+      },
+    };
+    let diff = diff_documents(&current, &backup)
+      .into_iter()
+      .map(|diff| (diff.field, diff.current, diff.backup))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      diff,
+      [
+        (
+          String::from("config.file_contents"),
+          String::from("\"new\""),
+          String::from("\"old\"")
+        ),
+        (
+          String::from("config.links"),
+          String::from("[\"a\"]"),
+          String::from("[\"a\",\"b\"]")
+        ),
+        (
+          String::from("config.server_id"),
+          String::new(),
+          String::from("\"server\"")
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn ignores_bookkeeping_fields() {
+    let current =
+      doc! { "_id": ObjectId::new(), "name": "a", "updated_at": 2 };
+    let backup =
+      doc! { "_id": ObjectId::new(), "name": "a", "updated_at": 1 };
+    assert!(diff_documents(&current, &backup).is_empty());
+  }
+
+  #[test]
+  fn matches_variables_by_name() {
+    let id = ObjectId::new();
+    let document = doc! { "_id": id, "name": "VAR" };
+    assert_eq!(
+      item_key(BackupItemVariant::Variable, &document).as_deref(),
+      Some("VAR")
+    );
+    assert_eq!(
+      item_key(BackupItemVariant::Stack, &document),
+      Some(id.to_hex())
+    );
+    assert_eq!(
+      item_key(BackupItemVariant::Stack, &doc! { "name": "a" }),
+      None
+    );
+  }
+
+  #[tokio::test]
+  async fn reads_backup_file_skipping_invalid_lines() {
+    let folder = temp_folder();
+    tokio::fs::create_dir_all(&folder).await.unwrap();
+    let file = folder.join("Stack.gz");
+    write_backup_file(
+      &file,
+      &[
+        String::from(r#"{"name":"a"}"#),
+        String::new(),
+        String::from("not json"),
+        String::from(r#"{"name":"b"}"#),
+      ],
+    )
+    .await;
+    let documents = read_backup_file(&file).await;
+    tokio::fs::remove_dir_all(&folder).await.unwrap();
+    let names = documents
+      .unwrap()
+      .iter()
+      .map(|doc| doc.get_str("name").unwrap().to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(names, ["a", "b"]);
+  }
+
+  #[tokio::test]
+  #[ignore = "requires a MongoDB at KOMODO_TEST_DATABASE_URI"]
+  async fn restores_only_selected_items() {
+    let db = crate::init(&DatabaseConfig {
+      uri: std::env::var("KOMODO_TEST_DATABASE_URI").unwrap(),
+      db_name: format!("komodo-test-{}", ObjectId::new()),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+    let backups = temp_folder();
+    let folder = backups.join("2025-01-01_00-00-00");
+    tokio::fs::create_dir_all(&folder).await.unwrap();
+
+    let (a, b) = (ObjectId::new(), ObjectId::new());
+    write_backup(
+      &folder,
+      "Build",
+      &[
+        doc! { "_id": a, "name": "a", "config": { "version": 1 } },
+        doc! { "_id": b, "name": "b", "config": { "version": 1 } },
+      ],
+    )
+    .await;
+    write_backup(
+      &folder,
+      "Variable",
+      &[doc! { "_id": ObjectId::new(), "name": "VAR", "value": "old" }],
+    )
+    .await;
+
+    let builds = db.collection::<Document>("Build");
+    let variables = db.collection::<Document>("Variable");
+    builds
+      .insert_one(
+        doc! { "_id": a, "name": "a", "config": { "version": 2 } },
+      )
+      .await
+      .unwrap();
+    // Recreated since the backup, with a different _id
+    variables
+      .insert_one(doc! { "_id": ObjectId::new(), "name": "VAR", "value": "new" })
+      .await
+      .unwrap();
+
+    let contents =
+      list_backup_items(&db, &backups, None).await.unwrap();
+    assert_eq!(contents.restore_folder, folder);
+    let states = contents
+      .items
+      .iter()
+      .map(|item| (item.variant, item.name.as_str(), item.state))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      states,
+      [
+        (BackupItemVariant::Build, "a", BackupItemState::Changed),
+        (BackupItemVariant::Build, "b", BackupItemState::Missing),
+        (
+          BackupItemVariant::Variable,
+          "VAR",
+          BackupItemState::Changed
+        ),
+      ]
+    );
+
+    // Nothing is written if any target is missing from the backup
+    let res = restore_backup_items(
+      &db,
+      &backups,
+      None,
+      &[
+        target(BackupItemVariant::Build, "a"),
+        target(BackupItemVariant::Build, "missing"),
+      ],
+    )
+    .await;
+    assert!(res.is_err());
+    let version = |doc: Document| match doc
+      .get_document("config")
+      .unwrap()
+      .get("version")
+    {
+      Some(Bson::Int32(version)) => *version as i64,
+      Some(Bson::Int64(version)) => *version,
+      version => panic!("Unexpected version {version:?}"),
+    };
+    let build_a = builds.find_one(doc! { "_id": a }).await.unwrap();
+    assert_eq!(version(build_a.unwrap()), 2);
+
+    let restored = restore_backup_items(
+      &db,
+      &backups,
+      None,
+      &[
+        target(BackupItemVariant::Build, "a"),
+        target(BackupItemVariant::Variable, "VAR"),
+      ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored.len(), 2);
+
+    let build_a = builds.find_one(doc! { "_id": a }).await.unwrap();
+    assert_eq!(version(build_a.unwrap()), 1);
+    // Unselected items are left alone
+    assert!(
+      builds.find_one(doc! { "_id": b }).await.unwrap().is_none()
+    );
+    let vars = variables
+      .find(Document::new())
+      .await
+      .unwrap()
+      .try_collect::<Vec<_>>()
+      .await
+      .unwrap();
+    assert_eq!(vars.len(), 1);
+    assert_eq!(vars[0].get_str("value").unwrap(), "old");
+
+    db.drop().await.unwrap();
+    tokio::fs::remove_dir_all(&backups).await.unwrap();
+  }
+}
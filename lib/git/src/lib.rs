@@ -113,3 +113,43 @@ pub async fn get_remote_url(path: &Path) -> anyhow::Result<String> {
     ))
   }
 }
+
+/// Lists the files changed between two commits,
+/// relative to the repo root.
+pub async fn get_changed_files(
+  repo_dir: &Path,
+  before: &str,
+  after: &str,
+) -> anyhow::Result<Vec<String>> {
+  for hash in [before, after] {
+    if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit())
+    {
+      return Err(anyhow!("Invalid commit hash: {hash}"));
+    }
+  }
+  check_installed().await?;
+  let output = run_standard_command(
+    &format!("git diff --name-only {before} {after}"),
+    CommandOptions::default()
+      .path(repo_dir)
+      .timeout(Duration::from_secs(10)),
+  )
+  .await;
+  if output.success() {
+    Ok(
+      output
+        .stdout
+        .lines()
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .map(str::to_string)
+        .collect(),
+    )
+  } else {
+    Err(anyhow!(
+      "Failed to get changed files | stdout: {} | stderr: {}",
+      output.stdout,
+      output.stderr
+    ))
+  }
+}
@@ -132,6 +132,19 @@ where
     }
 
     if let Some(commit) = args.commit {
+      // The commit may be a tag not reachable from the branch,
+      // eg. a tag pushed to a release branch.
+      let fetch_tags = run_komodo_standard_command(
+        "Git Fetch Tags",
+        "git fetch origin --tags --force",
+        CommandOptions::default().path(res.path.as_ref()),
+      )
+      .await;
+      if !fetch_tags.success {
+        res.logs.push(fetch_tags);
+        return Ok(res);
+      }
+
       let reset_log = run_komodo_standard_command(
         "Set commit",
         format!("git reset --hard {commit}"),
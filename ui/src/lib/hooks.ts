@@ -548,7 +548,7 @@ export function useTagsFilter() {
   return tags;
 }
 
-export type WebhookIntegration = "Github" | "Gitlab";
+export type WebhookIntegration = "Github" | "Gitlab" | "Bitbucket" | "Azure";
 export type WebhookIntegrations = {
   [key: string]: WebhookIntegration;
 };
@@ -574,7 +574,11 @@ export function useWebhookIntegrations() {
         ? integrations[provider]
         : provider.includes("gitlab")
           ? "Gitlab"
-          : "Github";
+          : provider.includes("bitbucket")
+            ? "Bitbucket"
+            : provider.includes("azure")
+              ? "Azure"
+              : "Github";
     },
   };
 }
@@ -21,11 +21,17 @@ import { useSearchParams } from "react-router-dom";
 const ALERT_TYPES_BY_RESOURCE: { [key: string]: Types.AlertData["type"][] } = {
   Server: ["ServerUnreachable", "ServerCpu", "ServerMem", "ServerDisk"],
   Swarm: ["SwarmUnhealthy"],
-  Stack: ["StackStateChange", "StackImageUpdateAvailable", "StackAutoUpdated"],
+  Stack: [
+    "StackStateChange",
+    "StackImageUpdateAvailable",
+    "StackAutoUpdated",
+    "ImageVulnerabilities",
+  ],
   Deployment: [
     "ContainerStateChange",
     "DeploymentImageUpdateAvailable",
     "DeploymentAutoUpdated",
+    "ImageVulnerabilities",
   ],
   Build: ["BuildFailed"],
   Repo: ["RepoBuildFailed"],
@@ -33,7 +39,7 @@ const ALERT_TYPES_BY_RESOURCE: { [key: string]: Types.AlertData["type"][] } = {
 };
 
 const FALLBACK_ALERT_TYPES = [
-  ...Object.values(ALERT_TYPES_BY_RESOURCE).flat(),
+  ...new Set(Object.values(ALERT_TYPES_BY_RESOURCE).flat()),
   "AwsBuilderTerminationFailed",
 ];
 
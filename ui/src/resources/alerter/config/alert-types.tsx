@@ -23,6 +23,7 @@ const ALERT_TYPES: Types.AlertData["type"][] = [
   "DeploymentAutoUpdated",
   // Misc
   "ScheduleRun",
+  "ImageVulnerabilities",
   "BuildFailed",
   "ResourceSyncPendingUpdates",
   "RepoBuildFailed",